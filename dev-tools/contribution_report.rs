@@ -0,0 +1,135 @@
+/// AI Contribution Report
+///
+/// CLI tool that links commits in a repository to the agent sessions recorded
+/// by the desktop app and summarizes agent vs human contribution.
+///
+/// Usage:
+///   # Report on HEAD (last 1000 commits)
+///   cargo run --bin contribution_report -- ~/code/my-repo
+///
+///   # Report on a range, e.g. a PR branch against main
+///   cargo run --bin contribution_report -- ~/code/my-repo main..feature/login
+///
+///   # Limit the walk and print JSON
+///   cargo run --bin contribution_report -- ~/code/my-repo HEAD --max 200 --json
+use guideai_desktop::contribution_report::generate_contribution_report;
+use guideai_desktop::database::init_database;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 2 {
+        print_usage();
+        std::process::exit(1);
+    }
+
+    let repo_path = &args[1];
+    let mut range: Option<String> = None;
+    let mut max_commits: Option<usize> = None;
+    let mut json = false;
+
+    let mut i = 2;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--json" => json = true,
+            "--max" => {
+                i += 1;
+                match args.get(i).and_then(|n| n.parse().ok()) {
+                    Some(n) => max_commits = Some(n),
+                    None => {
+                        eprintln!("Error: --max needs a number");
+                        print_usage();
+                        std::process::exit(1);
+                    }
+                }
+            }
+            other if range.is_none() && !other.starts_with("--") => {
+                range = Some(other.to_string());
+            }
+            other => {
+                eprintln!("Error: Unknown argument '{}'", other);
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    if let Err(e) = init_database() {
+        eprintln!("Error: Failed to open GuideAI database: {}", e);
+        std::process::exit(1);
+    }
+
+    let report = match generate_contribution_report(repo_path, range.as_deref(), max_commits) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return;
+    }
+
+    println!("=== AI Contribution Report ===");
+    println!("Repository: {}", report.repository);
+    println!("Range:      {}", report.range);
+    println!();
+    println!(
+        "Commits:    {} total, {} with agent involvement, {} without",
+        report.total_commits, report.agent_commits, report.human_commits
+    );
+    let share = if report.lines_added > 0 {
+        report.agent_lines as f64 * 100.0 / report.lines_added as f64
+    } else {
+        0.0
+    };
+    println!(
+        "Lines:      {} added, {} attributed to agents ({:.1}%)",
+        report.lines_added, report.agent_lines, share
+    );
+
+    if !report.branches.is_empty() {
+        println!();
+        println!("Sessions per branch:");
+        for branch in &report.branches {
+            println!(
+                "  {:<40} {:>3} sessions, {:>3} linked commits",
+                branch.branch,
+                branch.session_ids.len(),
+                branch.linked_commits
+            );
+        }
+    }
+
+    println!();
+    println!("Commits:");
+    for commit in &report.commits {
+        let marker = if commit.session_ids.is_empty() {
+            " "
+        } else {
+            "*"
+        };
+        println!(
+            "  {} {} +{:<5} -{:<5} agent {:<5} {}",
+            marker,
+            &commit.hash[..10.min(commit.hash.len())],
+            commit.lines_added,
+            commit.lines_deleted,
+            commit.agent_lines,
+            commit.summary
+        );
+    }
+    println!();
+    println!("* = linked to at least one agent session");
+}
+
+fn print_usage() {
+    eprintln!("Usage: contribution_report <repo-path> [range] [--max N] [--json]");
+    eprintln!();
+    eprintln!("  range    Revision or range to walk (default: HEAD), e.g. main..feature/x");
+    eprintln!("  --max N  Walk at most N commits (default: 1000)");
+    eprintln!("  --json   Print the full report as JSON");
+}
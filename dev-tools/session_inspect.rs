@@ -0,0 +1,169 @@
+/// Session Inspector
+///
+/// CLI over `providers::inspect`: shows each raw record of a session next to
+/// the canonical messages it produced, flags dropped/failed records and
+/// unmapped fields, and exports/replays fixtures for bug reports.
+///
+/// Usage:
+///   # Inspect a session (Cursor takes a store.db or session directory)
+///   cargo run --bin session_inspect -- claude-code ~/.claude/projects/foo/abc.jsonl
+///
+///   # Full record-by-record JSON
+///   cargo run --bin session_inspect -- codex ~/.codex/sessions/.../rollout.jsonl --json
+///
+///   # Export a fixture, then replay it anywhere
+///   cargo run --bin session_inspect -- cursor ~/.cursor/chats/<hash>/<id> --export /tmp
+///   cargo run --bin session_inspect -- --replay /tmp/cursor-<id>.fixture.json
+use guideai_desktop::providers::inspect::{
+    export_fixture, inspect_session, read_fixture, replay_fixture, RecordStatus, SessionInspection,
+};
+use std::path::Path;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() >= 3 && args[1] == "--replay" {
+        let replay = read_fixture(Path::new(&args[2])).and_then(|fixture| replay_fixture(&fixture));
+        match replay {
+            Ok(replay) => {
+                print_inspection(&replay.inspection);
+                println!();
+                if replay.matches_expected {
+                    println!("Replay matches the exported canonical output");
+                } else {
+                    println!("Replay DIFFERS from the exported canonical output");
+                    std::process::exit(2);
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if args.len() < 3 {
+        print_usage();
+        std::process::exit(1);
+    }
+
+    let provider = &args[1];
+    let path = Path::new(&args[2]);
+    let mut json = false;
+    let mut export: Option<String> = None;
+
+    let mut i = 3;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--json" => json = true,
+            "--export" => {
+                i += 1;
+                match args.get(i) {
+                    Some(output) => export = Some(output.clone()),
+                    None => {
+                        eprintln!("Error: --export needs an output path");
+                        print_usage();
+                        std::process::exit(1);
+                    }
+                }
+            }
+            other => {
+                eprintln!("Error: Unknown argument '{}'", other);
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    if let Some(output) = export {
+        match export_fixture(provider, path, Path::new(&output)) {
+            Ok(written) => println!("Wrote fixture to {}", written.display()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let inspection = match inspect_session(provider, path) {
+        Ok(inspection) => inspection,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&inspection).unwrap());
+        return;
+    }
+
+    print_inspection(&inspection);
+}
+
+fn print_inspection(inspection: &SessionInspection) {
+    let summary = &inspection.summary;
+    println!("=== Session Inspection ===");
+    println!("Provider: {}", inspection.provider);
+    println!("Session:  {}", inspection.session_id);
+    println!("CWD:      {}", inspection.cwd.as_deref().unwrap_or("-"));
+    println!(
+        "Records:  {} total, {} mapped, {} dropped, {} failed -> {} canonical messages",
+        summary.records,
+        summary.mapped,
+        summary.dropped,
+        summary.failed,
+        summary.canonical_messages
+    );
+
+    println!();
+    for record in &inspection.records {
+        let status = match &record.status {
+            RecordStatus::Mapped => format!("mapped x{}", record.canonical.len()),
+            RecordStatus::Dropped => "dropped".to_string(),
+            RecordStatus::Failed { error } => format!("FAILED: {}", error),
+        };
+        let raw = record.raw.to_string();
+        println!(
+            "  #{:<4} {:<60} {}",
+            record.index,
+            truncate(&raw, 60),
+            status
+        );
+        for message in &record.canonical {
+            println!("         -> {:?} {}", message.message_type, message.uuid);
+        }
+        if !record.unmapped_fields.is_empty() {
+            println!("         unmapped: {}", record.unmapped_fields.join(", "));
+        }
+    }
+
+    if !summary.unmapped_fields.is_empty() {
+        println!();
+        println!("Unmapped fields (records):");
+        for (field, count) in &summary.unmapped_fields {
+            println!("  {:<50} {}", field, count);
+        }
+    }
+}
+
+fn truncate(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        let head: String = s.chars().take(max_len - 3).collect();
+        format!("{}...", head)
+    }
+}
+
+fn print_usage() {
+    eprintln!("Usage: session_inspect <provider-id> <session-path> [--json] [--export <path>]");
+    eprintln!("       session_inspect --replay <fixture.json>");
+    eprintln!();
+    eprintln!("  --json           Print every record with its canonical output as JSON");
+    eprintln!("  --export <path>  Write a replayable fixture (file or directory)");
+    eprintln!("  --replay <file>  Re-run today's converters on a fixture and compare");
+}
@@ -0,0 +1,31 @@
+-- Create session_relations table for subagent / sidechain session trees
+-- One row per branch: the main conversation plus every subagent run
+
+CREATE TABLE IF NOT EXISTS session_relations (
+  session_id TEXT NOT NULL,
+  branch_id TEXT NOT NULL,
+  relation_type TEXT NOT NULL, -- 'main' | 'sidechain' | 'child_session'
+  parent_branch_id TEXT,
+  parent_session_id TEXT, -- Set for sessions spawned by another session
+  spawning_tool_use_id TEXT,
+  spawning_message_uuid TEXT,
+
+  -- Per-branch metrics
+  message_count INTEGER NOT NULL DEFAULT 0,
+  user_messages INTEGER NOT NULL DEFAULT 0,
+  assistant_messages INTEGER NOT NULL DEFAULT 0,
+  tool_calls INTEGER NOT NULL DEFAULT 0,
+  input_tokens INTEGER NOT NULL DEFAULT 0,
+  output_tokens INTEGER NOT NULL DEFAULT 0,
+  start_time TEXT,
+  end_time TEXT,
+  duration_ms INTEGER,
+
+  updated_at INTEGER NOT NULL DEFAULT (unixepoch() * 1000),
+
+  PRIMARY KEY (session_id, branch_id),
+  FOREIGN KEY (session_id) REFERENCES agent_sessions (session_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS session_relations_parent_session_idx ON session_relations (parent_session_id);
+CREATE INDEX IF NOT EXISTS session_relations_relation_type_idx ON session_relations (relation_type);
@@ -0,0 +1,3 @@
+-- Working-tree snapshots (git tree object ids) captured live at session start and end
+ALTER TABLE agent_sessions ADD COLUMN start_snapshot_tree TEXT;
+ALTER TABLE agent_sessions ADD COLUMN end_snapshot_tree TEXT;
@@ -0,0 +1,4 @@
+-- Stable project identity (normalized git remote or repository root) used to merge duplicate projects
+ALTER TABLE projects ADD COLUMN identity_key TEXT;
+
+CREATE INDEX IF NOT EXISTS projects_identity_key_idx ON projects(identity_key);
@@ -0,0 +1,6 @@
+-- Monorepo awareness: workspace tooling per project and the sub-package each session worked in
+ALTER TABLE projects ADD COLUMN workspace_kind TEXT;
+ALTER TABLE agent_sessions ADD COLUMN package_name TEXT;
+ALTER TABLE agent_sessions ADD COLUMN package_path TEXT;
+
+CREATE INDEX IF NOT EXISTS agent_sessions_package_name_idx ON agent_sessions(package_name);
@@ -0,0 +1,6 @@
+-- Analytics: the model each session mostly used, plus indexes for time-bucketed queries
+ALTER TABLE agent_sessions ADD COLUMN model TEXT;
+
+CREATE INDEX IF NOT EXISTS agent_sessions_start_time_idx ON agent_sessions(session_start_time);
+CREATE INDEX IF NOT EXISTS agent_sessions_model_idx ON agent_sessions(model);
+CREATE INDEX IF NOT EXISTS agent_sessions_git_branch_idx ON agent_sessions(git_branch);
@@ -0,0 +1,6 @@
+-- Session lifecycle: discovered → active → idle → completed → archived
+-- Persisted so sessions that were active when the app quit still complete after a restart
+ALTER TABLE agent_sessions ADD COLUMN lifecycle_state TEXT;
+ALTER TABLE agent_sessions ADD COLUMN last_activity_at INTEGER;
+
+CREATE INDEX IF NOT EXISTS agent_sessions_lifecycle_idx ON agent_sessions(provider, lifecycle_state);
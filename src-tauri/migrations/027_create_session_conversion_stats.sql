@@ -0,0 +1,17 @@
+-- Conversion fidelity per session: how many raw records became canonical messages
+-- and what was skipped, failed or not understood. Written by the converters, which
+-- can run before the session row exists, so there is no foreign key.
+
+CREATE TABLE IF NOT EXISTS session_conversion_stats (
+  session_id TEXT PRIMARY KEY,
+  provider TEXT NOT NULL,
+  records_seen INTEGER NOT NULL DEFAULT 0,
+  converted INTEGER NOT NULL DEFAULT 0,
+  skipped INTEGER NOT NULL DEFAULT 0,
+  failed INTEGER NOT NULL DEFAULT 0,
+  unknown INTEGER NOT NULL DEFAULT 0,
+  details TEXT NOT NULL, -- JSON-encoded ConversionStats (reasons, unknown types and keys)
+  updated_at INTEGER NOT NULL DEFAULT (unixepoch() * 1000)
+);
+
+CREATE INDEX IF NOT EXISTS session_conversion_stats_provider_idx ON session_conversion_stats (provider);
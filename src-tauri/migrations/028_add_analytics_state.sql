@@ -0,0 +1,56 @@
+-- Analytics: a revision counter bumped by every write to the tables charts read,
+-- so cached results are checked without scanning those tables. Also records the
+-- one-off backfill of agent_sessions.model for sessions stored before migration 25.
+
+CREATE TABLE IF NOT EXISTS analytics_state (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  revision INTEGER NOT NULL DEFAULT 0,
+  models_backfilled INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT OR IGNORE INTO analytics_state (id) VALUES (1);
+
+CREATE TRIGGER IF NOT EXISTS agent_sessions_analytics_insert AFTER INSERT ON agent_sessions
+BEGIN
+  UPDATE analytics_state SET revision = revision + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS agent_sessions_analytics_update AFTER UPDATE ON agent_sessions
+BEGIN
+  UPDATE analytics_state SET revision = revision + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS agent_sessions_analytics_delete AFTER DELETE ON agent_sessions
+BEGIN
+  UPDATE analytics_state SET revision = revision + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_metrics_analytics_insert AFTER INSERT ON session_metrics
+BEGIN
+  UPDATE analytics_state SET revision = revision + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_metrics_analytics_update AFTER UPDATE ON session_metrics
+BEGIN
+  UPDATE analytics_state SET revision = revision + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_metrics_analytics_delete AFTER DELETE ON session_metrics
+BEGIN
+  UPDATE analytics_state SET revision = revision + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS projects_analytics_insert AFTER INSERT ON projects
+BEGIN
+  UPDATE analytics_state SET revision = revision + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS projects_analytics_update AFTER UPDATE ON projects
+BEGIN
+  UPDATE analytics_state SET revision = revision + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS projects_analytics_delete AFTER DELETE ON projects
+BEGIN
+  UPDATE analytics_state SET revision = revision + 1 WHERE id = 1;
+END;
@@ -0,0 +1,10 @@
+-- Sessions removed by retention. Rescans skip them, since the provider's own
+-- files usually outlive ours and would otherwise bring them straight back.
+
+CREATE TABLE IF NOT EXISTS deleted_sessions (
+  session_id TEXT PRIMARY KEY,
+  provider TEXT NOT NULL,
+  deleted_at INTEGER NOT NULL DEFAULT (unixepoch() * 1000)
+);
+
+CREATE INDEX IF NOT EXISTS deleted_sessions_provider_idx ON deleted_sessions (provider);
@@ -0,0 +1,725 @@
+//! Local analytics over sessions and their metrics
+//!
+//! Dashboards used to assemble these numbers from raw SQL sent through
+//! `execute_sql`. This module answers the common questions directly:
+//!
+//! * bucketed time series (per day or week, in the caller's time zone) of a
+//!   [`Metric`], optionally split by provider, project, model or branch
+//! * percentile statistics of a metric over a time range
+//!
+//! Queries filter on `session_start_time`, which is indexed. Results are
+//! cached per query and invalidated whenever the session or metrics tables
+//! change (a trigger-maintained revision in `analytics_state`), so repeated
+//! dashboard renders don't rescan the database.
+
+use crate::database::with_connection_mut;
+use crate::logging::{log_info, log_warn};
+use crate::providers::common::canonical_path::extract_model_from_canonical_content;
+use crate::providers::common::read_session_file;
+use lazy_static::lazy_static;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+const MS_PER_WEEK: i64 = 7 * MS_PER_DAY;
+
+/// The Unix epoch was a Thursday; weeks start on Monday
+const WEEK_START_SHIFT_MS: i64 = 3 * MS_PER_DAY;
+
+/// Cached results kept before the cache is cleared wholesale
+const MAX_CACHE_ENTRIES: usize = 128;
+
+/// Time bucket size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bucket {
+    Day,
+    Week,
+}
+
+/// Dimension to split a series or statistic by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    Provider,
+    Project,
+    Model,
+    Branch,
+}
+
+/// Value aggregated per session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    Sessions,
+    DurationMs,
+    InputTokens,
+    OutputTokens,
+    TotalTokens,
+    ErrorCount,
+    InterruptionRate,
+    ReadWriteRatio,
+}
+
+impl Metric {
+    fn value_sql(self) -> &'static str {
+        match self {
+            Metric::Sessions => "1",
+            Metric::DurationMs => "s.duration_ms",
+            Metric::InputTokens => "m.total_input_tokens",
+            Metric::OutputTokens => "m.total_output_tokens",
+            Metric::TotalTokens => "m.total_input_tokens + m.total_output_tokens",
+            Metric::ErrorCount => "m.error_count",
+            Metric::InterruptionRate => "m.interruption_rate",
+            Metric::ReadWriteRatio => "m.read_write_ratio",
+        }
+    }
+}
+
+impl GroupBy {
+    fn group_sql(self) -> &'static str {
+        match self {
+            GroupBy::Provider => "s.provider",
+            GroupBy::Project => "COALESCE(p.name, s.project_name)",
+            GroupBy::Model => "COALESCE(s.model, 'unknown')",
+            GroupBy::Branch => "COALESCE(s.git_branch, 'unknown')",
+        }
+    }
+}
+
+/// Which sessions to aggregate and how
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsQuery {
+    pub metric: Metric,
+    #[serde(default)]
+    pub group_by: Option<GroupBy>,
+    /// Inclusive lower bound on session start (ms since epoch)
+    #[serde(default)]
+    pub start: Option<i64>,
+    /// Exclusive upper bound on session start (ms since epoch)
+    #[serde(default)]
+    pub end: Option<i64>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+}
+
+/// One bucket of a series, for one group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSeriesPoint {
+    /// Start of the bucket in the requested time zone (ms since epoch)
+    pub bucket_start: i64,
+    pub group: Option<String>,
+    /// Sessions with a value for the metric
+    pub count: i64,
+    pub sum: f64,
+    pub avg: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSeries {
+    pub metric: Metric,
+    pub bucket: Bucket,
+    pub group_by: Option<GroupBy>,
+    pub points: Vec<TimeSeriesPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PercentileStats {
+    pub metric: Metric,
+    pub group: Option<String>,
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+#[derive(Clone)]
+enum Cached {
+    Series(TimeSeries),
+    Percentiles(Vec<PercentileStats>),
+}
+
+lazy_static! {
+    static ref CACHE: Mutex<HashMap<String, (i64, Cached)>> = Mutex::new(HashMap::new());
+}
+
+/// Drop every cached result
+pub fn clear_analytics_cache() {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.clear();
+    }
+}
+
+/// Changes whenever sessions, metrics or projects are added, updated or removed
+fn data_fingerprint(conn: &Connection) -> Result<i64, rusqlite::Error> {
+    conn.query_row(
+        "SELECT revision FROM analytics_state WHERE id = 1",
+        [],
+        |row| row.get(0),
+    )
+}
+
+fn cached<F>(key: String, conn: &Connection, compute: F) -> Result<Cached, rusqlite::Error>
+where
+    F: FnOnce(&Connection) -> Result<Cached, rusqlite::Error>,
+{
+    let fingerprint = data_fingerprint(conn)?;
+    if let Ok(cache) = CACHE.lock() {
+        if let Some((stored, value)) = cache.get(&key) {
+            if *stored == fingerprint {
+                return Ok(value.clone());
+            }
+        }
+    }
+
+    let value = compute(conn)?;
+    if let Ok(mut cache) = CACHE.lock() {
+        if cache.len() >= MAX_CACHE_ENTRIES {
+            cache.clear();
+        }
+        cache.insert(key, (fingerprint, value.clone()));
+    }
+    Ok(value)
+}
+
+/// Fill in `agent_sessions.model` for sessions stored before the column existed
+///
+/// Runs once per database, so sessions whose files name no model are not
+/// re-read on every start. Files are read without holding the database lock.
+pub fn backfill_session_models() -> Result<usize, String> {
+    let pending = with_connection_mut(|conn| sessions_missing_model(conn))
+        .map_err(|e| format!("Failed to list sessions without a model: {}", e))?;
+    let Some(pending) = pending else {
+        return Ok(0);
+    };
+
+    let models = models_from_files(pending);
+    with_connection_mut(|conn| store_backfilled_models(conn, &models))
+        .map_err(|e| format!("Failed to store session models: {}", e))?;
+    Ok(models.len())
+}
+
+pub fn spawn_backfill_session_models() {
+    std::thread::spawn(|| match backfill_session_models() {
+        Ok(0) => {}
+        Ok(count) => {
+            let _ = log_info(
+                "analytics",
+                &format!("✓ Backfilled the model of {} sessions", count),
+            );
+        }
+        Err(e) => {
+            let _ = log_warn("analytics", &format!("⚠ Model backfill failed: {}", e));
+        }
+    });
+}
+
+/// Sessions without a model, or None once the backfill has run
+fn sessions_missing_model(
+    conn: &Connection,
+) -> Result<Option<Vec<(String, String)>>, rusqlite::Error> {
+    let done: bool = conn.query_row(
+        "SELECT models_backfilled FROM analytics_state WHERE id = 1",
+        [],
+        |row| row.get(0),
+    )?;
+    if done {
+        return Ok(None);
+    }
+
+    let mut stmt =
+        conn.prepare("SELECT session_id, file_path FROM agent_sessions WHERE model IS NULL")?;
+    let sessions = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(sessions))
+}
+
+/// The model each session's canonical file mostly used, where it names one
+fn models_from_files(sessions: Vec<(String, String)>) -> Vec<(String, String)> {
+    sessions
+        .into_iter()
+        .filter_map(|(session_id, file_path)| {
+            let content = read_session_file(Path::new(&file_path)).ok()?;
+            extract_model_from_canonical_content(&content).map(|model| (session_id, model))
+        })
+        .collect()
+}
+
+fn store_backfilled_models(
+    conn: &mut Connection,
+    models: &[(String, String)],
+) -> Result<(), rusqlite::Error> {
+    let tx = conn.transaction()?;
+    for (session_id, model) in models {
+        // A watcher may have set it while the files were read
+        tx.execute(
+            "UPDATE agent_sessions SET model = ?2 WHERE session_id = ?1 AND model IS NULL",
+            rusqlite::params![session_id, model],
+        )?;
+    }
+    tx.execute(
+        "UPDATE analytics_state SET models_backfilled = 1 WHERE id = 1",
+        [],
+    )?;
+    tx.commit()
+}
+
+/// SQL expression mapping `s.session_start_time` to the start of its bucket
+fn bucket_sql(bucket: Bucket, utc_offset_ms: i64) -> String {
+    match bucket {
+        Bucket::Day => format!(
+            "((s.session_start_time + {o}) / {d}) * {d} - {o}",
+            o = utc_offset_ms,
+            d = MS_PER_DAY
+        ),
+        Bucket::Week => format!(
+            "((s.session_start_time + {o} + {shift}) / {w}) * {w} - {shift} - {o}",
+            o = utc_offset_ms,
+            shift = WEEK_START_SHIFT_MS,
+            w = MS_PER_WEEK
+        ),
+    }
+}
+
+/// `FROM ... WHERE ...` shared by every query, with its parameters
+fn from_clause(query: &AnalyticsQuery) -> (String, Vec<SqlValue>) {
+    let mut sql = String::from(
+        "FROM agent_sessions s
+         LEFT JOIN session_metrics m ON m.session_id = s.session_id
+         LEFT JOIN projects p ON p.id = s.project_id
+         WHERE s.session_start_time IS NOT NULL",
+    );
+    let mut params = Vec::new();
+
+    if let Some(start) = query.start {
+        sql.push_str(" AND s.session_start_time >= ?");
+        params.push(SqlValue::Integer(start));
+    }
+    if let Some(end) = query.end {
+        sql.push_str(" AND s.session_start_time < ?");
+        params.push(SqlValue::Integer(end));
+    }
+    if let Some(provider) = &query.provider {
+        sql.push_str(" AND s.provider = ?");
+        params.push(SqlValue::Text(provider.clone()));
+    }
+    if let Some(project_id) = &query.project_id {
+        sql.push_str(" AND s.project_id = ?");
+        params.push(SqlValue::Text(project_id.clone()));
+    }
+
+    (sql, params)
+}
+
+fn group_select(group_by: Option<GroupBy>) -> &'static str {
+    group_by.map(GroupBy::group_sql).unwrap_or("NULL")
+}
+
+fn query_time_series(
+    conn: &Connection,
+    query: &AnalyticsQuery,
+    bucket: Bucket,
+    utc_offset_ms: i64,
+) -> Result<TimeSeries, rusqlite::Error> {
+    let (from, params) = from_clause(query);
+    let sql = format!(
+        "SELECT {bucket} AS bucket_start, {group} AS grp,
+                COUNT({value}), COALESCE(SUM({value}), 0), COALESCE(AVG({value}), 0)
+         {from}
+         GROUP BY bucket_start, grp
+         ORDER BY bucket_start, grp",
+        bucket = bucket_sql(bucket, utc_offset_ms),
+        group = group_select(query.group_by),
+        value = query.metric.value_sql(),
+        from = from,
+    );
+
+    let points = conn
+        .prepare(&sql)?
+        .query_map(params_from_iter(params), |row| {
+            Ok(TimeSeriesPoint {
+                bucket_start: row.get(0)?,
+                group: row.get(1)?,
+                count: row.get(2)?,
+                sum: row.get(3)?,
+                avg: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(TimeSeries {
+        metric: query.metric,
+        bucket,
+        group_by: query.group_by,
+        points,
+    })
+}
+
+/// Linear-interpolated percentile of sorted values (`p` in 0..=100)
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0],
+        len => {
+            let rank = (p / 100.0).clamp(0.0, 1.0) * (len - 1) as f64;
+            let lower = rank.floor() as usize;
+            let upper = rank.ceil() as usize;
+            sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+        }
+    }
+}
+
+fn summarize(metric: Metric, group: Option<String>, mut values: Vec<f64>) -> PercentileStats {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let count = values.len();
+    let mean = if count > 0 {
+        values.iter().sum::<f64>() / count as f64
+    } else {
+        0.0
+    };
+
+    PercentileStats {
+        metric,
+        group,
+        count,
+        min: values.first().copied().unwrap_or(0.0),
+        max: values.last().copied().unwrap_or(0.0),
+        mean,
+        p50: percentile(&values, 50.0),
+        p75: percentile(&values, 75.0),
+        p90: percentile(&values, 90.0),
+        p95: percentile(&values, 95.0),
+        p99: percentile(&values, 99.0),
+    }
+}
+
+fn query_percentiles(
+    conn: &Connection,
+    query: &AnalyticsQuery,
+) -> Result<Vec<PercentileStats>, rusqlite::Error> {
+    let (from, params) = from_clause(query);
+    let sql = format!(
+        "SELECT {group} AS grp, {value} AS value {from} AND {value} IS NOT NULL ORDER BY grp",
+        group = group_select(query.group_by),
+        value = query.metric.value_sql(),
+        from = from,
+    );
+
+    let mut groups: Vec<(Option<String>, Vec<f64>)> = Vec::new();
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query(params_from_iter(params))?;
+    while let Some(row) = rows.next()? {
+        let group: Option<String> = row.get(0)?;
+        let value: f64 = row.get(1)?;
+        match groups.last_mut() {
+            Some((last, values)) if *last == group => values.push(value),
+            _ => groups.push((group, vec![value])),
+        }
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|(group, values)| summarize(query.metric, group, values))
+        .collect())
+}
+
+fn local_utc_offset_ms() -> i64 {
+    i64::from(chrono::Local::now().offset().local_minus_utc()) * 1000
+}
+
+/// Bucketed time series; `utc_offset_minutes` defaults to the local time zone
+pub fn get_time_series(
+    query: &AnalyticsQuery,
+    bucket: Bucket,
+    utc_offset_minutes: Option<i32>,
+) -> Result<TimeSeries, String> {
+    let offset_ms = utc_offset_minutes
+        .map(|m| i64::from(m) * 60 * 1000)
+        .unwrap_or_else(local_utc_offset_ms);
+    let key = format!("series:{:?}:{:?}:{}", query, bucket, offset_ms);
+
+    let result = with_connection_mut(|conn| {
+        cached(key, conn, |conn| {
+            query_time_series(conn, query, bucket, offset_ms).map(Cached::Series)
+        })
+    })
+    .map_err(|e| format!("Failed to compute time series: {}", e))?;
+
+    match result {
+        Cached::Series(series) => Ok(series),
+        Cached::Percentiles(_) => Err("Analytics cache returned the wrong result type".to_string()),
+    }
+}
+
+/// Percentile statistics per group
+pub fn get_percentiles(query: &AnalyticsQuery) -> Result<Vec<PercentileStats>, String> {
+    let key = format!("percentiles:{:?}", query);
+
+    let result = with_connection_mut(|conn| {
+        cached(key, conn, |conn| {
+            query_percentiles(conn, query).map(Cached::Percentiles)
+        })
+    })
+    .map_err(|e| format!("Failed to compute percentiles: {}", e))?;
+
+    match result {
+        Cached::Percentiles(stats) => Ok(stats),
+        Cached::Series(_) => Err("Analytics cache returned the wrong result type".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::params;
+
+    const MONDAY: i64 = 1_772_409_600_000; // 2026-03-02T00:00:00Z
+
+    fn migrated() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in crate::schema::MIGRATIONS {
+            conn.execute_batch(migration.sql).unwrap();
+        }
+        conn
+    }
+
+    fn add_session(
+        conn: &Connection,
+        session_id: &str,
+        provider: &str,
+        model: &str,
+        start: i64,
+        input_tokens: i64,
+        error_count: Option<i64>,
+    ) {
+        conn.execute(
+            "INSERT INTO agent_sessions (id, provider, project_name, session_id, file_name, file_path, file_size, session_start_time, duration_ms, model, created_at, uploaded_at)
+             VALUES (?1, ?2, 'app', ?1, 'f', 'f', 1, ?3, 60000, ?4, 1, 1)",
+            params![session_id, provider, start, model],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO session_metrics (id, session_id, provider, timestamp, total_input_tokens, total_output_tokens, error_count, created_at)
+             VALUES (?1, ?1, ?2, 1, ?3, 10, ?4, 1)",
+            params![session_id, provider, input_tokens, error_count],
+        )
+        .unwrap();
+    }
+
+    fn query(metric: Metric, group_by: Option<GroupBy>) -> AnalyticsQuery {
+        AnalyticsQuery {
+            metric,
+            group_by,
+            start: None,
+            end: None,
+            provider: None,
+            project_id: None,
+        }
+    }
+
+    #[test]
+    fn test_time_series_buckets_and_groups() {
+        let conn = migrated();
+        let hour = 60 * 60 * 1000;
+        add_session(
+            &conn,
+            "a",
+            "claude-code",
+            "sonnet",
+            MONDAY + hour,
+            100,
+            Some(1),
+        );
+        add_session(
+            &conn,
+            "b",
+            "claude-code",
+            "opus",
+            MONDAY + 5 * hour,
+            300,
+            None,
+        );
+        add_session(
+            &conn,
+            "c",
+            "codex",
+            "gpt-5",
+            MONDAY + MS_PER_DAY + hour,
+            50,
+            Some(3),
+        );
+        add_session(
+            &conn,
+            "d",
+            "codex",
+            "gpt-5",
+            MONDAY + 8 * MS_PER_DAY,
+            70,
+            Some(0),
+        );
+
+        let daily =
+            query_time_series(&conn, &query(Metric::Sessions, None), Bucket::Day, 0).unwrap();
+        let counts: Vec<(i64, i64)> = daily
+            .points
+            .iter()
+            .map(|p| (p.bucket_start, p.count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                (MONDAY, 2),
+                (MONDAY + MS_PER_DAY, 1),
+                (MONDAY + 8 * MS_PER_DAY, 1)
+            ]
+        );
+
+        // Weeks start on Monday
+        let weekly = query_time_series(
+            &conn,
+            &query(Metric::InputTokens, Some(GroupBy::Provider)),
+            Bucket::Week,
+            0,
+        )
+        .unwrap();
+        let points: Vec<(i64, Option<&str>, f64)> = weekly
+            .points
+            .iter()
+            .map(|p| (p.bucket_start, p.group.as_deref(), p.sum))
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                (MONDAY, Some("claude-code"), 400.0),
+                (MONDAY, Some("codex"), 50.0),
+                (MONDAY + MS_PER_WEEK, Some("codex"), 70.0),
+            ]
+        );
+
+        // Sessions without a value don't count towards the average
+        let errors =
+            query_time_series(&conn, &query(Metric::ErrorCount, None), Bucket::Day, 0).unwrap();
+        assert_eq!(errors.points[0].count, 1);
+        assert_eq!(errors.points[0].avg, 1.0);
+
+        // A UTC-5 day starts five hours later, so session "a" falls on the previous day
+        let shifted = query_time_series(
+            &conn,
+            &query(Metric::Sessions, None),
+            Bucket::Day,
+            -5 * hour,
+        )
+        .unwrap();
+        assert_eq!(
+            shifted.points[0].bucket_start,
+            MONDAY - MS_PER_DAY + 5 * hour
+        );
+        assert_eq!(shifted.points[0].count, 1);
+
+        let mut filtered = query(Metric::Sessions, Some(GroupBy::Model));
+        filtered.provider = Some("claude-code".to_string());
+        filtered.end = Some(MONDAY + 2 * hour);
+        let series = query_time_series(&conn, &filtered, Bucket::Week, 0).unwrap();
+        assert_eq!(series.points.len(), 1);
+        assert_eq!(series.points[0].group.as_deref(), Some("sonnet"));
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_in_place_updates() {
+        let conn = migrated();
+        add_session(&conn, "a", "claude-code", "opus", MONDAY, 100, None);
+
+        let before = data_fingerprint(&conn).unwrap();
+        conn.execute(
+            "UPDATE session_metrics SET total_input_tokens = 5 WHERE session_id = 'a'",
+            [],
+        )
+        .unwrap();
+        assert_ne!(data_fingerprint(&conn).unwrap(), before);
+    }
+
+    #[test]
+    fn test_model_backfill_runs_once() {
+        let mut conn = migrated();
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("old.jsonl");
+        std::fs::write(
+            &file,
+            r#"{"message":{"role":"assistant","model":"claude-sonnet"}}"#,
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO agent_sessions (id, provider, project_name, session_id, file_name, file_path, file_size, created_at, uploaded_at)
+             VALUES ('old', 'claude-code', 'app', 'old', 'old.jsonl', ?1, 1, 1, 1)",
+            params![file.to_string_lossy()],
+        )
+        .unwrap();
+
+        let pending = sessions_missing_model(&conn).unwrap().unwrap();
+        let models = models_from_files(pending);
+        assert_eq!(
+            models,
+            vec![("old".to_string(), "claude-sonnet".to_string())]
+        );
+        store_backfilled_models(&mut conn, &models).unwrap();
+
+        let model: String = conn
+            .query_row(
+                "SELECT model FROM agent_sessions WHERE session_id = 'old'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(model, "claude-sonnet");
+        assert!(sessions_missing_model(&conn).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_percentiles() {
+        assert_eq!(percentile(&[], 50.0), 0.0);
+        assert_eq!(percentile(&[4.0], 90.0), 4.0);
+        assert_eq!(percentile(&[1.0, 2.0, 3.0, 4.0], 50.0), 2.5);
+        assert_eq!(percentile(&[1.0, 2.0, 3.0, 4.0, 5.0], 100.0), 5.0);
+
+        let conn = migrated();
+        for (i, tokens) in [10, 20, 30, 40, 50].iter().enumerate() {
+            add_session(
+                &conn,
+                &format!("s{}", i),
+                "codex",
+                "gpt-5",
+                MONDAY,
+                *tokens,
+                None,
+            );
+        }
+        add_session(&conn, "other", "claude-code", "opus", MONDAY, 1000, None);
+
+        let stats =
+            query_percentiles(&conn, &query(Metric::InputTokens, Some(GroupBy::Provider))).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].group.as_deref(), Some("claude-code"));
+        assert_eq!(stats[0].p99, 1000.0);
+        let codex = &stats[1];
+        assert_eq!(codex.count, 5);
+        assert_eq!((codex.min, codex.max, codex.mean), (10.0, 50.0, 30.0));
+        assert_eq!(codex.p50, 30.0);
+        assert_eq!(codex.p90, 46.0);
+    }
+}
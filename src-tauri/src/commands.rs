@@ -5,18 +5,22 @@ use crate::config::{
     ProviderConfig,
 };
 use crate::logging::{read_provider_logs, LogEntry};
+use crate::providers::common::{WatchTask, WatcherHeartbeat, WATCHER_SHUTDOWN_TIMEOUT};
 use crate::providers::{
-    ClaudeWatcher, ClaudeWatcherStatus, CodexWatcher, CodexWatcherStatus,
-    CopilotWatcher, CopilotWatcherStatus, CursorWatcher, CursorWatcherStatus, GeminiWatcher,
+    ClaudeWatcher, ClaudeWatcherStatus, CodexWatcher, CodexWatcherStatus, CopilotWatcher,
+    CopilotWatcherStatus, CursorWatcher, CursorWatcherStatus, CustomJsonlWatcher, GeminiWatcher,
     GeminiWatcherStatus, OpenCodeWatcher, OpenCodeWatcherStatus, SessionInfo,
 };
+use crate::shutdown::ShutdownCoordinator;
 use crate::upload_queue::{QueueItems, UploadQueue, UploadStatus};
+use crate::watcher_supervisor::{WatcherHealthReport, WatcherSupervisor};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::State;
+use tokio_util::sync::CancellationToken;
 
 #[tauri::command]
 pub async fn load_config_command() -> Result<GuideAIConfig, String> {
@@ -25,6 +29,7 @@ pub async fn load_config_command() -> Result<GuideAIConfig, String> {
 
 #[tauri::command]
 pub async fn save_config_command(config: GuideAIConfig) -> Result<(), String> {
+    crate::config_service::validate_global_config(&config)?;
     save_config(&config).map_err(|e| e.to_string())
 }
 
@@ -198,6 +203,7 @@ pub async fn save_provider_config_command(
     provider_id: String,
     config: ProviderConfig,
 ) -> Result<(), String> {
+    crate::config_service::validate_provider_config(&provider_id, &config)?;
     save_provider_config(&provider_id, &config).map_err(|e| e.to_string())
 }
 
@@ -327,6 +333,7 @@ pub enum Watcher {
     Codex(CodexWatcher),
     Gemini(GeminiWatcher),
     Cursor(CursorWatcher),
+    CustomJsonl(CustomJsonlWatcher),
 }
 
 impl Watcher {
@@ -338,18 +345,96 @@ impl Watcher {
             Watcher::Codex(watcher) => watcher.stop(),
             Watcher::Gemini(watcher) => watcher.stop(),
             Watcher::Cursor(watcher) => { let _ = watcher.stop(); },
+            Watcher::CustomJsonl(watcher) => watcher.stop(),
+        }
+    }
+
+    pub fn task(&self) -> &WatchTask {
+        match self {
+            Watcher::Claude(watcher) => watcher.task(),
+            Watcher::Copilot(watcher) => watcher.task(),
+            Watcher::OpenCode(watcher) => watcher.task(),
+            Watcher::Codex(watcher) => watcher.task(),
+            Watcher::Gemini(watcher) => watcher.task(),
+            Watcher::Cursor(watcher) => watcher.task(),
+            Watcher::CustomJsonl(watcher) => watcher.task(),
+        }
+    }
+
+    pub fn heartbeat(&self) -> &WatcherHeartbeat {
+        match self {
+            Watcher::Claude(watcher) => watcher.heartbeat(),
+            Watcher::Copilot(watcher) => watcher.heartbeat(),
+            Watcher::OpenCode(watcher) => watcher.heartbeat(),
+            Watcher::Codex(watcher) => watcher.heartbeat(),
+            Watcher::Gemini(watcher) => watcher.heartbeat(),
+            Watcher::Cursor(watcher) => watcher.heartbeat(),
+            Watcher::CustomJsonl(watcher) => watcher.heartbeat(),
         }
     }
 }
 
+/// Build a provider's watcher from its saved config (used by the supervisor to restart it)
+pub fn create_watcher(
+    provider_id: &str,
+    upload_queue: &Arc<UploadQueue>,
+    event_bus: &crate::events::EventBus,
+    shutdown: &CancellationToken,
+) -> Result<Watcher, String> {
+    let upload_queue = Arc::clone(upload_queue);
+    let event_bus = event_bus.clone();
+    let shutdown = shutdown.clone();
+
+    if crate::providers::custom_jsonl::is_custom_provider(provider_id) {
+        return CustomJsonlWatcher::new(provider_id.to_string(), upload_queue, event_bus, shutdown)
+            .map(Watcher::CustomJsonl)
+            .map_err(|e| e.to_string());
+    }
+
+    let projects = load_provider_config(provider_id)
+        .map_err(|e| format!("Failed to load provider config: {}", e))?
+        .selected_projects;
+
+    match provider_id {
+        "claude-code" => ClaudeWatcher::new(upload_queue, event_bus, shutdown).map(Watcher::Claude),
+        "github-copilot" => {
+            CopilotWatcher::new(projects, upload_queue, event_bus, shutdown).map(Watcher::Copilot)
+        }
+        "opencode" => {
+            OpenCodeWatcher::new(projects, upload_queue, event_bus, shutdown).map(Watcher::OpenCode)
+        }
+        "codex" => {
+            CodexWatcher::new(projects, upload_queue, event_bus, shutdown).map(Watcher::Codex)
+        }
+        "gemini-code" => {
+            GeminiWatcher::new(projects, upload_queue, event_bus, shutdown).map(Watcher::Gemini)
+        }
+        "cursor" => {
+            CursorWatcher::new(projects, upload_queue, event_bus, shutdown).map(Watcher::Cursor)
+        }
+        _ => return Err(format!("Unknown provider: {}", provider_id)),
+    }
+    .map_err(|e| e.to_string())
+}
+
 pub struct AppState {
     pub watchers: Arc<Mutex<HashMap<String, Watcher>>>,
     pub upload_queue: Arc<UploadQueue>,
     pub event_bus: crate::events::EventBus,
+    pub shutdown: ShutdownCoordinator,
+    /// Parent of every watcher task's token; cancelled before `shutdown` fires
+    pub watcher_shutdown: CancellationToken,
+    pub supervisor: Arc<WatcherSupervisor>,
+    /// Database event handler task, awaited on shutdown so its last writes land
+    db_handler: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
 }
 
 impl AppState {
-    pub fn new(event_bus: crate::events::EventBus) -> Self {
+    pub fn new(
+        event_bus: crate::events::EventBus,
+        shutdown: ShutdownCoordinator,
+        db_handler: tauri::async_runtime::JoinHandle<()>,
+    ) -> Self {
         let upload_queue = Arc::new(UploadQueue::new());
 
         // Start the upload queue processor
@@ -361,16 +446,56 @@ impl AppState {
             watchers: Arc::new(Mutex::new(HashMap::new())),
             upload_queue,
             event_bus,
+            shutdown,
+            watcher_shutdown: CancellationToken::new(),
+            supervisor: Arc::new(WatcherSupervisor::new()),
+            db_handler: Mutex::new(Some(db_handler)),
+        }
+    }
+
+    /// Stop every watcher, wait for it to flush its pending events, then
+    /// signal the event handlers so they still see what the watchers flushed,
+    /// and wait for the database handler to write it
+    pub async fn shutdown_watchers(&self) {
+        self.watcher_shutdown.cancel();
+
+        let watchers: Vec<(String, Watcher)> = match self.watchers.lock() {
+            Ok(mut watchers) => watchers.drain().collect(),
+            Err(_) => Vec::new(),
+        };
+
+        for (provider_id, watcher) in watchers {
+            if let Err(e) = watcher.task().join(WATCHER_SHUTDOWN_TIMEOUT).await {
+                let _ = crate::logging::log_warn(&provider_id, &e);
+            }
+        }
+
+        self.shutdown.shutdown();
+
+        let db_handler = match self.db_handler.lock() {
+            Ok(mut handle) => handle.take(),
+            Err(_) => None,
+        };
+        if let Some(handle) = db_handler {
+            if tokio::time::timeout(WATCHER_SHUTDOWN_TIMEOUT, handle)
+                .await
+                .is_err()
+            {
+                let _ = crate::logging::log_warn(
+                    "system",
+                    &format!(
+                        "Database event handler did not stop within {}s",
+                        WATCHER_SHUTDOWN_TIMEOUT.as_secs()
+                    ),
+                );
+            }
         }
     }
 }
 
 // Claude watcher commands
 #[tauri::command]
-pub async fn start_claude_watcher(
-    state: State<'_, AppState>,
-    projects: Vec<String>,
-) -> Result<(), String> {
+pub async fn start_claude_watcher(state: State<'_, AppState>) -> Result<(), String> {
     // Load provider config to check home directory
     let provider_config = load_provider_config("claude-code")
         .map_err(|e| format!("Failed to load provider config: {}", e))?;
@@ -391,9 +516,9 @@ pub async fn start_claude_watcher(
 
     // Create new watcher
     let watcher = ClaudeWatcher::new(
-        projects,
         Arc::clone(&state.upload_queue),
         state.event_bus.clone(),
+        state.watcher_shutdown.clone(),
     )
     .map_err(|e| format!("Failed to create Claude watcher: {}", e))?;
 
@@ -428,6 +553,8 @@ pub async fn get_claude_watcher_status(
                 pending_uploads: 0,
                 processing_uploads: 0,
                 failed_uploads: 0,
+                watched_paths: Vec::new(),
+                unsupported_formats: Vec::new(),
             })
         }
     } else {
@@ -464,6 +591,7 @@ pub async fn start_opencode_watcher(
         projects,
         Arc::clone(&state.upload_queue),
         state.event_bus.clone(),
+        state.watcher_shutdown.clone(),
     )
     .map_err(|e| format!("Failed to create OpenCode watcher: {}", e))?;
 
@@ -498,6 +626,8 @@ pub async fn get_opencode_watcher_status(
                 pending_uploads: 0,
                 processing_uploads: 0,
                 failed_uploads: 0,
+                watched_paths: Vec::new(),
+                unsupported_formats: Vec::new(),
             })
         }
     } else {
@@ -534,6 +664,7 @@ pub async fn start_codex_watcher(
         projects,
         Arc::clone(&state.upload_queue),
         state.event_bus.clone(),
+        state.watcher_shutdown.clone(),
     )
     .map_err(|e| format!("Failed to create Codex watcher: {}", e))?;
 
@@ -568,6 +699,8 @@ pub async fn get_codex_watcher_status(
                 pending_uploads: 0,
                 processing_uploads: 0,
                 failed_uploads: 0,
+                watched_paths: Vec::new(),
+                unsupported_formats: Vec::new(),
             })
         }
     } else {
@@ -593,7 +726,12 @@ pub async fn start_cursor_watcher(
     let upload_queue = Arc::clone(&state.upload_queue);
     let event_bus = state.event_bus.clone();
 
-    match CursorWatcher::new(projects, upload_queue, event_bus) {
+    match CursorWatcher::new(
+        projects,
+        upload_queue,
+        event_bus,
+        state.watcher_shutdown.clone(),
+    ) {
         Ok(watcher) => {
             if let Ok(mut watchers) = state.watchers.lock() {
                 watchers.insert("cursor".to_string(), Watcher::Cursor(watcher));
@@ -629,6 +767,92 @@ pub async fn get_cursor_watcher_status(
     }
 }
 
+/// Edited or regenerated turns of a Cursor session, which its canonical file omits
+#[tauri::command]
+pub async fn get_cursor_session_branches(
+    session_id: String,
+) -> Result<crate::providers::cursor::SessionBranches, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::providers::cursor::get_session_branches(&session_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Custom JSONL watcher commands (provider IDs starting with "custom-")
+#[tauri::command]
+pub async fn start_custom_jsonl_watcher(
+    state: State<'_, AppState>,
+    provider_id: String,
+) -> Result<(), String> {
+    // Validates the customJsonl mapping before anything is started
+    let (provider_config, _) = crate::providers::custom_jsonl::load_custom_config(&provider_id)?;
+
+    // Check if home directory exists before starting watcher
+    let expanded_path = shellexpand::tilde(&provider_config.home_directory).to_string();
+    if !std::path::Path::new(&expanded_path).exists() {
+        return Err(format!(
+            "Cannot start watcher: directory '{}' does not exist. Please configure a valid directory.",
+            provider_config.home_directory
+        ));
+    }
+
+    // Update upload queue with current config
+    if let Ok(config) = load_config() {
+        state.upload_queue.set_config(config);
+    }
+
+    let watcher = CustomJsonlWatcher::new(
+        provider_id.clone(),
+        Arc::clone(&state.upload_queue),
+        state.event_bus.clone(),
+        state.watcher_shutdown.clone(),
+    )
+    .map_err(|e| format!("Failed to create custom JSONL watcher: {}", e))?;
+
+    if let Ok(mut watchers) = state.watchers.lock() {
+        watchers.insert(provider_id, Watcher::CustomJsonl(watcher));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_custom_jsonl_watcher(
+    state: State<'_, AppState>,
+    provider_id: String,
+) -> Result<(), String> {
+    if let Ok(mut watchers) = state.watchers.lock() {
+        if let Some(watcher) = watchers.remove(&provider_id) {
+            watcher.stop();
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_custom_jsonl_watcher_status(
+    state: State<'_, AppState>,
+    provider_id: String,
+) -> Result<crate::providers::common::WatcherStatus, String> {
+    if let Ok(watchers) = state.watchers.lock() {
+        if let Some(Watcher::CustomJsonl(watcher)) = watchers.get(&provider_id) {
+            Ok(watcher.get_status())
+        } else {
+            Ok(crate::providers::common::WatcherStatus {
+                is_running: false,
+                pending_uploads: 0,
+                processing_uploads: 0,
+                failed_uploads: 0,
+                watched_paths: Vec::new(),
+                unsupported_formats: Vec::new(),
+            })
+        }
+    } else {
+        Err("Failed to access watcher state".to_string())
+    }
+}
+
 // Copilot watcher commands
 #[tauri::command]
 pub async fn start_copilot_watcher(
@@ -658,6 +882,7 @@ pub async fn start_copilot_watcher(
         projects,
         Arc::clone(&state.upload_queue),
         state.event_bus.clone(),
+        state.watcher_shutdown.clone(),
     )
     .map_err(|e| format!("Failed to create Copilot watcher: {}", e))?;
 
@@ -692,6 +917,8 @@ pub async fn get_copilot_watcher_status(
                 pending_uploads: 0,
                 processing_uploads: 0,
                 failed_uploads: 0,
+                watched_paths: Vec::new(),
+                unsupported_formats: Vec::new(),
             })
         }
     } else {
@@ -728,6 +955,7 @@ pub async fn start_gemini_watcher(
         projects,
         Arc::clone(&state.upload_queue),
         state.event_bus.clone(),
+        state.watcher_shutdown.clone(),
     )
     .map_err(|e| format!("Failed to create Gemini watcher: {}", e))?;
 
@@ -762,6 +990,8 @@ pub async fn get_gemini_watcher_status(
                 pending_uploads: 0,
                 processing_uploads: 0,
                 failed_uploads: 0,
+                watched_paths: Vec::new(),
+                unsupported_formats: Vec::new(),
             })
         }
     } else {
@@ -769,6 +999,36 @@ pub async fn get_gemini_watcher_status(
     }
 }
 
+/// Map Gemini project hashes to directories by hashing candidate paths
+///
+/// Saves newly resolved entries and returns conflicting registry entries.
+#[tauri::command]
+pub async fn resolve_gemini_projects(
+) -> Result<crate::providers::gemini::resolver::ResolveReport, String> {
+    use crate::providers::gemini::registry::GeminiProjectRegistry;
+    use crate::providers::gemini::resolver::{clear_unresolved, project_roots, resolve_registry};
+
+    let config = load_provider_config("gemini-code").map_err(|e| e.to_string())?;
+    let tmp_path = std::path::PathBuf::from(shellexpand::tilde(&config.home_directory).to_string())
+        .join("tmp");
+    if !tmp_path.exists() {
+        return Ok(Default::default());
+    }
+
+    // An explicit request retries hashes that recently failed to resolve
+    clear_unresolved();
+    let mut registry = GeminiProjectRegistry::load()
+        .map_err(|e| format!("Failed to load project registry: {}", e))?;
+    let report = resolve_registry(&tmp_path, &mut registry, &project_roots())?;
+    if !report.resolved.is_empty() {
+        registry
+            .save()
+            .map_err(|e| format!("Failed to save project registry: {}", e))?;
+    }
+
+    Ok(report)
+}
+
 #[tauri::command]
 pub async fn get_upload_queue_status(state: State<'_, AppState>) -> Result<UploadStatus, String> {
     Ok(state.upload_queue.get_status())
@@ -989,6 +1249,13 @@ pub async fn scan_historical_sessions(
         }),
     );
 
+    // Sessions removed by retention stay removed; drop the canonical copies
+    // the scan just regenerated for them
+    let deleted = crate::database::get_deleted_session_ids(&provider_id).unwrap_or_default();
+    let sessions_root = crate::config::get_config_dir()
+        .map(|dir| dir.join("sessions"))
+        .ok();
+
     // Insert all sessions into the database (just like file watcher does)
     // The upload queue poller will handle uploading them
     let mut inserted_count = 0;
@@ -1006,6 +1273,16 @@ pub async fn scan_historical_sessions(
                 }),
             );
         }
+        if deleted.contains(&session.session_id) {
+            let path = std::path::Path::new(&session.file_path);
+            if sessions_root
+                .as_ref()
+                .is_some_and(|root| path.starts_with(root))
+            {
+                let _ = std::fs::remove_file(path);
+            }
+            continue;
+        }
         match crate::providers::common::db_helpers::insert_session_immediately(
             &provider_id,
             &session.project_name,
@@ -1283,12 +1560,7 @@ pub async fn clear_all_sessions() -> Result<String, String> {
         .and_then(|v| v.as_i64())
         .unwrap_or(0);
 
-    // Clear both tables
-    crate::database::execute_sql_query("DELETE FROM session_metrics", vec![])
-        .map_err(|e| e.to_string())?;
-
-    crate::database::execute_sql_query("DELETE FROM agent_sessions", vec![])
-        .map_err(|e| e.to_string())?;
+    crate::database::delete_sessions_where("1 = 1", &[]).map_err(|e| e.to_string())?;
 
     let message = format!(
         "Cleared {} session metrics and {} sessions from database",
@@ -1332,20 +1604,9 @@ pub async fn clear_provider_sessions(provider_id: String) -> Result<String, Stri
         .and_then(|v| v.as_i64())
         .unwrap_or(0);
 
-    // Delete metrics for sessions from this provider
-    crate::database::execute_sql_query(
-        "DELETE FROM session_metrics WHERE session_id IN
-         (SELECT session_id FROM agent_sessions WHERE provider = ?)",
-        vec![serde_json::Value::String(provider_id.clone())],
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Delete sessions from this provider
-    crate::database::execute_sql_query(
-        "DELETE FROM agent_sessions WHERE provider = ?",
-        vec![serde_json::Value::String(provider_id.clone())],
-    )
-    .map_err(|e| e.to_string())?;
+    // Delete sessions from this provider along with their metrics, relations and stats
+    crate::database::delete_sessions_where("provider = ?", &[&provider_id])
+        .map_err(|e| e.to_string())?;
 
     let message = format!(
         "Cleared {} session metrics and {} sessions for provider '{}'",
@@ -1358,6 +1619,114 @@ pub async fn clear_provider_sessions(provider_id: String) -> Result<String, Stri
     Ok(message)
 }
 
+/// Get the local retention policy
+#[tauri::command]
+pub async fn get_retention_policy() -> Result<crate::retention::RetentionPolicy, String> {
+    crate::retention::load_retention_policy()
+}
+
+/// Save the local retention policy
+#[tauri::command]
+pub async fn save_retention_policy(
+    policy: crate::retention::RetentionPolicy,
+) -> Result<(), String> {
+    crate::retention::save_retention_policy(&policy)
+}
+
+/// Apply the retention policy (or report what it would do when `dry_run` is set)
+#[tauri::command]
+pub async fn run_retention(
+    dry_run: bool,
+    remove_orphans: Option<bool>,
+) -> Result<crate::retention::RetentionReport, String> {
+    crate::retention::run_retention(dry_run, remove_orphans.unwrap_or(false))
+}
+
+/// Snapshot the database into the backups directory
+#[tauri::command]
+pub async fn create_database_backup() -> Result<crate::db_backup::BackupInfo, String> {
+    crate::db_backup::create_backup(crate::db_backup::BackupKind::Manual)
+}
+
+/// List database snapshots, newest first
+#[tauri::command]
+pub async fn list_database_backups() -> Result<Vec<crate::db_backup::BackupInfo>, String> {
+    crate::db_backup::list_backups()
+}
+
+/// Replace the database with a snapshot (the current state is backed up first)
+#[tauri::command]
+pub async fn restore_database_backup(
+    path: String,
+) -> Result<crate::db_backup::RestoreResult, String> {
+    let result = crate::db_backup::restore_backup(&path)?;
+    crate::analytics::clear_analytics_cache();
+    Ok(result)
+}
+
+/// Get the automatic backup schedule
+#[tauri::command]
+pub async fn get_backup_settings() -> Result<crate::db_backup::BackupSettings, String> {
+    crate::db_backup::load_backup_settings()
+}
+
+/// Save the automatic backup schedule
+#[tauri::command]
+pub async fn save_backup_settings(
+    settings: crate::db_backup::BackupSettings,
+) -> Result<(), String> {
+    crate::db_backup::save_backup_settings(&settings)
+}
+
+/// Report database file size, free pages and row counts per table
+#[tauri::command]
+pub async fn get_database_size() -> Result<crate::db_backup::DatabaseSizeReport, String> {
+    crate::db_backup::get_database_size()
+}
+
+/// Run VACUUM and ANALYZE on the database
+#[tauri::command]
+pub async fn run_database_maintenance() -> Result<crate::db_backup::MaintenanceReport, String> {
+    crate::db_backup::run_database_maintenance()
+}
+
+/// Export all tables and canonical session files to a portable JSON archive
+#[tauri::command]
+pub async fn export_database_archive(
+    path: String,
+) -> Result<crate::db_archive::ExportSummary, String> {
+    crate::db_archive::export_archive(&path)
+}
+
+/// Merge an exported archive into the local database
+#[tauri::command]
+pub async fn import_database_archive(
+    path: String,
+    policy: Option<crate::db_archive::ConflictPolicy>,
+) -> Result<crate::db_archive::ImportSummary, String> {
+    let summary = crate::db_archive::import_archive(&path, policy.unwrap_or_default())?;
+    crate::analytics::clear_analytics_cache();
+    Ok(summary)
+}
+
+/// Per-day or per-week series of a session metric, optionally grouped
+#[tauri::command]
+pub async fn get_analytics_time_series(
+    query: crate::analytics::AnalyticsQuery,
+    bucket: crate::analytics::Bucket,
+    utc_offset_minutes: Option<i32>,
+) -> Result<crate::analytics::TimeSeries, String> {
+    crate::analytics::get_time_series(&query, bucket, utc_offset_minutes)
+}
+
+/// Percentile statistics of a session metric, optionally grouped
+#[tauri::command]
+pub async fn get_analytics_percentiles(
+    query: crate::analytics::AnalyticsQuery,
+) -> Result<Vec<crate::analytics::PercentileStats>, String> {
+    crate::analytics::get_percentiles(&query)
+}
+
 #[tauri::command]
 pub async fn get_session_content(
     provider: String,
@@ -1371,7 +1740,8 @@ pub async fn get_session_content(
 
     // All providers now use cached JSONL files - read directly
     // OpenCode sessions are aggregated to ~/.guideai/cache/opencode/{session_id}.jsonl
-    let content = std::fs::read_to_string(&path)
+    // Compacted sessions live next to it as .jsonl.gz
+    let content = crate::providers::common::read_session_file(&path)
         .map_err(|e| format!("Failed to read session file for {}: {}", provider, e))?;
 
     debug!(
@@ -1384,6 +1754,43 @@ pub async fn get_session_content(
     Ok(content)
 }
 
+/// Validate a session's canonical JSONL and return a structured report
+#[tauri::command]
+pub async fn validate_session(
+    session_id: String,
+) -> Result<crate::providers::canonical::validator::ValidationReport, String> {
+    use crate::database::get_full_session_by_id;
+    use crate::providers::canonical::validator::validate_canonical_content;
+
+    let session = get_full_session_by_id(&session_id)
+        .map_err(|e| format!("Failed to load session: {}", e))?
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+
+    let content =
+        crate::providers::common::read_session_file(std::path::Path::new(&session.file_path))
+            .map_err(|e| format!("Failed to read session file: {}", e))?;
+
+    Ok(validate_canonical_content(&content))
+}
+
+/// Get the subagent tree of a session (its branches plus child sessions)
+#[tauri::command]
+pub async fn get_session_relations(
+    session_id: String,
+) -> Result<Vec<crate::database::SessionRelation>, String> {
+    crate::database::get_session_relations(&session_id)
+        .map_err(|e| format!("Failed to load session relations: {}", e))
+}
+
+/// Watcher health with recent failures and restarts, optionally for one provider
+#[tauri::command]
+pub async fn get_watcher_health(
+    state: State<'_, AppState>,
+    provider_id: Option<String>,
+) -> Result<WatcherHealthReport, String> {
+    Ok(state.supervisor.report(provider_id.as_deref()))
+}
+
 // Autostart function for watchers
 pub fn start_enabled_watchers(app_state: &AppState) {
     use tracing::{error, info};
@@ -1414,17 +1821,17 @@ pub fn start_enabled_watchers(app_state: &AppState) {
                 match crate::providers::scan_projects("claude-code", &claude_config.home_directory)
                 {
                     Ok(projects) => {
-                        let projects_to_watch = if claude_config.project_selection == "ALL" {
-                            projects.iter().map(|p| p.name.clone()).collect()
+                        let has_projects = if claude_config.project_selection == "ALL" {
+                            !projects.is_empty()
                         } else {
-                            claude_config.selected_projects
+                            !claude_config.selected_projects.is_empty()
                         };
 
-                        if !projects_to_watch.is_empty() {
+                        if has_projects {
                             match ClaudeWatcher::new(
-                                projects_to_watch,
                                 Arc::clone(&app_state.upload_queue),
                                 app_state.event_bus.clone(),
+                                app_state.watcher_shutdown.clone(),
                             ) {
                                 Ok(watcher) => {
                                     if let Ok(mut watchers) = app_state.watchers.lock() {
@@ -1475,6 +1882,7 @@ pub fn start_enabled_watchers(app_state: &AppState) {
                                 projects_to_watch,
                                 Arc::clone(&app_state.upload_queue),
                                 app_state.event_bus.clone(),
+                                app_state.watcher_shutdown.clone(),
                             ) {
                                 Ok(watcher) => {
                                     if let Ok(mut watchers) = app_state.watchers.lock() {
@@ -1516,6 +1924,7 @@ pub fn start_enabled_watchers(app_state: &AppState) {
                             projects_to_watch,
                             Arc::clone(&app_state.upload_queue),
                             app_state.event_bus.clone(),
+                            app_state.watcher_shutdown.clone(),
                         ) {
                             Ok(watcher) => {
                                 if let Ok(mut watchers) = app_state.watchers.lock() {
@@ -1554,6 +1963,7 @@ pub fn start_enabled_watchers(app_state: &AppState) {
                             projects_to_watch,
                             Arc::clone(&app_state.upload_queue),
                             app_state.event_bus.clone(),
+                            app_state.watcher_shutdown.clone(),
                         ) {
                             Ok(watcher) => {
                                 if let Ok(mut watchers) = app_state.watchers.lock() {
@@ -1603,6 +2013,7 @@ pub fn start_enabled_watchers(app_state: &AppState) {
                                 projects_to_watch,
                                 Arc::clone(&app_state.upload_queue),
                                 app_state.event_bus.clone(),
+                                app_state.watcher_shutdown.clone(),
                             ) {
                                 Ok(watcher) => {
                                     if let Ok(mut watchers) = app_state.watchers.lock() {
@@ -1643,6 +2054,7 @@ pub fn start_enabled_watchers(app_state: &AppState) {
                             projects_to_watch,
                             Arc::clone(&app_state.upload_queue),
                             app_state.event_bus.clone(),
+                            app_state.watcher_shutdown.clone(),
                         ) {
                             Ok(watcher) => {
                                 if let Ok(mut watchers) = app_state.watchers.lock() {
@@ -1665,6 +2077,39 @@ pub fn start_enabled_watchers(app_state: &AppState) {
             }
         }
     }
+
+    // Try to start every enabled custom JSONL provider
+    for provider_id in crate::providers::custom_jsonl::list_custom_provider_ids() {
+        let provider_config = match crate::providers::custom_jsonl::load_custom_config(&provider_id)
+        {
+            Ok((provider_config, _)) => provider_config,
+            Err(e) => {
+                error!(provider = %provider_id, error = %e, "Invalid custom provider config");
+                continue;
+            }
+        };
+
+        if !provider_config.enabled {
+            continue;
+        }
+
+        match CustomJsonlWatcher::new(
+            provider_id.clone(),
+            Arc::clone(&app_state.upload_queue),
+            app_state.event_bus.clone(),
+            app_state.watcher_shutdown.clone(),
+        ) {
+            Ok(watcher) => {
+                if let Ok(mut watchers) = app_state.watchers.lock() {
+                    watchers.insert(provider_id.clone(), Watcher::CustomJsonl(watcher));
+                    info!(provider = %provider_id, "Custom JSONL watcher started automatically");
+                }
+            }
+            Err(e) => {
+                error!(provider = %provider_id, error = %e, "Failed to start custom JSONL watcher");
+            }
+        }
+    }
 }
 
 /// Get all projects with session counts
@@ -1687,6 +2132,7 @@ pub async fn get_all_projects() -> Result<Vec<serde_json::Value>, String> {
                 "createdAt": p.created_at,
                 "updatedAt": p.updated_at,
                 "sessionCount": p.session_count,
+                "workspaceKind": p.workspace_kind,
             })
         })
         .collect();
@@ -1712,10 +2158,17 @@ pub async fn get_project_by_id(project_id: String) -> Result<Option<serde_json::
             "createdAt": p.created_at,
             "updatedAt": p.updated_at,
             "sessionCount": p.session_count,
+            "workspaceKind": p.workspace_kind,
         })
     }))
 }
 
+/// Merge duplicate projects (worktrees, subdirectories, other clones) and relink their sessions
+#[tauri::command]
+pub async fn reconcile_projects() -> Result<crate::project_identity::ReconcileSummary, String> {
+    crate::project_identity::reconcile_projects()
+}
+
 /// Open a folder in the OS file manager (Finder on macOS, Explorer on Windows, etc.)
 #[tauri::command]
 pub async fn open_folder_in_os(path: String) -> Result<(), String> {
@@ -1764,16 +2217,50 @@ pub async fn get_session_rating(session_id: String) -> Result<Option<String>, St
     get_session_rating(&session_id).map_err(|e| format!("Failed to get rating: {}", e))
 }
 
-/// Get git diff between two commits for a session with timestamp filtering
+/// Get the git diff for a session
+///
+/// Uses the working-tree snapshots captured while the session was live when
+/// available, and falls back to commit-based reconstruction for older sessions
+/// or when a snapshot tree is gone (imported archive, re-cloned repo, gc).
+/// `options` controls word diffs, byte limits and lockfile/generated-file collapsing.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_session_git_diff(
+    session_id: Option<String>,
     cwd: String,
     first_commit_hash: String,
     latest_commit_hash: String,
     is_active: bool,
     session_start_time: Option<i64>,
     session_end_time: Option<i64>,
+    options: Option<crate::git_diff::DiffRenderOptions>,
 ) -> Result<Vec<crate::git_diff::FileDiff>, String> {
+    let options = options.unwrap_or_default();
+
+    if let Some(ref session_id) = session_id {
+        if let Ok((Some(start_tree), end_tree)) = crate::database::get_session_snapshots(session_id)
+        {
+            match crate::git_diff::get_snapshot_diff(
+                &cwd,
+                &start_tree,
+                end_tree.as_deref(),
+                is_active,
+                &options,
+            ) {
+                Ok(diffs) => return Ok(diffs),
+                Err(e) => {
+                    let _ = crate::logging::log_warn(
+                        "git-diff",
+                        &format!(
+                            "⚠ Snapshot diff unavailable for {}, using commits: {}",
+                            session_id, e
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
     crate::git_diff::get_commit_diff(
         &cwd,
         &first_commit_hash,
@@ -1781,7 +2268,74 @@ pub async fn get_session_git_diff(
         is_active,
         session_start_time,
         session_end_time,
+        &options,
+    )
+}
+
+/// Attribute a session's git diff hunks to the tool calls that produced them
+///
+/// Lines that no file-editing tool call accounts for are reported as human-written.
+#[tauri::command]
+pub async fn get_session_diff_attribution(
+    session_id: String,
+    cwd: String,
+    first_commit_hash: String,
+    latest_commit_hash: String,
+    is_active: bool,
+    session_start_time: Option<i64>,
+    session_end_time: Option<i64>,
+) -> Result<crate::diff_attribution::SessionAttribution, String> {
+    use crate::database::get_full_session_by_id;
+    use crate::providers::canonical::CanonicalMessage;
+
+    let diffs = get_session_git_diff(
+        Some(session_id.clone()),
+        cwd.clone(),
+        first_commit_hash,
+        latest_commit_hash,
+        is_active,
+        session_start_time,
+        session_end_time,
+        // Collapsed or truncated files would be counted as untouched
+        Some(crate::git_diff::DiffRenderOptions::unlimited()),
     )
+    .await?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let session = get_full_session_by_id(&session_id)
+            .map_err(|e| format!("Failed to load session: {}", e))?
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        let content =
+            crate::providers::common::read_session_file(std::path::Path::new(&session.file_path))
+                .map_err(|e| format!("Failed to read session file: {}", e))?;
+
+        // Unparseable lines can't contain tool calls we could replay, so skip them
+        let messages: Vec<CanonicalMessage> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+
+        Ok(crate::diff_attribution::attribute_session_diff(
+            &session_id,
+            &cwd,
+            &diffs,
+            &messages,
+        ))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Link commits in a repository to agent sessions and summarize AI contribution
+///
+/// `range` is a revision or `a..b` range (defaults to HEAD).
+#[tauri::command]
+pub async fn get_contribution_report(
+    cwd: String,
+    range: Option<String>,
+    max_commits: Option<usize>,
+) -> Result<crate::contribution_report::ContributionReport, String> {
+    crate::contribution_report::generate_contribution_report(&cwd, range.as_deref(), max_commits)
 }
 
 /// Scan a directory for context files (CLAUDE.md, AGENTS.md, GEMINI.md)
@@ -1931,6 +2485,60 @@ async fn migrate_codex(dry_run: bool) -> Result<MigrationReport, String> {
     Ok(report)
 }
 
+/// Show each raw record of a session next to the canonical messages it produced
+#[tauri::command]
+pub async fn inspect_session_source(
+    provider_id: String,
+    path: String,
+) -> Result<crate::providers::inspect::SessionInspection, String> {
+    let path = shellexpand::tilde(&path).to_string();
+    crate::providers::inspect::inspect_session(&provider_id, std::path::Path::new(&path))
+}
+
+/// Export a replayable fixture for a session; `output` may be a directory
+#[tauri::command]
+pub async fn export_inspection_fixture(
+    provider_id: String,
+    path: String,
+    output: String,
+) -> Result<String, String> {
+    let path = shellexpand::tilde(&path).to_string();
+    let output = shellexpand::tilde(&output).to_string();
+    crate::providers::inspect::export_fixture(
+        &provider_id,
+        std::path::Path::new(&path),
+        std::path::Path::new(&output),
+    )
+    .map(|written| written.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn replay_inspection_fixture(
+    path: String,
+) -> Result<crate::providers::inspect::FixtureReplay, String> {
+    let path = shellexpand::tilde(&path).to_string();
+    let fixture = crate::providers::inspect::read_fixture(std::path::Path::new(&path))?;
+    crate::providers::inspect::replay_fixture(&fixture)
+}
+
+/// Get conversion fidelity stats for a session (None if never converted)
+#[tauri::command]
+pub async fn get_session_conversion_stats(
+    session_id: String,
+) -> Result<Option<crate::providers::canonical::fidelity::ConversionStats>, String> {
+    crate::database::get_conversion_stats(&session_id)
+        .map_err(|e| format!("Failed to get conversion stats: {}", e))
+}
+
+/// Get conversion fidelity stats summed over a provider's sessions
+#[tauri::command]
+pub async fn get_provider_conversion_stats(
+    provider_id: String,
+) -> Result<crate::database::ProviderConversionStats, String> {
+    crate::database::get_provider_conversion_stats(&provider_id)
+        .map_err(|e| format!("Failed to get conversion stats: {}", e))
+}
+
 fn convert_codex_session(
     source: &std::path::Path,
     cache_dir: &std::path::Path,
@@ -101,6 +101,27 @@ pub struct ProviderConfig {
     pub last_scanned: Option<String>,
     #[serde(rename = "syncMode", default = "default_sync_mode")]
     pub sync_mode: String, // "Nothing", "Metrics Only", or "Transcript and Metrics"
+    /// Only set for declarative "custom-*" providers (see providers::custom_jsonl)
+    #[serde(
+        rename = "customJsonl",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub custom_jsonl: Option<CustomJsonlConfig>,
+    /// Minutes without file activity before a session counts as completed
+    #[serde(
+        rename = "idleTimeoutMinutes",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub idle_timeout_minutes: Option<u32>,
+    /// Directories searched for git repos when resolving Gemini project hashes
+    #[serde(
+        rename = "projectRoots",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub project_roots: Vec<String>,
 }
 
 fn default_sync_mode() -> String {
@@ -116,10 +137,63 @@ impl Default for ProviderConfig {
             selected_projects: Vec::new(),
             last_scanned: None,
             sync_mode: "Nothing".to_string(),
+            custom_jsonl: None,
+            idle_timeout_minutes: None,
+            project_roots: Vec::new(),
         }
     }
 }
 
+/// Settings for a custom JSONL provider
+///
+/// `session_glob` is matched relative to the provider's `homeDirectory`
+/// (e.g. `**/*.jsonl` or `logs/*/session-*.jsonl`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomJsonlConfig {
+    #[serde(rename = "sessionGlob")]
+    pub session_glob: String,
+    pub mapping: CustomJsonlMapping,
+    /// Version of the mapping semantics; absent means version 1
+    #[serde(
+        rename = "formatVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub format_version: Option<String>,
+}
+
+/// Selectors applied to each JSONL record to build a canonical message
+///
+/// Selectors are JSONPath-like (`$.payload.items[0].text`); several
+/// alternatives can be chained with `|` and the first match wins.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomJsonlMapping {
+    pub timestamp: String,
+    pub role: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_input: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_result: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Maps provider role names onto "user" / "assistant" / "system"
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub role_map: std::collections::HashMap<String, String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectInfo {
     pub name: String,
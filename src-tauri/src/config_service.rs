@@ -0,0 +1,614 @@
+//! Hot reload of the global and per-provider configuration
+//!
+//! Watches `~/.guideai/config.json` and `~/.guideai/providers/*.json`,
+//! validates every change and publishes a typed [`ConfigChange`] so running
+//! watchers and the upload queue can reconfigure in place. A file that fails
+//! to parse or validate is rolled back to the last good version; subscribers
+//! never see it.
+
+use crate::config::{
+    ensure_providers_dir, get_config_dir, get_config_file_path, get_providers_dir, load_config,
+    load_provider_config, save_config, save_provider_config, GuideAIConfig, ProviderConfig,
+};
+use crate::logging::{log_info, log_warn};
+use lazy_static::lazy_static;
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+
+const LOG_PROVIDER: &str = "config";
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+const SYNC_MODES: [&str; 3] = ["Nothing", "Metrics Only", "Transcript and Metrics"];
+const PROJECT_SELECTIONS: [&str; 2] = ["ALL", "SELECTED"];
+
+lazy_static! {
+    static ref SERVICE: ConfigService = ConfigService::new(64);
+}
+
+/// Provider settings that differ between two versions of a config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigField {
+    Enabled,
+    HomeDirectory,
+    ProjectSelection,
+    SelectedProjects,
+    SyncMode,
+    CustomJsonl,
+    IdleTimeout,
+    ProjectRoots,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConfigChange {
+    Global {
+        config: GuideAIConfig,
+    },
+    Provider {
+        provider_id: String,
+        config: ProviderConfig,
+        changed: Vec<ConfigField>,
+    },
+    ProviderRemoved {
+        provider_id: String,
+    },
+    /// An edit failed validation and was rolled back
+    Rejected {
+        provider_id: Option<String>,
+        reason: String,
+    },
+}
+
+/// Result of checking a freshly read file against the last good config
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+enum Outcome<T> {
+    Unchanged,
+    Changed(ConfigChange),
+    Rejected { reason: String, rollback: Option<T> },
+}
+
+struct ConfigService {
+    sender: broadcast::Sender<ConfigChange>,
+    global: Mutex<Option<GuideAIConfig>>,
+    providers: Mutex<HashMap<String, ProviderConfig>>,
+}
+
+impl ConfigService {
+    fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            global: Mutex::new(None),
+            providers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn publish(&self, change: ConfigChange) {
+        // No receivers is fine: nothing is running that cares
+        let _ = self.sender.send(change);
+    }
+
+    fn last_good(&self, provider_id: &str) -> Option<ProviderConfig> {
+        self.providers.lock().ok()?.get(provider_id).cloned()
+    }
+
+    /// `raw` is the file content, `None` when the file was deleted
+    fn apply_provider(&self, provider_id: &str, raw: Option<&str>) -> Outcome<ProviderConfig> {
+        let Ok(mut providers) = self.providers.lock() else {
+            return Outcome::Unchanged;
+        };
+        let previous = providers.get(provider_id).cloned();
+
+        let Some(raw) = raw else {
+            return match providers.remove(provider_id) {
+                Some(_) => Outcome::Changed(ConfigChange::ProviderRemoved {
+                    provider_id: provider_id.to_string(),
+                }),
+                None => Outcome::Unchanged,
+            };
+        };
+
+        let parsed = serde_json::from_str::<ProviderConfig>(raw)
+            .map_err(|e| format!("Invalid JSON: {}", e))
+            .and_then(|config| validate_provider_config(provider_id, &config).map(|_| config));
+
+        match parsed {
+            Ok(config) => {
+                providers.insert(provider_id.to_string(), config.clone());
+                let changed = match &previous {
+                    Some(previous) => changed_fields(previous, &config),
+                    None => vec![ConfigField::Enabled],
+                };
+                if changed.is_empty() {
+                    Outcome::Unchanged
+                } else {
+                    Outcome::Changed(ConfigChange::Provider {
+                        provider_id: provider_id.to_string(),
+                        config,
+                        changed,
+                    })
+                }
+            }
+            Err(reason) => Outcome::Rejected {
+                reason,
+                rollback: previous,
+            },
+        }
+    }
+
+    fn apply_global(&self, raw: Option<&str>) -> Outcome<GuideAIConfig> {
+        let Ok(mut global) = self.global.lock() else {
+            return Outcome::Unchanged;
+        };
+        let previous = global.clone();
+
+        let parsed = match raw {
+            Some(raw) => serde_json::from_str::<GuideAIConfig>(raw)
+                .map_err(|e| format!("Invalid JSON: {}", e))
+                .and_then(|config| validate_global_config(&config).map(|_| config)),
+            // Deleting config.json is how logout looks from the outside
+            None => Ok(GuideAIConfig::default()),
+        };
+
+        match parsed {
+            Ok(config) => {
+                let unchanged = previous
+                    .as_ref()
+                    .map(|previous| same_json(previous, &config))
+                    .unwrap_or(false);
+                *global = Some(config.clone());
+                if unchanged {
+                    Outcome::Unchanged
+                } else {
+                    Outcome::Changed(ConfigChange::Global { config })
+                }
+            }
+            Err(reason) => Outcome::Rejected {
+                reason,
+                rollback: previous,
+            },
+        }
+    }
+}
+
+fn same_json<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+fn changed_fields(old: &ProviderConfig, new: &ProviderConfig) -> Vec<ConfigField> {
+    let mut changed = Vec::new();
+    if old.enabled != new.enabled {
+        changed.push(ConfigField::Enabled);
+    }
+    if old.home_directory != new.home_directory {
+        changed.push(ConfigField::HomeDirectory);
+    }
+    if old.project_selection != new.project_selection {
+        changed.push(ConfigField::ProjectSelection);
+    }
+    if old.selected_projects != new.selected_projects {
+        changed.push(ConfigField::SelectedProjects);
+    }
+    if old.sync_mode != new.sync_mode {
+        changed.push(ConfigField::SyncMode);
+    }
+    if !same_json(&old.custom_jsonl, &new.custom_jsonl) {
+        changed.push(ConfigField::CustomJsonl);
+    }
+    if old.idle_timeout_minutes != new.idle_timeout_minutes {
+        changed.push(ConfigField::IdleTimeout);
+    }
+    if old.project_roots != new.project_roots {
+        changed.push(ConfigField::ProjectRoots);
+    }
+    changed
+}
+
+/// Check a provider config before it is saved or applied to running watchers
+pub fn validate_provider_config(provider_id: &str, config: &ProviderConfig) -> Result<(), String> {
+    if !SYNC_MODES.contains(&config.sync_mode.as_str()) {
+        return Err(format!(
+            "syncMode must be one of {}, got '{}'",
+            SYNC_MODES.join(", "),
+            config.sync_mode
+        ));
+    }
+    if !PROJECT_SELECTIONS.contains(&config.project_selection.as_str()) {
+        return Err(format!(
+            "projectSelection must be ALL or SELECTED, got '{}'",
+            config.project_selection
+        ));
+    }
+    if config.enabled && config.home_directory.trim().is_empty() {
+        return Err("homeDirectory is required when the provider is enabled".to_string());
+    }
+    if config.idle_timeout_minutes == Some(0) {
+        return Err("idleTimeoutMinutes must be greater than zero".to_string());
+    }
+
+    if crate::providers::custom_jsonl::is_custom_provider(provider_id) {
+        match &config.custom_jsonl {
+            Some(spec) => crate::providers::custom_jsonl::validate_spec(spec)?,
+            None => return Err("Custom providers need a customJsonl mapping".to_string()),
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_global_config(config: &GuideAIConfig) -> Result<(), String> {
+    if let Some(url) = &config.server_url {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("serverUrl must be an http(s) URL, got '{}'", url));
+        }
+    }
+    Ok(())
+}
+
+/// Subscribe to configuration changes
+pub fn subscribe() -> broadcast::Receiver<ConfigChange> {
+    SERVICE.sender.subscribe()
+}
+
+/// Last validated config for a provider, read from disk if not seen yet
+pub fn provider_config(provider_id: &str) -> Result<ProviderConfig, String> {
+    if let Some(config) = SERVICE.last_good(provider_id) {
+        return Ok(config);
+    }
+    load_provider_config(provider_id).map_err(|e| format!("Failed to load provider config: {}", e))
+}
+
+/// Per-provider view of the change stream for watcher loops
+///
+/// Watcher tasks poll this between file events, so it never waits and
+/// collapses several edits into the newest config.
+pub struct ProviderConfigUpdates {
+    provider_id: String,
+    receiver: broadcast::Receiver<ConfigChange>,
+}
+
+impl ProviderConfigUpdates {
+    pub fn new(provider_id: &str) -> Self {
+        Self {
+            provider_id: provider_id.to_string(),
+            receiver: subscribe(),
+        }
+    }
+
+    /// Newest config published for this provider since the last poll
+    pub fn poll(&mut self) -> Option<ProviderConfig> {
+        let mut latest = None;
+        loop {
+            match self.receiver.try_recv() {
+                Ok(ConfigChange::Provider {
+                    provider_id,
+                    config,
+                    ..
+                }) if provider_id == self.provider_id => latest = Some(config),
+                Ok(_) => {}
+                Err(broadcast::error::TryRecvError::Lagged(_)) => {
+                    latest = SERVICE.last_good(&self.provider_id);
+                }
+                Err(_) => break,
+            }
+        }
+        latest
+    }
+}
+
+/// Re-read one provider's file and publish the result
+pub fn reload_provider(provider_id: &str) -> Result<(), String> {
+    let path = crate::config::get_provider_config_path(provider_id).map_err(|e| e.to_string())?;
+    let raw = match std::fs::read_to_string(&path) {
+        Ok(raw) => Some(raw),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    match SERVICE.apply_provider(provider_id, raw.as_deref()) {
+        Outcome::Unchanged => Ok(()),
+        Outcome::Changed(change) => {
+            let _ = log_info(
+                LOG_PROVIDER,
+                &format!("🔄 Applied config change for {}", provider_id),
+            );
+            SERVICE.publish(change);
+            Ok(())
+        }
+        Outcome::Rejected { reason, rollback } => {
+            if let Some(good) = rollback {
+                save_provider_config(provider_id, &good).map_err(|e| e.to_string())?;
+            }
+            let _ = log_warn(
+                LOG_PROVIDER,
+                &format!("⚠ Rejected config for {}: {}", provider_id, reason),
+            );
+            SERVICE.publish(ConfigChange::Rejected {
+                provider_id: Some(provider_id.to_string()),
+                reason: reason.clone(),
+            });
+            Err(reason)
+        }
+    }
+}
+
+/// Re-read config.json and publish the result
+pub fn reload_global() -> Result<(), String> {
+    let path = get_config_file_path().map_err(|e| e.to_string())?;
+    let raw = std::fs::read_to_string(&path).ok();
+
+    match SERVICE.apply_global(raw.as_deref()) {
+        Outcome::Unchanged => Ok(()),
+        Outcome::Changed(change) => {
+            SERVICE.publish(change);
+            Ok(())
+        }
+        Outcome::Rejected { reason, rollback } => {
+            if let Some(good) = rollback {
+                save_config(&good).map_err(|e| e.to_string())?;
+            }
+            let _ = log_warn(LOG_PROVIDER, &format!("⚠ Rejected config.json: {}", reason));
+            SERVICE.publish(ConfigChange::Rejected {
+                provider_id: None,
+                reason: reason.clone(),
+            });
+            Err(reason)
+        }
+    }
+}
+
+/// Record the configs on disk at startup as the last good versions
+fn seed() {
+    if let Ok(config) = load_config() {
+        if let Ok(mut global) = SERVICE.global.lock() {
+            *global = Some(config);
+        }
+    }
+
+    let Ok(entries) = get_providers_dir().and_then(|dir| Ok(std::fs::read_dir(dir)?)) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if let Some(provider_id) = provider_id_for_path(&entry.path()) {
+            let raw = std::fs::read_to_string(entry.path()).ok();
+            if let Outcome::Rejected { reason, .. } =
+                SERVICE.apply_provider(&provider_id, raw.as_deref())
+            {
+                let _ = log_warn(
+                    LOG_PROVIDER,
+                    &format!("⚠ Ignoring invalid config for {}: {}", provider_id, reason),
+                );
+            }
+        }
+    }
+}
+
+fn provider_id_for_path(path: &Path) -> Option<String> {
+    if path.extension().and_then(|e| e.to_str()) != Some("json") {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    if stem.starts_with('.') {
+        return None;
+    }
+    Some(stem.to_string())
+}
+
+/// What a changed path means for the service
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ReloadTarget {
+    Global,
+    Provider(String),
+}
+
+fn reload_target(path: &Path, config_file: &Path, providers_dir: &Path) -> Option<ReloadTarget> {
+    if path == config_file {
+        return Some(ReloadTarget::Global);
+    }
+    if path.parent() == Some(providers_dir) {
+        return provider_id_for_path(path).map(ReloadTarget::Provider);
+    }
+    None
+}
+
+/// Keeps the file watcher alive; dropped with the app
+pub struct ConfigServiceWatcher {
+    _watcher: RecommendedWatcher,
+    _thread_handle: std::thread::JoinHandle<()>,
+}
+
+/// Seed the last good configs and start watching for edits
+///
+/// Global config reloads are emitted to the window as `config-changed`,
+/// rejections as `config-rejected`.
+pub fn start_config_service(
+    app_handle: Option<AppHandle>,
+) -> Result<ConfigServiceWatcher, Box<dyn std::error::Error + Send + Sync>> {
+    ensure_providers_dir().map_err(|e| e.to_string())?;
+    seed();
+
+    let config_dir = get_config_dir().map_err(|e| e.to_string())?;
+    let config_file = get_config_file_path().map_err(|e| e.to_string())?;
+    let providers_dir = get_providers_dir().map_err(|e| e.to_string())?;
+
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = RecommendedWatcher::new(
+        tx,
+        Config::default().with_poll_interval(Duration::from_secs(1)),
+    )?;
+    watcher.watch(&config_dir, RecursiveMode::NonRecursive)?;
+    watcher.watch(&providers_dir, RecursiveMode::NonRecursive)?;
+
+    let thread_handle =
+        std::thread::spawn(move || {
+            let mut pending: HashSet<ReloadTarget> = HashSet::new();
+            let mut last_event = Instant::now();
+
+            loop {
+                match rx.recv_timeout(RELOAD_DEBOUNCE) {
+                    Ok(Ok(event)) => {
+                        if matches!(
+                            event.kind,
+                            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                        ) {
+                            pending.extend(event.paths.iter().filter_map(|path| {
+                                reload_target(path, &config_file, &providers_dir)
+                            }));
+                            last_event = Instant::now();
+                        }
+                        continue;
+                    }
+                    Ok(Err(e)) => {
+                        let _ = log_warn(LOG_PROVIDER, &format!("Config watcher error: {:?}", e));
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+
+                if pending.is_empty() || last_event.elapsed() < RELOAD_DEBOUNCE {
+                    continue;
+                }
+
+                for target in pending.drain() {
+                    // Failures are already logged and published by the reload functions
+                    let (provider_id, result) = match target {
+                        ReloadTarget::Global => (None, reload_global()),
+                        ReloadTarget::Provider(provider_id) => {
+                            let result = reload_provider(&provider_id);
+                            (Some(provider_id), result)
+                        }
+                    };
+
+                    let Some(app_handle) = &app_handle else {
+                        continue;
+                    };
+                    match result {
+                        Ok(()) if provider_id.is_none() => {
+                            if let Err(e) = app_handle.emit("config-changed", ()) {
+                                eprintln!("Failed to emit config-changed event: {}", e);
+                            }
+                        }
+                        Ok(()) => {}
+                        Err(reason) => {
+                            let payload = ConfigChange::Rejected {
+                                provider_id,
+                                reason,
+                            };
+                            if let Err(e) = app_handle.emit("config-rejected", &payload) {
+                                eprintln!("Failed to emit config-rejected event: {}", e);
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+    Ok(ConfigServiceWatcher {
+        _watcher: watcher,
+        _thread_handle: thread_handle,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider_json(sync_mode: &str, selected: &[&str]) -> String {
+        serde_json::json!({
+            "enabled": true,
+            "homeDirectory": "~/.claude",
+            "projectSelection": "SELECTED",
+            "selectedProjects": selected,
+            "lastScanned": null,
+            "syncMode": sync_mode,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_apply_provider_reports_changed_fields() {
+        let service = ConfigService::new(8);
+        let first = service.apply_provider("claude-code", Some(&provider_json("Nothing", &["a"])));
+        assert!(matches!(
+            first,
+            Outcome::Changed(ConfigChange::Provider { .. })
+        ));
+
+        let same = service.apply_provider("claude-code", Some(&provider_json("Nothing", &["a"])));
+        assert!(matches!(same, Outcome::Unchanged));
+
+        match service.apply_provider(
+            "claude-code",
+            Some(&provider_json("Metrics Only", &["a", "b"])),
+        ) {
+            Outcome::Changed(ConfigChange::Provider { changed, .. }) => assert_eq!(
+                changed,
+                vec![ConfigField::SelectedProjects, ConfigField::SyncMode]
+            ),
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+
+        let removed = service.apply_provider("claude-code", None);
+        assert!(matches!(
+            removed,
+            Outcome::Changed(ConfigChange::ProviderRemoved { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_provider_config_rolls_back_to_last_good() {
+        let service = ConfigService::new(8);
+        service.apply_provider("claude-code", Some(&provider_json("Nothing", &["a"])));
+
+        match service.apply_provider("claude-code", Some(&provider_json("Everything", &["b"]))) {
+            Outcome::Rejected { reason, rollback } => {
+                assert!(reason.contains("syncMode"));
+                assert_eq!(rollback.unwrap().selected_projects, vec!["a".to_string()]);
+            }
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+
+        let broken = service.apply_provider("claude-code", Some("{ not json"));
+        assert!(matches!(broken, Outcome::Rejected { .. }));
+        assert_eq!(
+            service.last_good("claude-code").unwrap().sync_mode,
+            "Nothing"
+        );
+    }
+
+    #[test]
+    fn test_reload_target_for_paths() {
+        let config_file = PathBuf::from("/home/u/.guideai/config.json");
+        let providers_dir = PathBuf::from("/home/u/.guideai/providers");
+
+        assert_eq!(
+            reload_target(&config_file, &config_file, &providers_dir),
+            Some(ReloadTarget::Global)
+        );
+        assert_eq!(
+            reload_target(
+                &providers_dir.join("codex.json"),
+                &config_file,
+                &providers_dir
+            ),
+            Some(ReloadTarget::Provider("codex".to_string()))
+        );
+        assert_eq!(
+            reload_target(
+                &providers_dir.join(".codex.json.swp"),
+                &config_file,
+                &providers_dir
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,419 @@
+//! Link commits to agent sessions and report AI contribution per repository
+//!
+//! A commit is linked to a session when it was made while the session ran (or
+//! shortly after it ended) and touches a file the session edited. Added lines
+//! that match lines written by the session's file-editing tool calls count as
+//! agent lines.
+
+use crate::diff_attribution::agent_written_lines;
+use crate::providers::canonical::CanonicalMessage;
+use git2::{DiffOptions, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Commits made up to this long after a session ended still count as its work
+const COMMIT_GRACE_MS: i64 = 60 * 60 * 1000;
+
+/// Default number of commits walked when no limit is given
+const DEFAULT_MAX_COMMITS: usize = 1000;
+
+/// What a session did to a repository, as needed for commit linking
+#[derive(Debug, Clone)]
+pub struct SessionActivity {
+    pub session_id: String,
+    pub provider: String,
+    pub git_branch: Option<String>,
+    pub start_ms: i64,
+    /// `None` while the session is still active
+    pub end_ms: Option<i64>,
+    /// Trimmed lines written by the session, keyed by repository-relative path
+    pub written_lines: HashMap<String, HashSet<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitContribution {
+    pub hash: String,
+    pub summary: String,
+    pub author: String,
+    /// Commit time (Unix milliseconds)
+    pub timestamp: i64,
+    pub files_changed: u32,
+    pub lines_added: u32,
+    pub lines_deleted: u32,
+    /// Added lines that match lines written by a linked session
+    pub agent_lines: u32,
+    pub session_ids: Vec<String>,
+    /// Providers of the linked sessions
+    pub providers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchSessions {
+    pub branch: String,
+    pub session_ids: Vec<String>,
+    /// Commits in the range linked to one of these sessions
+    pub linked_commits: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributionReport {
+    pub repository: String,
+    pub range: String,
+    pub total_commits: u32,
+    pub agent_commits: u32,
+    pub human_commits: u32,
+    pub lines_added: u32,
+    pub agent_lines: u32,
+    pub commits: Vec<CommitContribution>,
+    pub branches: Vec<BranchSessions>,
+}
+
+/// Build a contribution report for the repository containing `cwd`
+///
+/// `range` is a git revision (`main`, `HEAD~50`) or range (`v1.0..HEAD`);
+/// defaults to HEAD. Sessions are loaded from the local database.
+pub fn generate_contribution_report(
+    cwd: &str,
+    range: Option<&str>,
+    max_commits: Option<usize>,
+) -> Result<ContributionReport, String> {
+    let repo = Repository::discover(cwd)
+        .map_err(|e| format!("Failed to open git repository at {}: {}", cwd, e))?;
+    let root = repo
+        .workdir()
+        .ok_or_else(|| "Bare repositories are not supported".to_string())?
+        .to_string_lossy()
+        .trim_end_matches('/')
+        .to_string();
+    let root_prefix = format!("{}/", root);
+
+    let sessions = crate::database::get_sessions_for_repository(&root)
+        .map_err(|e| format!("Failed to load sessions: {}", e))?;
+
+    let activities: Vec<SessionActivity> = sessions
+        .into_iter()
+        .filter_map(|session| {
+            let start_ms = session.session_start_time?;
+            let content =
+                crate::providers::common::read_session_file(Path::new(&session.file_path)).ok()?;
+            let messages: Vec<CanonicalMessage> = content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect();
+
+            // Tool paths are relative to the session cwd, which may sit below the root
+            let prefix = Path::new(&session.cwd)
+                .strip_prefix(&root)
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+            let written_lines = agent_written_lines(&messages, &session.cwd)
+                .into_iter()
+                .map(|(path, lines)| {
+                    let path = if let Some(relative) = path.strip_prefix(&root_prefix) {
+                        relative.to_string()
+                    } else if prefix.is_empty() || path.starts_with('/') {
+                        path
+                    } else {
+                        format!("{}/{}", prefix, path)
+                    };
+                    (path, lines)
+                })
+                .collect();
+
+            Some(SessionActivity {
+                session_id: session.session_id,
+                provider: session.provider,
+                git_branch: session.git_branch,
+                start_ms,
+                end_ms: session.session_end_time,
+                written_lines,
+            })
+        })
+        .collect();
+
+    build_contribution_report(&repo, range, max_commits, &activities)
+}
+
+/// Walk commits in `range` and link each one to the sessions that produced it
+pub fn build_contribution_report(
+    repo: &Repository,
+    range: Option<&str>,
+    max_commits: Option<usize>,
+    sessions: &[SessionActivity],
+) -> Result<ContributionReport, String> {
+    let range = range.unwrap_or("HEAD");
+    let mut revwalk = repo
+        .revwalk()
+        .map_err(|e| format!("Failed to create revwalk: {}", e))?;
+    revwalk
+        .set_sorting(Sort::TIME)
+        .map_err(|e| format!("Failed to sort revwalk: {}", e))?;
+    if range.contains("..") {
+        revwalk
+            .push_range(range)
+            .map_err(|e| format!("Invalid range {}: {}", range, e))?;
+    } else {
+        let commit = repo
+            .revparse_single(range)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| format!("Invalid revision {}: {}", range, e))?;
+        revwalk
+            .push(commit.id())
+            .map_err(|e| format!("Failed to walk {}: {}", range, e))?;
+    }
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let mut commits = Vec::new();
+
+    for oid in revwalk.take(max_commits.unwrap_or(DEFAULT_MAX_COMMITS)) {
+        let oid = oid.map_err(|e| format!("Failed to walk commits: {}", e))?;
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|e| format!("Failed to load commit {}: {}", oid, e))?;
+        let timestamp = commit.time().seconds() * 1000;
+        let changes = commit_changes(repo, &commit)?;
+
+        let linked: Vec<&SessionActivity> = sessions
+            .iter()
+            .filter(|session| {
+                let end = session.end_ms.unwrap_or(now_ms) + COMMIT_GRACE_MS;
+                timestamp >= session.start_ms
+                    && timestamp <= end
+                    && changes
+                        .keys()
+                        .any(|path| session.written_lines.contains_key(path))
+            })
+            .collect();
+
+        let mut agent_lines = 0;
+        for (path, change) in &changes {
+            agent_lines += change
+                .added
+                .iter()
+                .filter(|line| {
+                    linked.iter().any(|session| {
+                        session
+                            .written_lines
+                            .get(path)
+                            .is_some_and(|lines| lines.contains(line.as_str()))
+                    })
+                })
+                .count() as u32;
+        }
+
+        commits.push(CommitContribution {
+            hash: oid.to_string(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            author: commit.author().name().unwrap_or_default().to_string(),
+            timestamp,
+            files_changed: changes.len() as u32,
+            lines_added: changes.values().map(|c| c.lines_added).sum(),
+            lines_deleted: changes.values().map(|c| c.lines_deleted).sum(),
+            agent_lines,
+            session_ids: linked.iter().map(|s| s.session_id.clone()).collect(),
+            providers: linked
+                .iter()
+                .map(|s| s.provider.clone())
+                .collect::<std::collections::BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        });
+    }
+
+    // Sessions per branch, with how many commits in the range they account for
+    let mut by_branch: BTreeMap<String, Vec<&SessionActivity>> = BTreeMap::new();
+    for session in sessions {
+        if let Some(branch) = &session.git_branch {
+            by_branch.entry(branch.clone()).or_default().push(session);
+        }
+    }
+    let branches = by_branch
+        .into_iter()
+        .map(|(branch, branch_sessions)| {
+            let ids: HashSet<&str> = branch_sessions
+                .iter()
+                .map(|s| s.session_id.as_str())
+                .collect();
+            BranchSessions {
+                branch,
+                linked_commits: commits
+                    .iter()
+                    .filter(|c| c.session_ids.iter().any(|id| ids.contains(id.as_str())))
+                    .count() as u32,
+                session_ids: branch_sessions
+                    .iter()
+                    .map(|s| s.session_id.clone())
+                    .collect(),
+            }
+        })
+        .collect();
+
+    let agent_commits = commits.iter().filter(|c| !c.session_ids.is_empty()).count() as u32;
+
+    Ok(ContributionReport {
+        repository: repo
+            .workdir()
+            .unwrap_or(repo.path())
+            .to_string_lossy()
+            .to_string(),
+        range: range.to_string(),
+        total_commits: commits.len() as u32,
+        agent_commits,
+        human_commits: commits.len() as u32 - agent_commits,
+        lines_added: commits.iter().map(|c| c.lines_added).sum(),
+        agent_lines: commits.iter().map(|c| c.agent_lines).sum(),
+        commits,
+        branches,
+    })
+}
+
+/// Per-file changes of a commit against its first parent
+#[derive(Default)]
+struct FileChange {
+    lines_added: u32,
+    lines_deleted: u32,
+    /// Trimmed added lines
+    added: Vec<String>,
+}
+
+fn commit_changes(
+    repo: &Repository,
+    commit: &git2::Commit,
+) -> Result<HashMap<String, FileChange>, String> {
+    let tree = commit
+        .tree()
+        .map_err(|e| format!("Failed to get commit tree: {}", e))?;
+    let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+
+    let mut diff_opts = DiffOptions::new();
+    diff_opts.context_lines(0);
+    let diff = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_opts))
+        .map_err(|e| format!("Failed to diff commit {}: {}", commit.id(), e))?;
+
+    let mut changes: HashMap<String, FileChange> = HashMap::new();
+    for idx in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(idx) else {
+            continue;
+        };
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+        let change = changes
+            .entry(path.to_string_lossy().replace('\\', "/"))
+            .or_default();
+
+        // Binary files have no patch lines
+        let Some(patch) = git2::Patch::from_diff(&diff, idx)
+            .map_err(|e| format!("Failed to read commit diff: {}", e))?
+        else {
+            continue;
+        };
+        for hunk_idx in 0..patch.num_hunks() {
+            let line_count = patch
+                .num_lines_in_hunk(hunk_idx)
+                .map_err(|e| format!("Failed to read commit diff: {}", e))?;
+            for line_idx in 0..line_count {
+                let line = patch
+                    .line_in_hunk(hunk_idx, line_idx)
+                    .map_err(|e| format!("Failed to read commit diff: {}", e))?;
+                match line.origin() {
+                    '+' => {
+                        change.lines_added += 1;
+                        let text = String::from_utf8_lossy(line.content());
+                        change.added.push(text.trim().to_string());
+                    }
+                    '-' => change.lines_deleted += 1,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn commit_file(repo: &Repository, path: &str, content: &str, message: &str, time: i64) {
+        let workdir = repo.workdir().unwrap();
+        fs::write(workdir.join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig =
+            git2::Signature::new("dev", "dev@example.com", &git2::Time::new(time, 0)).unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_links_commits_to_overlapping_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        // Human commit before the session
+        commit_file(&repo, "notes.md", "# Notes by hand\n", "notes", 1_000);
+        // Commit made during the session, partly with agent-written lines
+        commit_file(
+            &repo,
+            "src.rs",
+            "fn agent_helper() {}\nfn tweaked_by_hand() {}\n",
+            "add helper",
+            2_000,
+        );
+
+        let session = SessionActivity {
+            session_id: "session-1".to_string(),
+            provider: "claude-code".to_string(),
+            git_branch: Some("feature/helper".to_string()),
+            start_ms: 1_500_000,
+            end_ms: Some(2_500_000),
+            written_lines: HashMap::from([(
+                "src.rs".to_string(),
+                HashSet::from(["fn agent_helper() {}".to_string()]),
+            )]),
+        };
+
+        let report = build_contribution_report(&repo, None, None, &[session]).unwrap();
+
+        assert_eq!(report.total_commits, 2);
+        assert_eq!(report.agent_commits, 1);
+        assert_eq!(report.human_commits, 1);
+
+        let helper = report
+            .commits
+            .iter()
+            .find(|c| c.summary == "add helper")
+            .unwrap();
+        assert_eq!(helper.session_ids, vec!["session-1".to_string()]);
+        assert_eq!(helper.lines_added, 2);
+        assert_eq!(helper.agent_lines, 1);
+        assert_eq!(helper.providers, vec!["claude-code".to_string()]);
+
+        let notes = report
+            .commits
+            .iter()
+            .find(|c| c.summary == "notes")
+            .unwrap();
+        assert!(notes.session_ids.is_empty());
+
+        assert_eq!(report.branches[0].branch, "feature/helper");
+        assert_eq!(report.branches[0].linked_commits, 1);
+    }
+}
@@ -92,7 +92,7 @@ pub fn init_database() -> Result<()> {
 }
 
 /// Get the database file path (same location as tauri-plugin-sql uses)
-fn get_db_path() -> Result<std::path::PathBuf> {
+pub(crate) fn get_db_path() -> Result<std::path::PathBuf> {
     // Use Tauri's app data directory (same as plugin)
     // On macOS: ~/Library/Application Support/com.guideai.desktop/
     // On Linux: ~/.local/share/com.guideai.desktop/
@@ -566,13 +566,15 @@ pub struct UnsyncedSession {
     pub session_end_time: Option<i64>,
 }
 
-/// Insert or get a project by CWD (upsert)
+/// Insert or get a project by identity key or CWD (upsert)
 /// Uses a transaction to ensure atomicity
 pub fn insert_or_get_project(
     name: &str,
     github_repo: Option<&str>,
     cwd: &str,
     project_type: &str,
+    identity_key: Option<&str>,
+    workspace_kind: Option<&str>,
 ) -> Result<String> {
     with_connection_mut(|conn| {
         // Use a transaction for atomic upsert
@@ -580,20 +582,23 @@ pub fn insert_or_get_project(
 
         let now = Utc::now().timestamp_millis();
 
-        // Try to get existing project by CWD
+        // Try to get existing project by identity first, then by CWD
         let existing: Option<String> = tx
             .query_row(
-                "SELECT id FROM projects WHERE cwd = ?",
-                params![cwd],
+                "SELECT id FROM projects
+                 WHERE (?1 IS NOT NULL AND identity_key = ?1) OR cwd = ?2
+                 ORDER BY identity_key IS NOT NULL AND identity_key = ?1 DESC, created_at ASC
+                 LIMIT 1",
+                params![identity_key, cwd],
                 |row| row.get(0),
             )
             .ok();
 
         let project_id = if let Some(project_id) = existing {
-            // Update existing project
+            // Update existing project (its cwd stays put so other machines' roots don't collide)
             tx.execute(
-                "UPDATE projects SET name = ?, github_repo = ?, type = ?, updated_at = ? WHERE id = ?",
-                params![name, github_repo, project_type, now, project_id],
+                "UPDATE projects SET name = ?, github_repo = ?, type = ?, identity_key = COALESCE(?, identity_key), workspace_kind = ?, updated_at = ? WHERE id = ?",
+                params![name, github_repo, project_type, identity_key, workspace_kind, now, project_id],
             )?;
 
             log_debug(
@@ -607,9 +612,9 @@ pub fn insert_or_get_project(
             // Insert new project
             let id = Uuid::new_v4().to_string();
             tx.execute(
-                "INSERT INTO projects (id, name, github_repo, cwd, type, created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?)",
-                params![id, name, github_repo, cwd, project_type, now, now],
+                "INSERT INTO projects (id, name, github_repo, cwd, type, identity_key, workspace_kind, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![id, name, github_repo, cwd, project_type, identity_key, workspace_kind, now, now],
             )?;
 
             log_info(
@@ -644,7 +649,7 @@ pub fn get_all_projects() -> Result<Vec<ProjectWithCount>> {
 
     let mut stmt = conn.prepare(
         "SELECT p.id, p.name, p.github_repo, p.cwd, p.type, p.created_at, p.updated_at,
-                COUNT(s.id) as session_count
+                COUNT(s.id) as session_count, p.workspace_kind
          FROM projects p
          LEFT JOIN agent_sessions s ON p.id = s.project_id
          GROUP BY p.id
@@ -662,6 +667,7 @@ pub fn get_all_projects() -> Result<Vec<ProjectWithCount>> {
                 created_at: row.get(5)?,
                 updated_at: row.get(6)?,
                 session_count: row.get(7)?,
+                workspace_kind: row.get(8)?,
             })
         })?
         .collect::<Result<Vec<_>>>()?;
@@ -679,7 +685,7 @@ pub fn get_project_by_id(project_id: &str) -> Result<Option<ProjectWithCount>> {
     let project: Option<ProjectWithCount> = conn
         .query_row(
             "SELECT p.id, p.name, p.github_repo, p.cwd, p.type, p.created_at, p.updated_at,
-                COUNT(s.id) as session_count
+                COUNT(s.id) as session_count, p.workspace_kind
          FROM projects p
          LEFT JOIN agent_sessions s ON p.id = s.project_id
          WHERE p.id = ?
@@ -695,6 +701,7 @@ pub fn get_project_by_id(project_id: &str) -> Result<Option<ProjectWithCount>> {
                     created_at: row.get(5)?,
                     updated_at: row.get(6)?,
                     session_count: row.get(7)?,
+                    workspace_kind: row.get(8)?,
                 })
             },
         )
@@ -727,6 +734,122 @@ pub fn attach_session_to_project(session_id: &str, project_id: &str) -> Result<(
     Ok(())
 }
 
+/// Record the workspace package (monorepo sub-package) a session worked in
+pub fn update_session_package(
+    session_id: &str,
+    package: Option<&crate::project_metadata::WorkspacePackage>,
+) -> Result<()> {
+    with_connection_mut(|conn| {
+        conn.execute(
+            "UPDATE agent_sessions SET package_name = ?, package_path = ? WHERE session_id = ?",
+            params![
+                package.map(|p| p.name.as_str()),
+                package.map(|p| p.path.as_str()),
+                session_id
+            ],
+        )?;
+
+        Ok(())
+    })
+}
+
+/// A session whose lifecycle was still open (discovered, active or idle)
+#[derive(Debug, Clone)]
+pub struct LifecycleRecord {
+    pub session_id: String,
+    pub state: String,
+    pub session_start_time: Option<i64>,
+    pub last_activity_at: Option<i64>,
+}
+
+/// Sessions of a provider that had not completed when the app last ran
+pub fn get_open_lifecycle_sessions(provider: &str) -> Result<Vec<LifecycleRecord>> {
+    with_connection_mut(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT session_id, lifecycle_state, session_start_time, COALESCE(last_activity_at, session_end_time)
+             FROM agent_sessions
+             WHERE provider = ? AND lifecycle_state IN ('discovered', 'active', 'idle')",
+        )?;
+        let records = stmt
+            .query_map(params![provider], |row| {
+                Ok(LifecycleRecord {
+                    session_id: row.get(0)?,
+                    state: row.get(1)?,
+                    session_start_time: row.get(2)?,
+                    last_activity_at: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(records)
+    })
+}
+
+/// Persist a session's lifecycle state and when its file last changed
+pub fn update_session_lifecycle(
+    session_id: &str,
+    state: &str,
+    last_activity_at: i64,
+) -> Result<()> {
+    with_connection_mut(|conn| {
+        conn.execute(
+            "UPDATE agent_sessions SET lifecycle_state = ?, last_activity_at = ? WHERE session_id = ?",
+            params![state, last_activity_at, session_id],
+        )?;
+
+        Ok(())
+    })
+}
+
+/// Start and end time recorded for a session (ms since epoch)
+pub fn get_session_times(session_id: &str) -> Result<(Option<i64>, Option<i64>)> {
+    with_connection_mut(|conn| {
+        conn.query_row(
+            "SELECT session_start_time, session_end_time FROM agent_sessions WHERE session_id = ?",
+            params![session_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    })
+}
+
+/// Record the final timing of a completed session without touching its file fields
+pub fn complete_session(
+    session_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    duration_ms: i64,
+) -> Result<()> {
+    with_connection_mut(|conn| {
+        conn.execute(
+            "UPDATE agent_sessions
+             SET session_start_time = COALESCE(session_start_time, ?),
+                 session_end_time = ?,
+                 duration_ms = ?,
+                 lifecycle_state = 'completed'
+             WHERE session_id = ?",
+            params![
+                start_time.timestamp_millis(),
+                end_time.timestamp_millis(),
+                duration_ms,
+                session_id
+            ],
+        )?;
+
+        Ok(())
+    })
+}
+
+/// Record the model a session mostly used
+pub fn update_session_model(session_id: &str, model: &str) -> Result<()> {
+    with_connection_mut(|conn| {
+        conn.execute(
+            "UPDATE agent_sessions SET model = ? WHERE session_id = ?",
+            params![model, session_id],
+        )?;
+
+        Ok(())
+    })
+}
+
 /// Update a session's project_name field
 /// Used when linking a session to a project to sync the project_name field
 pub fn update_session_project_name(session_id: &str, project_name: &str) -> Result<()> {
@@ -759,6 +882,7 @@ pub struct ProjectWithCount {
     pub created_at: i64,
     pub updated_at: i64,
     pub session_count: i64,
+    pub workspace_kind: Option<String>,
 }
 
 /// Execute a raw SQL query and return results as JSON
@@ -1071,15 +1195,7 @@ pub struct SessionMetrics {
 
 /// Clear all failed sessions from the database
 pub fn clear_failed_sessions() -> Result<()> {
-    let db_conn = DB_CONNECTION.lock().unwrap();
-    let conn = db_conn
-        .as_ref()
-        .ok_or_else(|| rusqlite::Error::InvalidQuery)?;
-
-    conn.execute(
-        "DELETE FROM agent_sessions WHERE sync_failed_reason IS NOT NULL",
-        [],
-    )?;
+    delete_sessions_where("sync_failed_reason IS NOT NULL", &[])?;
 
     log_info("database", "✓ Cleared all failed sessions from database").unwrap_or_default();
 
@@ -1107,15 +1223,7 @@ pub fn retry_failed_sessions() -> Result<()> {
 
 /// Remove a session from the database by ID
 pub fn remove_session_by_id(session_id: &str) -> Result<usize> {
-    let db_conn = DB_CONNECTION.lock().unwrap();
-    let conn = db_conn
-        .as_ref()
-        .ok_or_else(|| rusqlite::Error::InvalidQuery)?;
-
-    let rows_affected = conn.execute(
-        "DELETE FROM agent_sessions WHERE id = ?",
-        params![session_id],
-    )?;
+    let rows_affected = delete_sessions_where("id = ?", &[&session_id])?;
 
     if rows_affected > 0 {
         log_info(
@@ -1242,3 +1350,594 @@ pub fn get_session_metrics(session_id: &str) -> Result<Option<SessionMetrics>> {
 
     Ok(metrics)
 }
+
+/// Stored branch of a session tree (one row of `session_relations`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRelation {
+    pub session_id: String,
+    pub branch_id: String,
+    pub relation_type: String,
+    pub parent_branch_id: Option<String>,
+    pub parent_session_id: Option<String>,
+    pub spawning_tool_use_id: Option<String>,
+    pub spawning_message_uuid: Option<String>,
+    pub message_count: i64,
+    pub user_messages: i64,
+    pub assistant_messages: i64,
+    pub tool_calls: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+/// Replace the stored relations of a session with its reconstructed tree
+pub fn save_session_tree(tree: &crate::providers::common::SessionTree) -> Result<()> {
+    with_connection_mut(|conn| {
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM session_relations WHERE session_id = ?",
+            params![tree.session_id],
+        )?;
+
+        for (index, branch) in tree.branches.iter().enumerate() {
+            // Only the main branch of a child session points at another session
+            let parent_session_id = if index == 0 {
+                tree.parent_session_id.as_deref()
+            } else {
+                None
+            };
+
+            tx.execute(
+                "INSERT INTO session_relations (
+                    session_id, branch_id, relation_type, parent_branch_id, parent_session_id,
+                    spawning_tool_use_id, spawning_message_uuid,
+                    message_count, user_messages, assistant_messages, tool_calls,
+                    input_tokens, output_tokens, start_time, end_time, duration_ms, updated_at
+                 ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    tree.session_id,
+                    branch.branch_id,
+                    branch.relation_type.as_str(),
+                    branch.parent_branch_id,
+                    parent_session_id,
+                    branch.spawning_tool_use_id,
+                    branch.spawning_message_uuid,
+                    branch.metrics.message_count as i64,
+                    branch.metrics.user_messages as i64,
+                    branch.metrics.assistant_messages as i64,
+                    branch.metrics.tool_calls as i64,
+                    branch.metrics.input_tokens as i64,
+                    branch.metrics.output_tokens as i64,
+                    branch.metrics.start_time,
+                    branch.metrics.end_time,
+                    branch.metrics.duration_ms,
+                    Utc::now().timestamp_millis(),
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+
+        log_debug(
+            "database",
+            &format!(
+                "🌳 Saved {} branch(es) for session {}",
+                tree.branches.len(),
+                tree.session_id
+            ),
+        )
+        .unwrap_or_default();
+
+        Ok(())
+    })
+}
+
+/// Get the branches of a session plus any child sessions spawned from it
+pub fn get_session_relations(session_id: &str) -> Result<Vec<SessionRelation>> {
+    let db_conn = DB_CONNECTION.lock().unwrap();
+    let conn = db_conn
+        .as_ref()
+        .ok_or_else(|| rusqlite::Error::InvalidQuery)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT session_id, branch_id, relation_type, parent_branch_id, parent_session_id,
+                spawning_tool_use_id, spawning_message_uuid,
+                message_count, user_messages, assistant_messages, tool_calls,
+                input_tokens, output_tokens, start_time, end_time, duration_ms
+         FROM session_relations
+         WHERE session_id = ?1 OR parent_session_id = ?1
+         ORDER BY start_time ASC",
+    )?;
+
+    let relations = stmt
+        .query_map(params![session_id], |row| {
+            Ok(SessionRelation {
+                session_id: row.get(0)?,
+                branch_id: row.get(1)?,
+                relation_type: row.get(2)?,
+                parent_branch_id: row.get(3)?,
+                parent_session_id: row.get(4)?,
+                spawning_tool_use_id: row.get(5)?,
+                spawning_message_uuid: row.get(6)?,
+                message_count: row.get(7)?,
+                user_messages: row.get(8)?,
+                assistant_messages: row.get(9)?,
+                tool_calls: row.get(10)?,
+                input_tokens: row.get(11)?,
+                output_tokens: row.get(12)?,
+                start_time: row.get(13)?,
+                end_time: row.get(14)?,
+                duration_ms: row.get(15)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(relations)
+}
+
+/// Store a working-tree snapshot id for a session
+///
+/// The start snapshot is only written once; the end snapshot is overwritten
+/// on every capture while the session is live.
+pub fn save_session_snapshot(
+    session_id: &str,
+    kind: crate::git_snapshot::SnapshotKind,
+    tree_id: &str,
+) -> Result<()> {
+    with_connection_mut(|conn| {
+        let sql = match kind {
+            crate::git_snapshot::SnapshotKind::Start => {
+                "UPDATE agent_sessions SET start_snapshot_tree = ?
+                 WHERE session_id = ? AND start_snapshot_tree IS NULL"
+            }
+            crate::git_snapshot::SnapshotKind::End => {
+                "UPDATE agent_sessions SET end_snapshot_tree = ? WHERE session_id = ?"
+            }
+        };
+        conn.execute(sql, params![tree_id, session_id])?;
+        Ok(())
+    })
+}
+
+/// (session_id, cwd) of sessions matching `condition` that have pinned snapshots
+pub fn get_snapshotted_sessions(
+    condition: &str,
+    condition_params: &[&dyn rusqlite::ToSql],
+) -> Result<Vec<(String, String)>> {
+    with_connection_mut(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT session_id, cwd FROM agent_sessions
+             WHERE cwd IS NOT NULL
+               AND (start_snapshot_tree IS NOT NULL OR end_snapshot_tree IS NOT NULL)
+               AND ({})",
+            condition
+        ))?;
+        let sessions = stmt
+            .query_map(condition_params, |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(sessions)
+    })
+}
+
+/// Get the (start, end) working-tree snapshot ids for a session
+pub fn get_session_snapshots(session_id: &str) -> Result<(Option<String>, Option<String>)> {
+    with_connection_mut(|conn| {
+        conn.query_row(
+            "SELECT start_snapshot_tree, end_snapshot_tree FROM agent_sessions WHERE session_id = ?",
+            params![session_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    })
+}
+
+/// A session that ran inside a repository, for commit linking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositorySession {
+    pub session_id: String,
+    pub provider: String,
+    pub file_path: String,
+    pub cwd: String,
+    pub git_branch: Option<String>,
+    pub session_start_time: Option<i64>,
+    pub session_end_time: Option<i64>,
+}
+
+/// Get all sessions whose cwd is `repo_root` or a directory inside it
+pub fn get_sessions_for_repository(repo_root: &str) -> Result<Vec<RepositorySession>> {
+    let root = repo_root.trim_end_matches('/');
+
+    with_connection_mut(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT session_id, provider, file_path, cwd, git_branch,
+                    session_start_time, session_end_time
+             FROM agent_sessions
+             WHERE cwd = ?1 OR cwd LIKE ?2
+             ORDER BY session_start_time ASC",
+        )?;
+
+        let rows = stmt.query_map(params![root, format!("{}/%", root)], |row| {
+            Ok(RepositorySession {
+                session_id: row.get(0)?,
+                provider: row.get(1)?,
+                file_path: row.get(2)?,
+                cwd: row.get(3)?,
+                git_branch: row.get(4)?,
+                session_start_time: row.get(5)?,
+                session_end_time: row.get(6)?,
+            })
+        })?;
+
+        rows.collect()
+    })
+}
+
+/// Minimal project row used for identity reconciliation
+#[derive(Debug, Clone)]
+pub struct ProjectRecord {
+    pub id: String,
+    pub cwd: String,
+    pub github_repo: Option<String>,
+    pub created_at: i64,
+}
+
+/// Get every project row (id, cwd, remote, creation time)
+pub fn get_project_records() -> Result<Vec<ProjectRecord>> {
+    let db_conn = get_db_connection()?;
+    let conn = db_conn
+        .as_ref()
+        .ok_or_else(|| rusqlite::Error::InvalidQuery)?;
+
+    let mut stmt =
+        conn.prepare("SELECT id, cwd, github_repo, created_at FROM projects ORDER BY created_at")?;
+    let records = stmt
+        .query_map([], |row| {
+            Ok(ProjectRecord {
+                id: row.get(0)?,
+                cwd: row.get(1)?,
+                github_repo: row.get(2)?,
+                created_at: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(records)
+}
+
+/// Get every distinct working directory recorded on a session
+pub fn get_distinct_session_cwds() -> Result<Vec<String>> {
+    let db_conn = get_db_connection()?;
+    let conn = db_conn
+        .as_ref()
+        .ok_or_else(|| rusqlite::Error::InvalidQuery)?;
+
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT cwd FROM agent_sessions WHERE cwd IS NOT NULL AND cwd != ''")?;
+    let cwds = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>>>()?;
+
+    Ok(cwds)
+}
+
+/// Apply a project identity plan in one transaction
+///
+/// Sessions of duplicate projects move to the canonical project, duplicates are
+/// deleted, and the canonical row takes the identity key and repository root.
+/// Returns (projects merged, sessions relinked).
+pub fn apply_project_identity_plan(
+    plan: &crate::project_identity::ProjectIdentityPlan,
+) -> Result<(usize, usize)> {
+    with_connection_mut(|conn| {
+        let tx = conn.transaction()?;
+        let now = Utc::now().timestamp_millis();
+        let mut merged = 0;
+        let mut relinked = 0;
+
+        for merge in &plan.merges {
+            for duplicate_id in &merge.duplicate_ids {
+                relinked += tx.execute(
+                    "UPDATE agent_sessions
+                     SET project_id = ?1, project_name = (SELECT name FROM projects WHERE id = ?1)
+                     WHERE project_id = ?2",
+                    params![merge.canonical_id, duplicate_id],
+                )?;
+                merged += tx.execute("DELETE FROM projects WHERE id = ?", params![duplicate_id])?;
+            }
+
+            tx.execute(
+                "UPDATE projects SET identity_key = ?1 WHERE id = ?2",
+                params![merge.identity_key, merge.canonical_id],
+            )?;
+
+            // Move the project to the repository root unless another project already owns it
+            tx.execute(
+                "UPDATE projects SET cwd = ?1, updated_at = ?2
+                 WHERE id = ?3 AND cwd != ?1
+                   AND NOT EXISTS (SELECT 1 FROM projects WHERE cwd = ?1 AND id != ?3)",
+                params![merge.root, now, merge.canonical_id],
+            )?;
+        }
+
+        for (cwd, project_id) in &plan.session_links {
+            relinked += tx.execute(
+                "UPDATE agent_sessions
+                 SET project_id = ?1, project_name = (SELECT name FROM projects WHERE id = ?1)
+                 WHERE cwd = ?2 AND (project_id IS NULL OR project_id != ?1)",
+                params![project_id, cwd],
+            )?;
+        }
+
+        tx.commit()?;
+
+        if merged > 0 {
+            if let Ok(app_handle_guard) = APP_HANDLE.lock() {
+                if let Some(ref app_handle) = *app_handle_guard {
+                    let _ = app_handle.emit("project-updated", "");
+                }
+            }
+        }
+
+        Ok((merged, relinked))
+    })
+}
+
+/// Session fields the retention engine decides on
+#[derive(Debug, Clone)]
+pub struct RetentionSession {
+    pub session_id: String,
+    pub provider: String,
+    pub project_name: String,
+    pub project_id: Option<String>,
+    pub file_path: String,
+    pub file_size: i64,
+    /// Latest of session end, start or row creation (ms since epoch)
+    pub last_activity: i64,
+    pub synced: bool,
+    pub rated: bool,
+}
+
+/// Get every session with the fields needed for retention decisions
+pub fn get_retention_sessions() -> Result<Vec<RetentionSession>> {
+    let db_conn = get_db_connection()?;
+    let conn = db_conn
+        .as_ref()
+        .ok_or_else(|| rusqlite::Error::InvalidQuery)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT s.session_id, s.provider, s.project_name, s.project_id, s.file_path, s.file_size,
+                COALESCE(s.session_end_time, s.session_start_time, s.created_at),
+                COALESCE(s.synced_to_server, 0),
+                EXISTS (SELECT 1 FROM session_assessments a
+                        WHERE a.session_id = s.session_id AND a.rating IS NOT NULL)
+         FROM agent_sessions s",
+    )?;
+
+    let sessions = stmt
+        .query_map([], |row| {
+            Ok(RetentionSession {
+                session_id: row.get(0)?,
+                provider: row.get(1)?,
+                project_name: row.get(2)?,
+                project_id: row.get(3)?,
+                file_path: row.get(4)?,
+                file_size: row.get(5)?,
+                last_activity: row.get(6)?,
+                synced: row.get::<_, i64>(7)? == 1,
+                rated: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(sessions)
+}
+
+/// Tables keyed by session_id that hang off agent_sessions
+///
+/// Their `ON DELETE CASCADE` clauses are inert (foreign_keys is off), so deletes
+/// have to clear them explicitly.
+const SESSION_CHILD_TABLES: [&str; 4] = [
+    "session_metrics",
+    "session_assessments",
+    "session_relations",
+    "session_conversion_stats",
+];
+
+/// Delete the sessions matching `condition` (a WHERE clause on agent_sessions)
+/// and everything hanging off them
+pub fn delete_sessions_where(
+    condition: &str,
+    condition_params: &[&dyn rusqlite::ToSql],
+) -> Result<usize> {
+    crate::git_snapshot::remove_snapshots_where(condition, condition_params);
+
+    with_connection_mut(|conn| {
+        let tx = conn.transaction()?;
+
+        for table in SESSION_CHILD_TABLES {
+            tx.execute(
+                &format!(
+                    "DELETE FROM {} WHERE session_id IN
+                     (SELECT session_id FROM agent_sessions WHERE {})",
+                    table, condition
+                ),
+                condition_params,
+            )?;
+        }
+        let deleted = tx.execute(
+            &format!("DELETE FROM agent_sessions WHERE {}", condition),
+            condition_params,
+        )?;
+
+        tx.commit()?;
+        Ok(deleted)
+    })
+}
+
+/// Delete sessions and everything hanging off them (metrics, assessments, relations,
+/// git snapshot refs)
+pub fn delete_sessions(session_ids: &[String]) -> Result<usize> {
+    for session_id in session_ids {
+        crate::git_snapshot::remove_snapshots_where("session_id = ?", &[session_id]);
+    }
+
+    with_connection_mut(|conn| {
+        let tx = conn.transaction()?;
+        let mut deleted = 0;
+
+        for session_id in session_ids {
+            for table in SESSION_CHILD_TABLES {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE session_id = ?", table),
+                    params![session_id],
+                )?;
+            }
+            deleted += tx.execute(
+                "DELETE FROM agent_sessions WHERE session_id = ?",
+                params![session_id],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(deleted)
+    })
+}
+
+/// Remember sessions deleted by retention, as `(session_id, provider)` pairs
+pub fn record_deleted_sessions(sessions: &[(String, String)]) -> Result<()> {
+    with_connection_mut(|conn| {
+        let tx = conn.transaction()?;
+        for (session_id, provider) in sessions {
+            tx.execute(
+                "INSERT OR REPLACE INTO deleted_sessions (session_id, provider, deleted_at)
+                 VALUES (?, ?, ?)",
+                params![session_id, provider, Utc::now().timestamp_millis()],
+            )?;
+        }
+        tx.commit()
+    })
+}
+
+/// Sessions of a provider that retention deleted and rescans must skip
+pub fn get_deleted_session_ids(provider: &str) -> Result<std::collections::HashSet<String>> {
+    with_connection_mut(|conn| {
+        let mut stmt =
+            conn.prepare("SELECT session_id FROM deleted_sessions WHERE provider = ?")?;
+        let ids = stmt
+            .query_map(params![provider], |row| row.get(0))?
+            .collect::<Result<_>>()?;
+        Ok(ids)
+    })
+}
+
+/// Point a session at a new file (e.g. after compressing it to .jsonl.gz)
+///
+/// `file_size` keeps the uncompressed size so size-based change detection is unaffected.
+pub fn update_session_file_path(session_id: &str, file_path: &str) -> Result<()> {
+    with_connection_mut(|conn| {
+        conn.execute(
+            "UPDATE agent_sessions SET file_path = ? WHERE session_id = ?",
+            params![file_path, session_id],
+        )?;
+        Ok(())
+    })
+}
+
+/// Store the conversion fidelity stats of a session, replacing earlier runs
+pub fn save_conversion_stats(
+    session_id: &str,
+    provider: &str,
+    stats: &crate::providers::canonical::fidelity::ConversionStats,
+) -> Result<()> {
+    let details = serde_json::to_string(stats)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+    with_connection_mut(|conn| {
+        conn.execute(
+            "INSERT INTO session_conversion_stats
+                (session_id, provider, records_seen, converted, skipped, failed, unknown, details, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(session_id) DO UPDATE SET
+                provider = excluded.provider,
+                records_seen = excluded.records_seen,
+                converted = excluded.converted,
+                skipped = excluded.skipped,
+                failed = excluded.failed,
+                unknown = excluded.unknown,
+                details = excluded.details,
+                updated_at = excluded.updated_at",
+            params![
+                session_id,
+                provider,
+                stats.records_seen as i64,
+                stats.converted as i64,
+                stats.skipped_total() as i64,
+                stats.failed as i64,
+                stats.unknown_total() as i64,
+                details,
+                Utc::now().timestamp_millis()
+            ],
+        )?;
+        Ok(())
+    })
+}
+
+/// Conversion fidelity stats of a single session, if it has been converted
+pub fn get_conversion_stats(
+    session_id: &str,
+) -> Result<Option<crate::providers::canonical::fidelity::ConversionStats>> {
+    with_connection_mut(|conn| {
+        let details: Option<String> = conn
+            .query_row(
+                "SELECT details FROM session_conversion_stats WHERE session_id = ?",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .ok();
+
+        Ok(details.and_then(|json| serde_json::from_str(&json).ok()))
+    })
+}
+
+/// Conversion fidelity summed over every session of a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderConversionStats {
+    pub provider: String,
+    pub sessions: usize,
+    /// Sessions with failed records or unrecognized record types
+    pub sessions_with_errors: usize,
+    pub totals: crate::providers::canonical::fidelity::ConversionStats,
+}
+
+pub fn get_provider_conversion_stats(provider: &str) -> Result<ProviderConversionStats> {
+    with_connection_mut(|conn| {
+        let mut stmt =
+            conn.prepare("SELECT details FROM session_conversion_stats WHERE provider = ?")?;
+        let rows = stmt.query_map(params![provider], |row| row.get::<_, String>(0))?;
+
+        let mut summary = ProviderConversionStats {
+            provider: provider.to_string(),
+            sessions: 0,
+            sessions_with_errors: 0,
+            totals: Default::default(),
+        };
+        for details in rows {
+            let Ok(stats) = serde_json::from_str::<
+                crate::providers::canonical::fidelity::ConversionStats,
+            >(&details?) else {
+                continue;
+            };
+            summary.sessions += 1;
+            if !stats.is_clean() {
+                summary.sessions_with_errors += 1;
+            }
+            summary.totals.merge(&stats);
+        }
+
+        Ok(summary)
+    })
+}
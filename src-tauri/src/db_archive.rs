@@ -0,0 +1,1162 @@
+//! Portable JSON export/import of the local database
+//!
+//! Assessments, quick ratings and phase analysis only exist in `guideai.db`,
+//! so moving to a new machine loses them. An export writes every table plus
+//! the canonical session files into one versioned JSON archive (gzipped when
+//! the target path ends in `.gz`). An import merges that archive into the
+//! existing database by `session_id`:
+//!
+//! * sessions missing locally are added, with their metrics, assessments and
+//!   relations
+//! * sessions present on both sides are resolved by a [`ConflictPolicy`]; the
+//!   losing side's rows are left untouched, but the imported rows still fill
+//!   gaps (e.g. an assessment that only exists in the archive)
+//! * projects are matched by identity key or working directory
+//! * paths under the exporting machine's home directory are remapped to the
+//!   current one, and session files are only written inside
+//!   `~/.guideai/sessions`
+//! * git snapshot trees are dropped, since they only exist in the exporting
+//!   machine's repositories
+
+use crate::database::with_connection_mut;
+use crate::db_backup::TableSize;
+use crate::logging::{log_info, log_warn};
+use crate::providers::common::resolve_session_file;
+use crate::schema;
+use base64::{engine::general_purpose, Engine as _};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Bumped whenever the archive layout changes incompatibly
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// Tables keyed by `session_id` that follow their session on import
+const SESSION_TABLES: &[&str] = &[
+    "session_metrics",
+    "session_assessments",
+    "session_relations",
+    "session_conversion_stats",
+];
+
+/// Bookkeeping tables that describe this database rather than its sessions
+const LOCAL_TABLES: &[&str] = &["analytics_state", "deleted_sessions"];
+
+/// Columns that hold absolute paths and are remapped to the new home directory
+const PATH_COLUMNS: &[(&str, &str)] = &[
+    ("agent_sessions", "file_path"),
+    ("agent_sessions", "cwd"),
+    ("projects", "cwd"),
+];
+
+/// Session columns that only mean something on the exporting machine
+const MACHINE_LOCAL_COLUMNS: &[&str] = &["start_snapshot_tree", "end_snapshot_tree"];
+
+/// Columns whose maximum tells how recently a session was touched
+const FRESHNESS_COLUMNS: &[&str] = &[
+    "session_end_time",
+    "processed_at",
+    "core_metrics_processed_at",
+    "assessment_completed_at",
+    "synced_at",
+    "uploaded_at",
+];
+
+type Row = Map<String, Value>;
+
+/// A canonical session file, stored verbatim (compressed files stay compressed)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFile {
+    pub session_id: String,
+    /// Absolute path on the exporting machine
+    pub path: String,
+    /// Base64 of the raw file bytes
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Archive {
+    pub format_version: u32,
+    /// Highest migration applied to the exporting database
+    pub schema_version: i64,
+    pub exported_at: i64,
+    /// Home directory of the exporting machine, used to remap paths
+    #[serde(default)]
+    pub home_dir: Option<String>,
+    /// Rows per table, as column name → value
+    pub tables: BTreeMap<String, Vec<Row>>,
+    #[serde(default)]
+    pub files: Vec<ArchiveFile>,
+}
+
+/// What to do with a session that exists both locally and in the archive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Keep whichever side was updated more recently
+    #[default]
+    KeepNewest,
+    KeepLocal,
+    KeepImported,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub path: String,
+    pub tables: Vec<TableSize>,
+    pub files: usize,
+    pub missing_files: usize,
+    pub bytes_written: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub sessions_added: usize,
+    pub sessions_replaced: usize,
+    pub sessions_kept: usize,
+    pub projects_added: usize,
+    pub projects_matched: usize,
+    /// Rows inserted into metrics, assessments and relations
+    pub related_rows_imported: usize,
+    /// Empty columns of kept sessions filled from the archive (e.g. AI analysis)
+    pub session_columns_filled: usize,
+    pub files_written: usize,
+    pub files_skipped: usize,
+    /// Archive tables this version does not know how to merge
+    pub unknown_tables: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Result of merging the tables; `winners` are sessions whose files should be written
+struct MergeOutcome {
+    summary: ImportSummary,
+    winners: HashSet<String>,
+}
+
+fn to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).to_string()),
+        // No table stores blobs today; keep them lossless anyway
+        ValueRef::Blob(b) => Value::String(general_purpose::STANDARD.encode(b)),
+    }
+}
+
+fn to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => n
+            .as_i64()
+            .map(SqlValue::Integer)
+            .or_else(|| n.as_f64().map(SqlValue::Real))
+            .unwrap_or(SqlValue::Null),
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn user_tables(conn: &Connection) -> Result<Vec<String>, rusqlite::Error> {
+    let tables: Vec<String> = conn
+        .prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != '_sqlx_migrations' ORDER BY name",
+        )?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(tables
+        .into_iter()
+        .filter(|table| !LOCAL_TABLES.contains(&table.as_str()))
+        .collect())
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<HashSet<String>, rusqlite::Error> {
+    conn.prepare(&format!("PRAGMA table_info(\"{}\")", table))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect()
+}
+
+fn row_to_map(row: &rusqlite::Row<'_>, names: &[String]) -> Result<Row, rusqlite::Error> {
+    let mut map = Row::new();
+    for (i, name) in names.iter().enumerate() {
+        map.insert(name.clone(), to_json(row.get_ref(i)?));
+    }
+    Ok(map)
+}
+
+/// Highest applied migration; databases without plugin history are assumed current
+fn current_schema_version(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1",
+        [],
+        |row| row.get::<_, Option<i64>>(0),
+    )
+    .ok()
+    .flatten()
+    .unwrap_or_else(schema::latest_version)
+}
+
+/// Session files referenced by the exported `agent_sessions` rows
+type SessionFiles = Vec<(String, String)>;
+
+fn write_err(e: impl std::fmt::Display) -> String {
+    format!("Failed to write archive: {}", e)
+}
+
+/// Stream the archive header and every table into `out`, one row at a time
+///
+/// Leaves the top-level object open for [`write_files`].
+fn write_tables<W: Write>(
+    conn: &Connection,
+    home_dir: Option<&str>,
+    out: &mut W,
+) -> Result<(Vec<TableSize>, SessionFiles), String> {
+    let read_err = |e: rusqlite::Error| format!("Failed to read database: {}", e);
+
+    write!(
+        out,
+        "{{\"formatVersion\":{},\"schemaVersion\":{},\"exportedAt\":{},\"homeDir\":{},\"tables\":{{",
+        ARCHIVE_FORMAT_VERSION,
+        current_schema_version(conn),
+        chrono::Utc::now().timestamp_millis(),
+        serde_json::to_string(&home_dir).map_err(write_err)?
+    )
+    .map_err(write_err)?;
+
+    let mut sizes = Vec::new();
+    let mut files = SessionFiles::new();
+    for (i, table) in user_tables(conn).map_err(read_err)?.into_iter().enumerate() {
+        if i > 0 {
+            out.write_all(b",").map_err(write_err)?;
+        }
+        serde_json::to_writer(&mut *out, &table).map_err(write_err)?;
+        out.write_all(b":[").map_err(write_err)?;
+
+        let mut stmt = conn
+            .prepare(&format!("SELECT * FROM \"{}\"", table))
+            .map_err(read_err)?;
+        let names: Vec<String> = stmt.column_names().iter().map(|n| n.to_string()).collect();
+        let mut rows = stmt.query([]).map_err(read_err)?;
+        let mut count: i64 = 0;
+        while let Some(row) = rows.next().map_err(read_err)? {
+            let row = row_to_map(row, &names).map_err(read_err)?;
+            if count > 0 {
+                out.write_all(b",").map_err(write_err)?;
+            }
+            serde_json::to_writer(&mut *out, &row).map_err(write_err)?;
+            count += 1;
+
+            if table == "agent_sessions" {
+                if let (Some(session_id), Some(file_path)) = (
+                    string_field(&row, "session_id"),
+                    string_field(&row, "file_path"),
+                ) {
+                    files.push((session_id.to_string(), file_path.to_string()));
+                }
+            }
+        }
+
+        out.write_all(b"]").map_err(write_err)?;
+        sizes.push(TableSize {
+            name: table,
+            rows: count,
+        });
+    }
+
+    out.write_all(b"}").map_err(write_err)?;
+    Ok((sizes, files))
+}
+
+/// Stream the session files into `out` and close the archive, returning
+/// (files written, files missing)
+fn write_files<W: Write>(files: &SessionFiles, out: &mut W) -> Result<(usize, usize), String> {
+    out.write_all(b",\"files\":[").map_err(write_err)?;
+
+    let mut written = 0;
+    let mut missing = 0;
+    for (session_id, file_path) in files {
+        let resolved = resolve_session_file(Path::new(file_path));
+        let Ok(bytes) = fs::read(&resolved) else {
+            missing += 1;
+            continue;
+        };
+        if written > 0 {
+            out.write_all(b",").map_err(write_err)?;
+        }
+        let file = ArchiveFile {
+            session_id: session_id.clone(),
+            path: resolved.to_string_lossy().to_string(),
+            content: general_purpose::STANDARD.encode(bytes),
+        };
+        serde_json::to_writer(&mut *out, &file).map_err(write_err)?;
+        written += 1;
+    }
+
+    out.write_all(b"]}").map_err(write_err)?;
+    Ok((written, missing))
+}
+
+/// Write the whole archive to `out`
+///
+/// Tables are read under the database lock; session files are read
+/// afterwards, one at a time.
+fn write_archive<W: Write>(out: &mut W) -> Result<(Vec<TableSize>, usize, usize), String> {
+    let home_dir = home_dir_string();
+    let (tables, files) =
+        with_connection_mut(|conn| Ok(write_tables(conn, home_dir.as_deref(), out)))
+            .map_err(|e| format!("Failed to read database: {}", e))??;
+    let (written, missing) = write_files(&files, out)?;
+    Ok((tables, written, missing))
+}
+
+/// Resolve symlinks in the part of `path` that exists, keeping the rest as is
+///
+/// Relative paths and `..` components are rejected outright.
+fn resolve_existing(path: &Path) -> Option<PathBuf> {
+    if !path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+        return None;
+    }
+
+    let mut existing = path;
+    let mut missing = Vec::new();
+    while fs::symlink_metadata(existing).is_err() {
+        missing.push(existing.file_name()?);
+        existing = existing.parent()?;
+    }
+
+    let mut resolved = existing.canonicalize().ok()?;
+    resolved.extend(missing.iter().rev());
+    Some(resolved)
+}
+
+/// `path` resolved, if it lies strictly inside `root`
+fn contained_path(path: &Path, root: &Path) -> Option<PathBuf> {
+    let path = resolve_existing(path)?;
+    let root = resolve_existing(root)?;
+    (path != root && path.starts_with(&root)).then_some(path)
+}
+
+/// Rewrite `path` from one home directory to another, converting separators
+/// when the machines differ (e.g. Windows → macOS)
+fn remap_home(path: &str, from: Option<&str>, to: Option<&str>) -> String {
+    let (Some(from), Some(to)) = (from, to) else {
+        return path.to_string();
+    };
+    let from = from.trim_end_matches(['/', '\\']);
+    let Some(rest) = path.strip_prefix(from) else {
+        return path.to_string();
+    };
+    if !rest.is_empty() && !rest.starts_with(['/', '\\']) {
+        // `/home/al` must not match `/home/alice`
+        return path.to_string();
+    }
+
+    let separator = if to.contains('\\') && !to.contains('/') {
+        '\\'
+    } else {
+        '/'
+    };
+    let rest: String = rest
+        .chars()
+        .map(|c| if c == '/' || c == '\\' { separator } else { c })
+        .collect();
+    format!("{}{}", to.trim_end_matches(['/', '\\']), rest)
+}
+
+fn remap_row(table: &str, row: &mut Row, from: Option<&str>, to: Option<&str>) {
+    for (path_table, column) in PATH_COLUMNS {
+        if *path_table != table {
+            continue;
+        }
+        if let Some(Value::String(path)) = row.get(*column) {
+            let remapped = remap_home(path, from, to);
+            row.insert(column.to_string(), Value::String(remapped));
+        }
+    }
+}
+
+fn string_field<'a>(row: &'a Row, column: &str) -> Option<&'a str> {
+    row.get(column).and_then(Value::as_str)
+}
+
+fn freshness(row: &Row) -> i64 {
+    FRESHNESS_COLUMNS
+        .iter()
+        .filter_map(|column| row.get(*column).and_then(Value::as_i64))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Insert the columns of `row` the local table knows about, returning rows inserted
+fn insert_row(
+    conn: &Connection,
+    table: &str,
+    columns: &HashSet<String>,
+    row: &Row,
+) -> Result<usize, rusqlite::Error> {
+    let (names, values): (Vec<&String>, Vec<SqlValue>) = row
+        .iter()
+        .filter(|(name, _)| columns.contains(*name))
+        .map(|(name, value)| (name, to_sql(value)))
+        .unzip();
+    if names.is_empty() {
+        return Ok(0);
+    }
+
+    let sql = format!(
+        "INSERT OR IGNORE INTO \"{}\" ({}) VALUES ({})",
+        table,
+        names
+            .iter()
+            .map(|n| format!("\"{}\"", n))
+            .collect::<Vec<_>>()
+            .join(", "),
+        vec!["?"; names.len()].join(", ")
+    );
+    conn.execute(&sql, params_from_iter(values))
+}
+
+/// Copy the archive's values into columns that are NULL on the kept local row
+///
+/// A re-scan on the new machine recreates the row without AI analysis, so
+/// without this the archive's analysis would be lost whenever local wins.
+fn fill_null_columns(
+    conn: &Connection,
+    columns: &HashSet<String>,
+    session_id: &str,
+    imported: &Row,
+) -> Result<usize, rusqlite::Error> {
+    let Some(local) = dump_session(conn, session_id)? else {
+        return Ok(0);
+    };
+
+    let (names, values): (Vec<&String>, Vec<SqlValue>) = imported
+        .iter()
+        .filter(|(name, value)| {
+            name.as_str() != "id"
+                && columns.contains(*name)
+                && !value.is_null()
+                && local.get(*name).is_none_or(Value::is_null)
+        })
+        .map(|(name, value)| (name, to_sql(value)))
+        .unzip();
+    if names.is_empty() {
+        return Ok(0);
+    }
+
+    let sql = format!(
+        "UPDATE agent_sessions SET {} WHERE session_id = ?",
+        names
+            .iter()
+            .map(|n| format!("\"{}\" = ?", n))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let params = values
+        .into_iter()
+        .chain([SqlValue::Text(session_id.to_string())]);
+    conn.execute(&sql, params_from_iter(params))?;
+    Ok(names.len())
+}
+
+fn delete_local_session(conn: &Connection, session_id: &str) -> Result<(), rusqlite::Error> {
+    for table in SESSION_TABLES {
+        conn.execute(
+            &format!("DELETE FROM {} WHERE session_id = ?", table),
+            params![session_id],
+        )?;
+    }
+    conn.execute(
+        "DELETE FROM agent_sessions WHERE session_id = ?",
+        params![session_id],
+    )?;
+    Ok(())
+}
+
+/// Map archive project ids to local ones, inserting projects that are new here
+fn merge_projects(
+    conn: &Connection,
+    archive: &Archive,
+    home_dir: Option<&str>,
+    summary: &mut ImportSummary,
+) -> Result<HashMap<String, String>, rusqlite::Error> {
+    let mut id_map = HashMap::new();
+    let Some(rows) = archive.tables.get("projects") else {
+        return Ok(id_map);
+    };
+    let columns = table_columns(conn, "projects")?;
+
+    for row in rows {
+        let mut row = row.clone();
+        remap_row("projects", &mut row, archive.home_dir.as_deref(), home_dir);
+        let Some(imported_id) = string_field(&row, "id").map(str::to_string) else {
+            continue;
+        };
+
+        let by_identity = match string_field(&row, "identity_key") {
+            Some(key) if columns.contains("identity_key") => conn
+                .query_row(
+                    "SELECT id FROM projects WHERE identity_key = ? LIMIT 1",
+                    params![key],
+                    |r| r.get::<_, String>(0),
+                )
+                .optional()?,
+            _ => None,
+        };
+        let local_id = match by_identity {
+            Some(id) => Some(id),
+            None => conn
+                .query_row(
+                    "SELECT id FROM projects WHERE cwd = ?",
+                    params![string_field(&row, "cwd")],
+                    |r| r.get::<_, String>(0),
+                )
+                .optional()?,
+        };
+
+        if let Some(local_id) = local_id {
+            summary.projects_matched += 1;
+            id_map.insert(imported_id, local_id);
+            continue;
+        }
+
+        let id_taken: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?)",
+            params![imported_id],
+            |r| r.get(0),
+        )?;
+        let new_id = if id_taken {
+            uuid::Uuid::new_v4().to_string()
+        } else {
+            imported_id.clone()
+        };
+        row.insert("id".to_string(), Value::String(new_id.clone()));
+        if insert_row(conn, "projects", &columns, &row)? > 0 {
+            summary.projects_added += 1;
+        }
+        id_map.insert(imported_id, new_id);
+    }
+
+    Ok(id_map)
+}
+
+/// Merge archive rows into `conn` (expected to be inside a transaction)
+fn merge_archive(
+    conn: &Connection,
+    archive: &Archive,
+    policy: ConflictPolicy,
+    home_dir: Option<&str>,
+    sessions_root: &Path,
+) -> Result<MergeOutcome, rusqlite::Error> {
+    let mut summary = ImportSummary::default();
+    let mut winners = HashSet::new();
+    let mut kept = HashSet::new();
+
+    let local_tables: HashSet<String> = user_tables(conn)?.into_iter().collect();
+    summary.unknown_tables = archive
+        .tables
+        .keys()
+        .filter(|t| {
+            !local_tables.contains(*t)
+                || !(*t == "projects"
+                    || *t == "agent_sessions"
+                    || SESSION_TABLES.contains(&t.as_str()))
+        })
+        .cloned()
+        .collect();
+
+    let project_ids = merge_projects(conn, archive, home_dir, &mut summary)?;
+
+    let session_columns = table_columns(conn, "agent_sessions")?;
+    for row in archive.tables.get("agent_sessions").into_iter().flatten() {
+        let mut row = row.clone();
+        remap_row(
+            "agent_sessions",
+            &mut row,
+            archive.home_dir.as_deref(),
+            home_dir,
+        );
+        let Some(session_id) = string_field(&row, "session_id").map(str::to_string) else {
+            continue;
+        };
+        let file_path = string_field(&row, "file_path").map(Path::new);
+        if file_path
+            .and_then(|path| contained_path(path, sessions_root))
+            .is_none()
+        {
+            summary.errors.push(format!(
+                "{}: file path is outside the sessions directory",
+                session_id
+            ));
+            continue;
+        }
+        for column in MACHINE_LOCAL_COLUMNS {
+            row.remove(*column);
+        }
+        if let Some(local_project) = string_field(&row, "project_id")
+            .and_then(|id| project_ids.get(id))
+            .cloned()
+        {
+            row.insert("project_id".to_string(), Value::String(local_project));
+        }
+
+        let local = conn
+            .query_row(
+                "SELECT id FROM agent_sessions WHERE session_id = ?",
+                params![session_id],
+                |r| r.get::<_, String>(0),
+            )
+            .optional()?;
+
+        let Some(local_id) = local else {
+            if insert_row(conn, "agent_sessions", &session_columns, &row)? > 0 {
+                summary.sessions_added += 1;
+                winners.insert(session_id);
+            }
+            continue;
+        };
+
+        let take_imported = match policy {
+            ConflictPolicy::KeepLocal => false,
+            ConflictPolicy::KeepImported => true,
+            ConflictPolicy::KeepNewest => {
+                let local_row = dump_session(conn, &session_id)?;
+                freshness(&row) > local_row.as_ref().map(freshness).unwrap_or(0)
+            }
+        };
+
+        if take_imported {
+            delete_local_session(conn, &session_id)?;
+            row.insert("id".to_string(), Value::String(local_id));
+            insert_row(conn, "agent_sessions", &session_columns, &row)?;
+            summary.sessions_replaced += 1;
+            winners.insert(session_id);
+        } else {
+            summary.session_columns_filled +=
+                fill_null_columns(conn, &session_columns, &session_id, &row)?;
+            summary.sessions_kept += 1;
+            kept.insert(session_id);
+        }
+    }
+
+    for table in SESSION_TABLES {
+        let Some(rows) = archive.tables.get(*table) else {
+            continue;
+        };
+        if !local_tables.contains(*table) {
+            continue;
+        }
+        let columns = table_columns(conn, table)?;
+        let mut gap_checked: HashMap<String, bool> = HashMap::new();
+
+        for row in rows {
+            let Some(session_id) = string_field(row, "session_id") else {
+                continue;
+            };
+            let insert = if winners.contains(session_id) {
+                true
+            } else if kept.contains(session_id) {
+                // Only fill in what the local side doesn't have at all
+                match gap_checked.get(session_id) {
+                    Some(empty) => *empty,
+                    None => {
+                        let empty = !conn.query_row(
+                            &format!(
+                                "SELECT EXISTS(SELECT 1 FROM {} WHERE session_id = ?)",
+                                table
+                            ),
+                            params![session_id],
+                            |r| r.get::<_, bool>(0),
+                        )?;
+                        gap_checked.insert(session_id.to_string(), empty);
+                        empty
+                    }
+                }
+            } else {
+                false
+            };
+
+            if insert {
+                summary.related_rows_imported += insert_row(conn, table, &columns, row)?;
+            }
+        }
+    }
+
+    Ok(MergeOutcome { summary, winners })
+}
+
+fn dump_session(conn: &Connection, session_id: &str) -> Result<Option<Row>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT * FROM agent_sessions WHERE session_id = ?")?;
+    let names: Vec<String> = stmt.column_names().iter().map(|n| n.to_string()).collect();
+    stmt.query_row(params![session_id], |row| row_to_map(row, &names))
+        .optional()
+}
+
+fn home_dir_string() -> Option<String> {
+    dirs::home_dir().map(|home| home.to_string_lossy().to_string())
+}
+
+/// Write every table and canonical session file to `path`
+///
+/// The archive is streamed to disk (through gzip when `path` ends in `.gz`)
+/// rather than built in memory, so large histories export in constant memory.
+pub fn export_archive(path: &str) -> Result<ExportSummary, String> {
+    if let Some(parent) = Path::new(path).parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    let file = BufWriter::new(fs::File::create(path).map_err(write_err)?);
+
+    let (tables, files, missing_files) = if path.ends_with(".gz") {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        let counts = write_archive(&mut encoder)?;
+        encoder
+            .finish()
+            .and_then(|mut file| file.flush())
+            .map_err(|e| format!("Failed to compress archive: {}", e))?;
+        counts
+    } else {
+        let mut file = file;
+        let counts = write_archive(&mut file)?;
+        file.flush().map_err(write_err)?;
+        counts
+    };
+    let bytes_written = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+
+    let sessions = tables
+        .iter()
+        .find(|t| t.name == "agent_sessions")
+        .map_or(0, |t| t.rows);
+    let _ = log_info(
+        "database",
+        &format!(
+            "📦 Exported {} sessions and {} files to {}",
+            sessions, files, path
+        ),
+    );
+
+    Ok(ExportSummary {
+        path: path.to_string(),
+        tables,
+        files,
+        missing_files,
+        bytes_written,
+    })
+}
+
+/// Read an archive, gzipped or plain
+pub fn read_archive(path: &str) -> Result<Archive, String> {
+    let raw = fs::read(path).map_err(|e| format!("Failed to read archive: {}", e))?;
+    let json = if raw.starts_with(&[0x1f, 0x8b]) {
+        let mut decoded = Vec::new();
+        GzDecoder::new(raw.as_slice())
+            .read_to_end(&mut decoded)
+            .map_err(|e| format!("Failed to decompress archive: {}", e))?;
+        decoded
+    } else {
+        raw
+    };
+
+    let archive: Archive =
+        serde_json::from_slice(&json).map_err(|e| format!("Invalid archive: {}", e))?;
+    if archive.format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(format!(
+            "Archive format {} is newer than this app supports ({})",
+            archive.format_version, ARCHIVE_FORMAT_VERSION
+        ));
+    }
+    if archive.schema_version > schema::latest_version() {
+        return Err(format!(
+            "Archive uses schema version {} but this app only knows up to {}; update the app first",
+            archive.schema_version,
+            schema::latest_version()
+        ));
+    }
+    Ok(archive)
+}
+
+/// Merge an archive into the local database and restore its session files
+pub fn import_archive(path: &str, policy: ConflictPolicy) -> Result<ImportSummary, String> {
+    let archive = read_archive(path)?;
+    let home_dir = home_dir_string();
+    let sessions_root = crate::config::get_config_dir()
+        .map_err(|e| format!("Failed to locate config directory: {}", e))?
+        .join("sessions");
+
+    let outcome = with_connection_mut(|conn| {
+        let tx = conn.transaction()?;
+        let outcome = merge_archive(&tx, &archive, policy, home_dir.as_deref(), &sessions_root)?;
+        tx.commit()?;
+        Ok(outcome)
+    })
+    .map_err(|e| format!("Failed to merge archive: {}", e))?;
+    let MergeOutcome {
+        mut summary,
+        winners,
+    } = outcome;
+
+    for file in &archive.files {
+        if !winners.contains(&file.session_id) {
+            continue;
+        }
+        let target = remap_home(&file.path, archive.home_dir.as_deref(), home_dir.as_deref());
+        let Some(target) = contained_path(Path::new(&target), &sessions_root) else {
+            summary.errors.push(format!(
+                "{}: refusing to write {} outside the sessions directory",
+                file.session_id, target
+            ));
+            continue;
+        };
+        let target = target.as_path();
+        if target.exists() && policy == ConflictPolicy::KeepLocal {
+            summary.files_skipped += 1;
+            continue;
+        }
+
+        let bytes = match general_purpose::STANDARD.decode(&file.content) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                summary
+                    .errors
+                    .push(format!("{}: invalid file content: {}", file.session_id, e));
+                continue;
+            }
+        };
+        let written = target
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(target, bytes));
+        match written {
+            Ok(()) => summary.files_written += 1,
+            Err(e) => summary.errors.push(format!("{}: {}", target.display(), e)),
+        }
+    }
+
+    let _ = log_info(
+        "database",
+        &format!(
+            "📥 Imported {}: {} added, {} replaced, {} kept, {} files written",
+            path,
+            summary.sessions_added,
+            summary.sessions_replaced,
+            summary.sessions_kept,
+            summary.files_written
+        ),
+    );
+    if !summary.errors.is_empty() {
+        let _ = log_warn(
+            "database",
+            &format!("⚠ {} errors during import", summary.errors.len()),
+        );
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrated() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in schema::MIGRATIONS {
+            conn.execute_batch(migration.sql).unwrap();
+        }
+        conn
+    }
+
+    fn add_session(conn: &Connection, session_id: &str, cwd: &str, project_id: &str, end: i64) {
+        conn.execute(
+            "INSERT INTO agent_sessions (id, provider, project_name, session_id, file_name, file_path, file_size, session_end_time, project_id, cwd, created_at, uploaded_at)
+             VALUES (?1, 'claude-code', 'app', ?1, ?1, ?2, 10, ?3, ?4, ?5, 1, 1)",
+            params![
+                session_id,
+                format!("{}/.guideai/sessions/claude-code/app/{}.jsonl", cwd.rsplit_once("/code").unwrap().0, session_id),
+                end,
+                project_id,
+                cwd
+            ],
+        )
+        .unwrap();
+    }
+
+    fn add_project(conn: &Connection, id: &str, cwd: &str, identity_key: &str) {
+        conn.execute(
+            "INSERT INTO projects (id, name, cwd, type, created_at, updated_at, identity_key) VALUES (?1, 'app', ?2, 'nodejs', 1, 1, ?3)",
+            params![id, cwd, identity_key],
+        )
+        .unwrap();
+    }
+
+    fn add_rating(conn: &Connection, session_id: &str, rating: &str) {
+        conn.execute(
+            "INSERT INTO session_assessments (id, session_id, provider, responses, rating, completed_at) VALUES (?1, ?2, 'claude-code', '{}', ?3, 5)",
+            params![format!("a-{}-{}", session_id, rating), session_id, rating],
+        )
+        .unwrap();
+    }
+
+    fn ratings(conn: &Connection, session_id: &str) -> Vec<String> {
+        conn.prepare("SELECT rating FROM session_assessments WHERE session_id = ? ORDER BY rating")
+            .unwrap()
+            .query_map(params![session_id], |r| r.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    const SESSIONS_ROOT: &str = "/Users/ana/.guideai/sessions";
+
+    fn archive_of(conn: &Connection, home_dir: &str) -> Archive {
+        let mut out = Vec::new();
+        let (_, files) = write_tables(conn, Some(home_dir), &mut out).unwrap();
+        write_files(&files, &mut out).unwrap();
+        serde_json::from_slice(&out).unwrap()
+    }
+
+    fn merge(target: &Connection, archive: &Archive, policy: ConflictPolicy) -> MergeOutcome {
+        merge_archive(
+            target,
+            archive,
+            policy,
+            Some("/Users/ana"),
+            Path::new(SESSIONS_ROOT),
+        )
+        .unwrap()
+    }
+
+    fn exported() -> Archive {
+        let source = migrated();
+        add_project(
+            &source,
+            "p-old",
+            "/home/ana/code/app",
+            "git:github.com/acme/app",
+        );
+        add_session(&source, "s-shared", "/home/ana/code/app", "p-old", 200);
+        add_session(&source, "s-new", "/home/ana/code/app", "p-old", 300);
+        add_rating(&source, "s-shared", "thumbs_up");
+        add_rating(&source, "s-new", "meh");
+        archive_of(&source, "/home/ana")
+    }
+
+    fn local() -> Connection {
+        let target = migrated();
+        add_project(
+            &target,
+            "p-local",
+            "/Users/ana/code/app",
+            "git:github.com/acme/app",
+        );
+        add_session(&target, "s-shared", "/Users/ana/code/app", "p-local", 100);
+        target
+    }
+
+    #[test]
+    fn test_merge_adds_sessions_and_fills_gaps() {
+        let archive = exported();
+        assert_eq!(archive.schema_version, schema::latest_version());
+
+        let target = local();
+        let outcome = merge(&target, &archive, ConflictPolicy::KeepLocal);
+        let summary = outcome.summary;
+        assert_eq!(summary.sessions_added, 1);
+        assert_eq!(summary.sessions_kept, 1);
+        assert_eq!(summary.projects_matched, 1);
+        assert_eq!(summary.projects_added, 0);
+        assert!(summary.unknown_tables.is_empty());
+        assert!(outcome.winners.contains("s-new"));
+        assert!(!outcome.winners.contains("s-shared"));
+
+        let (file_path, project_id): (String, String) = target
+            .query_row(
+                "SELECT file_path, project_id FROM agent_sessions WHERE session_id = 's-new'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            file_path,
+            "/Users/ana/.guideai/sessions/claude-code/app/s-new.jsonl"
+        );
+        assert_eq!(project_id, "p-local");
+
+        // The kept session had no local rating, so the imported one fills the gap
+        assert_eq!(ratings(&target, "s-shared"), vec!["thumbs_up"]);
+        assert_eq!(ratings(&target, "s-new"), vec!["meh"]);
+
+        // Importing again changes nothing
+        let again = merge(&target, &archive, ConflictPolicy::KeepLocal);
+        assert_eq!(again.summary.sessions_added, 0);
+        assert_eq!(again.summary.related_rows_imported, 0);
+        assert_eq!(ratings(&target, "s-shared"), vec!["thumbs_up"]);
+    }
+
+    #[test]
+    fn test_conflict_policies() {
+        let archive = exported();
+
+        // The archive's copy ended later, so it wins under keep-newest
+        let target = local();
+        add_rating(&target, "s-shared", "thumbs_down");
+        let outcome = merge(&target, &archive, ConflictPolicy::KeepNewest);
+        assert_eq!(outcome.summary.sessions_replaced, 1);
+        assert_eq!(ratings(&target, "s-shared"), vec!["thumbs_up"]);
+        let end: i64 = target
+            .query_row(
+                "SELECT session_end_time FROM agent_sessions WHERE session_id = 's-shared'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(end, 200);
+
+        // Keep-local leaves an existing local rating alone
+        let target = local();
+        add_rating(&target, "s-shared", "thumbs_down");
+        merge(&target, &archive, ConflictPolicy::KeepLocal);
+        assert_eq!(ratings(&target, "s-shared"), vec!["thumbs_down"]);
+
+        // Keep-imported wins even when the local copy is newer
+        let target = migrated();
+        add_session(&target, "s-shared", "/Users/ana/code/app", "p-x", 999);
+        let outcome = merge(&target, &archive, ConflictPolicy::KeepImported);
+        assert_eq!(outcome.summary.sessions_replaced, 1);
+        assert_eq!(outcome.summary.projects_added, 1);
+    }
+
+    #[test]
+    fn test_kept_session_keeps_imported_ai_analysis() {
+        let source = migrated();
+        add_session(&source, "s-shared", "/home/ana/code/app", "p-old", 200);
+        source
+            .execute(
+                "UPDATE agent_sessions SET ai_model_phase_analysis = '{\"phases\":[]}', ai_model_summary = 'old summary' WHERE session_id = 's-shared'",
+                [],
+            )
+            .unwrap();
+        let archive = archive_of(&source, "/home/ana");
+
+        // The new laptop re-scanned the session, so its row is newer but has no analysis
+        let target = migrated();
+        add_session(&target, "s-shared", "/Users/ana/code/app", "p-local", 100);
+        target
+            .execute(
+                "UPDATE agent_sessions SET processed_at = 5000, ai_model_summary = 'new summary' WHERE session_id = 's-shared'",
+                [],
+            )
+            .unwrap();
+
+        let outcome = merge(&target, &archive, ConflictPolicy::KeepNewest);
+        assert_eq!(outcome.summary.sessions_kept, 1);
+        assert!(outcome.summary.session_columns_filled >= 1);
+
+        let (analysis, summary, cwd): (Option<String>, String, String) = target
+            .query_row(
+                "SELECT ai_model_phase_analysis, ai_model_summary, cwd FROM agent_sessions WHERE session_id = 's-shared'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(analysis.as_deref(), Some("{\"phases\":[]}"));
+        // Values the local row already has are left alone
+        assert_eq!(summary, "new summary");
+        assert_eq!(cwd, "/Users/ana/code/app");
+    }
+
+    #[test]
+    fn test_import_rejects_foreign_paths_and_snapshots() {
+        let source = migrated();
+        add_session(&source, "s-ok", "/home/ana/code/app", "p-old", 200);
+        add_session(&source, "s-evil", "/home/ana/code/app", "p-old", 200);
+        source
+            .execute(
+                "UPDATE agent_sessions SET start_snapshot_tree = 'abc', end_snapshot_tree = 'def' WHERE session_id = 's-ok'",
+                [],
+            )
+            .unwrap();
+        source
+            .execute(
+                "UPDATE agent_sessions SET file_path = '/home/ana/.bashrc' WHERE session_id = 's-evil'",
+                [],
+            )
+            .unwrap();
+        let archive = archive_of(&source, "/home/ana");
+
+        let target = migrated();
+        let outcome = merge(&target, &archive, ConflictPolicy::KeepNewest);
+        assert_eq!(outcome.summary.sessions_added, 1);
+        assert_eq!(outcome.summary.errors.len(), 1);
+        assert!(!outcome.winners.contains("s-evil"));
+
+        let snapshots: (Option<String>, Option<String>) = target
+            .query_row(
+                "SELECT start_snapshot_tree, end_snapshot_tree FROM agent_sessions WHERE session_id = 's-ok'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(snapshots, (None, None));
+    }
+
+    #[test]
+    fn test_contained_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("sessions");
+        fs::create_dir_all(root.join("claude-code")).unwrap();
+
+        assert!(contained_path(&root.join("claude-code/app/s.jsonl"), &root).is_some());
+        assert!(contained_path(&root, &root).is_none());
+        assert!(contained_path(&root.join("../.bashrc"), &root).is_none());
+        assert!(contained_path(&dir.path().join(".bashrc"), &root).is_none());
+        assert!(contained_path(Path::new("sessions/s.jsonl"), &root).is_none());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.path(), root.join("escape")).unwrap();
+            assert!(contained_path(&root.join("escape/.bashrc"), &root).is_none());
+        }
+    }
+
+    #[test]
+    fn test_remap_home() {
+        assert_eq!(
+            remap_home("/home/ana/code/app", Some("/home/ana/"), Some("/Users/ana")),
+            "/Users/ana/code/app"
+        );
+        assert_eq!(
+            remap_home(
+                r"C:\Users\ana\code\app",
+                Some(r"C:\Users\ana"),
+                Some("/home/ana")
+            ),
+            "/home/ana/code/app"
+        );
+        assert_eq!(
+            remap_home("/home/anabel/app", Some("/home/ana"), Some("/Users/ana")),
+            "/home/anabel/app"
+        );
+        assert_eq!(
+            remap_home("/srv/app", Some("/home/ana"), Some("/Users/ana")),
+            "/srv/app"
+        );
+        assert_eq!(
+            remap_home("/home/ana/app", None, Some("/Users/ana")),
+            "/home/ana/app"
+        );
+    }
+}
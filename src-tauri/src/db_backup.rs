@@ -0,0 +1,646 @@
+//! Backup, restore and maintenance of `guideai.db`
+//!
+//! Ratings, assessments, metrics and phase analysis only live in the local
+//! database, so it is snapshotted with SQLite's online backup API into a
+//! `backups` directory next to the database file. Snapshots are named
+//! `guideai-YYYYMMDD-HHMMSS-<kind>.db`.
+//!
+//! Restoring checks the snapshot's integrity and its `_sqlx_migrations`
+//! versions against [`crate::schema::MIGRATIONS`]; snapshots from a newer app
+//! are refused, the current database is snapshotted first, and migrations the
+//! snapshot predates are applied right after the restore. Automatic
+//! backups follow [`BackupSettings`] (stored in `~/.guideai/backup.json`) and
+//! only the newest `keep` automatic snapshots are retained.
+
+use crate::database::{get_db_path, with_connection_mut};
+use crate::logging::{log_info, log_warn};
+use crate::schema;
+use chrono::{Local, NaiveDateTime, TimeZone};
+use rusqlite::backup::Progress;
+use rusqlite::{params, Connection, DatabaseName, OpenFlags};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha384};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const SETTINGS_FILE: &str = "backup.json";
+const BACKUP_DIR: &str = "backups";
+const BACKUP_PREFIX: &str = "guideai-";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// How often the scheduler checks whether an automatic backup is due
+const SCHEDULER_TICK: Duration = Duration::from_secs(30 * 60);
+
+/// Why a snapshot was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackupKind {
+    Manual,
+    Auto,
+    PreRestore,
+}
+
+impl BackupKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            BackupKind::Manual => "manual",
+            BackupKind::Auto => "auto",
+            BackupKind::PreRestore => "pre-restore",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "manual" => Some(BackupKind::Manual),
+            "auto" => Some(BackupKind::Auto),
+            "pre-restore" => Some(BackupKind::PreRestore),
+            _ => None,
+        }
+    }
+}
+
+/// A snapshot file in the backups directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub path: String,
+    pub file_name: String,
+    pub kind: BackupKind,
+    /// Milliseconds since epoch, taken from the file name
+    pub created_at: i64,
+    pub size_bytes: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_hours() -> u32 {
+    24
+}
+
+fn default_keep() -> usize {
+    7
+}
+
+/// Automatic backup schedule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSettings {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Take an automatic backup when the newest one is older than this
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u32,
+    /// Number of automatic backups to keep (manual ones are never rotated)
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            interval_hours: default_interval_hours(),
+            keep: default_keep(),
+        }
+    }
+}
+
+/// Outcome of a restore
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub restored_from: String,
+    /// Snapshot of the database as it was before the restore
+    pub safety_backup: String,
+    pub schema_version: i64,
+    /// Migrations the snapshot predated, applied after restoring it
+    pub migrations_applied: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableSize {
+    pub name: String,
+    pub rows: i64,
+}
+
+/// Size and layout of the database file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseSizeReport {
+    pub file_bytes: u64,
+    pub wal_bytes: u64,
+    pub page_size: i64,
+    pub page_count: i64,
+    pub freelist_pages: i64,
+    pub tables: Vec<TableSize>,
+}
+
+/// Outcome of `VACUUM` / `ANALYZE`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceReport {
+    pub before: DatabaseSizeReport,
+    pub after: DatabaseSizeReport,
+    pub reclaimed_bytes: i64,
+    pub duration_ms: u64,
+}
+
+fn backup_dir() -> Result<PathBuf, String> {
+    let db_path = get_db_path().map_err(|e| format!("Failed to locate database: {}", e))?;
+    let parent = db_path
+        .parent()
+        .ok_or_else(|| "Database path has no parent directory".to_string())?;
+    Ok(parent.join(BACKUP_DIR))
+}
+
+fn settings_path() -> Result<PathBuf, String> {
+    crate::config::get_config_dir()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .map_err(|e| format!("Failed to locate config directory: {}", e))
+}
+
+/// Load the backup schedule (defaults if none has been saved)
+pub fn load_backup_settings() -> Result<BackupSettings, String> {
+    let path = settings_path()?;
+    if !path.exists() {
+        return Ok(BackupSettings::default());
+    }
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read backup settings: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid backup settings: {}", e))
+}
+
+pub fn save_backup_settings(settings: &BackupSettings) -> Result<(), String> {
+    crate::config::ensure_config_dir()
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize backup settings: {}", e))?;
+    fs::write(settings_path()?, content)
+        .map_err(|e| format!("Failed to save backup settings: {}", e))
+}
+
+fn backup_file_name(kind: BackupKind, at: NaiveDateTime) -> String {
+    format!(
+        "{}{}-{}.db",
+        BACKUP_PREFIX,
+        at.format(TIMESTAMP_FORMAT),
+        kind.as_str()
+    )
+}
+
+/// Parse `guideai-YYYYMMDD-HHMMSS-<kind>.db` into its kind and timestamp (ms)
+fn parse_backup_file_name(name: &str) -> Option<(BackupKind, i64)> {
+    let rest = name.strip_prefix(BACKUP_PREFIX)?.strip_suffix(".db")?;
+    let (stamp, kind) = rest.split_at_checked(15)?;
+    let kind = BackupKind::parse(kind.strip_prefix('-')?)?;
+    let at = NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok()?;
+    let at = Local.from_local_datetime(&at).earliest()?;
+    Some((kind, at.timestamp_millis()))
+}
+
+/// Copy a live connection into `dest` with the online backup API
+fn backup_connection(conn: &Connection, dest: &Path) -> Result<(), String> {
+    conn.backup(DatabaseName::Main, dest, None)
+        .map_err(|e| format!("Backup to {} failed: {}", dest.display(), e))
+}
+
+/// Snapshot the database into the backups directory
+pub fn create_backup(kind: BackupKind) -> Result<BackupInfo, String> {
+    let dir = backup_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup directory: {}", e))?;
+
+    // Two backups within the same second would collide; bump the timestamp
+    let stamp = Local::now().naive_local();
+    let mut dest = dir.join(backup_file_name(kind, stamp));
+    let mut attempt = 1;
+    while dest.exists() {
+        let stamp = stamp + chrono::Duration::seconds(attempt);
+        dest = dir.join(backup_file_name(kind, stamp));
+        attempt += 1;
+    }
+
+    with_connection_mut(|conn| Ok(backup_connection(conn, &dest)))
+        .map_err(|e| format!("Database not available: {}", e))??;
+
+    let info = backup_info(&dest)
+        .ok_or_else(|| format!("Backup written to unexpected path {}", dest.display()))?;
+    let _ = log_info(
+        "database",
+        &format!("💾 Database backed up to {}", dest.display()),
+    );
+    Ok(info)
+}
+
+fn backup_info(path: &Path) -> Option<BackupInfo> {
+    let file_name = path.file_name()?.to_str()?.to_string();
+    let (kind, created_at) = parse_backup_file_name(&file_name)?;
+    Some(BackupInfo {
+        path: path.to_string_lossy().to_string(),
+        file_name,
+        kind,
+        created_at,
+        size_bytes: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+    })
+}
+
+/// All snapshots, newest first
+pub fn list_backups() -> Result<Vec<BackupInfo>, String> {
+    list_backups_in(&backup_dir()?)
+}
+
+fn list_backups_in(dir: &Path) -> Result<Vec<BackupInfo>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups: Vec<BackupInfo> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read backup directory: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| backup_info(&entry.path()))
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+/// Automatic backups beyond the newest `keep` (expects newest-first input)
+fn rotation_victims(backups: &[BackupInfo], keep: usize) -> Vec<&BackupInfo> {
+    backups
+        .iter()
+        .filter(|b| b.kind == BackupKind::Auto)
+        .skip(keep)
+        .collect()
+}
+
+/// Delete old automatic backups, returning how many were removed
+pub fn rotate_backups(keep: usize) -> Result<usize, String> {
+    let backups = list_backups()?;
+    let mut removed = 0;
+    for backup in rotation_victims(&backups, keep) {
+        match fs::remove_file(&backup.path) {
+            Ok(()) => removed += 1,
+            Err(e) => {
+                let _ = log_warn(
+                    "database",
+                    &format!("⚠ Failed to remove old backup {}: {}", backup.path, e),
+                );
+            }
+        }
+    }
+    Ok(removed)
+}
+
+/// Check a snapshot is intact and was written by a schema this app knows,
+/// returning its schema version
+fn validate_backup_file(path: &Path) -> Result<i64, String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open backup: {}", e))?;
+
+    let check: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| format!("Backup is not a valid database: {}", e))?;
+    if check != "ok" {
+        return Err(format!("Backup failed integrity check: {}", check));
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT version FROM _sqlx_migrations WHERE success = 1 ORDER BY version")
+        .map_err(|_| "Backup has no migration history".to_string())?;
+    let versions = stmt
+        .query_map([], |row| row.get::<_, i64>(0))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Failed to read backup migrations: {}", e))?;
+
+    let latest = schema::latest_version();
+    let Some(&version) = versions.last() else {
+        return Err("Backup has no applied migrations".to_string());
+    };
+    if version > latest {
+        return Err(format!(
+            "Backup uses schema version {} but this app only knows up to {}; update the app first",
+            version, latest
+        ));
+    }
+    if let Some(unknown) = versions.iter().find(|v| !schema::is_known_version(**v)) {
+        return Err(format!("Backup contains unknown migration {}", unknown));
+    }
+
+    Ok(version)
+}
+
+/// Apply the migrations missing from `conn` and record them the way sqlx
+/// does, so tauri-plugin-sql sees them as applied on the next start
+fn apply_pending_migrations(conn: &mut Connection) -> Result<Vec<i64>, rusqlite::Error> {
+    let tx = conn.transaction()?;
+    let mut applied = Vec::new();
+    for migration in schema::MIGRATIONS {
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM _sqlx_migrations WHERE version = ? AND success = 1)",
+            [migration.version],
+            |row| row.get(0),
+        )?;
+        if exists {
+            continue;
+        }
+
+        let started = Instant::now();
+        tx.execute_batch(migration.sql)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+             VALUES (?, ?, 1, ?, ?)",
+            params![
+                migration.version,
+                migration.description,
+                Sha384::digest(migration.sql.as_bytes()).to_vec(),
+                started.elapsed().as_nanos() as i64
+            ],
+        )?;
+        applied.push(migration.version);
+    }
+    tx.commit()?;
+    Ok(applied)
+}
+
+/// Replace the live database with a snapshot
+pub fn restore_backup(path: &str) -> Result<RestoreResult, String> {
+    let source = Path::new(path);
+    if !source.is_file() {
+        return Err(format!("Backup not found: {}", path));
+    }
+
+    let version = validate_backup_file(source)?;
+    let safety = create_backup(BackupKind::PreRestore)?;
+
+    let migrations_applied = with_connection_mut(|conn| {
+        Ok(conn
+            .restore(DatabaseName::Main, source, None::<fn(Progress)>)
+            .map_err(|e| format!("Restore failed: {}", e))
+            .and_then(|_| {
+                apply_pending_migrations(conn).map_err(|e| {
+                    format!(
+                        "Restored backup but failed to migrate it (previous state saved to {}): {}",
+                        safety.path, e
+                    )
+                })
+            }))
+    })
+    .map_err(|e| format!("Database not available: {}", e))??;
+
+    let _ = log_info(
+        "database",
+        &format!(
+            "♻ Database restored from {} (schema {}, {} migrations applied, previous state saved to {})",
+            path,
+            version,
+            migrations_applied.len(),
+            safety.path
+        ),
+    );
+
+    Ok(RestoreResult {
+        restored_from: path.to_string(),
+        safety_backup: safety.path,
+        schema_version: version,
+        migrations_applied,
+    })
+}
+
+fn size_report(conn: &Connection, db_path: &Path) -> Result<DatabaseSizeReport, rusqlite::Error> {
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let freelist_pages: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+
+    let names: Vec<String> = conn
+        .prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    let mut tables = Vec::with_capacity(names.len());
+    for name in names {
+        let rows = conn.query_row(
+            &format!("SELECT COUNT(*) FROM \"{}\"", name.replace('"', "\"\"")),
+            [],
+            |row| row.get(0),
+        )?;
+        tables.push(TableSize { name, rows });
+    }
+
+    let wal_path = PathBuf::from(format!("{}-wal", db_path.display()));
+    Ok(DatabaseSizeReport {
+        file_bytes: fs::metadata(db_path).map(|m| m.len()).unwrap_or(0),
+        wal_bytes: fs::metadata(wal_path).map(|m| m.len()).unwrap_or(0),
+        page_size,
+        page_count,
+        freelist_pages,
+        tables,
+    })
+}
+
+/// Current size of the database
+pub fn get_database_size() -> Result<DatabaseSizeReport, String> {
+    let db_path = get_db_path().map_err(|e| format!("Failed to locate database: {}", e))?;
+    with_connection_mut(|conn| size_report(conn, &db_path))
+        .map_err(|e| format!("Failed to inspect database: {}", e))
+}
+
+/// Run `VACUUM` and `ANALYZE` and report the size before and after
+pub fn run_database_maintenance() -> Result<MaintenanceReport, String> {
+    let db_path = get_db_path().map_err(|e| format!("Failed to locate database: {}", e))?;
+    let started = Instant::now();
+
+    let (before, after) = with_connection_mut(|conn| {
+        let before = size_report(conn, &db_path)?;
+        conn.execute_batch("VACUUM; ANALYZE;")?;
+        // Fold the WAL back into the main file so the size report is accurate
+        let _ = conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()));
+        let after = size_report(conn, &db_path)?;
+        Ok((before, after))
+    })
+    .map_err(|e| format!("Database maintenance failed: {}", e))?;
+
+    let reclaimed_bytes =
+        (before.file_bytes + before.wal_bytes) as i64 - (after.file_bytes + after.wal_bytes) as i64;
+    let _ = log_info(
+        "database",
+        &format!(
+            "🧹 Database maintenance reclaimed {} bytes ({} free pages before)",
+            reclaimed_bytes, before.freelist_pages
+        ),
+    );
+
+    Ok(MaintenanceReport {
+        before,
+        after,
+        reclaimed_bytes,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Take an automatic backup if the newest one is older than the interval
+fn run_scheduled_backup() -> Result<(), String> {
+    let settings = load_backup_settings()?;
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let interval_ms = i64::from(settings.interval_hours.max(1)) * 60 * 60 * 1000;
+    let last_auto = list_backups()?
+        .into_iter()
+        .find(|b| b.kind == BackupKind::Auto)
+        .map(|b| b.created_at);
+    let now = Local::now().timestamp_millis();
+    if last_auto.is_some_and(|at| now - at < interval_ms) {
+        return Ok(());
+    }
+
+    create_backup(BackupKind::Auto)?;
+    rotate_backups(settings.keep)?;
+    Ok(())
+}
+
+/// Start the automatic backup schedule on a background thread
+pub fn start_backup_scheduler() {
+    std::thread::spawn(|| loop {
+        if let Err(e) = run_scheduled_backup() {
+            let _ = log_warn("database", &format!("⚠ Automatic backup failed: {}", e));
+        }
+        std::thread::sleep(SCHEDULER_TICK);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn database_with_migrations(path: &Path, versions: &[i64]) -> Connection {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE _sqlx_migrations (version BIGINT PRIMARY KEY, description TEXT NOT NULL, installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, success BOOLEAN NOT NULL, checksum BLOB NOT NULL DEFAULT x'', execution_time BIGINT NOT NULL DEFAULT 0);
+             CREATE TABLE ratings (id INTEGER PRIMARY KEY, value TEXT);
+             INSERT INTO ratings (value) VALUES ('thumbs_up'), ('meh');",
+        )
+        .unwrap();
+        for version in versions {
+            conn.execute(
+                "INSERT INTO _sqlx_migrations (version, description, success) VALUES (?1, 'm', 1)",
+                [version],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn test_backup_and_validate_snapshot() {
+        let dir = tempdir().unwrap();
+        let all: Vec<i64> = schema::MIGRATIONS.iter().map(|m| m.version).collect();
+        let conn = database_with_migrations(&dir.path().join("live.db"), &all);
+
+        let snapshot = dir.path().join("snapshot.db");
+        backup_connection(&conn, &snapshot).unwrap();
+        assert_eq!(
+            validate_backup_file(&snapshot).unwrap(),
+            schema::latest_version()
+        );
+
+        let report = size_report(&conn, &dir.path().join("live.db")).unwrap();
+        let ratings = report.tables.iter().find(|t| t.name == "ratings").unwrap();
+        assert_eq!(ratings.rows, 2);
+
+        // A snapshot from a newer app is refused
+        let newer = dir.path().join("newer.db");
+        database_with_migrations(&newer, &[1, schema::latest_version() + 1]);
+        assert!(validate_backup_file(&newer)
+            .unwrap_err()
+            .contains("update the app"));
+
+        // A file without migration history is refused
+        let plain = dir.path().join("plain.db");
+        Connection::open(&plain)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (x INTEGER);")
+            .unwrap();
+        assert!(validate_backup_file(&plain).is_err());
+    }
+
+    #[test]
+    fn test_restored_snapshot_gets_pending_migrations() {
+        let dir = tempdir().unwrap();
+        let mut conn = database_with_migrations(&dir.path().join("old.db"), &[]);
+        let (old, pending) = schema::MIGRATIONS.split_at(schema::MIGRATIONS.len() - 2);
+        for migration in old {
+            conn.execute_batch(migration.sql).unwrap();
+            conn.execute(
+                "INSERT INTO _sqlx_migrations (version, description, success) VALUES (?1, ?2, 1)",
+                params![migration.version, migration.description],
+            )
+            .unwrap();
+        }
+
+        let applied = apply_pending_migrations(&mut conn).unwrap();
+        assert_eq!(
+            applied,
+            pending.iter().map(|m| m.version).collect::<Vec<_>>()
+        );
+        let checksum: Vec<u8> = conn
+            .query_row(
+                "SELECT checksum FROM _sqlx_migrations WHERE version = ?",
+                [schema::latest_version()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let latest = schema::MIGRATIONS.last().unwrap();
+        assert_eq!(checksum, Sha384::digest(latest.sql.as_bytes()).to_vec());
+        let tombstones: i64 = conn
+            .query_row("SELECT COUNT(*) FROM deleted_sessions", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(tombstones, 0);
+
+        assert!(apply_pending_migrations(&mut conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_backup_names_and_rotation() {
+        let at = NaiveDateTime::parse_from_str("20260314-091502", TIMESTAMP_FORMAT).unwrap();
+        let name = backup_file_name(BackupKind::PreRestore, at);
+        assert_eq!(name, "guideai-20260314-091502-pre-restore.db");
+        let (kind, _) = parse_backup_file_name(&name).unwrap();
+        assert_eq!(kind, BackupKind::PreRestore);
+        assert!(parse_backup_file_name("guideai.db").is_none());
+        assert!(parse_backup_file_name("guideai-20260314-091502-other.db").is_none());
+
+        let dir = tempdir().unwrap();
+        for (day, kind) in [
+            (1, BackupKind::Auto),
+            (2, BackupKind::Manual),
+            (3, BackupKind::Auto),
+            (4, BackupKind::Auto),
+        ] {
+            let at = at + chrono::Duration::days(day);
+            fs::write(dir.path().join(backup_file_name(kind, at)), b"").unwrap();
+        }
+        fs::write(dir.path().join("notes.txt"), b"").unwrap();
+
+        let backups = list_backups_in(dir.path()).unwrap();
+        assert_eq!(backups.len(), 4);
+        assert!(backups[0].created_at > backups[3].created_at);
+
+        let victims = rotation_victims(&backups, 1);
+        let names: Vec<&str> = victims.iter().map(|b| b.file_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "guideai-20260317-091502-auto.db",
+                "guideai-20260315-091502-auto.db"
+            ]
+        );
+    }
+}
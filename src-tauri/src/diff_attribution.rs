@@ -0,0 +1,799 @@
+//! Attribute session diff hunks to the tool calls that produced them
+//!
+//! File-editing tool calls (Edit, MultiEdit, Write, str_replace, apply_patch, ...)
+//! are extracted from the canonical transcript and replayed, in order, on top of
+//! each file's content at session start. Every replayed line remembers the tool
+//! call that wrote it. The replayed file is then aligned with the file's final
+//! content: added lines that line up with a replayed line written by a tool call
+//! belong to the agent, everything else was changed outside the agent (by the human).
+
+use crate::git_diff::FileDiff;
+use crate::providers::canonical::{CanonicalMessage, ContentBlock, ContentValue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Largest `old_lines * new_lines` table we align line by line
+const MAX_ALIGNMENT_CELLS: usize = 4_000_000;
+
+/// Lines shorter than this (trimmed) are too ambiguous for text-only matching
+const MIN_FALLBACK_LINE_LEN: usize = 4;
+
+/// Tool call that wrote a line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditSource {
+    pub message_uuid: String,
+    pub tool_use_id: String,
+    pub tool_name: String,
+    pub timestamp: String,
+}
+
+/// Who wrote a hunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HunkAuthor {
+    Agent,
+    Human,
+    Mixed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HunkAttribution {
+    pub header: String,
+    pub new_start: u32,
+    pub author: HunkAuthor,
+    pub agent_lines: u32,
+    pub human_lines: u32,
+    /// New-file line numbers of added lines written by the human
+    pub human_line_numbers: Vec<u32>,
+    /// Tool calls that wrote the hunk's agent lines, in transcript order
+    pub sources: Vec<EditSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAttribution {
+    pub file_path: String,
+    pub agent_lines: u32,
+    pub human_lines: u32,
+    /// Edits whose `old` text could not be found while replaying
+    pub unreplayed_edits: u32,
+    pub hunks: Vec<HunkAttribution>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionAttribution {
+    pub session_id: String,
+    pub agent_lines: u32,
+    pub human_lines: u32,
+    pub files: Vec<FileAttribution>,
+}
+
+/// A file change requested by a tool call
+#[derive(Debug, Clone)]
+enum EditOp {
+    /// Replace `old` with `new` (all occurrences when `replace_all`)
+    Replace {
+        old: String,
+        new: String,
+        replace_all: bool,
+    },
+    /// Overwrite the whole file
+    Write { content: String },
+}
+
+#[derive(Debug, Clone)]
+struct FileEdit {
+    path: String,
+    op: EditOp,
+    source: usize,
+}
+
+/// Attribute every added line in `diffs` to a tool call or to the human
+pub fn attribute_session_diff(
+    session_id: &str,
+    cwd: &str,
+    diffs: &[FileDiff],
+    messages: &[CanonicalMessage],
+) -> SessionAttribution {
+    let (sources, edits) = extract_edits(messages);
+
+    let mut files = Vec::new();
+    for diff in diffs {
+        // Collapsed files (lockfiles, generated, oversized) have no lines to attribute
+        if diff.is_binary || diff.collapsed.is_some() || diff.change_type == "deleted" {
+            continue;
+        }
+
+        let file_edits: Vec<&FileEdit> = edits
+            .iter()
+            .filter(|edit| normalize_path(&edit.path, cwd) == diff.new_path)
+            .collect();
+        files.push(attribute_file(diff, &file_edits, &sources));
+    }
+
+    SessionAttribution {
+        session_id: session_id.to_string(),
+        agent_lines: files.iter().map(|f| f.agent_lines).sum(),
+        human_lines: files.iter().map(|f| f.human_lines).sum(),
+        files,
+    }
+}
+
+/// Lines written by file-editing tool calls, keyed by path relative to `cwd`
+///
+/// A cheaper, replay-free view of the transcript used to credit commits to
+/// sessions. Lines are trimmed; short lines such as `}` are left out.
+pub fn agent_written_lines(
+    messages: &[CanonicalMessage],
+    cwd: &str,
+) -> HashMap<String, HashSet<String>> {
+    let (_, edits) = extract_edits(messages);
+
+    let mut lines: HashMap<String, HashSet<String>> = HashMap::new();
+    for edit in edits {
+        let text = match &edit.op {
+            EditOp::Replace { new, .. } => new,
+            EditOp::Write { content } => content,
+        };
+        let file_lines = lines.entry(normalize_path(&edit.path, cwd)).or_default();
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if trimmed.len() >= MIN_FALLBACK_LINE_LEN {
+                file_lines.insert(trimmed.to_string());
+            }
+        }
+    }
+
+    lines
+}
+
+fn attribute_file(diff: &FileDiff, edits: &[&FileEdit], sources: &[EditSource]) -> FileAttribution {
+    let base = diff.old_content.clone().unwrap_or_default();
+    let (replayed, unreplayed_edits) = replay(&base, edits);
+
+    // Origin of each line of the final file, by aligning it with the replayed file
+    let final_origins: Option<Vec<Option<usize>>> = diff.new_content.as_ref().map(|content| {
+        let final_lines: Vec<&str> = content.lines().collect();
+        align_origins(&replayed, &final_lines)
+    });
+
+    // Text-only fallback for lines the replay couldn't place (e.g. drifted edits)
+    let mut written_by: HashMap<String, usize> = HashMap::new();
+    for edit in edits {
+        let text = match &edit.op {
+            EditOp::Replace { new, .. } => new,
+            EditOp::Write { content } => content,
+        };
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if trimmed.len() >= MIN_FALLBACK_LINE_LEN {
+                written_by.insert(trimmed.to_string(), edit.source);
+            }
+        }
+    }
+
+    let mut hunks = Vec::new();
+    for hunk in parse_hunks(diff) {
+        // Pure deletions have no lines to attribute
+        if hunk.added.is_empty() {
+            continue;
+        }
+
+        let mut agent_lines = 0;
+        let mut human_line_numbers = Vec::new();
+        let mut hunk_sources: Vec<usize> = Vec::new();
+
+        for (line_number, text) in &hunk.added {
+            let origin = final_origins
+                .as_ref()
+                .and_then(|origins| origins.get(*line_number as usize - 1).copied().flatten())
+                .or_else(|| written_by.get(text.trim()).copied());
+
+            match origin {
+                Some(source) => {
+                    agent_lines += 1;
+                    if !hunk_sources.contains(&source) {
+                        hunk_sources.push(source);
+                    }
+                }
+                None => human_line_numbers.push(*line_number),
+            }
+        }
+
+        let human_lines = human_line_numbers.len() as u32;
+        let author = match (agent_lines, human_lines) {
+            (_, 0) => HunkAuthor::Agent,
+            (0, _) => HunkAuthor::Human,
+            _ => HunkAuthor::Mixed,
+        };
+        hunk_sources.sort_unstable();
+
+        hunks.push(HunkAttribution {
+            header: hunk.header,
+            new_start: hunk.new_start,
+            author,
+            agent_lines,
+            human_lines,
+            human_line_numbers,
+            sources: hunk_sources.iter().map(|&i| sources[i].clone()).collect(),
+        });
+    }
+
+    FileAttribution {
+        file_path: diff.new_path.clone(),
+        agent_lines: hunks.iter().map(|h| h.agent_lines).sum(),
+        human_lines: hunks.iter().map(|h| h.human_lines).sum(),
+        unreplayed_edits,
+        hunks,
+    }
+}
+
+/// Collect file edits from tool calls, skipping calls whose result was an error
+fn extract_edits(messages: &[CanonicalMessage]) -> (Vec<EditSource>, Vec<FileEdit>) {
+    let failed: HashSet<&str> = messages
+        .iter()
+        .flat_map(|m| blocks(m).iter())
+        .filter_map(|block| match block {
+            ContentBlock::ToolResult {
+                tool_use_id,
+                is_error: Some(true),
+                ..
+            } => Some(tool_use_id.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut sources = Vec::new();
+    let mut edits = Vec::new();
+
+    for message in messages {
+        for block in blocks(message) {
+            let ContentBlock::ToolUse { id, name, input } = block else {
+                continue;
+            };
+            if failed.contains(id.as_str()) {
+                continue;
+            }
+
+            let ops = edits_from_tool_input(name, input);
+            if ops.is_empty() {
+                continue;
+            }
+
+            let source = sources.len();
+            sources.push(EditSource {
+                message_uuid: message.uuid.clone(),
+                tool_use_id: id.clone(),
+                tool_name: name.clone(),
+                timestamp: message.timestamp.clone(),
+            });
+            edits.extend(
+                ops.into_iter()
+                    .map(|(path, op)| FileEdit { path, op, source }),
+            );
+        }
+    }
+
+    (sources, edits)
+}
+
+fn blocks(message: &CanonicalMessage) -> &[ContentBlock] {
+    match &message.message.content {
+        ContentValue::Structured(blocks) => blocks,
+        ContentValue::Text(_) => &[],
+    }
+}
+
+/// Map a tool call's input to file edits
+///
+/// Detection is by input shape rather than tool name so it works across
+/// providers: Claude (`Edit`, `MultiEdit`, `Write`), Anthropic `str_replace`
+/// editors, Gemini (`replace`, `write_file`), Copilot (`replace_string_in_file`,
+/// `create_file`), Cursor (`search_replace`, `write`) and Codex `apply_patch`.
+fn edits_from_tool_input(name: &str, input: &Value) -> Vec<(String, EditOp)> {
+    if let Some(patch) = find_patch(input) {
+        return parse_apply_patch(&patch);
+    }
+    if name == "apply_patch" {
+        return Vec::new();
+    }
+
+    let Some(path) = string_field(input, &["file_path", "filePath", "path", "target_file"]) else {
+        return Vec::new();
+    };
+
+    // MultiEdit: {file_path, edits: [{old_string, new_string, replace_all}]}
+    if let Some(items) = input.get("edits").and_then(Value::as_array) {
+        return items
+            .iter()
+            .filter_map(replace_op)
+            .map(|op| (path.clone(), op))
+            .collect();
+    }
+
+    if let Some(op) = replace_op(input) {
+        return vec![(path, op)];
+    }
+
+    // str_replace editors use `command: create` with `file_text`
+    if let Some(content) = string_field(input, &["content", "contents", "file_text"]) {
+        return vec![(path, EditOp::Write { content })];
+    }
+
+    Vec::new()
+}
+
+fn replace_op(value: &Value) -> Option<EditOp> {
+    let old = string_field(value, &["old_string", "oldString", "old_str"])?;
+    let new = string_field(value, &["new_string", "newString", "new_str"]).unwrap_or_default();
+    let replace_all = value
+        .get("replace_all")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    Some(EditOp::Replace {
+        old,
+        new,
+        replace_all,
+    })
+}
+
+fn string_field(value: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+/// Find an apply_patch envelope anywhere in a tool input (string, array or object)
+fn find_patch(input: &Value) -> Option<String> {
+    match input {
+        Value::String(s) => {
+            let start = s.find("*** Begin Patch")?;
+            let end = s[start..]
+                .find("*** End Patch")
+                .map(|i| start + i + "*** End Patch".len())
+                .unwrap_or(s.len());
+            Some(s[start..end].to_string())
+        }
+        Value::Array(items) => items.iter().find_map(find_patch),
+        Value::Object(map) => map.values().find_map(find_patch),
+        _ => None,
+    }
+}
+
+/// Parse a Codex apply_patch envelope into file edits
+///
+/// `*** Add File` becomes a write; each `@@` chunk of `*** Update File`
+/// becomes a replace of its context and `-` lines with its context and `+` lines.
+fn parse_apply_patch(patch: &str) -> Vec<(String, EditOp)> {
+    let mut edits = Vec::new();
+    let mut current: Option<(String, bool)> = None; // (path, is_add)
+    let mut added = Vec::new();
+    let mut old = Vec::new();
+    let mut new = Vec::new();
+
+    fn flush_chunk(
+        path: &str,
+        old: &mut Vec<String>,
+        new: &mut Vec<String>,
+        edits: &mut Vec<(String, EditOp)>,
+    ) {
+        if !old.is_empty() || !new.is_empty() {
+            edits.push((
+                path.to_string(),
+                EditOp::Replace {
+                    old: old.join("\n"),
+                    new: new.join("\n"),
+                    replace_all: false,
+                },
+            ));
+        }
+        old.clear();
+        new.clear();
+    }
+
+    fn finish_file(
+        current: &Option<(String, bool)>,
+        added: &mut Vec<String>,
+        old: &mut Vec<String>,
+        new: &mut Vec<String>,
+        edits: &mut Vec<(String, EditOp)>,
+    ) {
+        if let Some((path, is_add)) = current {
+            if *is_add {
+                let mut content = added.join("\n");
+                content.push('\n');
+                edits.push((path.clone(), EditOp::Write { content }));
+            } else {
+                flush_chunk(path, old, new, edits);
+            }
+        }
+        added.clear();
+    }
+
+    for line in patch.lines() {
+        if let Some(path) = line.strip_prefix("*** Add File: ") {
+            finish_file(&current, &mut added, &mut old, &mut new, &mut edits);
+            current = Some((path.trim().to_string(), true));
+        } else if let Some(path) = line.strip_prefix("*** Update File: ") {
+            finish_file(&current, &mut added, &mut old, &mut new, &mut edits);
+            current = Some((path.trim().to_string(), false));
+        } else if let Some(path) = line.strip_prefix("*** Move to: ") {
+            if let Some((ref mut current_path, _)) = current {
+                *current_path = path.trim().to_string();
+            }
+        } else if line.starts_with("*** ") {
+            // Delete File, End Patch, End of File
+            finish_file(&current, &mut added, &mut old, &mut new, &mut edits);
+            if !line.starts_with("*** End of File") {
+                current = None;
+            }
+        } else if let Some((path, is_add)) = &current {
+            if *is_add {
+                if let Some(text) = line.strip_prefix('+') {
+                    added.push(text.to_string());
+                }
+            } else if line.starts_with("@@") {
+                flush_chunk(path, &mut old, &mut new, &mut edits);
+            } else if let Some(text) = line.strip_prefix('+') {
+                new.push(text.to_string());
+            } else if let Some(text) = line.strip_prefix('-') {
+                old.push(text.to_string());
+            } else {
+                let text = line.strip_prefix(' ').unwrap_or(line);
+                old.push(text.to_string());
+                new.push(text.to_string());
+            }
+        }
+    }
+    finish_file(&current, &mut added, &mut old, &mut new, &mut edits);
+
+    edits
+}
+
+/// Replay edits on `base`, returning each resulting line with the edit source that wrote it
+fn replay(base: &str, edits: &[&FileEdit]) -> (Vec<(String, Option<usize>)>, u32) {
+    let mut lines: Vec<(String, Option<usize>)> =
+        base.lines().map(|line| (line.to_string(), None)).collect();
+    let mut unreplayed = 0;
+
+    for edit in edits {
+        match &edit.op {
+            EditOp::Write { content } => {
+                lines = content
+                    .lines()
+                    .map(|line| (line.to_string(), Some(edit.source)))
+                    .collect();
+            }
+            EditOp::Replace {
+                old,
+                new,
+                replace_all,
+            } => {
+                if old.is_empty() {
+                    unreplayed += 1;
+                    continue;
+                }
+                let mut replaced_any = false;
+                let mut search_from = 0;
+                while let Some(next) = replace_once(&mut lines, old, new, edit.source, search_from)
+                {
+                    replaced_any = true;
+                    if !replace_all {
+                        break;
+                    }
+                    search_from = next;
+                }
+                if !replaced_any {
+                    unreplayed += 1;
+                }
+            }
+        }
+    }
+
+    (lines, unreplayed)
+}
+
+/// Replace the first occurrence of `old` at or after byte `from` of the joined text
+///
+/// Lines touched by the replacement take `source` as their origin. Returns the
+/// byte offset right after the inserted text.
+fn replace_once(
+    lines: &mut Vec<(String, Option<usize>)>,
+    old: &str,
+    new: &str,
+    source: usize,
+    from: usize,
+) -> Option<usize> {
+    let text: String = lines
+        .iter()
+        .map(|(line, _)| line.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let start = text.get(from..)?.find(old)? + from;
+    let end = start + old.len();
+
+    // Line range covered by the match
+    let first_line = text[..start].matches('\n').count();
+    let last_line = text[..end].matches('\n').count();
+    let line_start = text[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = text[end..]
+        .find('\n')
+        .map(|i| end + i)
+        .unwrap_or(text.len());
+
+    let replaced = format!(
+        "{}{}{}",
+        &text[line_start..start],
+        new,
+        &text[end..line_end]
+    );
+    let new_lines: Vec<(String, Option<usize>)> = replaced
+        .split('\n')
+        .map(|line| (line.to_string(), Some(source)))
+        .collect();
+
+    let last_line = last_line.min(lines.len().saturating_sub(1));
+    if lines.is_empty() {
+        lines.extend(new_lines);
+    } else {
+        lines.splice(first_line..=last_line, new_lines);
+    }
+
+    Some(start + new.len())
+}
+
+/// Give each final line the origin of the replayed line it aligns with
+///
+/// Uses a longest-common-subsequence alignment; very large files fall back
+/// to matching identical lines in order.
+fn align_origins(replayed: &[(String, Option<usize>)], final_lines: &[&str]) -> Vec<Option<usize>> {
+    let n = replayed.len();
+    let m = final_lines.len();
+    let mut origins = vec![None; m];
+
+    if n.saturating_mul(m) > MAX_ALIGNMENT_CELLS {
+        let mut by_text: HashMap<&str, Vec<Option<usize>>> = HashMap::new();
+        for (line, origin) in replayed.iter().rev() {
+            by_text.entry(line.as_str()).or_default().push(*origin);
+        }
+        for (i, line) in final_lines.iter().enumerate() {
+            if let Some(candidates) = by_text.get_mut(line) {
+                origins[i] = candidates.pop().flatten();
+            }
+        }
+        return origins;
+    }
+
+    // lcs[i][j] = LCS length of replayed[i..] and final_lines[j..]
+    let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+    let idx = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[idx(i, j)] = if replayed[i].0 == final_lines[j] {
+                lcs[idx(i + 1, j + 1)] + 1
+            } else {
+                lcs[idx(i + 1, j)].max(lcs[idx(i, j + 1)])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if replayed[i].0 == final_lines[j] {
+            origins[j] = replayed[i].1;
+            i += 1;
+            j += 1;
+        } else if lcs[idx(i + 1, j)] >= lcs[idx(i, j + 1)] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    origins
+}
+
+struct ParsedHunk {
+    header: String,
+    new_start: u32,
+    /// (new-file line number, text) of added lines
+    added: Vec<(u32, String)>,
+}
+
+/// Parse the unified diff text of a `FileDiff` into hunks of added lines
+fn parse_hunks(diff: &FileDiff) -> Vec<ParsedHunk> {
+    let mut hunks = Vec::new();
+
+    for text in &diff.hunks {
+        let mut current: Option<ParsedHunk> = None;
+        let mut new_line = 0;
+
+        for line in text.lines() {
+            if line.starts_with("@@") {
+                if let Some(hunk) = current.take() {
+                    hunks.push(hunk);
+                }
+                let new_start = parse_new_start(line).unwrap_or(1);
+                new_line = new_start;
+                current = Some(ParsedHunk {
+                    header: line.to_string(),
+                    new_start,
+                    added: Vec::new(),
+                });
+                continue;
+            }
+
+            let Some(hunk) = current.as_mut() else {
+                continue; // File headers before the first hunk
+            };
+            if let Some(added) = line.strip_prefix('+') {
+                hunk.added.push((new_line, added.to_string()));
+                new_line += 1;
+            } else if line.starts_with(' ') {
+                new_line += 1;
+            }
+        }
+
+        if let Some(hunk) = current.take() {
+            hunks.push(hunk);
+        }
+    }
+
+    hunks
+}
+
+/// Read `c` from a `@@ -a,b +c,d @@` header
+fn parse_new_start(header: &str) -> Option<u32> {
+    let plus = header
+        .split_whitespace()
+        .find(|part| part.starts_with('+'))?;
+    let start = plus.trim_start_matches('+').split(',').next()?;
+    start.parse::<u32>().ok().map(|n| n.max(1))
+}
+
+/// Make a tool's file path relative to the repository like `FileDiff::new_path`
+fn normalize_path(path: &str, cwd: &str) -> String {
+    let cwd = cwd.trim_end_matches('/');
+    let relative = path
+        .strip_prefix(cwd)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .unwrap_or(path);
+    relative.trim_start_matches("./").replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git_diff::DiffStats;
+    use crate::providers::canonical::{MessageContent, MessageType, CANONICAL_VERSION};
+    use serde_json::json;
+
+    fn tool_use(uuid: &str, id: &str, name: &str, input: Value) -> CanonicalMessage {
+        CanonicalMessage {
+            canonical_version: CANONICAL_VERSION.to_string(),
+            uuid: uuid.to_string(),
+            timestamp: "2025-01-01T00:00:00.000Z".to_string(),
+            message_type: MessageType::Assistant,
+            session_id: "session".to_string(),
+            provider: "claude-code".to_string(),
+            cwd: Some("/repo".to_string()),
+            git_branch: None,
+            version: None,
+            parent_uuid: None,
+            is_sidechain: None,
+            user_type: None,
+            message: MessageContent {
+                role: "assistant".to_string(),
+                content: ContentValue::Structured(vec![ContentBlock::ToolUse {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    input,
+                }]),
+                model: None,
+                usage: None,
+            },
+            provider_metadata: None,
+            is_meta: None,
+            request_id: None,
+            tool_use_result: None,
+        }
+    }
+
+    fn file_diff(path: &str, old: &str, new: &str, hunk: &str) -> FileDiff {
+        FileDiff {
+            old_path: path.to_string(),
+            new_path: path.to_string(),
+            change_type: "modified".to_string(),
+            language: None,
+            hunks: vec![hunk.to_string()],
+            structured_hunks: Vec::new(),
+            stats: DiffStats {
+                additions: 0,
+                deletions: 0,
+            },
+            is_binary: false,
+            is_lfs: false,
+            collapsed: None,
+            old_content: Some(old.to_string()),
+            new_content: Some(new.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_edit_attributed_and_human_line_flagged() {
+        let old = "fn main() {\n    println!(\"hi\");\n}\n";
+        let new = "fn main() {\n    println!(\"hello\");\n    // tweaked by hand\n}\n";
+        let hunk = "Index: src/main.rs\n--- src/main.rs\t\n+++ src/main.rs\t\n@@ -1,3 +1,4 @@\n fn main() {\n-    println!(\"hi\");\n+    println!(\"hello\");\n+    // tweaked by hand\n }";
+        let diff = file_diff("src/main.rs", old, new, hunk);
+
+        let messages = vec![tool_use(
+            "msg-1",
+            "tool-1",
+            "Edit",
+            json!({
+                "file_path": "/repo/src/main.rs",
+                "old_string": "println!(\"hi\");",
+                "new_string": "println!(\"hello\");"
+            }),
+        )];
+
+        let result = attribute_session_diff("session", "/repo", &[diff], &messages);
+        let file = &result.files[0];
+        assert_eq!(file.agent_lines, 1);
+        assert_eq!(file.human_lines, 1);
+        assert_eq!(file.unreplayed_edits, 0);
+
+        let hunk = &file.hunks[0];
+        assert_eq!(hunk.author, HunkAuthor::Mixed);
+        assert_eq!(hunk.human_line_numbers, vec![3]);
+        assert_eq!(hunk.sources[0].message_uuid, "msg-1");
+        assert_eq!(hunk.sources[0].tool_use_id, "tool-1");
+    }
+
+    #[test]
+    fn test_apply_patch_replay() {
+        let old = "a\nb\nc\n";
+        let new = "a\nB\nc\nd\n";
+        let hunk = "@@ -1,3 +1,4 @@\n a\n-b\n+B\n c\n+d";
+        let diff = file_diff("lib.py", old, new, hunk);
+
+        let patch =
+            "*** Begin Patch\n*** Update File: lib.py\n@@\n a\n-b\n+B\n c\n+d\n*** End Patch";
+        let messages = vec![tool_use(
+            "msg-1",
+            "call-1",
+            "shell",
+            json!({"command": ["apply_patch", patch]}),
+        )];
+
+        let result = attribute_session_diff("session", "/repo", &[diff], &messages);
+        assert_eq!(result.agent_lines, 2);
+        assert_eq!(result.human_lines, 0);
+        assert_eq!(result.files[0].hunks[0].author, HunkAuthor::Agent);
+        assert_eq!(result.files[0].hunks[0].sources[0].tool_name, "shell");
+    }
+
+    #[test]
+    fn test_unrelated_file_is_human() {
+        let diff = file_diff(
+            "README.md",
+            "",
+            "# Notes written by hand\n",
+            "@@ -0,0 +1,1 @@\n+# Notes written by hand",
+        );
+        let messages = vec![tool_use(
+            "msg-1",
+            "tool-1",
+            "Write",
+            json!({"file_path": "/repo/other.md", "content": "# Other\n"}),
+        )];
+
+        let result = attribute_session_diff("session", "/repo", &[diff], &messages);
+        assert_eq!(result.files[0].hunks[0].author, HunkAuthor::Human);
+        assert!(result.files[0].hunks[0].sources.is_empty());
+    }
+}
@@ -1,7 +1,9 @@
+use super::bus::EventReceiver;
 use super::{EventBus, SessionEvent, SessionEventPayload};
 use crate::database;
 use crate::logging::{log_error, log_info};
 use crate::shutdown::ShutdownCoordinator;
+use tauri::async_runtime::JoinHandle;
 use tauri::Emitter;
 use tokio::sync::broadcast;
 
@@ -19,38 +21,20 @@ impl DatabaseEventHandler {
         }
     }
 
-    pub fn start(self) {
-        tauri::async_runtime::spawn(async move {
-            let mut rx = self.event_bus.subscribe();
-            let mut shutdown_rx = self.shutdown.subscribe();
+    /// Spawn the handler; the handle resolves once it has drained the bus after shutdown
+    pub fn start(self) -> JoinHandle<()> {
+        let rx = self.event_bus.subscribe();
+        let shutdown_rx = self.shutdown.subscribe();
 
-            loop {
-                tokio::select! {
-                    result = rx.recv() => {
-                        match result {
-                            Ok(event) => {
-                                if let Err(e) = self.handle_event(&event) {
-                                    log_error(&event.provider, &format!("Database handler error: {}", e))
-                                        .unwrap_or_default();
-                                }
-                            }
-                            Err(broadcast::error::RecvError::Closed) => {
-                                log_info("events", "Database handler stopped (event bus closed)").unwrap_or_default();
-                                break;
-                            }
-                            Err(broadcast::error::RecvError::Lagged(n)) => {
-                                log_error("events", &format!("Database handler lagged {} events", n))
-                                    .unwrap_or_default();
-                            }
-                        }
-                    }
-                    _ = shutdown_rx.recv() => {
-                        log_info("events", "Database handler gracefully shutting down").unwrap_or_default();
-                        break;
-                    }
+        tauri::async_runtime::spawn(async move {
+            run_until_shutdown(rx, shutdown_rx, |event| {
+                if let Err(e) = self.handle_event(event) {
+                    log_error(&event.provider, &format!("Database handler error: {}", e))
+                        .unwrap_or_default();
                 }
-            }
-        });
+            })
+            .await;
+        })
     }
 
     fn handle_event(&self, event: &SessionEvent) -> Result<(), String> {
@@ -81,22 +65,14 @@ impl DatabaseEventHandler {
                 session_id,
                 start_time,
                 end_time,
-                ..
+                duration_ms,
             } => {
-                // Update with timing information
-                database::update_session(
-                    session_id,
-                    "", // file_name not changed
-                    "", // file_path not changed
-                    0, // file_size not changed
-                    None,
-                    Some(*start_time),
-                    Some(*end_time),
-                    None,
-                    None,
-                    None,
-                )
-                .map_err(|e| e.to_string())?;
+                // Only timing changes; the file fields stay as SessionChanged left them
+                database::complete_session(session_id, *start_time, *end_time, *duration_ms)
+                    .map_err(|e| e.to_string())?;
+
+                // Keep the end snapshot from the last agent write, not from now
+                crate::providers::common::db_helpers::finish_git_snapshots(session_id);
             }
 
             SessionEventPayload::Failed { session_id, reason } => {
@@ -109,6 +85,49 @@ impl DatabaseEventHandler {
     }
 }
 
+/// Database handler loop
+///
+/// Events already on the bus when shutdown fires are still handled, so
+/// whatever the watchers flushed before shutdown reaches the database.
+async fn run_until_shutdown<F>(
+    mut rx: EventReceiver,
+    mut shutdown_rx: broadcast::Receiver<()>,
+    mut handle: F,
+) where
+    F: FnMut(&SessionEvent),
+{
+    loop {
+        tokio::select! {
+            result = rx.recv() => {
+                match result {
+                    Ok(event) => handle(&event),
+                    Err(broadcast::error::RecvError::Closed) => {
+                        log_info("events", "Database handler stopped (event bus closed)").unwrap_or_default();
+                        return;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        log_error("events", &format!("Database handler lagged {} events", n))
+                            .unwrap_or_default();
+                    }
+                }
+            }
+            _ = shutdown_rx.recv() => break,
+        }
+    }
+
+    loop {
+        match rx.try_recv() {
+            Ok(event) => handle(&event),
+            Err(broadcast::error::TryRecvError::Lagged(n)) => {
+                log_error("events", &format!("Database handler lagged {} events", n))
+                    .unwrap_or_default();
+            }
+            Err(_) => break,
+        }
+    }
+    log_info("events", "Database handler gracefully shutting down").unwrap_or_default();
+}
+
 /// Handler that emits events to frontend
 pub struct FrontendEventHandler {
     event_bus: EventBus,
@@ -168,3 +187,58 @@ impl FrontendEventHandler {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::common::WatchTask;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio_util::sync::CancellationToken;
+
+    #[tokio::test]
+    async fn test_flushed_watcher_event_reaches_database_handler() {
+        let event_bus = EventBus::new(100);
+        let shutdown = ShutdownCoordinator::new();
+        let watcher_shutdown = CancellationToken::new();
+
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let handled_clone = Arc::clone(&handled);
+        let handler = tokio::spawn(run_until_shutdown(
+            event_bus.subscribe(),
+            shutdown.subscribe(),
+            move |event| handled_clone.lock().unwrap().push(event.clone()),
+        ));
+
+        // A watcher that only publishes while flushing
+        let bus = event_bus.clone();
+        let task = WatchTask::spawn(&watcher_shutdown, move |mut cancellation| async move {
+            cancellation.cancelled().await;
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            bus.publish(
+                "claude-code",
+                SessionEventPayload::SessionChanged {
+                    session_id: "flushed-session".to_string(),
+                    project_name: "project".to_string(),
+                    file_path: PathBuf::from("/tmp/flushed-session.jsonl"),
+                    file_size: 42,
+                },
+            )
+            .unwrap();
+        });
+
+        // Same order as AppState::shutdown_watchers
+        watcher_shutdown.cancel();
+        task.join(Duration::from_secs(5)).await.unwrap();
+        shutdown.shutdown();
+        handler.await.unwrap();
+
+        let handled = handled.lock().unwrap();
+        assert_eq!(handled.len(), 1);
+        assert!(matches!(
+            &handled[0].payload,
+            SessionEventPayload::SessionChanged { session_id, .. } if session_id == "flushed-session"
+        ));
+    }
+}
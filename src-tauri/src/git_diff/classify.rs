@@ -0,0 +1,237 @@
+//! File classification for diffs: language, lockfiles/generated files, binary and LFS
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// How many leading bytes are inspected for binary and generated-file markers
+pub const SNIFF_BYTES: usize = 8000;
+
+const LFS_POINTER_PREFIX: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+
+/// Why a file's diff body was collapsed (stats are still reported)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollapseReason {
+    /// Package manager lockfile
+    Lockfile,
+    /// Minified, vendored or code-generated file
+    Generated,
+    /// File exceeds the per-file byte limit
+    TooLarge,
+    /// The diff already exceeded the total byte limit
+    TotalLimit,
+}
+
+const LOCKFILES: &[&str] = &[
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "bun.lock",
+    "Cargo.lock",
+    "Gemfile.lock",
+    "composer.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "uv.lock",
+    "pdm.lock",
+    "go.sum",
+    "mix.lock",
+    "pubspec.lock",
+    "Podfile.lock",
+    "Package.resolved",
+    "flake.lock",
+    "packages.lock.json",
+    "gradle.lockfile",
+    "deno.lock",
+];
+
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".min.js",
+    ".min.css",
+    ".js.map",
+    ".css.map",
+    ".pb.go",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    ".pb.h",
+    ".pb.cc",
+    ".g.dart",
+    ".freezed.dart",
+    ".designer.cs",
+    ".generated.ts",
+    ".snap",
+];
+
+const GENERATED_DIRS: &[&str] = &["node_modules/", "vendor/", "__generated__/", "generated/"];
+
+/// Collapse reason based on the file path alone
+pub fn collapse_reason_for_path(file_path: &str) -> Option<CollapseReason> {
+    let file_name = Path::new(file_path).file_name()?.to_str()?;
+
+    if LOCKFILES.contains(&file_name) {
+        return Some(CollapseReason::Lockfile);
+    }
+
+    let normalized = file_path.replace('\\', "/");
+    if GENERATED_SUFFIXES
+        .iter()
+        .any(|suffix| file_name.ends_with(suffix))
+        || GENERATED_DIRS
+            .iter()
+            .any(|dir| normalized.starts_with(dir) || normalized.contains(&format!("/{}", dir)))
+    {
+        return Some(CollapseReason::Generated);
+    }
+
+    None
+}
+
+/// Whether the head of a file carries a code-generation marker
+pub fn has_generated_marker(head: &str) -> bool {
+    head.lines().take(5).any(|line| {
+        line.contains("@generated")
+            || line.contains("DO NOT EDIT")
+            || line.contains("Code generated by")
+            || line.contains("<auto-generated")
+    })
+}
+
+/// Git's heuristic: a NUL byte in the first few KB means binary
+pub fn is_binary_content(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(SNIFF_BYTES)].contains(&0)
+}
+
+/// Whether content is a Git LFS pointer rather than the real file
+pub fn is_lfs_pointer(bytes: &[u8]) -> bool {
+    bytes.starts_with(LFS_POINTER_PREFIX)
+}
+
+/// Detect programming language from file name or extension
+pub fn detect_language(file_path: &str) -> Option<String> {
+    let path = Path::new(file_path);
+
+    // Well-known extensionless file names
+    let by_name = match path.file_name()?.to_str()? {
+        "Dockerfile" | "Containerfile" => Some("dockerfile"),
+        "Makefile" | "makefile" | "GNUmakefile" => Some("makefile"),
+        "CMakeLists.txt" => Some("cmake"),
+        "Gemfile" | "Rakefile" | "Podfile" | "Brewfile" => Some("ruby"),
+        "Jenkinsfile" => Some("groovy"),
+        "Vagrantfile" => Some("ruby"),
+        ".bashrc" | ".zshrc" | ".profile" | ".bash_profile" => Some("bash"),
+        _ => None,
+    };
+    if let Some(lang) = by_name {
+        return Some(lang.to_string());
+    }
+
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+
+    let lang = match ext.as_str() {
+        "rs" => "rust",
+        "ts" | "tsx" | "mts" | "cts" => "typescript",
+        "js" | "jsx" | "mjs" | "cjs" => "javascript",
+        "py" | "pyi" | "pyw" => "python",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "scala" | "sc" => "scala",
+        "groovy" | "gradle" => "groovy",
+        "clj" | "cljs" | "cljc" | "edn" => "clojure",
+        "cpp" | "cc" | "cxx" | "hh" | "hxx" => "cpp",
+        "c" => "c",
+        "h" | "hpp" => "cpp",
+        "m" | "mm" => "objectivec",
+        "cs" => "csharp",
+        "fs" | "fsx" | "fsi" => "fsharp",
+        "vb" => "vbnet",
+        "swift" => "swift",
+        "dart" => "dart",
+        "rb" | "erb" => "ruby",
+        "php" => "php",
+        "pl" | "pm" => "perl",
+        "lua" => "lua",
+        "r" => "r",
+        "jl" => "julia",
+        "ex" | "exs" => "elixir",
+        "erl" | "hrl" => "erlang",
+        "hs" => "haskell",
+        "ml" | "mli" => "ocaml",
+        "elm" => "elm",
+        "zig" => "zig",
+        "nim" => "nim",
+        "v" | "sv" => "verilog",
+        "vhd" | "vhdl" => "vhdl",
+        "sol" => "solidity",
+        "sh" | "bash" | "zsh" | "fish" => "bash",
+        "ps1" | "psm1" => "powershell",
+        "bat" | "cmd" => "dos",
+        "sql" => "sql",
+        "graphql" | "gql" => "graphql",
+        "proto" => "protobuf",
+        "html" | "htm" => "html",
+        "vue" => "vue",
+        "svelte" => "svelte",
+        "astro" => "astro",
+        "css" => "css",
+        "scss" | "sass" => "scss",
+        "less" => "less",
+        "json" | "jsonc" | "json5" => "json",
+        "yaml" | "yml" => "yaml",
+        "toml" => "toml",
+        "ini" | "cfg" | "conf" => "ini",
+        "xml" | "xsd" | "xsl" | "plist" | "csproj" | "fsproj" => "xml",
+        "svg" => "xml",
+        "md" | "mdx" | "markdown" => "markdown",
+        "rst" => "rst",
+        "tex" => "latex",
+        "tf" | "tfvars" | "hcl" => "hcl",
+        "nix" => "nix",
+        "dockerfile" => "dockerfile",
+        "cmake" => "cmake",
+        "diff" | "patch" => "diff",
+        _ => return None,
+    };
+
+    Some(lang.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapse_reason_for_path() {
+        assert_eq!(
+            collapse_reason_for_path("web/package-lock.json"),
+            Some(CollapseReason::Lockfile)
+        );
+        assert_eq!(
+            collapse_reason_for_path("Cargo.lock"),
+            Some(CollapseReason::Lockfile)
+        );
+        assert_eq!(
+            collapse_reason_for_path("dist/app.min.js"),
+            Some(CollapseReason::Generated)
+        );
+        assert_eq!(
+            collapse_reason_for_path("api/generated/client.ts"),
+            Some(CollapseReason::Generated)
+        );
+        assert_eq!(collapse_reason_for_path("src/main.rs"), None);
+    }
+
+    #[test]
+    fn test_binary_and_lfs_detection() {
+        assert!(is_binary_content(b"PNG\0\0data"));
+        assert!(!is_binary_content(b"plain text"));
+        assert!(is_lfs_pointer(
+            b"version https://git-lfs.github.com/spec/v1\noid sha256:abc\nsize 12\n"
+        ));
+        assert!(has_generated_marker(
+            "// Code generated by protoc-gen-go. DO NOT EDIT.\n"
+        ));
+    }
+}
@@ -0,0 +1,873 @@
+mod classify;
+mod word_diff;
+
+use classify::{
+    collapse_reason_for_path, detect_language, has_generated_marker, is_binary_content,
+    is_lfs_pointer, SNIFF_BYTES,
+};
+use git2::{Diff, DiffFindOptions, DiffOptions, Repository};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use word_diff::diff_words;
+
+pub use classify::CollapseReason;
+pub use word_diff::WordSegment;
+
+/// Default per-file limit for diff bodies and file contents
+const DEFAULT_MAX_FILE_BYTES: usize = 512 * 1024;
+
+/// Default limit for all diff bodies and contents in one response
+const DEFAULT_MAX_TOTAL_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub old_path: String,
+    pub new_path: String,
+    pub change_type: String, // "added", "deleted", "modified", "renamed", "copied"
+    pub language: Option<String>,
+    /// Unified patch text for the whole file (empty when collapsed)
+    pub hunks: Vec<String>,
+    pub structured_hunks: Vec<DiffHunk>,
+    pub stats: DiffStats,
+    pub is_binary: bool,
+    /// The file is a Git LFS pointer
+    pub is_lfs: bool,
+    /// Set when the diff body was left out; stats are still accurate
+    pub collapsed: Option<CollapseReason>,
+    pub old_content: Option<String>,
+    pub new_content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub additions: u32,
+    pub deletions: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Addition,
+    Deletion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    pub content: String,
+    /// Intra-line segments, set for paired deletion/addition lines when word diffs are on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word_diff: Option<Vec<WordSegment>>,
+}
+
+/// Options controlling how diffs are rendered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiffRenderOptions {
+    /// Compute intra-line word diffs for changed line pairs
+    pub word_diff: bool,
+    /// Detect renames and copies (including of untracked files)
+    pub detect_renames: bool,
+    /// Collapse lockfiles and generated files
+    pub collapse_generated: bool,
+    pub max_file_bytes: usize,
+    pub max_total_bytes: usize,
+}
+
+impl Default for DiffRenderOptions {
+    fn default() -> Self {
+        Self {
+            word_diff: false,
+            detect_renames: true,
+            collapse_generated: true,
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            max_total_bytes: DEFAULT_MAX_TOTAL_BYTES,
+        }
+    }
+}
+
+impl DiffRenderOptions {
+    /// Every file in full, for analysis rather than display
+    pub fn unlimited() -> Self {
+        Self {
+            collapse_generated: false,
+            max_file_bytes: usize::MAX,
+            max_total_bytes: usize::MAX,
+            ..Self::default()
+        }
+    }
+
+    /// git2 diff options shared by all session diffs
+    fn git_options(&self) -> DiffOptions {
+        let mut diff_opts = DiffOptions::new();
+        diff_opts.context_lines(3); // Standard 3 lines of context
+        diff_opts.include_untracked(true); // Include untracked files
+        diff_opts.recurse_untracked_dirs(true); // Recurse into untracked directories
+                                                // Larger files are treated as binary by libgit2 instead of being diffed
+        diff_opts.max_size(self.max_file_bytes.min(i64::MAX as usize) as i64);
+        diff_opts
+    }
+}
+
+/// Get the diff between a session's start and end working-tree snapshots
+///
+/// Snapshots are tree objects captured by `git_snapshot` while the session was
+/// live, so the result doesn't depend on what the repository looks like now.
+/// Active sessions diff the start snapshot against a fresh snapshot of the
+/// working tree, so untracked files are compared like for like.
+///
+/// # Arguments
+/// * `cwd` - Working directory path
+/// * `start_tree` - Tree id captured when the session started
+/// * `end_tree` - Tree id last captured while the session was live
+/// * `is_active` - Whether the session is currently active
+/// * `options` - Rendering options (word diffs, limits, collapsing)
+pub fn get_snapshot_diff(
+    cwd: &str,
+    start_tree: &str,
+    end_tree: Option<&str>,
+    is_active: bool,
+    options: &DiffRenderOptions,
+) -> Result<Vec<FileDiff>, String> {
+    let repo = Repository::open(cwd)
+        .map_err(|e| format!("Failed to open git repository at {}: {}", cwd, e))?;
+
+    let mut diff_opts = options.git_options();
+
+    let start = find_tree(&repo, start_tree)?;
+
+    match end_tree {
+        Some(end_tree) if !is_active => {
+            let end = find_tree(&repo, end_tree)?;
+            let diff = repo
+                .diff_tree_to_tree(Some(&start), Some(&end), Some(&mut diff_opts))
+                .map_err(|e| format!("Failed to create snapshot diff: {}", e))?;
+            parse_diff(&repo, diff, Some(&start), Some(&end), cwd, options)
+        }
+        _ => {
+            let live_tree = crate::git_snapshot::snapshot_working_tree(cwd)?;
+            let live = find_tree(&repo, &live_tree)?;
+            let diff = repo
+                .diff_tree_to_tree(Some(&start), Some(&live), Some(&mut diff_opts))
+                .map_err(|e| format!("Failed to create diff to working directory: {}", e))?;
+            parse_diff(&repo, diff, Some(&start), Some(&live), cwd, options)
+        }
+    }
+}
+
+/// Get diff between two commits in a repository, with optional timestamp filtering
+///
+/// Fallback for sessions recorded before working-tree snapshots existed:
+/// - If session is active (live): Shows committed changes since the first commit plus uncommitted changes
+/// - If session is inactive: Shows changes committed during the session window only. The
+///   current working tree says nothing about that session once the user has moved on.
+///
+/// # Arguments
+/// * `cwd` - Working directory path
+/// * `first_commit_hash` - Starting commit hash
+/// * `latest_commit_hash` - Ending commit hash (can be same as first)
+/// * `is_active` - Whether the session is currently active
+/// * `session_start_time` - Optional session start timestamp (Unix milliseconds)
+/// * `session_end_time` - Optional session end timestamp (Unix milliseconds)
+/// * `options` - Rendering options (word diffs, limits, collapsing)
+pub fn get_commit_diff(
+    cwd: &str,
+    first_commit_hash: &str,
+    latest_commit_hash: &str,
+    is_active: bool,
+    session_start_time: Option<i64>,
+    session_end_time: Option<i64>,
+    options: &DiffRenderOptions,
+) -> Result<Vec<FileDiff>, String> {
+    // Open repository
+    let repo = Repository::open(cwd)
+        .map_err(|e| format!("Failed to open git repository at {}: {}", cwd, e))?;
+
+    // Create diff options
+    let mut diff_opts = options.git_options();
+
+    // Get the first commit object
+    let first_oid = repo
+        .revparse_single(first_commit_hash)
+        .map_err(|e| format!("Failed to find first commit {}: {}", first_commit_hash, e))?;
+    let first_commit = first_oid
+        .peel_to_commit()
+        .map_err(|e| format!("Failed to peel first commit: {}", e))?;
+    let first_tree = first_commit
+        .tree()
+        .map_err(|e| format!("Failed to get first commit tree: {}", e))?;
+
+    if is_active {
+        // Active: everything from the session's starting point to the live working tree
+        let diff = repo
+            .diff_tree_to_workdir_with_index(Some(&first_tree), Some(&mut diff_opts))
+            .map_err(|e| format!("Failed to create diff to working directory: {}", e))?;
+
+        return parse_diff(&repo, diff, Some(&first_tree), None, cwd, options);
+    }
+
+    let latest_oid = repo
+        .revparse_single(latest_commit_hash)
+        .map_err(|e| format!("Failed to find latest commit {}: {}", latest_commit_hash, e))?;
+    let latest_commit = latest_oid
+        .peel_to_commit()
+        .map_err(|e| format!("Failed to peel latest commit: {}", e))?;
+
+    // The recorded latest commit may lag behind commits made at the very end of the session
+    let end_commit =
+        find_session_end_commit(&repo, latest_commit, session_start_time, session_end_time);
+    let end_tree = end_commit
+        .tree()
+        .map_err(|e| format!("Failed to get latest commit tree: {}", e))?;
+
+    let diff = repo
+        .diff_tree_to_tree(Some(&first_tree), Some(&end_tree), Some(&mut diff_opts))
+        .map_err(|e| format!("Failed to create diff: {}", e))?;
+
+    parse_diff(
+        &repo,
+        diff,
+        Some(&first_tree),
+        Some(&end_tree),
+        cwd,
+        options,
+    )
+}
+
+/// Look up a tree object by id
+fn find_tree<'r>(repo: &'r Repository, tree_id: &str) -> Result<git2::Tree<'r>, String> {
+    let oid =
+        git2::Oid::from_str(tree_id).map_err(|e| format!("Invalid tree id {}: {}", tree_id, e))?;
+    repo.find_tree(oid)
+        .map_err(|e| format!("Snapshot tree {} not found: {}", tree_id, e))
+}
+
+/// Find the newest commit made during the session window
+///
+/// Walks HEAD's history for commits whose commit time falls within
+/// `[session_start_time, session_end_time]` and that descend from the
+/// recorded latest commit. Falls back to the recorded latest commit.
+fn find_session_end_commit<'r>(
+    repo: &'r Repository,
+    latest_commit: git2::Commit<'r>,
+    session_start_time: Option<i64>,
+    session_end_time: Option<i64>,
+) -> git2::Commit<'r> {
+    let Some(end_ms) = session_end_time else {
+        return latest_commit;
+    };
+    let start_ms = session_start_time.unwrap_or(i64::MIN);
+
+    let Ok(mut revwalk) = repo.revwalk() else {
+        return latest_commit;
+    };
+    if revwalk.push_head().is_err() {
+        return latest_commit;
+    }
+    let _ = revwalk.set_sorting(git2::Sort::TIME);
+
+    for oid in revwalk.flatten() {
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        let commit_ms = commit.time().seconds() * 1000;
+        if commit_ms > end_ms {
+            continue;
+        }
+        if commit_ms < start_ms {
+            break;
+        }
+        // Only move forward from the recorded latest commit, never to an unrelated branch
+        if commit.id() == latest_commit.id()
+            || repo
+                .graph_descendant_of(commit.id(), latest_commit.id())
+                .unwrap_or(false)
+        {
+            return commit;
+        }
+    }
+
+    latest_commit
+}
+
+/// Parse git2 Diff into structured FileDiff objects
+///
+/// Each file gets structured hunks plus the unified patch text the frontend
+/// renders (`hunks`). Lockfiles, generated files and files over the byte
+/// limits keep their stats but are collapsed (no hunks, no contents).
+fn parse_diff(
+    repo: &Repository,
+    mut diff: Diff,
+    old_tree: Option<&git2::Tree>,
+    new_tree: Option<&git2::Tree>,
+    cwd: &str,
+    options: &DiffRenderOptions,
+) -> Result<Vec<FileDiff>, String> {
+    if options.detect_renames {
+        let mut find_opts = DiffFindOptions::new();
+        find_opts.renames(true).copies(true).for_untracked(true);
+        diff.find_similar(Some(&mut find_opts))
+            .map_err(|e| format!("Failed to detect renames: {}", e))?;
+    }
+
+    let mut file_diffs: Vec<FileDiff> = Vec::new();
+    let mut total_bytes = 0usize;
+
+    for idx in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(idx) else {
+            continue;
+        };
+
+        let old_path = delta
+            .old_file()
+            .path()
+            .unwrap_or(Path::new(""))
+            .to_string_lossy()
+            .to_string();
+        let new_path = delta
+            .new_file()
+            .path()
+            .unwrap_or(Path::new(""))
+            .to_string_lossy()
+            .to_string();
+
+        let change_type = match delta.status() {
+            git2::Delta::Added => "added",
+            git2::Delta::Untracked => "added", // Treat untracked files as added
+            git2::Delta::Deleted => "deleted",
+            git2::Delta::Modified => "modified",
+            git2::Delta::Renamed => "renamed",
+            git2::Delta::Copied => "copied",
+            _ => "modified",
+        };
+
+        let mut file = FileDiff {
+            old_path: if delta.status() == git2::Delta::Untracked {
+                String::new()
+            } else {
+                old_path
+            },
+            new_path: new_path.clone(),
+            change_type: change_type.to_string(),
+            language: detect_language(&new_path),
+            hunks: Vec::new(),
+            structured_hunks: Vec::new(),
+            stats: DiffStats {
+                additions: 0,
+                deletions: 0,
+            },
+            is_binary: false,
+            is_lfs: false,
+            collapsed: if options.collapse_generated {
+                collapse_reason_for_path(&new_path)
+            } else {
+                None
+            },
+            old_content: None,
+            new_content: None,
+        };
+
+        if delta.status() == git2::Delta::Untracked {
+            // Untracked files have no patch: synthesize one from the working directory
+            read_untracked_file(&mut file, cwd, options);
+        } else {
+            let patch = git2::Patch::from_diff(&diff, idx)
+                .map_err(|e| format!("Failed to create patch for {}: {}", new_path, e))?;
+
+            let file_size = delta.old_file().size().max(delta.new_file().size()) as usize;
+            let too_large = file_size > options.max_file_bytes;
+            if too_large && file.collapsed.is_none() {
+                file.collapsed = Some(CollapseReason::TooLarge);
+            }
+
+            // libgit2 flags files over `max_size` as binary; those are just large text
+            file.is_binary =
+                !too_large && (delta.old_file().is_binary() || delta.new_file().is_binary());
+            file.is_lfs = [delta.old_file().id(), delta.new_file().id()]
+                .iter()
+                .filter(|oid| !oid.is_zero())
+                .filter_map(|oid| repo.find_blob(*oid).ok())
+                .any(|blob| is_lfs_pointer(blob.content()));
+
+            if let Some(patch) = patch {
+                let (_, additions, deletions) = patch
+                    .line_stats()
+                    .map_err(|e| format!("Failed to read line stats: {}", e))?;
+                file.stats.additions = additions as u32;
+                file.stats.deletions = deletions as u32;
+
+                if !file.is_binary && file.collapsed.is_none() {
+                    file.structured_hunks = collect_hunks(&patch)?;
+                }
+            }
+        }
+
+        if file.is_binary || file.collapsed.is_some() {
+            file.structured_hunks.clear();
+        } else {
+            load_contents(repo, &mut file, old_tree, new_tree, cwd, options);
+        }
+
+        if options.word_diff {
+            for hunk in &mut file.structured_hunks {
+                add_word_diffs(hunk);
+            }
+        }
+
+        if file.collapsed.is_none() {
+            file.hunks = vec![render_patch(&file)];
+
+            // Stop shipping diff bodies once the whole response is too big
+            let file_bytes = file.hunks[0].len()
+                + file.old_content.as_ref().map_or(0, String::len)
+                + file.new_content.as_ref().map_or(0, String::len);
+            if total_bytes + file_bytes > options.max_total_bytes {
+                file.collapsed = Some(CollapseReason::TotalLimit);
+            } else {
+                total_bytes += file_bytes;
+            }
+        }
+
+        if file.collapsed.is_some() {
+            file.hunks.clear();
+            file.structured_hunks.clear();
+            file.old_content = None;
+            file.new_content = None;
+        }
+
+        file_diffs.push(file);
+    }
+
+    Ok(file_diffs)
+}
+
+/// Build structured hunks from a git2 patch
+fn collect_hunks(patch: &git2::Patch) -> Result<Vec<DiffHunk>, String> {
+    let mut hunks = Vec::new();
+
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch
+            .hunk(hunk_idx)
+            .map_err(|e| format!("Failed to read hunk: {}", e))?;
+
+        let mut lines = Vec::with_capacity(line_count);
+        for line_idx in 0..line_count {
+            let line = patch
+                .line_in_hunk(hunk_idx, line_idx)
+                .map_err(|e| format!("Failed to read diff line: {}", e))?;
+
+            let kind = match line.origin() {
+                '+' => DiffLineKind::Addition,
+                '-' => DiffLineKind::Deletion,
+                ' ' => DiffLineKind::Context,
+                _ => continue, // "\ No newline at end of file" and friends
+            };
+
+            lines.push(DiffLine {
+                kind,
+                old_line: line.old_lineno(),
+                new_line: line.new_lineno(),
+                content: String::from_utf8_lossy(line.content())
+                    .trim_end_matches(['\n', '\r'])
+                    .to_string(),
+                word_diff: None,
+            });
+        }
+
+        hunks.push(DiffHunk {
+            header: format!(
+                "@@ -{},{} +{},{} @@",
+                hunk.old_start(),
+                hunk.old_lines(),
+                hunk.new_start(),
+                hunk.new_lines()
+            ),
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+            lines,
+        });
+    }
+
+    Ok(hunks)
+}
+
+/// Fill in an untracked file as a single all-added hunk, respecting size limits
+fn read_untracked_file(file: &mut FileDiff, cwd: &str, options: &DiffRenderOptions) {
+    let full_path = Path::new(cwd).join(&file.new_path);
+    let Ok(metadata) = std::fs::metadata(&full_path) else {
+        return;
+    };
+
+    let head = read_head(&full_path).unwrap_or_default();
+    file.is_binary = is_binary_content(&head);
+    file.is_lfs = is_lfs_pointer(&head);
+
+    if metadata.len() as usize > options.max_file_bytes {
+        file.stats.additions = count_lines(&full_path);
+        if file.collapsed.is_none() {
+            file.collapsed = Some(CollapseReason::TooLarge);
+        }
+        return;
+    }
+
+    if file.is_binary {
+        return;
+    }
+
+    let Ok(content) = std::fs::read_to_string(&full_path) else {
+        return;
+    };
+
+    file.stats.additions = content.lines().count() as u32;
+    if options.collapse_generated
+        && file.collapsed.is_none()
+        && has_generated_marker(&String::from_utf8_lossy(&head))
+    {
+        file.collapsed = Some(CollapseReason::Generated);
+    }
+
+    if file.collapsed.is_none() {
+        let lines: Vec<DiffLine> = content
+            .lines()
+            .enumerate()
+            .map(|(i, line)| DiffLine {
+                kind: DiffLineKind::Addition,
+                old_line: None,
+                new_line: Some(i as u32 + 1),
+                content: line.to_string(),
+                word_diff: None,
+            })
+            .collect();
+        file.structured_hunks = vec![DiffHunk {
+            header: format!("@@ -0,0 +1,{} @@", lines.len()),
+            old_start: 0,
+            old_lines: 0,
+            new_start: 1,
+            new_lines: lines.len() as u32,
+            lines,
+        }];
+        file.new_content = Some(content);
+    }
+}
+
+/// Load old/new file contents for syntax highlighting
+fn load_contents(
+    repo: &Repository,
+    file: &mut FileDiff,
+    old_tree: Option<&git2::Tree>,
+    new_tree: Option<&git2::Tree>,
+    cwd: &str,
+    options: &DiffRenderOptions,
+) {
+    // Get old file content
+    if !file.old_path.is_empty() && file.change_type != "added" {
+        if let Some(tree) = old_tree {
+            file.old_content =
+                get_file_content_from_tree(repo, tree, &file.old_path, options.max_file_bytes).ok();
+        }
+    }
+
+    // Get new file content (untracked files already have theirs)
+    if file.new_content.is_none() && !file.new_path.is_empty() && file.change_type != "deleted" {
+        if let Some(tree) = new_tree {
+            // From tree (committed)
+            file.new_content =
+                get_file_content_from_tree(repo, tree, &file.new_path, options.max_file_bytes).ok();
+        } else {
+            // From working directory (uncommitted changes)
+            file.new_content =
+                get_file_content_from_workdir(cwd, &file.new_path, options.max_file_bytes).ok();
+        }
+    }
+
+    if options.collapse_generated && file.collapsed.is_none() {
+        let head: String = file
+            .new_content
+            .as_deref()
+            .unwrap_or_default()
+            .chars()
+            .take(SNIFF_BYTES)
+            .collect();
+        if has_generated_marker(&head) {
+            file.collapsed = Some(CollapseReason::Generated);
+        }
+    }
+}
+
+/// Pair each run of deleted lines with the following added lines and word-diff them
+fn add_word_diffs(hunk: &mut DiffHunk) {
+    let mut i = 0;
+    while i < hunk.lines.len() {
+        if hunk.lines[i].kind != DiffLineKind::Deletion {
+            i += 1;
+            continue;
+        }
+
+        let del_start = i;
+        while i < hunk.lines.len() && hunk.lines[i].kind == DiffLineKind::Deletion {
+            i += 1;
+        }
+        let add_start = i;
+        while i < hunk.lines.len() && hunk.lines[i].kind == DiffLineKind::Addition {
+            i += 1;
+        }
+
+        let pairs = (add_start - del_start).min(i - add_start);
+        for k in 0..pairs {
+            let (old_idx, new_idx) = (del_start + k, add_start + k);
+            if let Some((old_segments, new_segments)) =
+                diff_words(&hunk.lines[old_idx].content, &hunk.lines[new_idx].content)
+            {
+                hunk.lines[old_idx].word_diff = Some(old_segments);
+                hunk.lines[new_idx].word_diff = Some(new_segments);
+            }
+        }
+    }
+}
+
+/// Render structured hunks as the unified patch text expected by @git-diff-view
+fn render_patch(file: &FileDiff) -> String {
+    let mut patch = String::new();
+
+    // Add Index header
+    patch.push_str(&format!("Index: {}\n", file.new_path));
+    patch.push_str("===================================================================\n");
+    // Add file headers with tabs (like the library expects)
+    // Use /dev/null for added files (when old_path is empty)
+    let old_path_display = if file.old_path.is_empty() {
+        "/dev/null"
+    } else {
+        &file.old_path
+    };
+    patch.push_str(&format!("--- {}\t\n", old_path_display));
+    patch.push_str(&format!("+++ {}\t\n", file.new_path));
+
+    for hunk in &file.structured_hunks {
+        if !patch.ends_with('\n') {
+            patch.push('\n');
+        }
+        patch.push_str(&hunk.header);
+        for line in &hunk.lines {
+            patch.push('\n');
+            patch.push(match line.kind {
+                DiffLineKind::Addition => '+',
+                DiffLineKind::Deletion => '-',
+                DiffLineKind::Context => ' ',
+            });
+            patch.push_str(&line.content);
+        }
+    }
+
+    patch
+}
+
+/// Get file content from a git tree
+fn get_file_content_from_tree(
+    repo: &Repository,
+    tree: &git2::Tree,
+    path: &str,
+    max_bytes: usize,
+) -> Result<String, String> {
+    let entry = tree
+        .get_path(Path::new(path))
+        .map_err(|e| format!("File not found in tree: {}", e))?;
+
+    let object = entry
+        .to_object(repo)
+        .map_err(|e| format!("Failed to get object: {}", e))?;
+
+    let blob = object
+        .as_blob()
+        .ok_or_else(|| "Object is not a blob".to_string())?;
+
+    if blob.size() > max_bytes {
+        return Err(format!("File is larger than {} bytes", max_bytes));
+    }
+
+    let content = String::from_utf8(blob.content().to_vec())
+        .map_err(|_| "File content is not valid UTF-8".to_string())?;
+
+    Ok(content)
+}
+
+/// Get file content from working directory
+fn get_file_content_from_workdir(
+    cwd: &str,
+    path: &str,
+    max_bytes: usize,
+) -> Result<String, String> {
+    let full_path = Path::new(cwd).join(path);
+    let metadata = std::fs::metadata(&full_path)
+        .map_err(|e| format!("Failed to read file from working directory: {}", e))?;
+    if metadata.len() as usize > max_bytes {
+        return Err(format!("File is larger than {} bytes", max_bytes));
+    }
+    std::fs::read_to_string(&full_path)
+        .map_err(|e| format!("Failed to read file from working directory: {}", e))
+}
+
+/// Read the first few KB of a file for binary/LFS/generated sniffing
+fn read_head(path: &Path) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    std::fs::File::open(path)?
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut head)?;
+    Ok(head)
+}
+
+/// Count lines without loading the whole file into memory
+fn count_lines(path: &Path) -> u32 {
+    use std::io::BufRead;
+
+    std::fs::File::open(path)
+        .map(|file| std::io::BufReader::new(file).split(b'\n').count() as u32)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("main.rs"), Some("rust".to_string()));
+        assert_eq!(detect_language("App.tsx"), Some("typescript".to_string()));
+        assert_eq!(detect_language("script.py"), Some("python".to_string()));
+        assert_eq!(detect_language("unknown.xyz"), None);
+    }
+
+    #[test]
+    fn test_snapshot_diff_ignores_later_changes() {
+        use crate::git_snapshot::snapshot_working_tree;
+        use std::fs;
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.rs")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+
+        // Untracked before the session and never touched by it
+        fs::write(dir.path().join("notes.txt"), "todo\n").unwrap();
+
+        let cwd = dir.path().to_str().unwrap();
+        let start = snapshot_working_tree(cwd).unwrap();
+        fs::write(dir.path().join("a.rs"), "fn a() { b() }\n").unwrap();
+        let end = snapshot_working_tree(cwd).unwrap();
+
+        // Work done after the session must not leak into its diff
+        fs::write(dir.path().join("later.rs"), "fn later() {}\n").unwrap();
+
+        let options = DiffRenderOptions::default();
+        let diffs = get_snapshot_diff(cwd, &start, Some(&end), false, &options).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].new_path, "a.rs");
+        assert_eq!(diffs[0].new_content.as_deref(), Some("fn a() { b() }\n"));
+
+        let live = get_snapshot_diff(cwd, &start, Some(&end), true, &options).unwrap();
+        assert!(live.iter().any(|d| d.new_path == "later.rs"));
+        assert!(!live
+            .iter()
+            .any(|d| d.new_path == "notes.txt" || d.old_path == "notes.txt"));
+    }
+
+    #[test]
+    fn test_structured_hunks_renames_and_collapsing() {
+        use crate::git_snapshot::snapshot_working_tree;
+        use std::fs;
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let body: String = (0..20).map(|i| format!("line {}\n", i)).collect();
+        fs::write(dir.path().join("old_name.py"), &body).unwrap();
+        fs::write(dir.path().join("edit.rs"), "let x = foo(1);\n").unwrap();
+        fs::write(dir.path().join("Cargo.lock"), "version = 3\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+
+        let cwd = dir.path().to_str().unwrap();
+        let start = snapshot_working_tree(cwd).unwrap();
+        fs::rename(
+            dir.path().join("old_name.py"),
+            dir.path().join("new_name.py"),
+        )
+        .unwrap();
+        fs::write(dir.path().join("edit.rs"), "let x = bar(1);\n").unwrap();
+        fs::write(dir.path().join("Cargo.lock"), "version = 4\n").unwrap();
+        let end = snapshot_working_tree(cwd).unwrap();
+
+        let options = DiffRenderOptions {
+            word_diff: true,
+            ..DiffRenderOptions::default()
+        };
+        let diffs = get_snapshot_diff(cwd, &start, Some(&end), false, &options).unwrap();
+        let by_path = |path: &str| diffs.iter().find(|d| d.new_path == path).unwrap();
+
+        let renamed = by_path("new_name.py");
+        assert_eq!(renamed.change_type, "renamed");
+        assert_eq!(renamed.old_path, "old_name.py");
+
+        let lock = by_path("Cargo.lock");
+        assert_eq!(lock.collapsed, Some(CollapseReason::Lockfile));
+        assert!(lock.hunks.is_empty());
+        assert_eq!(lock.stats.additions, 1);
+
+        let edit = by_path("edit.rs");
+        let lines = &edit.structured_hunks[0].lines;
+        assert_eq!(lines[0].kind, DiffLineKind::Deletion);
+        assert_eq!(lines[1].kind, DiffLineKind::Addition);
+        assert_eq!(lines[1].new_line, Some(1));
+        let changed: Vec<&str> = lines[1]
+            .word_diff
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|s| s.changed)
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(changed, vec!["bar"]);
+        assert!(edit.hunks[0].contains("@@ -1,1 +1,1 @@\n-let x = foo(1);\n+let x = bar(1);"));
+
+        let tight = DiffRenderOptions {
+            max_total_bytes: 10,
+            ..DiffRenderOptions::default()
+        };
+        let diffs = get_snapshot_diff(cwd, &start, Some(&end), false, &tight).unwrap();
+        assert!(diffs
+            .iter()
+            .all(|d| d.collapsed.is_some() || d.stats.additions == 0));
+
+        let unlimited = DiffRenderOptions::unlimited();
+        let diffs = get_snapshot_diff(cwd, &start, Some(&end), false, &unlimited).unwrap();
+        assert!(diffs.iter().all(|d| d.collapsed.is_none()));
+        let lock = diffs.iter().find(|d| d.new_path == "Cargo.lock").unwrap();
+        assert!(!lock.hunks.is_empty());
+    }
+}
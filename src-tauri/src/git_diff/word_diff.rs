@@ -0,0 +1,128 @@
+//! Intra-line word diffs for paired deleted/added lines
+
+use serde::{Deserialize, Serialize};
+
+/// Lines longer than this are not word-diffed (the token table grows quadratically)
+const MAX_WORD_DIFF_LINE_LEN: usize = 1000;
+
+/// A run of text within a line, marked if it differs from the paired line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordSegment {
+    pub text: String,
+    pub changed: bool,
+}
+
+/// Split a line into words, whitespace runs and single punctuation characters
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut prev_class: Option<u8> = None;
+
+    for (i, c) in line.char_indices() {
+        let class = if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        };
+
+        // Punctuation never merges with its neighbours
+        if let Some(prev) = prev_class {
+            if prev != class || class == 2 {
+                tokens.push(&line[start..i]);
+                start = i;
+            }
+        }
+        prev_class = Some(class);
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+
+    tokens
+}
+
+/// Word-level diff of an old and new line
+///
+/// Returns the segments of the old line and of the new line, or `None` when
+/// either line is too long to diff.
+pub fn diff_words(old: &str, new: &str) -> Option<(Vec<WordSegment>, Vec<WordSegment>)> {
+    if old.len() > MAX_WORD_DIFF_LINE_LEN || new.len() > MAX_WORD_DIFF_LINE_LEN {
+        return None;
+    }
+
+    let a = tokenize(old);
+    let b = tokenize(new);
+    let (n, m) = (a.len(), b.len());
+
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let mut lcs = vec![0u16; (n + 1) * (m + 1)];
+    let idx = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[idx(i, j)] = if a[i] == b[j] {
+                lcs[idx(i + 1, j + 1)] + 1
+            } else {
+                lcs[idx(i + 1, j)].max(lcs[idx(i, j + 1)])
+            };
+        }
+    }
+
+    let mut old_segments = Vec::new();
+    let mut new_segments = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a[i] == b[j] {
+            push_segment(&mut old_segments, a[i], false);
+            push_segment(&mut new_segments, b[j], false);
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[idx(i, j + 1)] >= lcs[idx(i + 1, j)]) {
+            push_segment(&mut new_segments, b[j], true);
+            j += 1;
+        } else {
+            push_segment(&mut old_segments, a[i], true);
+            i += 1;
+        }
+    }
+
+    Some((old_segments, new_segments))
+}
+
+/// Append a token, merging it with the previous segment when both have the same state
+fn push_segment(segments: &mut Vec<WordSegment>, text: &str, changed: bool) {
+    match segments.last_mut() {
+        Some(last) if last.changed == changed => last.text.push_str(text),
+        _ => segments.push(WordSegment {
+            text: text.to_string(),
+            changed,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_words_marks_changed_token() {
+        let (old, new) = diff_words("let x = foo(1);", "let x = bar(1);").unwrap();
+
+        let changed_old: Vec<&str> = old
+            .iter()
+            .filter(|s| s.changed)
+            .map(|s| s.text.as_str())
+            .collect();
+        let changed_new: Vec<&str> = new
+            .iter()
+            .filter(|s| s.changed)
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(changed_old, vec!["foo"]);
+        assert_eq!(changed_new, vec!["bar"]);
+
+        let rebuilt: String = new.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(rebuilt, "let x = bar(1);");
+    }
+}
@@ -0,0 +1,356 @@
+//! Working-tree snapshots for sessions
+//!
+//! A snapshot is a git tree object that captures the working tree (tracked
+//! changes and untracked, non-ignored files) at one point in time, similar
+//! to what `git stash create` records. Snapshots are written into the
+//! repository's object database without touching the index or HEAD, and are
+//! pinned by a ref under `refs/guideai/sessions/` so `git gc` keeps them.
+//!
+//! Watchers take a snapshot when a session starts and refresh the end snapshot
+//! while it is live, so the session diff stays correct after the user moves on.
+//! The refs are removed when the session is deleted (including by retention).
+
+use crate::logging::{log_debug, log_warn};
+use git2::{Index, IndexEntry, IndexTime, Oid, Repository, Status, StatusOptions};
+use std::path::Path;
+
+/// Ref namespace that keeps snapshot trees reachable
+const SNAPSHOT_REF_PREFIX: &str = "refs/guideai/sessions";
+
+/// Untracked files above this size are left out of snapshots rather than
+/// written into the repository's object database
+const MAX_UNTRACKED_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Which end of a session a snapshot belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotKind {
+    Start,
+    End,
+}
+
+impl SnapshotKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotKind::Start => "start",
+            SnapshotKind::End => "end",
+        }
+    }
+}
+
+/// Write a tree object for the current working tree and return its id
+///
+/// The tree starts from HEAD (empty for unborn branches) and applies every
+/// modified, deleted and untracked file from the working directory.
+/// Ignored files and untracked files over `MAX_UNTRACKED_FILE_BYTES` are skipped.
+pub fn snapshot_working_tree(cwd: &str) -> Result<String, String> {
+    let repo = Repository::open(cwd)
+        .map_err(|e| format!("Failed to open git repository at {}: {}", cwd, e))?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| "Cannot snapshot a bare repository".to_string())?
+        .to_path_buf();
+
+    let mut index = Index::new().map_err(|e| format!("Failed to create index: {}", e))?;
+    if let Ok(head_tree) = repo.head().and_then(|head| head.peel_to_tree()) {
+        index
+            .read_tree(&head_tree)
+            .map_err(|e| format!("Failed to read HEAD tree: {}", e))?;
+    }
+
+    let mut status_opts = StatusOptions::new();
+    status_opts
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        .exclude_submodules(true);
+    let statuses = repo
+        .statuses(Some(&mut status_opts))
+        .map_err(|e| format!("Failed to read git status: {}", e))?;
+
+    for entry in statuses.iter() {
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        let status = entry.status();
+
+        if status.intersects(Status::WT_DELETED | Status::INDEX_DELETED)
+            && !workdir.join(path).exists()
+        {
+            // Missing from the working tree: drop it from the snapshot
+            let _ = index.remove_path(Path::new(path));
+            continue;
+        }
+
+        if status == Status::WT_NEW {
+            let size = std::fs::symlink_metadata(workdir.join(path)).map_or(0, |m| m.len());
+            if size > MAX_UNTRACKED_FILE_BYTES {
+                let _ = log_debug(
+                    "git-snapshot",
+                    &format!("Skipping large untracked file {} ({} bytes)", path, size),
+                );
+                continue;
+            }
+        }
+
+        if status.intersects(
+            Status::WT_NEW
+                | Status::WT_MODIFIED
+                | Status::WT_TYPECHANGE
+                | Status::WT_RENAMED
+                | Status::INDEX_NEW
+                | Status::INDEX_MODIFIED
+                | Status::INDEX_TYPECHANGE
+                | Status::INDEX_RENAMED,
+        ) {
+            add_workdir_file(&repo, &mut index, &workdir, path)?;
+        }
+    }
+
+    let tree_id = index
+        .write_tree_to(&repo)
+        .map_err(|e| format!("Failed to write snapshot tree: {}", e))?;
+
+    Ok(tree_id.to_string())
+}
+
+/// Snapshot the working tree and pin it under the session's ref
+pub fn capture_session_snapshot(
+    cwd: &str,
+    session_id: &str,
+    kind: SnapshotKind,
+) -> Result<String, String> {
+    let tree_id = snapshot_working_tree(cwd)?;
+
+    let repo = Repository::open(cwd)
+        .map_err(|e| format!("Failed to open git repository at {}: {}", cwd, e))?;
+    let oid = Oid::from_str(&tree_id).map_err(|e| format!("Invalid tree id: {}", e))?;
+    repo.reference(
+        &snapshot_ref_name(session_id, kind),
+        oid,
+        true,
+        "guideai: session snapshot",
+    )
+    .map_err(|e| format!("Failed to store snapshot ref: {}", e))?;
+
+    Ok(tree_id)
+}
+
+/// Delete the refs that pin a session's snapshots
+pub fn remove_session_snapshots(cwd: &str, session_id: &str) -> Result<(), String> {
+    let repo = Repository::open(cwd)
+        .map_err(|e| format!("Failed to open git repository at {}: {}", cwd, e))?;
+
+    for kind in [SnapshotKind::Start, SnapshotKind::End] {
+        if let Ok(mut reference) = repo.find_reference(&snapshot_ref_name(session_id, kind)) {
+            reference
+                .delete()
+                .map_err(|e| format!("Failed to delete snapshot ref: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove the snapshot refs of the sessions matching `condition` before they are deleted
+///
+/// Failures (e.g. the repository was moved) are logged and never block the deletion.
+pub fn remove_snapshots_where(condition: &str, condition_params: &[&dyn rusqlite::ToSql]) {
+    let sessions = match crate::database::get_snapshotted_sessions(condition, condition_params) {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            let _ = log_warn(
+                "git",
+                &format!("⚠ Failed to look up session snapshots: {}", e),
+            );
+            return;
+        }
+    };
+
+    for (session_id, cwd) in sessions {
+        if let Err(e) = remove_session_snapshots(&cwd, &session_id) {
+            let _ = log_debug(
+                "git",
+                &format!(
+                    "Could not remove snapshots for session {}: {}",
+                    session_id, e
+                ),
+            );
+        }
+    }
+}
+
+fn snapshot_ref_name(session_id: &str, kind: SnapshotKind) -> String {
+    // Ref names can't contain spaces, colons and a few other characters
+    let safe_id: String = session_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}/{}/{}", SNAPSHOT_REF_PREFIX, safe_id, kind.as_str())
+}
+
+/// Write a working-tree file as a blob and stage it in the snapshot index
+fn add_workdir_file(
+    repo: &Repository,
+    index: &mut Index,
+    workdir: &Path,
+    path: &str,
+) -> Result<(), String> {
+    let full_path = workdir.join(path);
+    let metadata = match std::fs::symlink_metadata(&full_path) {
+        Ok(metadata) => metadata,
+        // File vanished between status and snapshot
+        Err(_) => {
+            let _ = index.remove_path(Path::new(path));
+            return Ok(());
+        }
+    };
+
+    let (blob_id, mode) = if metadata.file_type().is_symlink() {
+        let target = std::fs::read_link(&full_path)
+            .map_err(|e| format!("Failed to read symlink {}: {}", path, e))?;
+        let blob_id = repo
+            .blob(target.to_string_lossy().as_bytes())
+            .map_err(|e| format!("Failed to write blob for {}: {}", path, e))?;
+        (blob_id, 0o120000)
+    } else if metadata.is_file() {
+        let blob_id = repo
+            .blob_path(&full_path)
+            .map_err(|e| format!("Failed to write blob for {}: {}", path, e))?;
+        (blob_id, file_mode(&metadata))
+    } else {
+        return Ok(());
+    };
+
+    let path_bytes = path.as_bytes().to_vec();
+    let entry = IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: metadata.len() as u32,
+        id: blob_id,
+        flags: path_bytes.len().min(0xfff) as u16,
+        flags_extended: 0,
+        path: path_bytes,
+    };
+
+    index
+        .add(&entry)
+        .map_err(|e| format!("Failed to add {} to snapshot: {}", path, e))
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    if metadata.permissions().mode() & 0o111 != 0 {
+        0o100755
+    } else {
+        0o100644
+    }
+}
+
+#[cfg(not(unix))]
+fn file_mode(_metadata: &std::fs::Metadata) -> u32 {
+    0o100644
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn init_repo_with_commit(dir: &Path) -> Repository {
+        let repo = Repository::init(dir).unwrap();
+        fs::write(dir.join("tracked.txt"), "one\n").unwrap();
+        fs::write(dir.join("removed.txt"), "bye\n").unwrap();
+        fs::write(dir.join(".gitignore"), "ignored.log\n").unwrap();
+
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree_id = index.write_tree().unwrap();
+        {
+            let tree = repo.find_tree(tree_id).unwrap();
+            let sig = git2::Signature::now("test", "test@example.com").unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+                .unwrap();
+        }
+        repo
+    }
+
+    fn read_blob(repo: &Repository, tree_id: &str, path: &str) -> Option<String> {
+        let tree = repo.find_tree(Oid::from_str(tree_id).unwrap()).unwrap();
+        let entry = tree.get_path(Path::new(path)).ok()?;
+        let blob = repo.find_blob(entry.id()).unwrap();
+        Some(String::from_utf8(blob.content().to_vec()).unwrap())
+    }
+
+    #[test]
+    fn test_snapshot_captures_working_tree() {
+        let dir = tempdir().unwrap();
+        let repo = init_repo_with_commit(dir.path());
+
+        fs::write(dir.path().join("tracked.txt"), "two\n").unwrap();
+        fs::remove_file(dir.path().join("removed.txt")).unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/new.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.path().join("ignored.log"), "noise\n").unwrap();
+        let large = vec![b'x'; MAX_UNTRACKED_FILE_BYTES as usize + 1];
+        fs::write(dir.path().join("large.bin"), large).unwrap();
+
+        let cwd = dir.path().to_str().unwrap();
+        let tree_id = snapshot_working_tree(cwd).unwrap();
+
+        assert_eq!(
+            read_blob(&repo, &tree_id, "tracked.txt").as_deref(),
+            Some("two\n")
+        );
+        assert_eq!(
+            read_blob(&repo, &tree_id, "src/new.rs").as_deref(),
+            Some("fn main() {}\n")
+        );
+        assert!(read_blob(&repo, &tree_id, "removed.txt").is_none());
+        assert!(read_blob(&repo, &tree_id, "ignored.log").is_none());
+        assert!(read_blob(&repo, &tree_id, "large.bin").is_none());
+
+        // The user's index and HEAD are left alone
+        let statuses = repo.statuses(None).unwrap();
+        assert!(statuses
+            .iter()
+            .any(|s| s.path() == Some("tracked.txt") && s.status().contains(Status::WT_MODIFIED)));
+    }
+
+    #[test]
+    fn test_capture_session_snapshot_pins_ref() {
+        let dir = tempdir().unwrap();
+        let repo = init_repo_with_commit(dir.path());
+        let cwd = dir.path().to_str().unwrap();
+
+        let start = capture_session_snapshot(cwd, "session:1", SnapshotKind::Start).unwrap();
+        fs::write(dir.path().join("tracked.txt"), "changed\n").unwrap();
+        let end = capture_session_snapshot(cwd, "session:1", SnapshotKind::End).unwrap();
+
+        assert_ne!(start, end);
+        let reference = repo
+            .find_reference("refs/guideai/sessions/session_1/start")
+            .unwrap();
+        assert_eq!(reference.target().unwrap().to_string(), start);
+
+        remove_session_snapshots(cwd, "session:1").unwrap();
+        assert!(repo
+            .find_reference("refs/guideai/sessions/session_1/end")
+            .is_err());
+    }
+}
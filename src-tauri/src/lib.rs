@@ -3,14 +3,25 @@
 
 #![recursion_limit = "256"]
 
+pub mod analytics;
 pub mod claude_files;
 pub mod config;
+pub mod config_service;
+pub mod contribution_report;
 pub mod database;
+pub mod db_archive;
+pub mod db_backup;
+pub mod diff_attribution;
 pub mod error;
 pub mod events;
+pub mod git_diff;
+pub mod git_snapshot;
 pub mod logging;
+pub mod project_identity;
 pub mod project_metadata;
 pub mod providers;
+pub mod retention;
+pub mod schema;
 pub mod shutdown;
 pub mod upload_queue;
 pub mod validation;
@@ -2,27 +2,36 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 #![recursion_limit = "256"]
 
+mod analytics;
 mod auth_server;
 mod claude_files;
 mod commands;
 mod config;
+mod config_service;
 mod context_files;
+mod contribution_report;
 mod database;
+mod db_archive;
+mod db_backup;
+mod diff_attribution;
 mod error;
 mod events;
-mod file_watcher;
 mod git_diff;
+mod git_snapshot;
 mod logging;
+mod project_identity;
 mod project_metadata;
 mod providers;
+mod retention;
+mod schema;
 mod shutdown;
 mod types;
 mod upload_queue;
 mod validation;
+mod watcher_supervisor;
 
 use commands::{start_enabled_watchers, AppState};
 use events::{DatabaseEventHandler, EventBus, FrontendEventHandler};
-use file_watcher::start_config_file_watcher;
 use shutdown::ShutdownCoordinator;
 use tauri::Manager;
 
@@ -36,136 +45,15 @@ fn main() {
             tauri_plugin_sql::Builder::new()
                 .add_migrations(
                     "sqlite:guideai.db",
-                    vec![
-                        tauri_plugin_sql::Migration {
-                            version: 1,
-                            description: "create_agent_sessions",
-                            sql: include_str!("../migrations/001_create_agent_sessions.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 2,
-                            description: "create_session_metrics",
-                            sql: include_str!("../migrations/002_create_session_metrics.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 3,
-                            description: "add_cwd_column",
-                            sql: include_str!("../migrations/003_add_cwd_column.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 4,
-                            description: "add_sync_failed_reason",
-                            sql: include_str!("../migrations/004_add_sync_failed_reason.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 5,
-                            description: "unique_session_id",
-                            sql: include_str!("../migrations/005_unique_session_id.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 6,
-                            description: "unique_session_metrics",
-                            sql: include_str!("../migrations/006_unique_session_id.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 7,
-                            description: "create_projects",
-                            sql: include_str!("../migrations/007_create_projects.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 8,
-                            description: "add_project_foreign_key",
-                            sql: include_str!("../migrations/008_add_project_foreign_key.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 9,
-                            description: "create_session_assessments",
-                            sql: include_str!("../migrations/009_create_session_assessments.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 10,
-                            description: "add_phase_analysis",
-                            sql: include_str!("../migrations/010_add_phase_analysis.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 11,
-                            description: "add_core_metrics_tracking",
-                            sql: include_str!("../migrations/011_add_core_metrics_tracking.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 12,
-                            description: "add_category_improvement_tips",
-                            sql: include_str!(
-                                "../migrations/012_add_category_improvement_tips.sql"
-                            ),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 13,
-                            description: "add_file_hash",
-                            sql: include_str!("../migrations/013_add_file_hash.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 14,
-                            description: "add_git_tracking",
-                            sql: include_str!("../migrations/014_add_git_tracking.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 15,
-                            description: "add_git_diff_metrics",
-                            sql: include_str!("../migrations/015_add_git_diff_metrics.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 16,
-                            description: "add_git_diff_improvement_tips",
-                            sql: include_str!(
-                                "../migrations/016_add_git_diff_improvement_tips.sql"
-                            ),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 17,
-                            description: "add_context_management_metrics",
-                            sql: include_str!(
-                                "../migrations/017_add_context_management_metrics.sql"
-                            ),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 18,
-                            description: "update_context_metrics_structure",
-                            sql: include_str!(
-                                "../migrations/018_update_context_metrics_structure.sql"
-                            ),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 19,
-                            description: "remove_per_message_tokens",
-                            sql: include_str!("../migrations/019_remove_per_message_tokens.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                        tauri_plugin_sql::Migration {
-                            version: 20,
-                            description: "remove_peak_context_tokens",
-                            sql: include_str!("../migrations/020_remove_peak_context_tokens.sql"),
-                            kind: tauri_plugin_sql::MigrationKind::Up,
-                        },
-                    ],
+                    schema::MIGRATIONS
+                        .iter()
+                        .map(|m| tauri_plugin_sql::Migration {
+                            version: m.version,
+                            description: m.description,
+                            sql: m.sql,
+                            kind: tauri_plugin_sql::MigrationKind::Up,
+                        })
+                        .collect(),
                 )
                 .build(),
         )
@@ -186,6 +74,15 @@ fn main() {
             // Set app handle on database for event emission
             database::set_app_handle(app.handle().clone());
 
+            // Merge projects that resolve to the same repository
+            project_identity::spawn_reconcile_projects();
+
+            // Sessions stored before the model column existed
+            analytics::spawn_backfill_session_models();
+
+            // Snapshot the database on the configured schedule
+            db_backup::start_backup_scheduler();
+
             // Create shutdown coordinator for graceful shutdown
             let shutdown = ShutdownCoordinator::new();
 
@@ -194,7 +91,7 @@ fn main() {
 
             // Start event handlers with shutdown coordination
             let db_handler = DatabaseEventHandler::new(event_bus.clone(), shutdown.clone());
-            db_handler.start();
+            let db_handler = db_handler.start();
 
             let frontend_handler = FrontendEventHandler::new(
                 event_bus.clone(),
@@ -204,33 +101,30 @@ fn main() {
             frontend_handler.start();
 
             // Initialize application state with event bus
-            let app_state = AppState::new(event_bus);
+            let app_state = AppState::new(event_bus, shutdown.clone(), db_handler);
 
             // Set app handle on upload queue for event emission
             app_state.upload_queue.set_app_handle(app.handle().clone());
 
-            // Start enabled file watchers
-            start_enabled_watchers(&app_state);
-
-            app.manage(app_state);
-
-            // Get reference to main window for config file watcher
-            let main_window = app
-                .get_webview_window("main")
-                .ok_or("Main window not found")?;
-
-            // Start config file watcher with main window for event emission
-            match start_config_file_watcher(main_window.as_ref().window()) {
-                Ok(_watcher) => {
-                    // Store the watcher in app state so it doesn't get dropped
-                    app.manage(_watcher);
+            // Reload configuration into running watchers as it is edited
+            app_state.upload_queue.spawn_config_listener();
+            match config_service::start_config_service(Some(app.handle().clone())) {
+                Ok(watcher) => {
+                    app.manage(watcher);
                 }
                 Err(e) => {
-                    warn!("Failed to start config file watcher: {}", e);
-                    // Continue without file watcher - not critical for app functionality
+                    warn!("Failed to start config service: {}", e);
                 }
             }
 
+            // Start enabled file watchers
+            start_enabled_watchers(&app_state);
+
+            // Restart watchers that die, stall or lose their directory
+            app_state.supervisor.start(&app_state);
+
+            app.manage(app_state);
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -265,6 +159,10 @@ fn main() {
             commands::start_cursor_watcher,
             commands::stop_cursor_watcher,
             commands::get_cursor_watcher_status,
+            commands::get_cursor_session_branches,
+            commands::start_custom_jsonl_watcher,
+            commands::stop_custom_jsonl_watcher,
+            commands::get_custom_jsonl_watcher_status,
             commands::get_upload_queue_status,
             commands::retry_failed_uploads,
             commands::clear_failed_uploads,
@@ -278,19 +176,52 @@ fn main() {
             commands::reset_session_sync_progress,
             commands::execute_sql,
             commands::get_session_content,
+            commands::validate_session,
+            commands::get_session_relations,
             commands::clear_all_sessions,
             commands::clear_provider_sessions,
+            commands::get_retention_policy,
+            commands::save_retention_policy,
+            commands::run_retention,
+            commands::create_database_backup,
+            commands::list_database_backups,
+            commands::restore_database_backup,
+            commands::get_backup_settings,
+            commands::save_backup_settings,
+            commands::get_database_size,
+            commands::run_database_maintenance,
+            commands::export_database_archive,
+            commands::import_database_archive,
+            commands::get_analytics_time_series,
+            commands::get_analytics_percentiles,
             commands::get_all_projects,
             commands::get_project_by_id,
+            commands::reconcile_projects,
             commands::open_folder_in_os,
             commands::quick_rate_session,
             commands::get_session_rating,
             commands::get_session_git_diff,
+            commands::get_session_diff_attribution,
+            commands::get_contribution_report,
             commands::scan_context_files,
             commands::scan_claude_files,
             commands::log_updater_event_command,
-            commands::migrate_to_canonical_command
+            commands::migrate_to_canonical_command,
+            commands::inspect_session_source,
+            commands::export_inspection_fixture,
+            commands::replay_inspection_fixture,
+            commands::get_session_conversion_stats,
+            commands::get_provider_conversion_stats,
+            commands::resolve_gemini_projects,
+            commands::get_watcher_health
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Let watchers flush in-progress conversions before the process exits
+            if let tauri::RunEvent::Exit = event {
+                let state = app_handle.state::<AppState>();
+                tauri::async_runtime::block_on(state.shutdown_watchers());
+            }
+        });
 }
@@ -1,17 +1,44 @@
 use git2::Repository;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectMetadata {
     pub project_name: String,
     pub git_remote_url: Option<String>,
+    /// Project root: the git root (or workspace root) containing the session's cwd
     pub cwd: String,
     pub detected_project_type: String,
+    /// Monorepo tooling found at the root, e.g. "cargo", "pnpm", "turbo+pnpm"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_kind: Option<String>,
+    /// Sub-package the session's cwd is in, when it is below the root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<WorkspacePackage>,
+}
+
+/// A package inside a monorepo or workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspacePackage {
+    pub name: String,
+    /// Path relative to the project root, using `/` separators
+    pub path: String,
+    pub project_type: String,
+}
+
+/// Workspace tooling and member patterns declared at a project root
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Workspace {
+    kinds: Vec<&'static str>,
+    members: Vec<String>,
 }
 
 /// Extract project metadata from a directory
+///
+/// Walks up to the git root (or the nearest workspace root outside git) so every
+/// session in a monorepo maps to the same project, and records the sub-package
+/// the directory belongs to separately.
 pub fn extract_project_metadata(cwd: &str) -> Result<ProjectMetadata, String> {
     let path = Path::new(cwd);
 
@@ -31,27 +58,346 @@ pub fn extract_project_metadata(cwd: &str) -> Result<ProjectMetadata, String> {
         .canonicalize()
         .map_err(|e| format!("Failed to resolve path '{}': {}", cwd, e))?;
 
-    // Detect project type and extract name
-    let (project_name, detected_project_type) = detect_project_type_and_name(path)?;
+    let root = find_project_root(path);
+    let workspace = detect_workspace(&root);
+
+    // Detect project type and extract name at the root
+    let (project_name, detected_project_type) = detect_project_type_and_name(&root)?;
 
     // Extract Git remote URL
-    let git_remote_url = extract_git_remote_url(path);
+    let git_remote_url = extract_git_remote_url(&root);
+
+    let package = find_workspace_package(&root, path, &workspace);
 
     Ok(ProjectMetadata {
         project_name,
         git_remote_url,
-        cwd: cwd.to_string(),
+        cwd: root.to_string_lossy().to_string(),
         detected_project_type,
+        workspace_kind: (!workspace.kinds.is_empty()).then(|| workspace.kinds.join("+")),
+        package,
     })
 }
 
+/// Find the directory that represents the whole project for `path`
+///
+/// Inside a git repository that is the working tree root. Outside git, the
+/// nearest ancestor declaring a workspace is used, falling back to `path`.
+pub(crate) fn find_project_root(path: &Path) -> PathBuf {
+    if let Some(workdir) = Repository::discover(path)
+        .ok()
+        .and_then(|repo| repo.workdir().map(Path::to_path_buf))
+    {
+        // Keep the caller's spelling of the path (no symlink resolution) when possible
+        let depth = path
+            .canonicalize()
+            .ok()
+            .zip(workdir.canonicalize().ok())
+            .and_then(|(dir, root)| {
+                dir.strip_prefix(&root)
+                    .ok()
+                    .map(|rel| rel.components().count())
+            });
+        return match depth {
+            Some(depth) => path.ancestors().nth(depth).unwrap_or(path).to_path_buf(),
+            None => workdir,
+        };
+    }
+
+    path.ancestors()
+        .find(|dir| !detect_workspace(dir).kinds.is_empty())
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
+/// Detect monorepo tooling and workspace member patterns at a root directory
+fn detect_workspace(root: &Path) -> Workspace {
+    let mut workspace = Workspace::default();
+
+    // Task runners and build systems first, they describe the repo best
+    if root.join("nx.json").is_file() {
+        workspace.kinds.push("nx");
+    }
+    if root.join("turbo.json").is_file() {
+        workspace.kinds.push("turbo");
+    }
+    if ["MODULE.bazel", "WORKSPACE", "WORKSPACE.bazel"]
+        .iter()
+        .any(|marker| root.join(marker).is_file())
+    {
+        workspace.kinds.push("bazel");
+    }
+
+    if let Some(members) = cargo_workspace_members(&root.join("Cargo.toml")) {
+        workspace.kinds.push("cargo");
+        workspace.members.extend(members);
+    }
+
+    if let Ok(content) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        workspace.kinds.push("pnpm");
+        workspace.members.extend(pnpm_workspace_packages(&content));
+    } else if let Some(members) = package_json_workspaces(&root.join("package.json")) {
+        let manager = if root.join("yarn.lock").is_file() {
+            "yarn"
+        } else if root.join("bun.lockb").is_file() || root.join("bun.lock").is_file() {
+            "bun"
+        } else {
+            "npm"
+        };
+        workspace.kinds.push(manager);
+        workspace.members.extend(members);
+    }
+
+    if let Ok(content) = fs::read_to_string(root.join("go.work")) {
+        workspace.kinds.push("go");
+        workspace.members.extend(go_work_uses(&content));
+    }
+
+    workspace
+}
+
+/// Member patterns of a Cargo workspace, or `None` if the manifest has no `[workspace]`
+fn cargo_workspace_members(cargo_toml: &Path) -> Option<Vec<String>> {
+    let content = fs::read_to_string(cargo_toml).ok()?;
+    let manifest: toml::Value = content.parse().ok()?;
+    let workspace = manifest.get("workspace")?;
+
+    Some(
+        workspace
+            .get("members")
+            .and_then(|m| m.as_array())
+            .map(|members| {
+                members
+                    .iter()
+                    .filter_map(|m| m.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    )
+}
+
+/// `packages:` entries of a pnpm-workspace.yaml (negated patterns are skipped)
+fn pnpm_workspace_packages(content: &str) -> Vec<String> {
+    let mut packages = Vec::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if !line.starts_with(char::is_whitespace) && !trimmed.starts_with('-') {
+            in_packages = trimmed.starts_with("packages:");
+            continue;
+        }
+
+        if in_packages {
+            if let Some(entry) = trimmed.strip_prefix('-') {
+                let entry = entry.trim().trim_matches('"').trim_matches('\'');
+                if !entry.is_empty() && !entry.starts_with('!') {
+                    packages.push(entry.to_string());
+                }
+            }
+        }
+    }
+
+    packages
+}
+
+/// `workspaces` of a package.json (array form or `{ "packages": [...] }` form)
+fn package_json_workspaces(package_json: &Path) -> Option<Vec<String>> {
+    let content = fs::read_to_string(package_json).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let workspaces = json.get("workspaces")?;
+    let entries = workspaces
+        .as_array()
+        .or_else(|| workspaces.get("packages").and_then(|p| p.as_array()))?;
+
+    Some(
+        entries
+            .iter()
+            .filter_map(|e| e.as_str())
+            .filter(|e| !e.starts_with('!'))
+            .map(String::from)
+            .collect(),
+    )
+}
+
+/// Module directories listed by `use` directives in a go.work file
+fn go_work_uses(content: &str) -> Vec<String> {
+    let mut uses = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let trimmed = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if trimmed == ")" {
+                in_block = false;
+            } else if !trimmed.is_empty() {
+                uses.push(trimmed.to_string());
+            }
+        } else if let Some(rest) = trimmed.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                uses.push(rest.to_string());
+            }
+        }
+    }
+
+    uses
+}
+
+/// Match a relative path against a workspace member pattern (`*`, `?` and `**` supported)
+fn matches_member_pattern(pattern: &str, rel_path: &str) -> bool {
+    fn matches_segment(pattern: &[u8], text: &[u8]) -> bool {
+        match (pattern.first(), text.first()) {
+            (None, None) => true,
+            (Some(b'*'), _) => {
+                matches_segment(&pattern[1..], text)
+                    || (!text.is_empty() && matches_segment(pattern, &text[1..]))
+            }
+            (Some(b'?'), Some(_)) => matches_segment(&pattern[1..], &text[1..]),
+            (Some(p), Some(t)) if p == t => matches_segment(&pattern[1..], &text[1..]),
+            _ => false,
+        }
+    }
+
+    fn matches_segments(pattern: &[&str], path: &[&str]) -> bool {
+        match pattern.first() {
+            None => path.is_empty(),
+            Some(&"**") => {
+                (0..=path.len()).any(|skip| matches_segments(&pattern[1..], &path[skip..]))
+            }
+            Some(segment) => {
+                !path.is_empty()
+                    && matches_segment(segment.as_bytes(), path[0].as_bytes())
+                    && matches_segments(&pattern[1..], &path[1..])
+            }
+        }
+    }
+
+    let normalize = |s: &str| -> Vec<String> {
+        s.trim_start_matches("./")
+            .split('/')
+            .filter(|seg| !seg.is_empty() && *seg != ".")
+            .map(String::from)
+            .collect()
+    };
+    let pattern = normalize(pattern);
+    let path = normalize(rel_path);
+    let pattern: Vec<&str> = pattern.iter().map(String::as_str).collect();
+    let path: Vec<&str> = path.iter().map(String::as_str).collect();
+
+    matches_segments(&pattern, &path)
+}
+
+/// Find the workspace package containing `cwd`
+///
+/// Prefers the nearest directory matching a declared workspace member, then the
+/// nearest directory with its own manifest. Returns `None` at the root itself.
+fn find_workspace_package(
+    root: &Path,
+    cwd: &Path,
+    workspace: &Workspace,
+) -> Option<WorkspacePackage> {
+    let rel = cwd.strip_prefix(root).ok()?;
+    if rel.as_os_str().is_empty() {
+        return None;
+    }
+
+    let mut nearest_manifest = None;
+
+    for dir in cwd.ancestors() {
+        if dir == root || !dir.starts_with(root) {
+            break;
+        }
+
+        let rel_path = dir
+            .strip_prefix(root)
+            .ok()?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let Some((name, project_type)) = detect_package_manifest(dir, &rel_path) else {
+            continue;
+        };
+        let package = WorkspacePackage {
+            name,
+            path: rel_path.clone(),
+            project_type,
+        };
+
+        if workspace
+            .members
+            .iter()
+            .any(|pattern| matches_member_pattern(pattern, &rel_path))
+        {
+            return Some(package);
+        }
+        if nearest_manifest.is_none() {
+            nearest_manifest = Some(package);
+        }
+    }
+
+    nearest_manifest
+}
+
+/// Name and type of a package directory from its manifest, if it has one
+fn detect_package_manifest(dir: &Path, rel_path: &str) -> Option<(String, String)> {
+    if let Some(found) = detect_manifest(dir) {
+        return Some(found);
+    }
+
+    // Nx project without a package.json
+    if let Ok(content) = fs::read_to_string(dir.join("project.json")) {
+        let name = serde_json::from_str::<serde_json::Value>(&content)
+            .ok()
+            .and_then(|json| json.get("name").and_then(|n| n.as_str()).map(String::from));
+        if let Some(name) = name {
+            return Some((name, "nx".to_string()));
+        }
+    }
+
+    // Bazel package
+    if dir.join("BUILD.bazel").is_file() || dir.join("BUILD").is_file() {
+        return Some((format!("//{}", rel_path), "bazel".to_string()));
+    }
+
+    None
+}
+
 /// Detect project type and extract project name
 fn detect_project_type_and_name(path: &Path) -> Result<(String, String), String> {
+    if let Some(found) = detect_manifest(path) {
+        return Ok(found);
+    }
+
+    // Fallback: use directory name
+    let dir_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid directory name")?
+        .to_string();
+
+    Ok((dir_name, "generic".to_string()))
+}
+
+/// Project name and type from the first recognized manifest in a directory
+///
+/// The type is a framework (e.g. "nextjs", "django", "rails") when one is
+/// recognized in the manifest's dependencies, otherwise the ecosystem.
+fn detect_manifest(path: &Path) -> Option<(String, String)> {
     // Check for Node.js project (package.json)
     let package_json = path.join("package.json");
     if package_json.exists() {
         if let Ok(project_name) = extract_nodejs_project_name(&package_json) {
-            return Ok((project_name, "nodejs".to_string()));
+            let project_type = detect_nodejs_framework(&package_json).unwrap_or("nodejs");
+            return Some((project_name, project_type.to_string()));
         }
     }
 
@@ -59,7 +405,7 @@ fn detect_project_type_and_name(path: &Path) -> Result<(String, String), String>
     let cargo_toml = path.join("Cargo.toml");
     if cargo_toml.exists() {
         if let Ok(project_name) = extract_rust_project_name(&cargo_toml) {
-            return Ok((project_name, "rust".to_string()));
+            return Some((project_name, "rust".to_string()));
         }
     }
 
@@ -67,7 +413,8 @@ fn detect_project_type_and_name(path: &Path) -> Result<(String, String), String>
     let pyproject_toml = path.join("pyproject.toml");
     if pyproject_toml.exists() {
         if let Ok(project_name) = extract_python_project_name(&pyproject_toml) {
-            return Ok((project_name, "python".to_string()));
+            let project_type = detect_python_framework(path, &pyproject_toml).unwrap_or("python");
+            return Some((project_name, project_type.to_string()));
         }
     }
 
@@ -75,18 +422,101 @@ fn detect_project_type_and_name(path: &Path) -> Result<(String, String), String>
     let go_mod = path.join("go.mod");
     if go_mod.exists() {
         if let Ok(project_name) = extract_go_project_name(&go_mod) {
-            return Ok((project_name, "go".to_string()));
+            return Some((project_name, "go".to_string()));
         }
     }
 
-    // Fallback: use directory name
-    let dir_name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or("Invalid directory name")?
-        .to_string();
+    // Check for Maven project (pom.xml)
+    let pom_xml = path.join("pom.xml");
+    if pom_xml.exists() {
+        if let Ok(project_name) = extract_maven_project_name(&pom_xml) {
+            let project_type = detect_jvm_framework(&pom_xml).unwrap_or("java");
+            return Some((project_name, project_type.to_string()));
+        }
+    }
 
-    Ok((dir_name, "generic".to_string()))
+    // Check for Gradle project (build.gradle / build.gradle.kts)
+    let gradle_build = ["build.gradle.kts", "build.gradle"]
+        .iter()
+        .map(|name| path.join(name))
+        .find(|p| p.exists());
+    if let Some(gradle_build) = gradle_build {
+        let project_name = extract_gradle_project_name(path)?;
+        let project_type = detect_jvm_framework(&gradle_build).unwrap_or_else(|| {
+            let content = fs::read_to_string(&gradle_build).unwrap_or_default();
+            if content.contains("kotlin(\"jvm\")") || content.contains("org.jetbrains.kotlin") {
+                "kotlin"
+            } else {
+                "java"
+            }
+        });
+        return Some((project_name, project_type.to_string()));
+    }
+
+    // Check for .NET project (*.sln, *.csproj, *.fsproj)
+    if let Some(project_file) = find_file_with_extension(path, &["csproj", "fsproj", "vbproj"]) {
+        if let Ok(project_name) = extract_dotnet_project_name(path, &project_file) {
+            let project_type = detect_dotnet_framework(&project_file).unwrap_or("dotnet");
+            return Some((project_name, project_type.to_string()));
+        }
+    } else if let Some(solution) = find_file_with_extension(path, &["sln"]) {
+        if let Ok(project_name) = extract_dotnet_project_name(path, &solution) {
+            return Some((project_name, "dotnet".to_string()));
+        }
+    }
+
+    // Check for Ruby project (*.gemspec / Gemfile)
+    let gemfile = path.join("Gemfile");
+    let gemspec = find_file_with_extension(path, &["gemspec"]);
+    if gemfile.exists() || gemspec.is_some() {
+        if let Ok(project_name) = extract_ruby_project_name(path, gemspec.as_deref()) {
+            let project_type = detect_ruby_framework(path, &gemfile).unwrap_or("ruby");
+            return Some((project_name, project_type.to_string()));
+        }
+    }
+
+    // Check for PHP project (composer.json)
+    let composer_json = path.join("composer.json");
+    if composer_json.exists() {
+        if let Ok(project_name) = extract_php_project_name(path, &composer_json) {
+            let project_type = detect_php_framework(&composer_json).unwrap_or("php");
+            return Some((project_name, project_type.to_string()));
+        }
+    }
+
+    // Check for Elixir project (mix.exs)
+    let mix_exs = path.join("mix.exs");
+    if mix_exs.exists() {
+        if let Ok(project_name) = extract_elixir_project_name(&mix_exs) {
+            let content = fs::read_to_string(&mix_exs).unwrap_or_default();
+            let project_type = if content.contains("{:phoenix,") {
+                "phoenix"
+            } else {
+                "elixir"
+            };
+            return Some((project_name, project_type.to_string()));
+        }
+    }
+
+    // Check for Swift package (Package.swift)
+    let package_swift = path.join("Package.swift");
+    if package_swift.exists() {
+        if let Ok(project_name) = extract_swift_project_name(&package_swift) {
+            return Some((project_name, "swift".to_string()));
+        }
+    }
+
+    // Check for C/C++ project (CMakeLists.txt / meson.build)
+    for build_file in ["CMakeLists.txt", "meson.build"] {
+        let build_path = path.join(build_file);
+        if build_path.exists() {
+            if let Ok(project_name) = extract_native_project_name(&build_path) {
+                return Some((project_name, "cpp".to_string()));
+            }
+        }
+    }
+
+    None
 }
 
 /// Extract project name from package.json
@@ -189,8 +619,336 @@ fn extract_go_project_name(go_mod: &Path) -> Result<String, String> {
     Err("No 'module' directive found in go.mod".to_string())
 }
 
+/// First file in a directory with one of the given extensions (sorted by name for stable results)
+fn find_file_with_extension(dir: &Path, extensions: &[&str]) -> Option<PathBuf> {
+    let mut matches: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| extensions.contains(&ext))
+        })
+        .collect();
+    matches.sort();
+    matches.into_iter().next()
+}
+
+/// Name of the directory, used when a manifest does not carry a project name
+fn directory_name(dir: &Path) -> Result<String, String> {
+    dir.file_name()
+        .and_then(|n| n.to_str())
+        .map(String::from)
+        .ok_or_else(|| "Invalid directory name".to_string())
+}
+
+/// Text between the first `<tag>` and `</tag>` in an XML document
+fn xml_tag_value<'a>(content: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = content.find(&open)? + open.len();
+    let end = content[start..].find(&close)? + start;
+    let value = content[start..end].trim();
+    (!value.is_empty()).then_some(value)
+}
+
+/// First quoted string after `marker` on the same line (handles '' and "")
+fn quoted_after<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = &line[line.find(marker)? + marker.len()..];
+    let quote_pos = rest.find(['"', '\''])?;
+    let quote = rest[quote_pos..].chars().next()?;
+    let value_start = quote_pos + 1;
+    let value_end = rest[value_start..].find(quote)? + value_start;
+    let value = &rest[value_start..value_end];
+    (!value.is_empty()).then_some(value)
+}
+
+/// Whether a dependency key is present in package.json dependencies or devDependencies
+fn has_npm_dependency(json: &serde_json::Value, name: &str) -> bool {
+    ["dependencies", "devDependencies", "peerDependencies"]
+        .iter()
+        .any(|section| json.get(section).and_then(|deps| deps.get(name)).is_some())
+}
+
+/// Framework hint for a Node.js project from package.json dependencies
+fn detect_nodejs_framework(package_json: &Path) -> Option<&'static str> {
+    let content = fs::read_to_string(package_json).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+
+    // Meta-frameworks before the UI libraries they build on
+    const FRAMEWORKS: &[(&str, &str)] = &[
+        ("next", "nextjs"),
+        ("nuxt", "nuxt"),
+        ("@remix-run/react", "remix"),
+        ("@sveltejs/kit", "sveltekit"),
+        ("astro", "astro"),
+        ("@angular/core", "angular"),
+        ("@nestjs/core", "nestjs"),
+        ("electron", "electron"),
+        ("react-native", "react-native"),
+        ("vue", "vue"),
+        ("svelte", "svelte"),
+        ("react", "react"),
+        ("express", "express"),
+    ];
+
+    FRAMEWORKS
+        .iter()
+        .find(|(dependency, _)| has_npm_dependency(&json, dependency))
+        .map(|(_, framework)| *framework)
+}
+
+/// Whether a Python/Ruby style manifest lists `name` as a dependency
+///
+/// Matches the name as a whole word directly after a quote or at the start of
+/// a line, so `django` matches `"django>=4"` and `django = "^4"` but not
+/// `django-environ`.
+fn mentions_dependency(content: &str, name: &str) -> bool {
+    content.lines().any(|line| {
+        let line = line.trim().to_ascii_lowercase();
+        let candidates = std::iter::once(line.as_str())
+            .chain(line.match_indices(['"', '\'']).map(|(i, _)| &line[i + 1..]));
+        candidates.into_iter().any(|rest| {
+            rest.strip_prefix(name).is_some_and(|after| {
+                !after.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            })
+        })
+    })
+}
+
+/// Framework hint for a Python project
+fn detect_python_framework(dir: &Path, pyproject_toml: &Path) -> Option<&'static str> {
+    let content = fs::read_to_string(pyproject_toml).unwrap_or_default();
+
+    if dir.join("manage.py").exists() || mentions_dependency(&content, "django") {
+        return Some("django");
+    }
+    ["fastapi", "flask"]
+        .into_iter()
+        .find(|framework| mentions_dependency(&content, framework))
+}
+
+/// Extract project name from pom.xml (the project's own artifactId, not its parent's)
+fn extract_maven_project_name(pom_xml: &Path) -> Result<String, String> {
+    let content =
+        fs::read_to_string(pom_xml).map_err(|e| format!("Failed to read pom.xml: {}", e))?;
+
+    // Drop the <parent> block so its artifactId is not picked up
+    let own = match (content.find("<parent>"), content.find("</parent>")) {
+        (Some(start), Some(end)) if start < end => {
+            format!(
+                "{}{}",
+                &content[..start],
+                &content[end + "</parent>".len()..]
+            )
+        }
+        _ => content.clone(),
+    };
+
+    xml_tag_value(&own, "artifactId")
+        .map(String::from)
+        .ok_or_else(|| "No 'artifactId' found in pom.xml".to_string())
+}
+
+/// Extract project name from settings.gradle(.kts), falling back to the directory name
+fn extract_gradle_project_name(dir: &Path) -> Option<String> {
+    for settings in ["settings.gradle.kts", "settings.gradle"] {
+        if let Ok(content) = fs::read_to_string(dir.join(settings)) {
+            if let Some(name) = content
+                .lines()
+                .find_map(|line| quoted_after(line, "rootProject.name"))
+            {
+                return Some(name.to_string());
+            }
+        }
+    }
+
+    directory_name(dir).ok()
+}
+
+/// Framework hint for a Maven or Gradle build file
+fn detect_jvm_framework(build_file: &Path) -> Option<&'static str> {
+    let content = fs::read_to_string(build_file).ok()?;
+
+    if content.contains("com.android.application") || content.contains("com.android.library") {
+        Some("android")
+    } else if content.contains("spring-boot") || content.contains("org.springframework.boot") {
+        Some("spring-boot")
+    } else if content.contains("io.quarkus") {
+        Some("quarkus")
+    } else {
+        None
+    }
+}
+
+/// Extract project name from a .NET project or solution file
+///
+/// Uses `<AssemblyName>` when a project file sets it, otherwise the file name.
+fn extract_dotnet_project_name(dir: &Path, project_file: &Path) -> Result<String, String> {
+    if project_file.extension().and_then(|e| e.to_str()) != Some("sln") {
+        if let Ok(content) = fs::read_to_string(project_file) {
+            if let Some(name) = xml_tag_value(&content, "AssemblyName") {
+                return Ok(name.to_string());
+            }
+        }
+    }
+
+    project_file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(String::from)
+        .map_or_else(|| directory_name(dir), Ok)
+}
+
+/// Framework hint for a .NET project file
+fn detect_dotnet_framework(project_file: &Path) -> Option<&'static str> {
+    let content = fs::read_to_string(project_file).ok()?;
+
+    if content.contains("Microsoft.NET.Sdk.BlazorWebAssembly") {
+        Some("blazor")
+    } else if content.contains("Microsoft.NET.Sdk.Web") {
+        Some("aspnet")
+    } else {
+        None
+    }
+}
+
+/// Extract project name from a .gemspec, falling back to the directory name for Gemfile-only apps
+fn extract_ruby_project_name(dir: &Path, gemspec: Option<&Path>) -> Result<String, String> {
+    if let Some(gemspec) = gemspec {
+        let content =
+            fs::read_to_string(gemspec).map_err(|e| format!("Failed to read gemspec: {}", e))?;
+        if let Some(name) = content.lines().find_map(|line| {
+            let trimmed = line.trim();
+            let (_, rest) = trimmed.split_once(".name")?;
+            rest.trim_start()
+                .starts_with('=')
+                .then(|| quoted_after(rest, "="))?
+        }) {
+            return Ok(name.to_string());
+        }
+    }
+
+    directory_name(dir)
+}
+
+/// Framework hint for a Ruby project
+fn detect_ruby_framework(dir: &Path, gemfile: &Path) -> Option<&'static str> {
+    if dir.join("config").join("application.rb").exists() {
+        return Some("rails");
+    }
+
+    let content = fs::read_to_string(gemfile).ok()?;
+    let gems: Vec<&str> = content
+        .lines()
+        .filter_map(|line| quoted_after(line.trim().strip_prefix("gem ")?, ""))
+        .collect();
+
+    ["rails", "sinatra", "hanami"]
+        .into_iter()
+        .find(|framework| gems.contains(framework))
+}
+
+/// Extract project name from composer.json ("vendor/package" -> "package")
+fn extract_php_project_name(dir: &Path, composer_json: &Path) -> Result<String, String> {
+    let content = fs::read_to_string(composer_json)
+        .map_err(|e| format!("Failed to read composer.json: {}", e))?;
+    let json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse composer.json: {}", e))?;
+
+    match json.get("name").and_then(|v| v.as_str()) {
+        Some(name) => Ok(name.rsplit('/').next().unwrap_or(name).to_string()),
+        // Applications often omit the name
+        None => directory_name(dir),
+    }
+}
+
+/// Framework hint for a PHP project from composer.json requirements
+fn detect_php_framework(composer_json: &Path) -> Option<&'static str> {
+    let content = fs::read_to_string(composer_json).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let requires = |package: &str| {
+        ["require", "require-dev"].iter().any(|section| {
+            json.get(section)
+                .and_then(|deps| deps.get(package))
+                .is_some()
+        })
+    };
+
+    if requires("laravel/framework") {
+        Some("laravel")
+    } else if requires("symfony/framework-bundle") {
+        Some("symfony")
+    } else {
+        None
+    }
+}
+
+/// Extract project name from mix.exs (`app: :my_app`)
+fn extract_elixir_project_name(mix_exs: &Path) -> Result<String, String> {
+    let content =
+        fs::read_to_string(mix_exs).map_err(|e| format!("Failed to read mix.exs: {}", e))?;
+
+    content
+        .lines()
+        .find_map(|line| {
+            let rest = line.split_once("app:")?.1.trim_start().strip_prefix(':')?;
+            let name: String = rest
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                .collect();
+            (!name.is_empty()).then_some(name)
+        })
+        .ok_or_else(|| "No 'app:' found in mix.exs".to_string())
+}
+
+/// Extract package name from Package.swift (`name: "MyPackage"`)
+fn extract_swift_project_name(package_swift: &Path) -> Result<String, String> {
+    let content = fs::read_to_string(package_swift)
+        .map_err(|e| format!("Failed to read Package.swift: {}", e))?;
+
+    content
+        .lines()
+        .find_map(|line| quoted_after(line, "name:"))
+        .map(String::from)
+        .ok_or_else(|| "No 'name:' found in Package.swift".to_string())
+}
+
+/// Extract project name from CMakeLists.txt (`project(Name ...)`) or meson.build (`project('name', ...)`)
+fn extract_native_project_name(build_file: &Path) -> Result<String, String> {
+    let content = fs::read_to_string(build_file)
+        .map_err(|e| format!("Failed to read {}: {}", build_file.display(), e))?;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        let lower = trimmed.to_ascii_lowercase();
+        let Some(args) = lower
+            .strip_prefix("project")
+            .map(str::trim_start)
+            .filter(|rest| rest.starts_with('('))
+        else {
+            continue;
+        };
+
+        // Slice the original line so the name keeps its case
+        let args = &trimmed[trimmed.len() - args.len() + 1..];
+        let name = args
+            .trim_start()
+            .split(|c: char| c.is_whitespace() || c == ',' || c == ')')
+            .next()
+            .unwrap_or("")
+            .trim_matches(|c| c == '"' || c == '\'');
+        if !name.is_empty() {
+            return Ok(name.to_string());
+        }
+    }
+
+    Err(format!("No project() found in {}", build_file.display()))
+}
+
 /// Convert SSH Git URL to HTTPS URL for GitHub
-fn convert_ssh_to_https(url: &str) -> String {
+pub(crate) fn convert_ssh_to_https(url: &str) -> String {
     // Check if it's a GitHub SSH URL (git@github.com:owner/repo.git)
     if url.starts_with("git@github.com:") {
         // Extract the owner/repo part
@@ -347,6 +1105,210 @@ mod tests {
         assert_eq!(metadata.detected_project_type, "go");
     }
 
+    #[test]
+    fn test_extract_nextjs_project() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{"name": "web-app", "dependencies": {"next": "14.0.0", "react": "18.2.0"}}"#,
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "web-app");
+        assert_eq!(metadata.detected_project_type, "nextjs");
+    }
+
+    #[test]
+    fn test_extract_django_project() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            "[project]\nname = \"my-site\"\ndependencies = [\"django>=4.2\", \"django-environ\"]",
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "my-site");
+        assert_eq!(metadata.detected_project_type, "django");
+
+        // A plugin alone is not the framework
+        assert!(!mentions_dependency(
+            "dependencies = [\"django-environ\"]",
+            "django"
+        ));
+        assert!(mentions_dependency("fastapi = \"^0.110\"", "fastapi"));
+    }
+
+    #[test]
+    fn test_extract_maven_project() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("pom.xml"),
+            r#"<project>
+  <parent>
+    <groupId>org.springframework.boot</groupId>
+    <artifactId>spring-boot-starter-parent</artifactId>
+  </parent>
+  <groupId>com.example</groupId>
+  <artifactId>orders-service</artifactId>
+</project>"#,
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "orders-service");
+        assert_eq!(metadata.detected_project_type, "spring-boot");
+    }
+
+    #[test]
+    fn test_extract_gradle_project() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("settings.gradle.kts"),
+            "rootProject.name = \"inventory\"\ninclude(\":core\")",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("build.gradle.kts"),
+            "plugins {\n    kotlin(\"jvm\") version \"1.9.0\"\n}",
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "inventory");
+        assert_eq!(metadata.detected_project_type, "kotlin");
+    }
+
+    #[test]
+    fn test_extract_dotnet_project() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("Billing.Api.csproj"),
+            r#"<Project Sdk="Microsoft.NET.Sdk.Web"><PropertyGroup><TargetFramework>net8.0</TargetFramework></PropertyGroup></Project>"#,
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "Billing.Api");
+        assert_eq!(metadata.detected_project_type, "aspnet");
+
+        let sln_dir = tempdir().unwrap();
+        fs::write(sln_dir.path().join("Platform.sln"), "").unwrap();
+        let metadata = extract_project_metadata(sln_dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(metadata.project_name, "Platform");
+        assert_eq!(metadata.detected_project_type, "dotnet");
+    }
+
+    #[test]
+    fn test_extract_ruby_project() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("toolbox.gemspec"),
+            "Gem::Specification.new do |spec|\n  spec.name = \"toolbox\"\n  spec.version = \"1.0\"\nend",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("Gemfile"),
+            "source \"https://rubygems.org\"\ngem 'sinatra', '~> 3.0'\n",
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "toolbox");
+        assert_eq!(metadata.detected_project_type, "sinatra");
+    }
+
+    #[test]
+    fn test_extract_rails_project() {
+        let temp_dir = tempdir().unwrap();
+        let app_dir = temp_dir.path().join("storefront");
+        fs::create_dir_all(app_dir.join("config")).unwrap();
+        fs::write(app_dir.join("Gemfile"), "gem \"rails\", \"~> 7.1\"\n").unwrap();
+        fs::write(app_dir.join("config").join("application.rb"), "").unwrap();
+
+        let metadata = extract_project_metadata(app_dir.to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "storefront");
+        assert_eq!(metadata.detected_project_type, "rails");
+    }
+
+    #[test]
+    fn test_extract_php_project() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("composer.json"),
+            r#"{"name": "acme/helpdesk", "require": {"php": "^8.2", "laravel/framework": "^11.0"}}"#,
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "helpdesk");
+        assert_eq!(metadata.detected_project_type, "laravel");
+    }
+
+    #[test]
+    fn test_extract_elixir_project() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("mix.exs"),
+            "defmodule Chat.MixProject do\n  def project do\n    [\n      app: :chat_server,\n      version: \"0.1.0\"\n    ]\n  end\n\n  defp deps do\n    [{:phoenix, \"~> 1.7\"}]\n  end\nend",
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "chat_server");
+        assert_eq!(metadata.detected_project_type, "phoenix");
+    }
+
+    #[test]
+    fn test_extract_swift_project() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("Package.swift"),
+            "// swift-tools-version:5.9\nimport PackageDescription\n\nlet package = Package(\n    name: \"NetworkKit\",\n    targets: [.target(name: \"NetworkKitCore\")]\n)",
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "NetworkKit");
+        assert_eq!(metadata.detected_project_type, "swift");
+    }
+
+    #[test]
+    fn test_extract_cpp_project() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("CMakeLists.txt"),
+            "cmake_minimum_required(VERSION 3.20)\nproject(RayTracer VERSION 1.0 LANGUAGES CXX)\n",
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "RayTracer");
+        assert_eq!(metadata.detected_project_type, "cpp");
+
+        let meson_dir = tempdir().unwrap();
+        fs::write(
+            meson_dir.path().join("meson.build"),
+            "project('audio-engine', 'c', version : '0.3')\n",
+        )
+        .unwrap();
+        let metadata = extract_project_metadata(meson_dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(metadata.project_name, "audio-engine");
+        assert_eq!(metadata.detected_project_type, "cpp");
+    }
+
     #[test]
     fn test_extract_git_remote_url() {
         let temp_dir = tempdir().unwrap();
@@ -425,4 +1387,100 @@ mod tests {
             Some("https://github.com/guideai-dev/guideai.git".to_string())
         );
     }
+
+    #[test]
+    fn test_pnpm_turbo_monorepo_in_git() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        Repository::init(root).unwrap();
+
+        fs::write(
+            root.join("package.json"),
+            r#"{"name": "acme", "private": true}"#,
+        )
+        .unwrap();
+        fs::write(root.join("turbo.json"), "{}").unwrap();
+        fs::write(
+            root.join("pnpm-workspace.yaml"),
+            "packages:\n  - 'apps/*'\n  - \"packages/*\"\n  - '!**/test/**'\n",
+        )
+        .unwrap();
+        let web = root.join("apps").join("web");
+        fs::create_dir_all(web.join("src")).unwrap();
+        fs::write(web.join("package.json"), r#"{"name": "@acme/web"}"#).unwrap();
+
+        let metadata = extract_project_metadata(web.join("src").to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "acme");
+        assert_eq!(metadata.cwd, root.to_string_lossy());
+        assert_eq!(metadata.workspace_kind.as_deref(), Some("turbo+pnpm"));
+        assert_eq!(
+            metadata.package,
+            Some(WorkspacePackage {
+                name: "@acme/web".to_string(),
+                path: "apps/web".to_string(),
+                project_type: "nodejs".to_string(),
+            })
+        );
+
+        // Sessions at the root belong to the project, not a package
+        let at_root = extract_project_metadata(root.to_str().unwrap()).unwrap();
+        assert_eq!(at_root.project_name, "acme");
+        assert_eq!(at_root.package, None);
+    }
+
+    #[test]
+    fn test_cargo_workspace_outside_git() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("acme");
+        let member = root.join("crates").join("core");
+        fs::create_dir_all(member.join("src")).unwrap();
+
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\nresolver = \"2\"\n",
+        )
+        .unwrap();
+        fs::write(
+            member.join("Cargo.toml"),
+            "[package]\nname = \"acme-core\"\nversion = \"0.1.0\"",
+        )
+        .unwrap();
+
+        let metadata = extract_project_metadata(member.join("src").to_str().unwrap()).unwrap();
+
+        assert_eq!(metadata.project_name, "acme");
+        assert_eq!(metadata.workspace_kind.as_deref(), Some("cargo"));
+        let package = metadata.package.unwrap();
+        assert_eq!(package.name, "acme-core");
+        assert_eq!(package.path, "crates/core");
+        assert_eq!(package.project_type, "rust");
+    }
+
+    #[test]
+    fn test_workspace_member_parsing() {
+        assert_eq!(
+            go_work_uses("go 1.22\n\nuse (\n\t./api // service\n\t./tools/cli\n)\nuse ./web\n"),
+            vec!["./api", "./tools/cli", "./web"]
+        );
+
+        assert!(matches_member_pattern("packages/*", "packages/ui"));
+        assert!(!matches_member_pattern("packages/*", "packages/ui/src"));
+        assert!(matches_member_pattern("apps/**", "apps/web/admin"));
+        assert!(matches_member_pattern("./tools/cli", "tools/cli"));
+        assert!(matches_member_pattern("libs/lib-?", "libs/lib-a"));
+
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("MODULE.bazel"), "module(name = \"acme\")\n").unwrap();
+        let pkg = root.join("services").join("auth");
+        fs::create_dir_all(&pkg).unwrap();
+        fs::write(pkg.join("BUILD.bazel"), "").unwrap();
+
+        let metadata = extract_project_metadata(pkg.to_str().unwrap()).unwrap();
+        assert_eq!(metadata.workspace_kind.as_deref(), Some("bazel"));
+        let package = metadata.package.unwrap();
+        assert_eq!(package.name, "//services/auth");
+        assert_eq!(package.project_type, "bazel");
+    }
 }
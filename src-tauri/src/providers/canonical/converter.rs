@@ -39,6 +39,48 @@ pub trait ToCanonical {
     }
 }
 
+/// Trait for converting canonical JSONL back to a provider-specific format
+///
+/// Used for session replay and for resuming a conversation in another agent.
+/// Conversion must be stable: `provider → canonical → provider → canonical`
+/// yields the same canonical messages as the first conversion.
+pub trait FromCanonical: Sized {
+    /// Convert a canonical message to the provider's native records
+    ///
+    /// A canonical message may map to several native records (e.g. a text
+    /// block and a tool call), or to none when the provider has no equivalent.
+    fn from_canonical(message: &CanonicalMessage) -> Result<Vec<Self>>;
+}
+
+/// Convert canonical messages to a provider's native records
+#[allow(dead_code)]
+pub fn convert_batch_from_canonical<T: FromCanonical>(
+    messages: &[CanonicalMessage],
+) -> Result<Vec<T>> {
+    let mut native = Vec::new();
+    for message in messages {
+        native.extend(T::from_canonical(message)?);
+    }
+    Ok(native)
+}
+
+/// Convert canonical JSONL to a provider's native JSONL
+#[allow(dead_code)]
+pub fn from_jsonl<T: FromCanonical + serde::Serialize>(content: &str) -> Result<String> {
+    let messages: Vec<CanonicalMessage> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<std::result::Result<_, _>>()?;
+
+    let lines: Result<Vec<String>> = convert_batch_from_canonical::<T>(&messages)?
+        .iter()
+        .map(|record| serde_json::to_string(record).map_err(Into::into))
+        .collect();
+
+    Ok(lines?.join("\n"))
+}
+
 /// Batch conversion helper for converting multiple messages
 /// Filters out None values (skipped messages)
 #[allow(dead_code)]
@@ -67,10 +109,51 @@ pub fn to_jsonl<T: ToCanonical>(messages: Vec<T>) -> Result<String> {
     Ok(lines?.join("\n"))
 }
 
+/// Run `provider → canonical → provider (JSONL) → canonical` for round-trip tests
+///
+/// Returns the canonical messages from the first and second conversion as
+/// JSON values so callers can assert they are identical.
+#[cfg(test)]
+pub(crate) fn round_trip<T>(
+    records: &[T],
+) -> Result<(Vec<serde_json::Value>, Vec<serde_json::Value>)>
+where
+    T: ToCanonical + FromCanonical + serde::Serialize + serde::de::DeserializeOwned,
+{
+    let to_values = |records: &[T]| -> Result<Vec<serde_json::Value>> {
+        let mut values = Vec::new();
+        for record in records {
+            if let Some(message) = record.to_canonical()? {
+                values.push(serde_json::to_value(message)?);
+            }
+        }
+        Ok(values)
+    };
+
+    let first = to_values(records)?;
+    let canonical: Vec<CanonicalMessage> = first
+        .iter()
+        .map(|value| serde_json::from_value(value.clone()))
+        .collect::<std::result::Result<_, _>>()?;
+
+    let native_jsonl = convert_batch_from_canonical::<T>(&canonical)?
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let reparsed: Vec<T> = native_jsonl
+        .iter()
+        .map(|line| serde_json::from_str(line))
+        .collect::<std::result::Result<_, _>>()?;
+
+    Ok((first, to_values(&reparsed)?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::providers::canonical::{ContentValue, MessageContent, MessageType};
+    use crate::providers::canonical::{
+        ContentValue, MessageContent, MessageType, CANONICAL_VERSION,
+    };
 
     // Mock implementation for testing
     struct MockMessage {
@@ -81,6 +164,7 @@ mod tests {
     impl ToCanonical for MockMessage {
         fn to_canonical(&self) -> Result<Option<CanonicalMessage>> {
             Ok(Some(CanonicalMessage {
+                canonical_version: CANONICAL_VERSION.to_string(),
                 uuid: self.id.clone(),
                 timestamp: "2025-01-01T00:00:00.000Z".to_string(),
                 message_type: MessageType::User,
@@ -0,0 +1,205 @@
+//! Conversion fidelity statistics
+//!
+//! Converters skip records (`Ok(None)`), log and continue on parse errors, and
+//! ignore JSON keys they don't know about. `ConversionStats` counts all of that
+//! per session so data loss shows up in the UI and in tests instead of
+//! disappearing silently when a provider changes its format.
+//!
+//! Every raw record ends up in exactly one bucket:
+//! `records_seen == converted + skipped + failed`. Unknown event types are
+//! noted on top of that, since some converters fail on them and others fall
+//! back to a generic meta message.
+//!
+//! Converters that `trace` their records also leave a `RecordOrigin` per
+//! record, which session inspection uses to line raw records up with the
+//! canonical messages they produced.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionStats {
+    pub records_seen: usize,
+    /// Records that produced at least one canonical message
+    pub converted: usize,
+    pub messages_produced: usize,
+    /// Skipped records by reason (usually the record type)
+    pub skipped: BTreeMap<String, usize>,
+    /// Records that failed to parse or convert
+    pub failed: usize,
+    /// Record types the converter has no specific mapping for
+    pub unknown_event_types: BTreeMap<String, usize>,
+    /// Keys the converter does not read, as `path.key` -> occurrences
+    pub unknown_keys: BTreeMap<String, usize>,
+    /// Per-record provenance, in conversion order (not persisted or merged)
+    #[serde(skip)]
+    pub origins: Vec<RecordOrigin>,
+}
+
+/// What one raw record became
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordOrigin {
+    /// Position in the source (line number - 1 for JSONL)
+    pub index: usize,
+    /// Canonical messages produced, consecutive in the converter's output
+    pub messages: usize,
+    /// Why the record failed, if it did
+    pub error: Option<String>,
+}
+
+impl ConversionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_converted(&mut self, messages: usize) {
+        self.records_seen += 1;
+        self.converted += 1;
+        self.messages_produced += messages;
+    }
+
+    pub fn record_skipped(&mut self, reason: &str) {
+        self.records_seen += 1;
+        *self.skipped.entry(reason.to_string()).or_default() += 1;
+    }
+
+    pub fn record_failed(&mut self) {
+        self.records_seen += 1;
+        self.failed += 1;
+    }
+
+    /// Note which source record produced the next `messages` outputs
+    pub fn trace(&mut self, index: usize, messages: usize, error: Option<String>) {
+        self.origins.push(RecordOrigin {
+            index,
+            messages,
+            error,
+        });
+    }
+
+    /// Note an unrecognized record type (in addition to its outcome)
+    pub fn note_unknown_event_type(&mut self, event_type: &str) {
+        *self
+            .unknown_event_types
+            .entry(event_type.to_string())
+            .or_default() += 1;
+    }
+
+    /// Record the outcome of a `ToCanonical`-style conversion
+    pub fn record_result<T, E>(&mut self, result: &Result<Option<T>, E>, skip_reason: &str) {
+        match result {
+            Ok(Some(_)) => self.record_converted(1),
+            Ok(None) => self.record_skipped(skip_reason),
+            Err(_) => self.record_failed(),
+        }
+    }
+
+    /// Count keys of `object` not listed in `known`, reported as `label.key`
+    ///
+    /// Use an empty label for the top level and e.g. `messages[]` for array
+    /// items. Non-objects are ignored.
+    pub fn observe_keys(&mut self, object: &Value, label: &str, known: &[&str]) {
+        let Some(map) = object.as_object() else {
+            return;
+        };
+
+        for key in map.keys() {
+            if !known.contains(&key.as_str()) {
+                let path = if label.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", label, key)
+                };
+                *self.unknown_keys.entry(path).or_default() += 1;
+            }
+        }
+    }
+
+    pub fn skipped_total(&self) -> usize {
+        self.skipped.values().sum()
+    }
+
+    pub fn unknown_total(&self) -> usize {
+        self.unknown_event_types.values().sum()
+    }
+
+    /// Nothing failed and no record had an unrecognized type
+    pub fn is_clean(&self) -> bool {
+        self.failed == 0 && self.unknown_event_types.is_empty()
+    }
+
+    pub fn merge(&mut self, other: &ConversionStats) {
+        self.records_seen += other.records_seen;
+        self.converted += other.converted;
+        self.messages_produced += other.messages_produced;
+        self.failed += other.failed;
+        for (target, source) in [
+            (&mut self.skipped, &other.skipped),
+            (&mut self.unknown_event_types, &other.unknown_event_types),
+            (&mut self.unknown_keys, &other.unknown_keys),
+        ] {
+            for (key, count) in source {
+                *target.entry(key.clone()).or_default() += count;
+            }
+        }
+    }
+
+    /// Store the stats for a session, replacing any earlier run
+    ///
+    /// Best effort: conversions also run where no database is open (tests,
+    /// dev tools), so failures are only logged at debug level.
+    pub fn persist(&self, provider_id: &str, session_id: &str) {
+        if let Err(e) = crate::database::save_conversion_stats(session_id, provider_id, self) {
+            let _ = crate::logging::log_debug(
+                provider_id,
+                &format!("Could not store conversion stats for {}: {}", session_id, e),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_every_record_lands_in_one_bucket() {
+        let mut stats = ConversionStats::new();
+        stats.record_converted(2);
+        stats.record_result(&Ok::<_, ()>(Some(())), "unused");
+        stats.record_result(&Ok::<Option<()>, ()>(None), "token_count");
+        stats.record_skipped("token_count");
+        stats.record_result(&Err::<Option<()>, _>("bad"), "unused");
+        stats.note_unknown_event_type("queue-operation");
+
+        assert_eq!(stats.records_seen, 5);
+        assert_eq!(stats.converted, 2);
+        assert_eq!(stats.messages_produced, 3);
+        assert_eq!(stats.skipped.get("token_count"), Some(&2));
+        assert_eq!(
+            stats.records_seen,
+            stats.converted + stats.skipped_total() + stats.failed
+        );
+        assert_eq!(stats.unknown_total(), 1);
+        assert!(!stats.is_clean());
+    }
+
+    #[test]
+    fn test_observe_keys_and_merge() {
+        let raw = json!({"type": "x", "extra": 1, "payload": {"id": "a", "newField": true}});
+        let mut stats = ConversionStats::new();
+        stats.observe_keys(&raw, "", &["type", "payload"]);
+        stats.observe_keys(&raw["payload"], "payload", &["id"]);
+        stats.observe_keys(&raw["type"], "type", &[]);
+
+        let mut total = ConversionStats::new();
+        total.merge(&stats);
+        total.merge(&stats);
+        assert_eq!(total.unknown_keys.get("extra"), Some(&2));
+        assert_eq!(total.unknown_keys.get("payload.newField"), Some(&2));
+        assert_eq!(total.unknown_keys.len(), 2);
+    }
+}
@@ -2,16 +2,32 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 pub mod converter;
+pub mod fidelity;
+pub mod validator;
 
 #[cfg(test)]
 mod tests;
 
+/// Current canonical schema version written by all converters
+pub const CANONICAL_VERSION: &str = "1.0";
+
+/// Version assumed for canonical files written before versioning existed
+pub const LEGACY_CANONICAL_VERSION: &str = "0";
+
+fn legacy_canonical_version() -> String {
+    LEGACY_CANONICAL_VERSION.to_string()
+}
+
 /// Canonical JSONL message format (based on Claude Code)
 ///
 /// This is the unified format that all providers convert to for consistent processing.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CanonicalMessage {
+    /// Canonical schema version (missing in legacy files)
+    #[serde(default = "legacy_canonical_version")]
+    pub canonical_version: String,
+
     /// Unique message identifier
     pub uuid: String,
 
@@ -170,6 +186,7 @@ impl CanonicalMessage {
         text: String,
     ) -> Self {
         Self {
+            canonical_version: CANONICAL_VERSION.to_string(),
             uuid,
             timestamp,
             message_type,
@@ -206,6 +223,7 @@ impl CanonicalMessage {
         blocks: Vec<ContentBlock>,
     ) -> Self {
         Self {
+            canonical_version: CANONICAL_VERSION.to_string(),
             uuid,
             timestamp,
             message_type,
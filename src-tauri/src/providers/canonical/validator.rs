@@ -0,0 +1,531 @@
+//! Canonical JSONL validator
+//!
+//! Rust port of the checks described in `providers/VALIDATION_GUIDE.md`.
+//! Errors make a session unfit for upload; warnings are reported but do not
+//! block anything.
+
+use super::{
+    CanonicalMessage, ContentBlock, ContentValue, MessageType, CANONICAL_VERSION,
+    LEGACY_CANONICAL_VERSION,
+};
+use chrono::DateTime;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// A single validation finding
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    /// 1-based line number in the JSONL file (None for session-wide issues)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// Stable error code, e.g. "ORPHAN_TOOL_RESULT"
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+}
+
+/// Structured result of validating a canonical session
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    pub is_valid: bool,
+    pub total_lines: usize,
+    pub parsed_lines: usize,
+    pub valid_messages: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical_version: Option<String>,
+    pub errors: Vec<ValidationIssue>,
+    pub warnings: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// One-line description of the first few errors (for logs and upload failures)
+    pub fn error_summary(&self) -> String {
+        let mut parts: Vec<String> = self
+            .errors
+            .iter()
+            .take(3)
+            .map(|issue| match issue.line {
+                Some(line) => format!("line {}: [{}] {}", line, issue.code, issue.message),
+                None => format!("[{}] {}", issue.code, issue.message),
+            })
+            .collect();
+
+        if self.errors.len() > 3 {
+            parts.push(format!("and {} more", self.errors.len() - 3));
+        }
+
+        parts.join("; ")
+    }
+}
+
+/// Major component of a canonical version string ("1.0" -> "1")
+fn major_version(version: &str) -> &str {
+    version.split('.').next().unwrap_or(version)
+}
+
+/// Collects issues while walking the session
+struct Collector {
+    errors: Vec<ValidationIssue>,
+    warnings: Vec<ValidationIssue>,
+}
+
+impl Collector {
+    fn error(&mut self, line: Option<usize>, code: &str, message: String, uuid: Option<&str>) {
+        self.errors.push(ValidationIssue {
+            line,
+            code: code.to_string(),
+            message,
+            uuid: uuid.map(|u| u.to_string()),
+        });
+    }
+
+    fn warning(&mut self, line: Option<usize>, code: &str, message: String, uuid: Option<&str>) {
+        self.warnings.push(ValidationIssue {
+            line,
+            code: code.to_string(),
+            message,
+            uuid: uuid.map(|u| u.to_string()),
+        });
+    }
+}
+
+/// Validate the content of a canonical JSONL file
+pub fn validate_canonical_content(content: &str) -> ValidationReport {
+    let mut issues = Collector {
+        errors: Vec::new(),
+        warnings: Vec::new(),
+    };
+    let mut total_lines = 0;
+    let mut messages: Vec<(usize, CanonicalMessage)> = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        total_lines += 1;
+
+        let value: serde_json::Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => {
+                issues.error(
+                    Some(line_number),
+                    "INVALID_JSON",
+                    format!("Line is not valid JSON: {}", e),
+                    None,
+                );
+                continue;
+            }
+        };
+
+        match serde_json::from_value::<CanonicalMessage>(value) {
+            Ok(message) => messages.push((line_number, message)),
+            Err(e) => issues.error(
+                Some(line_number),
+                "INVALID_SCHEMA",
+                format!("Line does not match the canonical schema: {}", e),
+                None,
+            ),
+        }
+    }
+
+    let parsed_lines = messages.len();
+    let mut lines_with_errors: HashSet<usize> = HashSet::new();
+
+    for (line, message) in &messages {
+        let before = issues.errors.len();
+        validate_message(*line, message, &mut issues);
+        if issues.errors.len() > before {
+            lines_with_errors.insert(*line);
+        }
+    }
+
+    validate_session_messages(&messages, &mut issues, &mut lines_with_errors);
+
+    let session_id = messages.first().map(|(_, m)| m.session_id.clone());
+    let canonical_version = messages.first().map(|(_, m)| m.canonical_version.clone());
+
+    ValidationReport {
+        is_valid: issues.errors.is_empty(),
+        total_lines,
+        parsed_lines,
+        valid_messages: parsed_lines - lines_with_errors.len(),
+        session_id,
+        canonical_version,
+        errors: issues.errors,
+        warnings: issues.warnings,
+    }
+}
+
+/// Checks that only need a single message
+fn validate_message(line: usize, message: &CanonicalMessage, issues: &mut Collector) {
+    let uuid = Some(message.uuid.as_str());
+
+    if message.canonical_version == LEGACY_CANONICAL_VERSION {
+        issues.warning(
+            Some(line),
+            "LEGACY_CANONICAL_VERSION",
+            "Message has no canonicalVersion (written before schema versioning)".to_string(),
+            uuid,
+        );
+    } else if major_version(&message.canonical_version) != major_version(CANONICAL_VERSION) {
+        issues.error(
+            Some(line),
+            "UNSUPPORTED_CANONICAL_VERSION",
+            format!(
+                "canonicalVersion '{}' is not supported (expected {})",
+                message.canonical_version, CANONICAL_VERSION
+            ),
+            uuid,
+        );
+    }
+
+    for (field, value) in [
+        ("uuid", &message.uuid),
+        ("sessionId", &message.session_id),
+        ("provider", &message.provider),
+    ] {
+        if value.trim().is_empty() {
+            issues.error(
+                Some(line),
+                "EMPTY_REQUIRED_FIELD",
+                format!("Required field '{}' is empty", field),
+                uuid,
+            );
+        }
+    }
+
+    if DateTime::parse_from_rfc3339(&message.timestamp).is_err() {
+        issues.error(
+            Some(line),
+            "INVALID_TIMESTAMP_FORMAT",
+            format!("Timestamp '{}' is not RFC3339", message.timestamp),
+            uuid,
+        );
+    }
+
+    let expected_role = match message.message_type {
+        MessageType::User => Some("user"),
+        MessageType::Assistant => Some("assistant"),
+        MessageType::Meta => None,
+    };
+    if let Some(expected_role) = expected_role {
+        if message.message.role != expected_role {
+            issues.error(
+                Some(line),
+                "ROLE_TYPE_MISMATCH",
+                format!(
+                    "Message type '{}' has role '{}'",
+                    expected_role, message.message.role
+                ),
+                uuid,
+            );
+        }
+    }
+
+    let ContentValue::Structured(blocks) = &message.message.content else {
+        return;
+    };
+
+    for block in blocks {
+        match block {
+            ContentBlock::ToolUse { id, name, .. } => {
+                if id.trim().is_empty() {
+                    issues.error(
+                        Some(line),
+                        "EMPTY_TOOL_USE_ID",
+                        "tool_use block has an empty id".to_string(),
+                        uuid,
+                    );
+                }
+                if name.trim().is_empty() {
+                    issues.error(
+                        Some(line),
+                        "EMPTY_TOOL_USE_NAME",
+                        "tool_use block has an empty name".to_string(),
+                        uuid,
+                    );
+                }
+            }
+            ContentBlock::ToolResult {
+                tool_use_id,
+                content,
+                ..
+            } => {
+                if message.message_type != MessageType::User {
+                    issues.error(
+                        Some(line),
+                        "INVALID_TOOL_RESULT_MESSAGE_TYPE",
+                        "tool_result blocks must be in user messages".to_string(),
+                        uuid,
+                    );
+                }
+                if tool_use_id.trim().is_empty() {
+                    issues.error(
+                        Some(line),
+                        "EMPTY_TOOL_RESULT_ID",
+                        "tool_result block has an empty tool_use_id".to_string(),
+                        uuid,
+                    );
+                }
+                // Commands without output legitimately produce empty results
+                if content.is_empty() {
+                    issues.warning(
+                        Some(line),
+                        "EMPTY_TOOL_RESULT_CONTENT",
+                        format!("tool_result for '{}' has empty content", tool_use_id),
+                        uuid,
+                    );
+                }
+            }
+            ContentBlock::Text { .. } | ContentBlock::Thinking { .. } => {}
+        }
+    }
+}
+
+/// Cross-message checks: uniqueness, threading, tool pairing and ordering
+fn validate_session_messages(
+    messages: &[(usize, CanonicalMessage)],
+    issues: &mut Collector,
+    lines_with_errors: &mut HashSet<usize>,
+) {
+    let all_uuids: HashSet<&str> = messages.iter().map(|(_, m)| m.uuid.as_str()).collect();
+    let tool_use_ids: HashSet<&str> = messages
+        .iter()
+        .filter_map(|(_, m)| match &m.message.content {
+            ContentValue::Structured(blocks) => Some(blocks),
+            ContentValue::Text(_) => None,
+        })
+        .flatten()
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { id, .. } => Some(id.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut seen_uuids: HashSet<&str> = HashSet::new();
+    let mut session_ids: HashSet<&str> = HashSet::new();
+    let mut previous_timestamp = None;
+
+    for (line, message) in messages {
+        let line = *line;
+        let uuid = Some(message.uuid.as_str());
+        session_ids.insert(message.session_id.as_str());
+
+        if !message.uuid.is_empty() && !seen_uuids.insert(message.uuid.as_str()) {
+            issues.error(
+                Some(line),
+                "DUPLICATE_UUID",
+                format!("uuid '{}' appears more than once", message.uuid),
+                uuid,
+            );
+            lines_with_errors.insert(line);
+        }
+
+        if let Some(parent) = &message.parent_uuid {
+            if !all_uuids.contains(parent.as_str()) {
+                issues.warning(
+                    Some(line),
+                    "MISSING_PARENT_UUID",
+                    format!("parentUuid '{}' does not match any message", parent),
+                    uuid,
+                );
+            }
+        }
+
+        if let ContentValue::Structured(blocks) = &message.message.content {
+            for block in blocks {
+                if let ContentBlock::ToolResult { tool_use_id, .. } = block {
+                    if !tool_use_id.is_empty() && !tool_use_ids.contains(tool_use_id.as_str()) {
+                        issues.error(
+                            Some(line),
+                            "ORPHAN_TOOL_RESULT",
+                            format!(
+                                "tool_result references tool_use_id '{}' which doesn't exist",
+                                tool_use_id
+                            ),
+                            uuid,
+                        );
+                        lines_with_errors.insert(line);
+                    }
+                }
+            }
+        }
+
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(&message.timestamp) {
+            if let Some(previous) = previous_timestamp {
+                if timestamp < previous {
+                    issues.warning(
+                        Some(line),
+                        "TIMESTAMP_OUT_OF_ORDER",
+                        format!(
+                            "Timestamp {} is earlier than the previous message",
+                            message.timestamp
+                        ),
+                        uuid,
+                    );
+                }
+            }
+            previous_timestamp = Some(timestamp);
+        }
+    }
+
+    if session_ids.len() > 1 {
+        issues.warning(
+            None,
+            "MULTIPLE_SESSION_IDS",
+            format!("File contains {} different sessionIds", session_ids.len()),
+            None,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::canonical::MessageContent;
+    use serde_json::json;
+
+    fn message(uuid: &str, timestamp: &str, message_type: MessageType) -> CanonicalMessage {
+        let role = match message_type {
+            MessageType::Assistant => "assistant",
+            _ => "user",
+        };
+        CanonicalMessage::new_text_message(
+            uuid.to_string(),
+            timestamp.to_string(),
+            message_type,
+            "session-1".to_string(),
+            "claude-code".to_string(),
+            role.to_string(),
+            "hello".to_string(),
+        )
+    }
+
+    fn to_jsonl(messages: &[CanonicalMessage]) -> String {
+        messages
+            .iter()
+            .map(|m| serde_json::to_string(m).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn codes(issues: &[ValidationIssue]) -> Vec<&str> {
+        issues.iter().map(|i| i.code.as_str()).collect()
+    }
+
+    #[test]
+    fn test_valid_session() {
+        let mut tool_use = message("a-2", "2025-01-01T10:00:01Z", MessageType::Assistant);
+        tool_use.parent_uuid = Some("u-1".to_string());
+        tool_use.message.content = ContentValue::Structured(vec![ContentBlock::ToolUse {
+            id: "call-1".to_string(),
+            name: "Read".to_string(),
+            input: json!({"path": "a.rs"}),
+        }]);
+        let mut tool_result = message("u-3", "2025-01-01T10:00:02Z", MessageType::User);
+        tool_result.parent_uuid = Some("a-2".to_string());
+        tool_result.message.content = ContentValue::Structured(vec![ContentBlock::ToolResult {
+            tool_use_id: "call-1".to_string(),
+            content: "fn main() {}".to_string(),
+            is_error: None,
+        }]);
+
+        let content = to_jsonl(&[
+            message("u-1", "2025-01-01T10:00:00Z", MessageType::User),
+            tool_use,
+            tool_result,
+        ]);
+        let report = validate_canonical_content(&content);
+
+        assert!(report.is_valid, "{:?}", report.errors);
+        assert_eq!(report.total_lines, 3);
+        assert_eq!(report.valid_messages, 3);
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.canonical_version.as_deref(), Some(CANONICAL_VERSION));
+    }
+
+    #[test]
+    fn test_session_level_errors() {
+        let mut orphan = message("u-2", "2025-01-01T10:00:01Z", MessageType::User);
+        orphan.message.content = ContentValue::Structured(vec![ContentBlock::ToolResult {
+            tool_use_id: "call-999".to_string(),
+            content: "output".to_string(),
+            is_error: None,
+        }]);
+        let mut mismatched = message("u-3", "2025-01-01T10:00:02Z", MessageType::Assistant);
+        mismatched.message = MessageContent {
+            role: "user".to_string(),
+            content: ContentValue::Text("hi".to_string()),
+            model: None,
+            usage: None,
+        };
+
+        let content = to_jsonl(&[
+            message("u-1", "2025-01-01T10:00:00Z", MessageType::User),
+            orphan,
+            mismatched,
+            message("u-1", "2025-01-01T10:00:03Z", MessageType::User),
+        ]);
+        let report = validate_canonical_content(&content);
+
+        assert!(!report.is_valid);
+        let error_codes = codes(&report.errors);
+        assert!(error_codes.contains(&"ORPHAN_TOOL_RESULT"));
+        assert!(error_codes.contains(&"ROLE_TYPE_MISMATCH"));
+        assert!(error_codes.contains(&"DUPLICATE_UUID"));
+        assert_eq!(report.valid_messages, 1);
+        assert!(report.error_summary().contains("line 2"));
+    }
+
+    #[test]
+    fn test_warnings_and_legacy_version() {
+        let mut out_of_order = message("u-2", "2025-01-01T09:00:00Z", MessageType::User);
+        out_of_order.parent_uuid = Some("missing".to_string());
+
+        let mut legacy =
+            serde_json::to_value(message("u-1", "2025-01-01T10:00:00Z", MessageType::User))
+                .unwrap();
+        legacy.as_object_mut().unwrap().remove("canonicalVersion");
+
+        let content = format!(
+            "{}\n{}\n",
+            legacy,
+            serde_json::to_string(&out_of_order).unwrap()
+        );
+        let report = validate_canonical_content(&content);
+
+        assert!(report.is_valid, "{:?}", report.errors);
+        let warning_codes = codes(&report.warnings);
+        assert!(warning_codes.contains(&"LEGACY_CANONICAL_VERSION"));
+        assert!(warning_codes.contains(&"MISSING_PARENT_UUID"));
+        assert!(warning_codes.contains(&"TIMESTAMP_OUT_OF_ORDER"));
+    }
+
+    #[test]
+    fn test_unparseable_lines() {
+        let mut future =
+            serde_json::to_value(message("u-1", "2025-01-01T10:00:00Z", MessageType::User))
+                .unwrap();
+        future["canonicalVersion"] = json!("2.0");
+
+        let content = format!("not json\n{{\"uuid\": \"x\"}}\n{}", future);
+        let report = validate_canonical_content(&content);
+
+        assert_eq!(report.total_lines, 3);
+        assert_eq!(report.parsed_lines, 1);
+        assert_eq!(
+            codes(&report.errors),
+            vec![
+                "INVALID_JSON",
+                "INVALID_SCHEMA",
+                "UNSUPPORTED_CANONICAL_VERSION"
+            ]
+        );
+    }
+}
@@ -1,7 +1,8 @@
 //! Converter implementation for Claude Code to canonical format
 
 use crate::providers::canonical::{
-    converter::ToCanonical, CanonicalMessage, MessageContent, MessageType,
+    converter::{FromCanonical, ToCanonical},
+    CanonicalMessage, MessageContent, MessageType, CANONICAL_VERSION,
 };
 use anyhow::{Context, Result};
 
@@ -95,6 +96,7 @@ impl ToCanonical for ClaudeEntry {
 
         // 7. Build canonical message
         Ok(Some(CanonicalMessage {
+            canonical_version: CANONICAL_VERSION.to_string(),
             uuid,
             timestamp,
             message_type,
@@ -131,6 +133,40 @@ impl ToCanonical for ClaudeEntry {
     }
 }
 
+impl FromCanonical for ClaudeEntry {
+    fn from_canonical(message: &CanonicalMessage) -> Result<Vec<Self>> {
+        let entry_type = match message.message_type {
+            MessageType::User => ClaudeEntryType::User,
+            MessageType::Assistant => ClaudeEntryType::Assistant,
+            MessageType::Meta => ClaudeEntryType::Meta,
+        };
+
+        let message_value = serde_json::to_value(&message.message)
+            .context("Failed to serialize message content")?;
+
+        Ok(vec![ClaudeEntry {
+            uuid: Some(message.uuid.clone()),
+            timestamp: Some(message.timestamp.clone()),
+            entry_type,
+            session_id: Some(message.session_id.clone()),
+            cwd: message.cwd.clone(),
+            git_branch: message.git_branch.clone(),
+            version: message.version.clone(),
+            parent_uuid: message.parent_uuid.clone(),
+            is_sidechain: message.is_sidechain,
+            user_type: message.user_type.clone(),
+            message: Some(message_value),
+            request_id: message.request_id.clone(),
+            subtype: None,
+            is_meta: message.is_meta,
+            content: None,
+            message_id: None,
+            snapshot: None,
+            tool_use_result: message.tool_use_result.clone(),
+        }])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,21 +2,81 @@
 
 use crate::logging::log_debug;
 use crate::providers::canonical::converter::ToCanonical;
+use crate::providers::canonical::fidelity::ConversionStats;
+use crate::providers::canonical::{CanonicalMessage, ContentBlock, ContentValue};
 use crate::providers::claude::types::ClaudeEntry;
-use crate::providers::common::get_canonical_path;
+use crate::providers::common::format_version::{FormatSpec, FormatVersionRange, NewerVersions};
+use crate::providers::common::{get_canonical_path, load_agent_messages};
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Entry types `ClaudeEntryType` can deserialize
+const KNOWN_ENTRY_TYPES: &[&str] = &[
+    "user",
+    "assistant",
+    "meta",
+    "system",
+    "file-history-snapshot",
+    "summary",
+];
+
+/// Top-level keys read by `ClaudeEntry`
+const KNOWN_KEYS: &[&str] = &[
+    "uuid",
+    "timestamp",
+    "type",
+    "sessionId",
+    "cwd",
+    "gitBranch",
+    "version",
+    "parentUuid",
+    "isSidechain",
+    "userType",
+    "message",
+    "requestId",
+    "subtype",
+    "isMeta",
+    "content",
+    "messageId",
+    "snapshot",
+    "toolUseResult",
+];
+
+/// Claude Code session formats this converter can decode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClaudeFormat {
+    /// One `ClaudeEntry` per line, agent sidechains in `agent-*.jsonl`
+    Jsonl,
+}
+
+/// Versioned by the Claude Code `version` stamped on each entry
+pub static CLAUDE_FORMATS: FormatSpec<ClaudeFormat> = FormatSpec {
+    provider_id: "claude-code",
+    version_field: "version",
+    // 0.2.x research previews already wrote the same JSONL entries
+    ranges: &[FormatVersionRange {
+        min: "0.2.0",
+        max: Some("3.0.0"),
+        decoder: ClaudeFormat::Jsonl,
+    }],
+    // Summary-only files carry no version
+    unversioned: Some(ClaudeFormat::Jsonl),
+    // A new Claude Code major doesn't imply a new entry format
+    newer: NewerVersions::Warn,
+};
+
 /// Convert a Claude Code session file to canonical format
 ///
 /// This function:
 /// 1. Reads the native Claude JSONL
-/// 2. Parses each line as ClaudeEntry
-/// 3. Filters out system events (file-history-snapshot, summary, etc.)
-/// 4. Adds `provider: "claude-code"` field
-/// 5. Fixes empty tool_result content
-/// 6. Merges agent sidechain files
-/// 7. Writes canonical JSONL to cache
+/// 2. Refuses Claude Code versions older than `CLAUDE_FORMATS`
+/// 3. Parses each line as ClaudeEntry
+/// 4. Filters out system events (file-history-snapshot, summary, etc.)
+/// 5. Adds `provider: "claude-code"` field
+/// 6. Fixes empty tool_result content
+/// 7. Merges agent sidechain files
+/// 8. Writes canonical JSONL to cache
 ///
 /// # Arguments
 /// * `claude_file` - Path to native Claude session file
@@ -38,8 +98,57 @@ pub fn convert_to_canonical_file(
         .parent()
         .ok_or("Source file has no parent directory")?;
 
-    let mut canonical_lines = Vec::new();
     let mut cwd_value: Option<String> = cwd.map(|s| s.to_string());
+    let mut stats = ConversionStats::new();
+    let canonical_lines = convert_content(
+        &content,
+        |agent_id| load_agent_messages(source_dir, agent_id, session_id).ok(),
+        session_id,
+        &mut cwd_value,
+        &mut stats,
+    )?;
+
+    // Get project-organized canonical path
+    // Uses ~/.guideai/sessions/{provider}/{project}/{session_id}.jsonl
+    let canonical_path = get_canonical_path("claude-code", cwd_value.as_deref(), session_id)?;
+
+    // Write converted canonical JSONL
+    if let Some(parent) = canonical_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&canonical_path, canonical_lines.join("\n"))?;
+    stats.persist("claude-code", session_id);
+
+    Ok(canonical_path)
+}
+
+/// Convert session content with the decoder for its Claude Code version
+///
+/// `load_agent` returns the raw lines of an agent's sidechain, if available.
+pub(crate) fn convert_content(
+    content: &str,
+    load_agent: impl Fn(&str) -> Option<Vec<String>>,
+    session_id: &str,
+    cwd_value: &mut Option<String>,
+    stats: &mut ConversionStats,
+) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+    let version = detect_version(content);
+    match CLAUDE_FORMATS.resolve(session_id, version.as_deref())? {
+        ClaudeFormat::Jsonl => {
+            convert_jsonl_entries(content, load_agent, session_id, cwd_value, stats)
+        }
+    }
+}
+
+/// Decode `ClaudeEntry` JSONL lines, appending agent sidechains after their tool results
+fn convert_jsonl_entries(
+    content: &str,
+    load_agent: impl Fn(&str) -> Option<Vec<String>>,
+    session_id: &str,
+    cwd_value: &mut Option<String>,
+    stats: &mut ConversionStats,
+) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut canonical_lines = Vec::new();
 
     // Parse and convert each line independently
     for (line_num, line) in content.lines().enumerate() {
@@ -47,11 +156,16 @@ pub fn convert_to_canonical_file(
             continue;
         }
 
+        let raw: Option<serde_json::Value> = serde_json::from_str(line).ok();
+        if let Some(ref raw) = raw {
+            stats.observe_keys(raw, "", KNOWN_KEYS);
+        }
+
         match serde_json::from_str::<ClaudeEntry>(line) {
             Ok(claude_entry) => {
                 // Extract CWD from first entry that has it (if not provided)
                 if cwd_value.is_none() {
-                    cwd_value = claude_entry.extract_cwd();
+                    *cwd_value = claude_entry.extract_cwd();
                 }
 
                 // Convert to canonical format (filters out system events)
@@ -60,24 +174,29 @@ pub fn convert_to_canonical_file(
                         // Ensure session_id is set correctly
                         canonical_msg.session_id = session_id.to_string();
 
-                        canonical_lines.push(serde_json::to_string(&canonical_msg)?);
+                        let mut produced = vec![serde_json::to_string(&canonical_msg)?];
+                        stats.record_converted(1);
 
                         // Check if this message has an agent sidechain
-                        if let Some(agent_id) = extract_agent_id_from_tool_use_result(&claude_entry)
+                        if let Some(agent_lines) =
+                            extract_agent_id_from_tool_use_result(&claude_entry)
+                                .and_then(|agent_id| load_agent(&agent_id))
                         {
-                            // Load and convert agent messages
-                            if let Ok(agent_lines) =
-                                load_and_convert_agent_messages(source_dir, &agent_id, session_id)
-                            {
-                                canonical_lines.extend(agent_lines);
-                            }
+                            produced.extend(convert_agent_lines(&agent_lines, session_id));
                         }
+
+                        stats.trace(line_num, produced.len(), None);
+                        canonical_lines.extend(produced);
                     }
                     Ok(None) => {
                         // Message was filtered out (e.g., file-history-snapshot)
+                        stats.record_skipped(&skip_reason(raw.as_ref()));
+                        stats.trace(line_num, 0, None);
                     }
                     Err(e) => {
                         // Log parsing errors but continue processing
+                        stats.record_failed();
+                        stats.trace(line_num, 0, Some(e.to_string()));
                         if let Err(log_err) = log_debug(
                             "claude-code",
                             &format!("Failed to convert line {}: {}", line_num + 1, e),
@@ -88,6 +207,23 @@ pub fn convert_to_canonical_file(
                 }
             }
             Err(e) => {
+                // Unknown entry types fail to deserialize; note which ones
+                stats.record_failed();
+                let error = match raw {
+                    Some(_) => format!("Unrecognized record: {}", e),
+                    None => format!("Invalid JSON: {}", e),
+                };
+                stats.trace(line_num, 0, Some(error));
+                if let Some(entry_type) = raw
+                    .as_ref()
+                    .and_then(|raw| raw.get("type"))
+                    .and_then(|t| t.as_str())
+                {
+                    if !KNOWN_ENTRY_TYPES.contains(&entry_type) {
+                        stats.note_unknown_event_type(entry_type);
+                    }
+                }
+
                 // Log parsing errors but continue processing
                 if let Err(log_err) = log_debug(
                     "claude-code",
@@ -99,17 +235,96 @@ pub fn convert_to_canonical_file(
         }
     }
 
-    // Get project-organized canonical path
-    // Uses ~/.guideai/sessions/{provider}/{project}/{session_id}.jsonl
-    let canonical_path = get_canonical_path("claude-code", cwd_value.as_deref(), session_id)?;
+    let detached = detach_orphan_tool_results(&mut canonical_lines)?;
+    if detached > 0 {
+        let _ = log_debug(
+            "claude-code",
+            &format!(
+                "Kept {} tool result(s) from an earlier session as text in {}",
+                detached, session_id
+            ),
+        );
+    }
 
-    // Write converted canonical JSONL
-    if let Some(parent) = canonical_path.parent() {
-        fs::create_dir_all(parent)?;
+    Ok(canonical_lines)
+}
+
+/// Turn tool results whose tool_use is not in this session into text
+///
+/// Resumed and compacted sessions carry results for calls made in an earlier
+/// session file. Their output is kept, but nothing here can pair it.
+fn detach_orphan_tool_results(lines: &mut [String]) -> Result<usize, serde_json::Error> {
+    let mut messages = lines
+        .iter()
+        .map(|line| serde_json::from_str::<CanonicalMessage>(line))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let tool_use_ids: HashSet<String> = messages
+        .iter()
+        .filter_map(|message| match &message.message.content {
+            ContentValue::Structured(blocks) => Some(blocks),
+            ContentValue::Text(_) => None,
+        })
+        .flatten()
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { id, .. } => Some(id.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut detached = 0;
+    for (line, message) in lines.iter_mut().zip(messages.iter_mut()) {
+        let ContentValue::Structured(blocks) = &mut message.message.content else {
+            continue;
+        };
+        let mut changed = false;
+        for block in blocks.iter_mut() {
+            let ContentBlock::ToolResult {
+                tool_use_id,
+                content,
+                ..
+            } = block
+            else {
+                continue;
+            };
+            if tool_use_id.is_empty() || tool_use_ids.contains(tool_use_id.as_str()) {
+                continue;
+            }
+            let text = format!(
+                "[Result of tool call {} from an earlier session]\n{}",
+                tool_use_id, content
+            );
+            *block = ContentBlock::Text { text };
+            changed = true;
+            detached += 1;
+        }
+        if changed {
+            *line = serde_json::to_string(message)?;
+        }
     }
-    fs::write(&canonical_path, canonical_lines.join("\n"))?;
 
-    Ok(canonical_path)
+    Ok(detached)
+}
+
+/// Claude Code version that wrote the session (first entry carrying `version`)
+fn detect_version(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        serde_json::from_str::<serde_json::Value>(line)
+            .ok()?
+            .get("version")?
+            .as_str()
+            .map(str::to_string)
+    })
+}
+
+/// Skip reason for a filtered entry: its type, plus the subtype for system events
+fn skip_reason(raw: Option<&serde_json::Value>) -> String {
+    let field = |key: &str| raw.and_then(|raw| raw.get(key)).and_then(|v| v.as_str());
+    match (field("type"), field("subtype")) {
+        (Some(entry_type), Some(subtype)) => format!("{}:{}", entry_type, subtype),
+        (Some(entry_type), None) => entry_type.to_string(),
+        _ => "unknown".to_string(),
+    }
 }
 
 /// Extract agent ID from a Claude entry's toolUseResult
@@ -122,23 +337,13 @@ fn extract_agent_id_from_tool_use_result(entry: &ClaudeEntry) -> Option<String>
         .map(|s| s.to_string())
 }
 
-/// Load and convert agent messages from agent-*.jsonl file
-fn load_and_convert_agent_messages(
-    source_dir: &Path,
-    agent_id: &str,
-    session_id: &str,
-) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-    let agent_file = source_dir.join(format!("agent-{}.jsonl", agent_id));
-
-    if !agent_file.exists() {
-        // Agent file may not exist yet during partial writes
-        return Ok(Vec::new());
-    }
-
-    let agent_content = fs::read_to_string(&agent_file)?;
+/// Convert the lines of an agent-*.jsonl sidechain
+///
+/// Lines that fail to parse or convert are left out.
+fn convert_agent_lines(lines: &[String], session_id: &str) -> Vec<String> {
     let mut agent_lines = Vec::new();
 
-    for line in agent_content.lines() {
+    for line in lines {
         if line.trim().is_empty() {
             continue;
         }
@@ -155,5 +360,46 @@ fn load_and_convert_agent_messages(
         }
     }
 
-    Ok(agent_lines)
+    agent_lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orphan_tool_results_become_text() {
+        let content = r#"{"uuid":"a-1","timestamp":"2025-01-01T10:00:00.000Z","type":"assistant","sessionId":"s-1","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_here","name":"Bash","input":{}}]}}
+{"uuid":"u-1","parentUuid":"a-1","timestamp":"2025-01-01T10:00:01.000Z","type":"user","sessionId":"s-1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_here","content":"ok"}]}}
+{"uuid":"u-2","parentUuid":"u-1","timestamp":"2025-01-01T10:00:02.000Z","type":"user","sessionId":"s-1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_resumed","content":"earlier output"}]}}"#;
+
+        let mut cwd = None;
+        let mut stats = ConversionStats::new();
+        let lines = convert_jsonl_entries(content, |_| None, "s-1", &mut cwd, &mut stats).unwrap();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].contains("\"tool_use_id\":\"toolu_here\""));
+        assert!(!lines[2].contains("tool_result"));
+        assert!(lines[2].contains("earlier output"));
+
+        let report =
+            crate::providers::canonical::validator::validate_canonical_content(&lines.join("\n"));
+        assert!(report.is_valid, "{}", report.error_summary());
+    }
+
+    #[test]
+    fn test_claude_formats_cover_preview_versions() {
+        // 3.x is past the known range but only warns
+        for version in ["0.2.9", "0.2.125", "1.0.0", "2.0.21", "3.0.0"] {
+            assert_eq!(
+                CLAUDE_FORMATS.resolve("claude-format-test", Some(version)),
+                Ok(ClaudeFormat::Jsonl),
+                "{} should be supported",
+                version
+            );
+        }
+        assert!(CLAUDE_FORMATS
+            .resolve("claude-format-test", Some("0.1.0"))
+            .is_err());
+    }
 }
@@ -61,24 +61,31 @@ pub struct ClaudeEntry {
     pub message: Option<Value>,
 
     /// Request ID
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub request_id: Option<String>,
 
     /// System event subtype (for system messages)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub subtype: Option<String>,
 
     /// Whether this is a meta message
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub is_meta: Option<bool>,
 
     /// Content (for summary and other events)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
 
     /// Message ID (for file-history-snapshot)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub message_id: Option<String>,
 
     /// Snapshot data (for file-history-snapshot)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub snapshot: Option<Value>,
 
     /// Tool use result
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_use_result: Option<Value>,
 }
 
@@ -1,17 +1,21 @@
 use crate::config::load_provider_config;
+use crate::config_service::ProviderConfigUpdates;
 use crate::events::{EventBus, SessionEventPayload};
 use crate::logging::{log_debug, log_error, log_info, log_warn};
 use crate::providers::common::{
-    extract_session_id_from_filename,
-    get_file_size, has_extension, should_skip_file, SessionStateManager, WatcherStatus,
-    EVENT_TIMEOUT, FILE_WATCH_POLL_INTERVAL, MIN_SIZE_CHANGE_BYTES,
+    extract_session_id_from_filename, format_version, fs_event_channel, get_file_size,
+    has_extension, run_blocking, should_skip_file, Debouncer, FsEventReceiver, FsWatcher,
+    SessionLifecycle, SessionStateManager, WatchCancellation, WatchSet, WatchTask,
+    WatcherHeartbeat, WatcherStatus, EVENT_TIMEOUT, FILE_WATCH_POLL_INTERVAL,
+    MIN_SIZE_CHANGE_BYTES,
 };
 use crate::upload_queue::UploadQueue;
-use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Config, Event, EventKind, RecursiveMode, Watcher};
 use shellexpand::tilde;
 use std::path::{Path, PathBuf};
-use std::sync::{mpsc, Arc, Mutex};
-use std::thread;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 const PROVIDER_ID: &str = "claude-code";
 
@@ -25,17 +29,19 @@ pub struct FileChangeEvent {
 
 #[derive(Debug)]
 pub struct ClaudeWatcher {
-    _watcher: RecommendedWatcher,
-    _thread_handle: thread::JoinHandle<()>,
+    _watcher: Arc<Mutex<FsWatcher>>,
+    task: WatchTask,
     upload_queue: Arc<UploadQueue>,
     is_running: Arc<Mutex<bool>>,
+    watch_set: Arc<Mutex<WatchSet>>,
+    heartbeat: WatcherHeartbeat,
 }
 
 impl ClaudeWatcher {
     pub fn new(
-        _projects: Vec<String>,
         upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
+        shutdown: CancellationToken,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         if let Err(e) = log_info(PROVIDER_ID, "🔍 Starting Claude Code file monitoring") {
             eprintln!("Logging error: {}", e);
@@ -49,8 +55,7 @@ impl ClaudeWatcher {
             return Err("Claude Code provider is not enabled".into());
         }
 
-        let home_directory = config.home_directory;
-        let expanded_home = tilde(&home_directory);
+        let expanded_home = tilde(&config.home_directory);
         let base_path = Path::new(expanded_home.as_ref());
 
         if !base_path.exists() {
@@ -70,214 +75,251 @@ impl ClaudeWatcher {
             .into());
         }
 
-        // Determine which projects to watch
-        let projects_to_watch = if config.project_selection == "ALL" {
-            // Watch all available projects
-            Self::discover_all_projects(&projects_path)?
-        } else {
-            // Watch only selected projects that exist on disk
-            let selected_projects: Vec<String> = config
-                .selected_projects
-                .into_iter()
-                .filter(|project| {
-                    let project_path = projects_path.join(project);
-                    project_path.exists() && project_path.is_dir()
-                })
-                .collect();
-
-            if selected_projects.is_empty() {
-                return Err("No valid projects selected for watching".into());
-            }
-
-            selected_projects
-        };
-
-        if let Err(e) = log_info(
-            PROVIDER_ID,
-            &format!(
-                "📁 Monitoring {} Claude Code projects: {}",
-                projects_to_watch.len(),
-                projects_to_watch.join(", ")
-            ),
-        ) {
-            eprintln!("Logging error: {}", e);
+        if config.project_selection != "ALL" && config.selected_projects.is_empty() {
+            return Err("No valid projects selected for watching".into());
         }
 
         // Create file system event channel
-        let (tx, rx) = mpsc::channel();
+        let (tx, rx) = fs_event_channel(PROVIDER_ID);
 
         // Create the file watcher
-        let mut watcher = RecommendedWatcher::new(
+        let mut watcher = FsWatcher::for_provider(
+            PROVIDER_ID,
             tx,
             Config::default().with_poll_interval(FILE_WATCH_POLL_INTERVAL),
         )?;
 
-        // Watch each selected project directory
-        for project_name in &projects_to_watch {
-            let project_path = projects_path.join(project_name);
-            if project_path.exists() && project_path.is_dir() {
-                watcher.watch(&project_path, RecursiveMode::Recursive)?;
-                if let Err(e) = log_info(
-                    PROVIDER_ID,
-                    &format!(
-                        "📂 Watching Claude Code project: {}",
-                        project_path.display()
-                    ),
-                ) {
-                    eprintln!("Logging error: {}", e);
-                }
-            } else if let Err(e) = log_warn(
-                PROVIDER_ID,
-                &format!("⚠ Project directory not found: {}", project_path.display()),
-            ) {
-                eprintln!("Logging error: {}", e);
-            }
+        // Watch the projects root so projects created later are picked up,
+        // plus each project directory that currently matches the selection
+        let mut watch_set = WatchSet::new(
+            PROVIDER_ID,
+            projects_path.clone(),
+            None,
+            RecursiveMode::Recursive,
+        );
+        watch_set.watch_root(&mut watcher)?;
+        watch_set.sync(&mut watcher, &config);
+
+        if let Err(e) = log_info(
+            PROVIDER_ID,
+            &format!("📁 Monitoring {} Claude Code projects", watch_set.len()),
+        ) {
+            eprintln!("Logging error: {}", e);
         }
 
+        let heartbeat = WatcherHeartbeat::new(PROVIDER_ID, &projects_path, watcher.is_polling());
+        let watcher = Arc::new(Mutex::new(watcher));
+        let watch_set = Arc::new(Mutex::new(watch_set));
         let is_running = Arc::new(Mutex::new(true));
         let is_running_clone = Arc::clone(&is_running);
         let upload_queue_clone = Arc::clone(&upload_queue);
         let projects_path_clone = projects_path.clone();
         let event_bus_clone = event_bus.clone();
+        let watcher_clone = Arc::clone(&watcher);
+        let watch_set_clone = Arc::clone(&watch_set);
+        let heartbeat_clone = heartbeat.clone();
 
-        // Start background thread to handle file events
-        let thread_handle = thread::spawn(move || {
+        // Start background task to handle file events
+        let task = WatchTask::spawn(&shutdown, move |cancellation| async move {
+            let _exit_guard = heartbeat_clone.exit_guard();
             Self::file_event_processor(
                 rx,
                 projects_path_clone,
+                watcher_clone,
+                watch_set_clone,
                 upload_queue_clone,
                 event_bus_clone,
                 is_running_clone,
-            );
+                heartbeat_clone,
+                cancellation,
+            )
+            .await;
         });
 
         Ok(ClaudeWatcher {
             _watcher: watcher,
-            _thread_handle: thread_handle,
+            task,
             upload_queue,
             is_running,
+            watch_set,
+            heartbeat,
         })
     }
 
-    fn discover_all_projects(
-        projects_path: &Path,
-    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-        let entries = std::fs::read_dir(projects_path)
-            .map_err(|e| format!("Failed to read projects directory: {}", e))?;
-
-        let mut projects = Vec::new();
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    projects.push(name.to_string());
-                }
-            }
-        }
-
-        Ok(projects)
-    }
-
-    fn file_event_processor(
-        rx: mpsc::Receiver<Result<Event, notify::Error>>,
+    #[allow(clippy::too_many_arguments)]
+    async fn file_event_processor(
+        mut rx: FsEventReceiver,
         projects_path: PathBuf,
+        watcher: Arc<Mutex<FsWatcher>>,
+        watch_set: Arc<Mutex<WatchSet>>,
         _upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
         is_running: Arc<Mutex<bool>>,
+        heartbeat: WatcherHeartbeat,
+        mut cancellation: WatchCancellation,
     ) {
         let mut session_states = SessionStateManager::new();
+        let mut lifecycle = SessionLifecycle::new(PROVIDER_ID, event_bus.clone());
+        let mut config_updates = ProviderConfigUpdates::new(PROVIDER_ID);
+        let mut debouncer = Debouncer::default();
 
         loop {
-            // Check if we should continue running
-            {
-                if let Ok(running) = is_running.lock() {
-                    if !*running {
-                        break;
-                    }
-                }
+            heartbeat.beat();
+
+            // Move quiet sessions towards idle/completed
+            for session_id in lifecycle.tick() {
+                session_states.mark_inactive(&session_id);
             }
 
-            // Process file system events with timeout
-            match rx.recv_timeout(EVENT_TIMEOUT) {
-                Ok(Ok(event)) => {
-                    if let Some(file_event) = Self::process_file_event(&event, &projects_path) {
-                        // Check if this is a new session (before get_or_create)
-                        let is_new_session = !session_states.contains(&file_event.session_id);
-
-                        // Get or create session state
-                        let state = session_states
-                            .get_or_create(&file_event.session_id, file_event.file_size);
-                        let should_log = state.should_log(
-                            file_event.file_size,
-                            MIN_SIZE_CHANGE_BYTES,
-                            is_new_session,
-                        );
-
-                        // Publish SessionChanged event to event bus
-                        // DatabaseEventHandler will call db_helpers which does smart insert-or-update
-                        let payload = SessionEventPayload::SessionChanged {
-                            session_id: file_event.session_id.clone(),
-                            project_name: file_event.project_name.clone(),
-                            file_path: file_event.path.clone(),
-                            file_size: file_event.file_size,
-                        };
+            // Apply config edits without restarting the watcher
+            if let Some(config) = config_updates.poll() {
+                if !config.enabled {
+                    cancellation.stop_disabled(PROVIDER_ID, &is_running);
+                    continue;
+                }
+                lifecycle.set_config(&config);
+                Self::resync_watch_set(&watcher, &watch_set, &mut debouncer);
+            }
 
-                        if let Err(e) = event_bus.publish(PROVIDER_ID, payload) {
-                            if let Err(log_err) = log_error(
-                                PROVIDER_ID,
-                                &format!("Failed to publish session event: {}", e),
-                            ) {
-                                eprintln!("Logging error: {}", log_err);
-                            }
-                        }
+            let rescan_due = watch_set
+                .lock()
+                .map(|set| set.rescan_due())
+                .unwrap_or(false);
+            if rescan_due {
+                Self::resync_watch_set(&watcher, &watch_set, &mut debouncer);
+            }
 
-                        // Update session state immediately to prevent duplicate events
-                        state.update(file_event.file_size);
+            // Events were dropped, so look at every watched file again
+            if rx.take_overflow() {
+                let events = watch_set
+                    .lock()
+                    .map(|set| set.file_events(&set.names()))
+                    .unwrap_or_default();
+                let _ = log_warn(
+                    PROVIDER_ID,
+                    &format!(
+                        "⚠ Rescanning {} watched files after dropped events",
+                        events.len()
+                    ),
+                );
+                let now = Instant::now();
+                for event in events {
+                    debouncer.push(event, now);
+                }
+            }
 
-                        // Mark session as seen so it's not treated as new again
-                        if is_new_session {
-                            state.mark_as_seen();
+            // Wait for file system events, debouncing bursts per path
+            let wake_at = debouncer.wake_at(Instant::now(), EVENT_TIMEOUT);
+            let stopping = tokio::select! {
+                _ = cancellation.cancelled() => true,
+                received = rx.recv() => match received {
+                    Some(Ok(event)) => {
+                        debouncer.push(event, Instant::now());
+                        false
+                    }
+                    Some(Err(error)) => {
+                        heartbeat.report(&error);
+                        if let Err(e) =
+                            log_error(PROVIDER_ID, &format!("File watcher error: {:?}", error))
+                        {
+                            eprintln!("Logging error: {}", e);
                         }
-
-                        if should_log {
-                            if is_new_session {
-                                let log_message = format!(
-                                    "🆕 New Claude Code session detected: {}",
-                                    file_event.session_id
-                                );
-                                if let Err(e) = log_info(PROVIDER_ID, &log_message) {
-                                    eprintln!("Logging error: {}", e);
-                                }
-                            } else {
-                                // Log session updates at info level
-                                let log_message = format!(
-                                    "📝 Claude Code session changed: {} (size: {} bytes)",
-                                    file_event.session_id, file_event.file_size
-                                );
-                                if let Err(e) = log_info(PROVIDER_ID, &log_message) {
-                                    eprintln!("Logging error: {}", e);
-                                }
-                            }
+                        false
+                    }
+                    None => {
+                        if let Err(e) = log_error(PROVIDER_ID, "File watcher channel disconnected") {
+                            eprintln!("Logging error: {}", e);
                         }
+                        true
                     }
+                },
+                _ = tokio::time::sleep_until(wake_at) => false,
+            };
+
+            // Stopping flushes whatever is still being debounced
+            let events = if stopping {
+                debouncer.drain()
+            } else {
+                debouncer.ready(Instant::now())
+            };
+            for event in events {
+                let is_root_event = watch_set
+                    .lock()
+                    .map(|set| set.is_root_event(&event))
+                    .unwrap_or(false);
+                if is_root_event {
+                    Self::resync_watch_set(&watcher, &watch_set, &mut debouncer);
                 }
-                Ok(Err(error)) => {
-                    if let Err(e) =
-                        log_error(PROVIDER_ID, &format!("File watcher error: {:?}", error))
-                    {
-                        eprintln!("Logging error: {}", e);
+
+                let projects_path = projects_path.clone();
+                let file_event =
+                    run_blocking(move || Self::process_file_event(&event, &projects_path)).await;
+                if let Some(file_event) = file_event {
+                    // Check if this is a new session (before get_or_create)
+                    let is_new_session = !session_states.contains(&file_event.session_id);
+
+                    // Get or create session state
+                    let state =
+                        session_states.get_or_create(&file_event.session_id, file_event.file_size);
+                    let should_log = state.should_log(
+                        file_event.file_size,
+                        MIN_SIZE_CHANGE_BYTES,
+                        is_new_session,
+                    );
+
+                    // Publish SessionChanged event to event bus
+                    // DatabaseEventHandler will call db_helpers which does smart insert-or-update
+                    let payload = SessionEventPayload::SessionChanged {
+                        session_id: file_event.session_id.clone(),
+                        project_name: file_event.project_name.clone(),
+                        file_path: file_event.path.clone(),
+                        file_size: file_event.file_size,
+                    };
+
+                    if let Err(e) = event_bus.publish(PROVIDER_ID, payload) {
+                        if let Err(log_err) = log_error(
+                            PROVIDER_ID,
+                            &format!("Failed to publish session event: {}", e),
+                        ) {
+                            eprintln!("Logging error: {}", log_err);
+                        }
                     }
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // Timeout is normal, continue to check pending files
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    if let Err(e) = log_error(PROVIDER_ID, "File watcher channel disconnected") {
-                        eprintln!("Logging error: {}", e);
+
+                    // Update session state immediately to prevent duplicate events
+                    state.update(file_event.file_size);
+                    lifecycle.record_activity(&file_event.session_id, &file_event.path);
+
+                    // Mark session as seen so it's not treated as new again
+                    if is_new_session {
+                        state.mark_as_seen();
+                    }
+
+                    if should_log {
+                        if is_new_session {
+                            let log_message = format!(
+                                "🆕 New Claude Code session detected: {}",
+                                file_event.session_id
+                            );
+                            if let Err(e) = log_info(PROVIDER_ID, &log_message) {
+                                eprintln!("Logging error: {}", e);
+                            }
+                        } else {
+                            // Log session updates at info level
+                            let log_message = format!(
+                                "📝 Claude Code session changed: {} (size: {} bytes)",
+                                file_event.session_id, file_event.file_size
+                            );
+                            if let Err(e) = log_info(PROVIDER_ID, &log_message) {
+                                eprintln!("Logging error: {}", e);
+                            }
+                        }
                     }
-                    break;
                 }
             }
+
+            if stopping {
+                break;
+            }
         }
 
         if let Err(e) = log_info(PROVIDER_ID, "🛑 Claude Code file monitoring stopped") {
@@ -285,6 +327,45 @@ impl ClaudeWatcher {
         }
     }
 
+    /// Update project subscriptions from the current provider config
+    ///
+    /// Files already inside newly watched projects are queued too; they were
+    /// written before the watch existed and would otherwise wait for their next write.
+    fn resync_watch_set(
+        watcher: &Arc<Mutex<FsWatcher>>,
+        watch_set: &Arc<Mutex<WatchSet>>,
+        debouncer: &mut Debouncer,
+    ) {
+        // Last validated config, so a broken edit never empties the watch set
+        let config = match crate::config_service::provider_config(PROVIDER_ID) {
+            Ok(config) => config,
+            Err(e) => {
+                let _ = log_warn(PROVIDER_ID, &e);
+                return;
+            }
+        };
+
+        if let (Ok(mut watcher), Ok(mut set)) = (watcher.lock(), watch_set.lock()) {
+            let changes = set.sync(&mut *watcher, &config);
+            if !changes.is_empty() {
+                let _ = log_info(
+                    PROVIDER_ID,
+                    &format!(
+                        "📁 Watch set updated: +{} -{} ({} projects)",
+                        changes.added.len(),
+                        changes.removed.len(),
+                        set.len()
+                    ),
+                );
+            }
+
+            let now = Instant::now();
+            for event in set.file_events(&changes.added) {
+                debouncer.push(event, now);
+            }
+        }
+    }
+
     fn convert_to_canonical_file(
         claude_file: &Path,
         session_id: &str,
@@ -387,15 +468,25 @@ impl ClaudeWatcher {
         if let Ok(mut running) = self.is_running.lock() {
             *running = false;
         }
+        self.task.stop();
 
         if let Err(e) = log_info(PROVIDER_ID, "🛑 Stopping Claude Code file monitoring") {
             eprintln!("Logging error: {}", e);
         }
     }
 
+    pub fn task(&self) -> &WatchTask {
+        &self.task
+    }
+
+    pub fn heartbeat(&self) -> &WatcherHeartbeat {
+        &self.heartbeat
+    }
+
     pub fn get_status(&self) -> WatcherStatus {
+        // A panicked task never clears the flag, so ask the heartbeat too
         let is_running = if let Ok(running) = self.is_running.lock() {
-            *running
+            *running && self.heartbeat.is_alive()
         } else {
             false
         };
@@ -407,6 +498,12 @@ impl ClaudeWatcher {
             pending_uploads: upload_status.pending,
             processing_uploads: upload_status.processing,
             failed_uploads: upload_status.failed,
+            watched_paths: self
+                .watch_set
+                .lock()
+                .map(|set| set.watched_paths())
+                .unwrap_or_default(),
+            unsupported_formats: format_version::unsupported_formats(PROVIDER_ID),
         }
     }
 }
@@ -435,27 +532,6 @@ mod tests {
         assert_eq!(project_name, Some("my-project".to_string()));
     }
 
-    #[test]
-    fn test_discover_all_projects() {
-        let temp_dir = tempdir().unwrap();
-        let projects_path = temp_dir.path();
-
-        // Create some project directories
-        fs::create_dir_all(projects_path.join("project1")).unwrap();
-        fs::create_dir_all(projects_path.join("project2")).unwrap();
-        fs::create_dir_all(projects_path.join("project3")).unwrap();
-
-        // Create a file (should be ignored)
-        fs::write(projects_path.join("not_a_project.txt"), "content").unwrap();
-
-        let projects = ClaudeWatcher::discover_all_projects(projects_path).unwrap();
-
-        assert_eq!(projects.len(), 3);
-        assert!(projects.contains(&"project1".to_string()));
-        assert!(projects.contains(&"project2".to_string()));
-        assert!(projects.contains(&"project3".to_string()));
-    }
-
     #[test]
     fn test_process_file_event_skips_hidden_files() {
         use notify::event::{CreateKind, ModifyKind};
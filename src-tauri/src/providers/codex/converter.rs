@@ -1,19 +1,22 @@
 use crate::providers::canonical::{
-    converter::ToCanonical, CanonicalMessage, ContentBlock, ContentValue, MessageContent,
-    MessageType, TokenUsage,
+    converter::{FromCanonical, ToCanonical},
+    fidelity::ConversionStats,
+    CanonicalMessage, ContentBlock, ContentValue, MessageContent, MessageType, TokenUsage,
+    CANONICAL_VERSION,
 };
+use crate::providers::common::format_version::{FormatSpec, FormatVersionRange, NewerVersions};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashSet;
 
 /// Codex JSONL message format
 ///
 /// Codex messages have a consistent wrapper with timestamp, type, and payload.
 /// The payload structure varies based on type.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone)]
 pub struct CodexMessage {
     pub timestamp: String,
-    #[serde(rename = "type")]
     pub message_type: String,
     pub payload: CodexPayload,
 }
@@ -70,6 +73,28 @@ impl<'de> Deserialize<'de> for CodexMessage {
     }
 }
 
+// Custom serializer mirroring the deserializer: the payload is written untagged,
+// with its variant carried by the top-level "type" field
+impl Serialize for CodexMessage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("CodexMessage", 3)?;
+        state.serialize_field("timestamp", &self.timestamp)?;
+        state.serialize_field("type", &self.message_type)?;
+        match &self.payload {
+            CodexPayload::SessionMeta(payload) => state.serialize_field("payload", payload)?,
+            CodexPayload::ResponseItem(payload) => state.serialize_field("payload", payload)?,
+            CodexPayload::EventMsg(payload) => state.serialize_field("payload", payload)?,
+            CodexPayload::TurnContext(payload) => state.serialize_field("payload", payload)?,
+        }
+        state.end()
+    }
+}
+
 /// Codex payload wrapper
 ///
 /// The payload structure is polymorphic based on parent message_type
@@ -93,6 +118,9 @@ pub struct SessionMetaPayload {
     pub originator: String,
     pub cli_version: Option<String>,
     pub git: Option<GitInfo>,
+    /// Who started the session; sub-agents carry their parent thread here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Value>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -176,6 +204,7 @@ impl ToCanonical for CodexMessage {
             CodexPayload::SessionMeta(_) => {
                 // Session meta becomes a meta message
                 Ok(Some(CanonicalMessage {
+                    canonical_version: CANONICAL_VERSION.to_string(),
                     uuid,
                     timestamp: self.timestamp.clone(),
                     message_type: MessageType::Meta,
@@ -202,6 +231,7 @@ impl ToCanonical for CodexMessage {
             CodexPayload::TurnContext(_) => {
                 // Turn context is metadata - preserve full payload
                 Ok(Some(CanonicalMessage {
+                    canonical_version: CANONICAL_VERSION.to_string(),
                     uuid,
                     timestamp: self.timestamp.clone(),
                     message_type: MessageType::Meta,
@@ -275,6 +305,7 @@ impl CodexMessage {
                 };
 
                 Ok(Some(CanonicalMessage {
+                    canonical_version: CANONICAL_VERSION.to_string(),
                     uuid: uuid.to_string(),
                     timestamp: self.timestamp.clone(),
                     message_type: if role == "user" {
@@ -326,6 +357,7 @@ impl CodexMessage {
                 };
 
                 Ok(Some(CanonicalMessage {
+                    canonical_version: CANONICAL_VERSION.to_string(),
                     uuid: uuid.to_string(),
                     timestamp: self.timestamp.clone(),
                     message_type: MessageType::Assistant,
@@ -378,6 +410,7 @@ impl CodexMessage {
                 };
 
                 Ok(Some(CanonicalMessage {
+                    canonical_version: CANONICAL_VERSION.to_string(),
                     uuid: uuid.to_string(),
                     timestamp: self.timestamp.clone(),
                     message_type: MessageType::User,  // Tool results are USER messages
@@ -420,6 +453,7 @@ impl CodexMessage {
                 };
 
                 Ok(Some(CanonicalMessage {
+                    canonical_version: CANONICAL_VERSION.to_string(),
                     uuid: uuid.to_string(),
                     timestamp: self.timestamp.clone(),
                     message_type: MessageType::Assistant,
@@ -449,6 +483,7 @@ impl CodexMessage {
             _ => {
                 // Unknown type, preserve full payload for future analysis
                 Ok(Some(CanonicalMessage {
+                    canonical_version: CANONICAL_VERSION.to_string(),
                     uuid: uuid.to_string(),
                     timestamp: self.timestamp.clone(),
                     message_type: MessageType::Assistant,
@@ -505,6 +540,7 @@ impl CodexMessage {
                 };
 
                 Ok(Some(CanonicalMessage {
+                    canonical_version: CANONICAL_VERSION.to_string(),
                     uuid: uuid.to_string(),
                     timestamp: self.timestamp.clone(),
                     message_type: MessageType::Meta,
@@ -549,6 +585,7 @@ impl CodexMessage {
             _ => {
                 // Unknown event type - preserve type for debugging
                 Ok(Some(CanonicalMessage {
+                    canonical_version: CANONICAL_VERSION.to_string(),
                     uuid: uuid.to_string(),
                     timestamp: self.timestamp.clone(),
                     message_type: MessageType::Meta,
@@ -580,6 +617,324 @@ impl CodexMessage {
     }
 }
 
+impl FromCanonical for CodexMessage {
+    fn from_canonical(message: &CanonicalMessage) -> Result<Vec<Self>> {
+        let metadata = message.provider_metadata.as_ref();
+
+        // Session meta and turn context keep their full payload in provider_metadata
+        if let Some(payload) =
+            metadata.and_then(|m| serde_json::from_value::<CodexPayload>(m.clone()).ok())
+        {
+            return Ok(vec![CodexMessage::from_payload(
+                &message.timestamp,
+                payload,
+            )]);
+        }
+
+        let codex_type = metadata.and_then(|m| m["codex_type"].as_str());
+
+        if codex_type == Some("event_msg") {
+            let event_type = metadata
+                .and_then(|m| m["event_type"].as_str())
+                .unwrap_or_default();
+            return Ok(vec![Self::event_msg_from_canonical(message, event_type)]);
+        }
+
+        if codex_type == Some("response_item") {
+            let item_type = metadata
+                .and_then(|m| m["item_type"].as_str())
+                .unwrap_or_default();
+
+            if item_type == "reasoning" {
+                let text = match &message.message.content {
+                    ContentValue::Text(text) => text.clone(),
+                    ContentValue::Structured(_) => String::new(),
+                };
+                return Ok(vec![Self::reasoning_item(&message.timestamp, &text)]);
+            }
+
+            if !matches!(
+                item_type,
+                "message" | "function_call" | "function_call_output"
+            ) {
+                return Ok(vec![Self::response_item(
+                    &message.timestamp,
+                    item_type,
+                    serde_json::json!({}),
+                )]);
+            }
+        }
+
+        // Meta messages from other providers have no Codex equivalent
+        if message.message_type == MessageType::Meta {
+            return Ok(Vec::new());
+        }
+
+        let role = message.message.role.as_str();
+        let blocks = match &message.message.content {
+            ContentValue::Text(text) => {
+                return Ok(vec![Self::message_item(&message.timestamp, role, text)]);
+            }
+            ContentValue::Structured(blocks) => blocks,
+        };
+
+        let mut records = Vec::new();
+        for block in blocks {
+            let record = match block {
+                ContentBlock::Text { text } => Self::message_item(&message.timestamp, role, text),
+                ContentBlock::Thinking { thinking } => {
+                    Self::reasoning_item(&message.timestamp, thinking)
+                }
+                ContentBlock::ToolUse { id, name, input } => Self::response_item(
+                    &message.timestamp,
+                    "function_call",
+                    serde_json::json!({
+                        "name": name,
+                        "arguments": serde_json::to_string(input)?,
+                        "call_id": id,
+                    }),
+                ),
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    ..
+                } => Self::response_item(
+                    &message.timestamp,
+                    "function_call_output",
+                    serde_json::json!({
+                        "call_id": tool_use_id,
+                        "output": content,
+                    }),
+                ),
+            };
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+}
+
+impl CodexMessage {
+    fn from_payload(timestamp: &str, payload: CodexPayload) -> Self {
+        let message_type = match &payload {
+            CodexPayload::SessionMeta(_) => "session_meta",
+            CodexPayload::ResponseItem(_) => "response_item",
+            CodexPayload::EventMsg(_) => "event_msg",
+            CodexPayload::TurnContext(_) => "turn_context",
+        };
+
+        CodexMessage {
+            timestamp: timestamp.to_string(),
+            message_type: message_type.to_string(),
+            payload,
+        }
+    }
+
+    fn response_item(timestamp: &str, item_type: &str, data: Value) -> Self {
+        Self::from_payload(
+            timestamp,
+            CodexPayload::ResponseItem(ResponseItemPayload {
+                item_type: item_type.to_string(),
+                data,
+            }),
+        )
+    }
+
+    fn message_item(timestamp: &str, role: &str, text: &str) -> Self {
+        let content_type = if role == "user" {
+            "input_text"
+        } else {
+            "output_text"
+        };
+
+        Self::response_item(
+            timestamp,
+            "message",
+            serde_json::json!({
+                "role": role,
+                "content": [{"type": content_type, "text": text}],
+            }),
+        )
+    }
+
+    fn reasoning_item(timestamp: &str, text: &str) -> Self {
+        let summary = if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![serde_json::json!({"type": "summary_text", "text": text})]
+        };
+
+        Self::response_item(
+            timestamp,
+            "reasoning",
+            serde_json::json!({ "summary": summary }),
+        )
+    }
+
+    fn event_msg_from_canonical(message: &CanonicalMessage, event_type: &str) -> Self {
+        let data = if event_type == "token_count" {
+            let info = message.message.usage.as_ref().map(|usage| {
+                serde_json::json!({
+                    "last_token_usage": {
+                        "input_tokens": usage.input_tokens,
+                        "output_tokens": usage.output_tokens,
+                        "cached_input_tokens": usage.cache_read_input_tokens,
+                    }
+                })
+            });
+            serde_json::json!({ "info": info })
+        } else {
+            serde_json::json!({})
+        };
+
+        Self::from_payload(
+            &message.timestamp,
+            CodexPayload::EventMsg(EventMsgPayload {
+                event_type: event_type.to_string(),
+                data,
+            }),
+        )
+    }
+}
+
+/// Record types the `CodexMessage` deserializer understands
+const KNOWN_RECORD_TYPES: &[&str] = &["session_meta", "response_item", "event_msg", "turn_context"];
+
+/// Keys of the record wrapper (payloads vary too much by type to check)
+const KNOWN_KEYS: &[&str] = &["timestamp", "type", "payload"];
+
+/// Codex rollout formats this converter can decode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CodexFormat {
+    /// `{timestamp, type, payload}` records (see `KNOWN_RECORD_TYPES`)
+    Rollout,
+}
+
+/// Versioned by `session_meta.cli_version`
+pub static CODEX_FORMATS: FormatSpec<CodexFormat> = FormatSpec {
+    provider_id: "codex",
+    version_field: "session_meta.cli_version",
+    // Source builds of the CLI report 0.0.0
+    ranges: &[FormatVersionRange {
+        min: "0.0.0",
+        max: Some("1.0.0"),
+        decoder: CodexFormat::Rollout,
+    }],
+    // Early rollouts have a session_meta without cli_version
+    unversioned: Some(CodexFormat::Rollout),
+    // The CLI's release number, not a rollout schema version
+    newer: NewerVersions::Warn,
+};
+
+/// Convert a Codex session file with the decoder for its CLI version
+///
+/// Fails without converting anything if `CODEX_FORMATS` refuses the version.
+pub fn convert_session(
+    lines: &[&str],
+    session_id: &str,
+) -> std::result::Result<(Vec<CanonicalMessage>, ConversionStats), String> {
+    // session_meta is the first record of a rollout
+    let version = lines
+        .iter()
+        .find_map(|line| serde_json::from_str::<CodexMessage>(line).ok())
+        .and_then(|message| message.get_version());
+
+    match CODEX_FORMATS.resolve(session_id, version.as_deref())? {
+        CodexFormat::Rollout => Ok(convert_session_lines(lines.iter().copied(), session_id)),
+    }
+}
+
+/// Convert the lines of a Codex session file with fidelity stats
+///
+/// Every message gets `session_id`, since only `session_meta` carries it.
+/// Records written in the same millisecond would share a uuid, so repeats are
+/// re-derived from their line number (stable while the rollout is appended to).
+/// Lines that fail to parse or convert are logged and counted, not fatal.
+pub fn convert_session_lines<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+    session_id: &str,
+) -> (Vec<CanonicalMessage>, ConversionStats) {
+    let mut messages = Vec::new();
+    let mut stats = ConversionStats::new();
+    let mut seen_uuids = HashSet::new();
+
+    for (line_num, line) in lines.into_iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let raw: Option<Value> = serde_json::from_str(line).ok();
+        if let Some(ref raw) = raw {
+            stats.observe_keys(raw, "", KNOWN_KEYS);
+        }
+        let field = |path: &[&str]| {
+            raw.as_ref()
+                .and_then(|raw| path.iter().try_fold(raw, |value, key| value.get(key)))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+
+        let error = match serde_json::from_str::<CodexMessage>(line) {
+            Ok(codex_msg) => match codex_msg.to_canonical() {
+                Ok(Some(mut canonical_msg)) => {
+                    canonical_msg.session_id = session_id.to_string();
+                    if !seen_uuids.insert(canonical_msg.uuid.clone()) {
+                        canonical_msg.uuid = generate_uuid_from_codex(
+                            &canonical_msg.timestamp,
+                            &format!("{}:{}", session_id, line_num),
+                        );
+                        seen_uuids.insert(canonical_msg.uuid.clone());
+                    }
+                    messages.push(canonical_msg);
+                    stats.record_converted(1);
+                    stats.trace(line_num, 1, None);
+                    continue;
+                }
+                Ok(None) => {
+                    // e.g. token_count events and duplicated event_msg records
+                    let reason = match (field(&["type"]), field(&["payload", "type"])) {
+                        (Some(record_type), Some(payload_type)) => {
+                            format!("{}:{}", record_type, payload_type)
+                        }
+                        (record_type, _) => record_type.unwrap_or_else(|| "unknown".to_string()),
+                    };
+                    stats.record_skipped(&reason);
+                    stats.trace(line_num, 0, None);
+                    continue;
+                }
+                Err(e) => {
+                    stats.record_failed();
+                    format!(
+                        "Failed to convert Codex message at line {}: {}",
+                        line_num + 1,
+                        e
+                    )
+                }
+            },
+            Err(e) => {
+                stats.record_failed();
+                if let Some(record_type) = field(&["type"]) {
+                    if !KNOWN_RECORD_TYPES.contains(&record_type.as_str()) {
+                        stats.note_unknown_event_type(&record_type);
+                    }
+                }
+                format!(
+                    "Failed to parse Codex message at line {}: {}",
+                    line_num + 1,
+                    e
+                )
+            }
+        };
+        stats.trace(line_num, 0, Some(error.clone()));
+
+        if let Err(log_err) = crate::logging::log_error("codex", &error) {
+            eprintln!("Logging error: {}", log_err);
+        }
+    }
+
+    (messages, stats)
+}
+
 /// Generate a deterministic UUID from Codex timestamp and session ID
 fn generate_uuid_from_codex(timestamp: &str, session_id: &str) -> String {
     use std::collections::hash_map::DefaultHasher;
@@ -686,6 +1041,73 @@ mod tests {
         }
     }
 
+    /// Rollout covering every payload kind the converter handles
+    const ROLLOUT_FIXTURE: &str = r#"{"timestamp":"2025-10-20T06:46:43.215Z","type":"session_meta","payload":{"id":"019a005e-c8fc-7512-8e78-c2322cbf0875","timestamp":"2025-10-20T06:46:43.196Z","cwd":"/Users/cliftonc/work/guideai","originator":"codex_cli_rs","cli_version":"0.45.0","git":{"commit_hash":"77a017","branch":"main","repository_url":"git@github.com:guideai-dev/guideai.git"}}}
+{"timestamp":"2025-10-20T06:46:43.300Z","type":"turn_context","payload":{"cwd":"/Users/cliftonc/work/guideai","model":"gpt-5-codex","approval_policy":"on-request"}}
+{"timestamp":"2025-10-20T06:46:47.990Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Can you review the claude.md?"}]}}
+{"timestamp":"2025-10-20T06:46:47.991Z","type":"event_msg","payload":{"type":"user_message","message":"Can you review the claude.md?"}}
+{"timestamp":"2025-10-20T06:46:50.100Z","type":"response_item","payload":{"type":"reasoning","summary":[{"type":"summary_text","text":"Reading the file first"}]}}
+{"timestamp":"2025-10-20T06:46:51.694Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"bash\",\"-lc\",\"ls\"]}","call_id":"call_XhFBtWxG4rvlC5GE7r6MIwDO"}}
+{"timestamp":"2025-10-20T06:46:52.010Z","type":"response_item","payload":{"type":"function_call_output","call_id":"call_XhFBtWxG4rvlC5GE7r6MIwDO","output":"CLAUDE.md\nsrc"}}
+{"timestamp":"2025-10-20T06:46:53.500Z","type":"event_msg","payload":{"type":"token_count","info":{"last_token_usage":{"input_tokens":1200,"output_tokens":80,"cached_input_tokens":1024}}}}
+{"timestamp":"2025-10-20T06:46:55.000Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"The file looks good."}]}}"#;
+
+    #[test]
+    fn test_round_trip_is_stable() {
+        use crate::providers::canonical::converter::round_trip;
+
+        let records: Vec<CodexMessage> = ROLLOUT_FIXTURE
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        let (first, second) = round_trip(&records).unwrap();
+
+        // user_message event is skipped as a duplicate of the response_item
+        assert_eq!(first.len(), records.len() - 1);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_serialize_matches_rollout_format() {
+        let line = ROLLOUT_FIXTURE.lines().nth(5).unwrap();
+        let msg: CodexMessage = serde_json::from_str(line).unwrap();
+
+        let original: Value = serde_json::from_str(line).unwrap();
+        assert_eq!(serde_json::to_value(&msg).unwrap(), original);
+    }
+
+    #[test]
+    fn test_from_canonical_splits_content_blocks() {
+        let canonical = CanonicalMessage::new_structured_message(
+            "uuid-1".to_string(),
+            "2025-01-01T00:00:00.000Z".to_string(),
+            MessageType::Assistant,
+            "session-1".to_string(),
+            "claude-code".to_string(),
+            "assistant".to_string(),
+            vec![
+                ContentBlock::Text {
+                    text: "Let me check".to_string(),
+                },
+                ContentBlock::ToolUse {
+                    id: "toolu_1".to_string(),
+                    name: "Bash".to_string(),
+                    input: serde_json::json!({"command": "ls"}),
+                },
+            ],
+        );
+
+        let records = CodexMessage::from_canonical(&canonical).unwrap();
+        assert_eq!(records.len(), 2);
+
+        let call = serde_json::to_value(&records[1]).unwrap();
+        assert_eq!(call["type"], "response_item");
+        assert_eq!(call["payload"]["type"], "function_call");
+        assert_eq!(call["payload"]["call_id"], "toolu_1");
+        assert_eq!(call["payload"]["arguments"], "{\"command\":\"ls\"}");
+    }
+
     #[test]
     fn test_uuid_generation() {
         let uuid1 = generate_uuid_from_codex("2025-01-01T00:00:00.000Z", "session-1");
@@ -698,4 +1120,65 @@ mod tests {
         // Different inputs should generate different UUIDs
         assert_ne!(uuid1, uuid3);
     }
+
+    #[test]
+    fn test_same_millisecond_records_get_distinct_uuids() {
+        let lines = [
+            r#"{"timestamp":"2025-10-20T06:46:51.694Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{}","call_id":"call_1"}}"#,
+            r#"{"timestamp":"2025-10-20T06:46:51.694Z","type":"response_item","payload":{"type":"function_call_output","call_id":"call_1","output":"ok"}}"#,
+        ];
+
+        let (messages, _) = convert_session_lines(lines, "session-1");
+        let (again, _) = convert_session_lines(lines, "session-1");
+
+        assert_eq!(messages.len(), 2);
+        assert_ne!(messages[0].uuid, messages[1].uuid);
+        assert_eq!(messages[1].uuid, again[1].uuid);
+    }
+
+    #[test]
+    fn test_convert_session_lines_counts_every_record() {
+        let lines = [
+            r#"{"timestamp":"2025-10-20T06:46:47.990Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"hi"}]},"trace_id":"t1"}"#,
+            r#"{"timestamp":"2025-10-20T06:46:48.000Z","type":"compaction_marker","payload":{}}"#,
+            "not json",
+            "",
+        ];
+
+        let (messages, stats) = convert_session_lines(lines, "session-1");
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].session_id, "session-1");
+        assert_eq!(stats.records_seen, 3);
+        assert_eq!(stats.converted, 1);
+        assert_eq!(stats.failed, 2);
+        assert_eq!(stats.unknown_event_types.get("compaction_marker"), Some(&1));
+        assert_eq!(stats.unknown_keys.get("trace_id"), Some(&1));
+    }
+
+    #[test]
+    fn test_codex_formats_cover_dev_builds() {
+        // 1.0.0 is past the known range but only warns
+        for version in ["0.0.0", "0.1.2504301751", "0.45.0", "1.0.0"] {
+            assert_eq!(
+                CODEX_FORMATS.resolve("codex-format-test", Some(version)),
+                Ok(CodexFormat::Rollout),
+                "{} should be supported",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn test_convert_session_accepts_newer_cli_versions() {
+        let supported: Vec<&str> = ROLLOUT_FIXTURE.lines().collect();
+        let (messages, _) = convert_session(&supported, "supported-session").unwrap();
+        assert!(!messages.is_empty());
+
+        let future =
+            ROLLOUT_FIXTURE.replace("\"cli_version\":\"0.45.0\"", "\"cli_version\":\"1.2.0\"");
+        let future: Vec<&str> = future.lines().collect();
+        let (future_messages, _) = convert_session(&future, "future-session").unwrap();
+        assert_eq!(future_messages.len(), messages.len());
+    }
 }
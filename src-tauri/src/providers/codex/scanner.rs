@@ -88,9 +88,8 @@ fn parse_codex_session(
     file_path: &Path,
     selected_projects: Option<&[String]>,
 ) -> Result<Option<SessionInfo>, String> {
-    use super::super::canonical::converter::ToCanonical;
     use super::super::common::{extract_cwd_from_canonical_content, get_canonical_path};
-    use super::CodexMessage;
+    use super::converter::convert_session;
 
     let content =
         fs::read_to_string(file_path).map_err(|e| format!("Failed to read file: {}", e))?;
@@ -131,29 +130,11 @@ fn parse_codex_session(
     // Convert Codex JSONL to canonical format - simple 1-to-1 conversion
     // The watcher uses MessageAggregator for real-time processing, but the scanner
     // reads complete files that are already in final form, so just convert directly
-    let mut canonical_lines = Vec::new();
-
-    for line in lines.iter() {
-        if let Ok(codex_msg) = serde_json::from_str::<CodexMessage>(line) {
-            match codex_msg.to_canonical() {
-                Ok(Some(mut canonical_msg)) => {
-                    // Fix session_id for all messages (not just session_meta)
-                    canonical_msg.session_id = session_id.clone();
-
-                    if let Ok(serialized) = serde_json::to_string(&canonical_msg) {
-                        canonical_lines.push(serialized);
-                    }
-                }
-                Ok(None) => {
-                    // Message was skipped (e.g., duplicate event_msg)
-                }
-                Err(e) => {
-                    // Log error but continue processing
-                    eprintln!("Failed to convert Codex message: {}", e);
-                }
-            }
-        }
-    }
+    let (messages, stats) = convert_session(&lines, &session_id)?;
+    let canonical_lines: Vec<String> = messages
+        .iter()
+        .filter_map(|msg| serde_json::to_string(msg).ok())
+        .collect();
 
     let canonical_content = canonical_lines.join("\n");
 
@@ -167,6 +148,7 @@ fn parse_codex_session(
     // Write canonical JSONL to project-organized path
     fs::write(&cache_path, &canonical_content)
         .map_err(|e| format!("Failed to write canonical JSONL: {}", e))?;
+    stats.persist("codex", &session_id);
 
     // Parse session timing from first and last lines
     let session_start_time = first_entry
@@ -1,20 +1,22 @@
 use crate::config::load_provider_config;
+use crate::config_service::ProviderConfigUpdates;
 use crate::events::{EventBus, SessionEventPayload};
 use crate::logging::{log_error, log_info};
-use crate::providers::canonical::converter::ToCanonical;
-use crate::providers::codex::converter::CodexMessage;
+use crate::providers::codex::converter::convert_session;
 use crate::providers::common::{
-    extract_cwd_from_canonical_content, get_canonical_path, get_file_size, has_extension,
-    should_skip_file, SessionStateManager, WatcherStatus, EVENT_TIMEOUT, FILE_WATCH_POLL_INTERVAL,
-    MIN_SIZE_CHANGE_BYTES,
+    extract_cwd_from_canonical_content, format_version, fs_event_channel, get_canonical_path,
+    get_file_size, has_extension, run_blocking, should_skip_file, Debouncer, FsEventReceiver,
+    SessionLifecycle, SessionStateManager, WatchCancellation, WatchTask, WatcherHeartbeat,
+    WatcherStatus, EVENT_TIMEOUT, FILE_WATCH_POLL_INTERVAL, MIN_SIZE_CHANGE_BYTES,
 };
 use crate::upload_queue::UploadQueue;
 use notify::{Config, Event, EventKind, PollWatcher, RecursiveMode, Watcher};
 use shellexpand::tilde;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{mpsc, Arc, Mutex};
-use std::thread;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 const PROVIDER_ID: &str = "codex";
 
@@ -29,9 +31,10 @@ pub struct FileChangeEvent {
 #[derive(Debug)]
 pub struct CodexWatcher {
     _watcher: PollWatcher,
-    _thread_handle: thread::JoinHandle<()>,
+    task: WatchTask,
     upload_queue: Arc<UploadQueue>,
     is_running: Arc<Mutex<bool>>,
+    heartbeat: WatcherHeartbeat,
 }
 
 impl CodexWatcher {
@@ -39,6 +42,7 @@ impl CodexWatcher {
         _projects: Vec<String>,
         upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
+        shutdown: CancellationToken,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         if let Err(e) = log_info(PROVIDER_ID, "🔍 Starting Codex file monitoring") {
             eprintln!("Logging error: {}", e);
@@ -84,7 +88,7 @@ impl CodexWatcher {
         }
 
         // Create file system event channel
-        let (tx, rx) = mpsc::channel();
+        let (tx, rx) = fs_event_channel(PROVIDER_ID);
 
         // Create the file watcher with aggressive polling
         // Note: We use PollWatcher instead of RecommendedWatcher because Codex keeps
@@ -140,28 +144,36 @@ impl CodexWatcher {
             eprintln!("Logging error: {}", e);
         }
 
+        // Always polling, so it never trips the OS watch limit
+        let heartbeat = WatcherHeartbeat::new(PROVIDER_ID, &sessions_path, true);
         let is_running = Arc::new(Mutex::new(true));
         let is_running_clone = Arc::clone(&is_running);
         let upload_queue_clone = Arc::clone(&upload_queue);
         let sessions_path_clone = sessions_path.clone();
         let event_bus_clone = event_bus.clone();
+        let heartbeat_clone = heartbeat.clone();
 
-        // Start background thread to handle file events
-        let thread_handle = thread::spawn(move || {
+        // Start background task to handle file events
+        let task = WatchTask::spawn(&shutdown, move |cancellation| async move {
+            let _exit_guard = heartbeat_clone.exit_guard();
             Self::file_event_processor(
                 rx,
                 sessions_path_clone,
                 upload_queue_clone,
                 event_bus_clone,
                 is_running_clone,
-            );
+                heartbeat_clone,
+                cancellation,
+            )
+            .await;
         });
 
         Ok(CodexWatcher {
             _watcher: watcher,
-            _thread_handle: thread_handle,
+            task,
             upload_queue,
             is_running,
+            heartbeat,
         })
     }
 
@@ -235,58 +247,12 @@ impl CodexWatcher {
         // Read original Codex JSONL
         let content = fs::read_to_string(codex_file)?;
 
-        let mut canonical_lines = Vec::new();
-
         // Parse and convert each line independently (no aggregation needed)
-        for (line_num, line) in content.lines().enumerate() {
-            if line.trim().is_empty() {
-                continue;
-            }
-
-            match serde_json::from_str::<CodexMessage>(line) {
-                Ok(codex_msg) => {
-                    // Convert to canonical format
-                    match codex_msg.to_canonical() {
-                        Ok(Some(mut canonical_msg)) => {
-                            // Update session_id from the known session ID (from filename)
-                            // This ensures all messages have the correct session_id,
-                            // not just the session_meta message
-                            canonical_msg.session_id = session_id.to_string();
-
-                            canonical_lines.push(serde_json::to_string(&canonical_msg)?);
-                        }
-                        Ok(None) => {
-                            // Message was skipped (e.g., duplicate event_msg)
-                        }
-                        Err(e) => {
-                            if let Err(log_err) = log_error(
-                                PROVIDER_ID,
-                                &format!(
-                                    "Failed to convert Codex message at line {}: {}",
-                                    line_num + 1,
-                                    e
-                                ),
-                            ) {
-                                eprintln!("Logging error: {}", log_err);
-                            }
-                            // Continue processing other lines
-                        }
-                    }
-                }
-                Err(e) => {
-                    if let Err(log_err) = log_error(
-                        PROVIDER_ID,
-                        &format!(
-                            "Failed to parse Codex message at line {}: {}",
-                            line_num + 1,
-                            e
-                        ),
-                    ) {
-                        eprintln!("Logging error: {}", log_err);
-                    }
-                    // Continue processing other lines
-                }
-            }
+        let lines: Vec<&str> = content.lines().collect();
+        let (messages, stats) = convert_session(&lines, session_id)?;
+        let mut canonical_lines = Vec::new();
+        for canonical_msg in &messages {
+            canonical_lines.push(serde_json::to_string(canonical_msg)?);
         }
 
         // Join canonical lines into content
@@ -301,132 +267,171 @@ impl CodexWatcher {
 
         // Write to project-organized path
         fs::write(&canonical_path, canonical_content)?;
+        stats.persist(PROVIDER_ID, session_id);
 
         Ok(canonical_path)
     }
 
-    fn file_event_processor(
-        rx: mpsc::Receiver<Result<Event, notify::Error>>,
+    async fn file_event_processor(
+        mut rx: FsEventReceiver,
         sessions_path: PathBuf,
         _upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
         is_running: Arc<Mutex<bool>>,
+        heartbeat: WatcherHeartbeat,
+        mut cancellation: WatchCancellation,
     ) {
         let mut session_states = SessionStateManager::new();
+        let mut lifecycle = SessionLifecycle::new(PROVIDER_ID, event_bus.clone());
+        let mut config_updates = ProviderConfigUpdates::new(PROVIDER_ID);
+        let mut debouncer = Debouncer::default();
 
         loop {
-            // Check if we should continue running
-            {
-                if let Ok(running) = is_running.lock() {
-                    if !*running {
-                        break;
-                    }
+            heartbeat.beat();
+
+            // Move quiet sessions towards idle/completed
+            for session_id in lifecycle.tick() {
+                session_states.mark_inactive(&session_id);
+            }
+
+            // Apply config edits without restarting the watcher
+            if let Some(config) = config_updates.poll() {
+                if !config.enabled {
+                    cancellation.stop_disabled(PROVIDER_ID, &is_running);
+                    continue;
                 }
+                lifecycle.set_config(&config);
             }
 
-            // Process file system events with timeout
-            match rx.recv_timeout(EVENT_TIMEOUT) {
-                Ok(Ok(event)) => {
-                    if let Some(file_event) = Self::process_file_event(&event, &sessions_path) {
-                        // Check if this is a new session (before get_or_create)
-                        let is_new_session = !session_states.contains(&file_event.session_id);
-
-                        // Get or create session state
-                        let state = session_states
-                            .get_or_create(&file_event.session_id, file_event.file_size);
-                        let should_log = state.should_log(
-                            file_event.file_size,
-                            MIN_SIZE_CHANGE_BYTES,
-                            is_new_session,
-                        );
-
-                        // Convert to canonical format
-                        match Self::convert_to_canonical_file(&file_event.path, &file_event.session_id) {
-                            Ok(canonical_path) => {
-                                // Get size of canonical file
-                                let canonical_size = get_file_size(&canonical_path).unwrap_or(0);
-
-                                // Publish SessionChanged event with CANONICAL path
-                                // DatabaseEventHandler will call db_helpers which does smart insert-or-update
-                                let payload = SessionEventPayload::SessionChanged {
-                                    session_id: file_event.session_id.clone(),
-                                    project_name: file_event.project_name.clone(),
-                                    file_path: canonical_path,
-                                    file_size: canonical_size,
-                                };
-
-                                if let Err(e) = event_bus.publish(PROVIDER_ID, payload) {
-                                    if let Err(log_err) = log_error(
-                                        PROVIDER_ID,
-                                        &format!("Failed to publish session event: {}", e),
-                                    ) {
-                                        eprintln!("Logging error: {}", log_err);
-                                    }
-                                }
-                            }
-                            Err(e) => {
+            // Wait for file system events, debouncing bursts per path
+            let wake_at = debouncer.wake_at(Instant::now(), EVENT_TIMEOUT);
+            let stopping = tokio::select! {
+                _ = cancellation.cancelled() => true,
+                received = rx.recv() => match received {
+                    Some(Ok(event)) => {
+                        debouncer.push(event, Instant::now());
+                        false
+                    }
+                    Some(Err(error)) => {
+                        heartbeat.report(&error);
+                        if let Err(e) = log_error(
+                            PROVIDER_ID,
+                            &format!("Codex file watcher error: {:?}", error),
+                        ) {
+                            eprintln!("Logging error: {}", e);
+                        }
+                        false
+                    }
+                    None => {
+                        if let Err(e) =
+                            log_error(PROVIDER_ID, "Codex file watcher channel disconnected")
+                        {
+                            eprintln!("Logging error: {}", e);
+                        }
+                        true
+                    }
+                },
+                _ = tokio::time::sleep_until(wake_at) => false,
+            };
+
+            // Stopping flushes whatever is still being debounced
+            let events = if stopping {
+                debouncer.drain()
+            } else {
+                debouncer.ready(Instant::now())
+            };
+            for event in events {
+                let sessions_path = sessions_path.clone();
+                let file_event =
+                    run_blocking(move || Self::process_file_event(&event, &sessions_path)).await;
+                if let Some(file_event) = file_event {
+                    // Check if this is a new session (before get_or_create)
+                    let is_new_session = !session_states.contains(&file_event.session_id);
+
+                    // Get or create session state
+                    let state =
+                        session_states.get_or_create(&file_event.session_id, file_event.file_size);
+                    let should_log = state.should_log(
+                        file_event.file_size,
+                        MIN_SIZE_CHANGE_BYTES,
+                        is_new_session,
+                    );
+
+                    // Convert to canonical format
+                    let (path, session_id) =
+                        (file_event.path.clone(), file_event.session_id.clone());
+                    let converted =
+                        run_blocking(move || Self::convert_to_canonical_file(&path, &session_id))
+                            .await;
+                    match converted {
+                        Ok(canonical_path) => {
+                            // Get size of canonical file
+                            let canonical_size = get_file_size(&canonical_path).unwrap_or(0);
+
+                            // Publish SessionChanged event with CANONICAL path
+                            // DatabaseEventHandler will call db_helpers which does smart insert-or-update
+                            let payload = SessionEventPayload::SessionChanged {
+                                session_id: file_event.session_id.clone(),
+                                project_name: file_event.project_name.clone(),
+                                file_path: canonical_path,
+                                file_size: canonical_size,
+                            };
+
+                            if let Err(e) = event_bus.publish(PROVIDER_ID, payload) {
                                 if let Err(log_err) = log_error(
                                     PROVIDER_ID,
-                                    &format!(
-                                        "Failed to convert session {} to canonical format: {}",
-                                        file_event.session_id, e
-                                    ),
+                                    &format!("Failed to publish session event: {}", e),
                                 ) {
                                     eprintln!("Logging error: {}", log_err);
                                 }
-                                // Continue processing - don't crash on conversion errors
                             }
                         }
+                        Err(e) => {
+                            if let Err(log_err) = log_error(
+                                PROVIDER_ID,
+                                &format!(
+                                    "Failed to convert session {} to canonical format: {}",
+                                    file_event.session_id, e
+                                ),
+                            ) {
+                                eprintln!("Logging error: {}", log_err);
+                            }
+                            // Continue processing - don't crash on conversion errors
+                        }
+                    }
 
-                        // Update session state immediately to prevent duplicate events
-                        state.update(file_event.file_size);
+                    // Update session state immediately to prevent duplicate events
+                    state.update(file_event.file_size);
+                    lifecycle.record_activity(&file_event.session_id, &file_event.path);
 
-                        // Mark session as seen so it's not treated as new again
-                        if is_new_session {
-                            state.mark_as_seen();
-                        }
+                    // Mark session as seen so it's not treated as new again
+                    if is_new_session {
+                        state.mark_as_seen();
+                    }
 
-                        if should_log {
-                            if is_new_session {
-                                let log_message = format!(
-                                    "🆕 New Codex session detected: {}",
-                                    file_event.session_id
-                                );
-                                if let Err(e) = log_info(PROVIDER_ID, &log_message) {
-                                    eprintln!("Logging error: {}", e);
-                                }
-                            } else {
-                                // Log session updates at info level
-                                let log_message = format!(
-                                    "📝 Codex session changed: {} (size: {} bytes)",
-                                    file_event.session_id, file_event.file_size
-                                );
-                                if let Err(e) = log_info(PROVIDER_ID, &log_message) {
-                                    eprintln!("Logging error: {}", e);
-                                }
+                    if should_log {
+                        if is_new_session {
+                            let log_message =
+                                format!("🆕 New Codex session detected: {}", file_event.session_id);
+                            if let Err(e) = log_info(PROVIDER_ID, &log_message) {
+                                eprintln!("Logging error: {}", e);
+                            }
+                        } else {
+                            // Log session updates at info level
+                            let log_message = format!(
+                                "📝 Codex session changed: {} (size: {} bytes)",
+                                file_event.session_id, file_event.file_size
+                            );
+                            if let Err(e) = log_info(PROVIDER_ID, &log_message) {
+                                eprintln!("Logging error: {}", e);
                             }
                         }
                     }
                 }
-                Ok(Err(error)) => {
-                    if let Err(e) = log_error(
-                        PROVIDER_ID,
-                        &format!("Codex file watcher error: {:?}", error),
-                    ) {
-                        eprintln!("Logging error: {}", e);
-                    }
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // Timeout is normal, continue watching
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    if let Err(e) =
-                        log_error(PROVIDER_ID, "Codex file watcher channel disconnected")
-                    {
-                        eprintln!("Logging error: {}", e);
-                    }
-                    break;
-                }
+            }
+
+            if stopping {
+                break;
             }
         }
 
@@ -609,15 +614,25 @@ impl CodexWatcher {
         if let Ok(mut running) = self.is_running.lock() {
             *running = false;
         }
+        self.task.stop();
 
         if let Err(e) = log_info(PROVIDER_ID, "🛑 Stopping Codex file monitoring") {
             eprintln!("Logging error: {}", e);
         }
     }
 
+    pub fn task(&self) -> &WatchTask {
+        &self.task
+    }
+
+    pub fn heartbeat(&self) -> &WatcherHeartbeat {
+        &self.heartbeat
+    }
+
     pub fn get_status(&self) -> WatcherStatus {
+        // A panicked task never clears the flag, so ask the heartbeat too
         let is_running = if let Ok(running) = self.is_running.lock() {
-            *running
+            *running && self.heartbeat.is_alive()
         } else {
             false
         };
@@ -629,6 +644,8 @@ impl CodexWatcher {
             pending_uploads: upload_status.pending,
             processing_uploads: upload_status.processing,
             failed_uploads: upload_status.failed,
+            watched_paths: Vec::new(),
+            unsupported_formats: format_version::unsupported_formats(PROVIDER_ID),
         }
     }
 }
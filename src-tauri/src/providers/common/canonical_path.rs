@@ -36,6 +36,31 @@ pub fn extract_cwd_from_canonical_content(content: &str) -> Option<String> {
     None
 }
 
+/// Extract the model used for most assistant messages in canonical JSONL content
+pub fn extract_model_from_canonical_content(content: &str) -> Option<String> {
+    let mut counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if let Some(model) = entry
+            .get("message")
+            .and_then(|m| m.get("model"))
+            .and_then(|v| v.as_str())
+            .filter(|model| !model.is_empty() && *model != "<synthetic>")
+        {
+            *counts.entry(model.to_string()).or_default() += 1;
+        }
+    }
+
+    // Ties go to the alphabetically first model so the result is stable
+    counts
+        .into_iter()
+        .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))
+        .map(|(model, _)| model)
+}
+
 /// Sanitize project name for filesystem safety
 /// Replaces spaces, slashes, and special characters with safe alternatives
 pub fn sanitize_project_name(name: &str) -> String {
@@ -136,6 +161,22 @@ mod tests {
         assert_eq!(extract_cwd_from_canonical_content(""), None);
     }
 
+    #[test]
+    fn test_extract_model_from_canonical_content() {
+        let content = r#"{"type":"user","message":{"role":"user","content":"hi"}}
+{"type":"assistant","message":{"role":"assistant","content":"a","model":"claude-sonnet-4"}}
+{"type":"assistant","message":{"role":"assistant","content":"b","model":"claude-haiku-4"}}
+{"type":"assistant","message":{"role":"assistant","content":"c","model":"claude-sonnet-4"}}
+{"type":"assistant","message":{"role":"assistant","content":"d","model":"<synthetic>"}}"#;
+        assert_eq!(
+            extract_model_from_canonical_content(content),
+            Some("claude-sonnet-4".to_string())
+        );
+
+        let content = r#"{"type":"user","message":{"role":"user","content":"hi"}}"#;
+        assert_eq!(extract_model_from_canonical_content(content), None);
+    }
+
     #[test]
     fn test_sanitize_project_name() {
         assert_eq!(sanitize_project_name("my-project"), "my-project");
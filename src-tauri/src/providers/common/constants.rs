@@ -6,3 +6,20 @@ pub const MIN_SIZE_CHANGE_BYTES: u64 = 1024; // 1KB minimum change to log
 // Polling intervals
 pub const FILE_WATCH_POLL_INTERVAL: Duration = Duration::from_secs(2);
 pub const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+pub const WATCH_SET_RESCAN_INTERVAL: Duration = Duration::from_secs(15);
+pub const GIT_SNAPSHOT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+// Watcher supervision
+pub const SUPERVISOR_TICK: Duration = Duration::from_secs(5);
+pub const HEARTBEAT_STALE_AFTER: Duration = Duration::from_secs(120);
+pub const RESTART_BACKOFF_BASE: Duration = Duration::from_secs(2);
+pub const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(300);
+pub const RESTART_STABLE_AFTER: Duration = Duration::from_secs(600);
+pub const HEALTH_HISTORY_LIMIT: usize = 200;
+
+// Async watcher tasks
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+pub const WATCH_DEBOUNCE_MAX: Duration = Duration::from_secs(2);
+pub const WATCH_DEBOUNCE_CAPACITY: usize = 1024;
+pub const WATCH_EVENT_CHANNEL_CAPACITY: usize = 4096;
+pub const WATCHER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
@@ -1,7 +1,24 @@
 use crate::database::{insert_session, update_session};
 use crate::logging::{log_debug, log_info, log_warn};
+use crate::providers::common::session_tree::build_session_tree_from_content;
+use crate::providers::common::GIT_SNAPSHOT_REFRESH_INTERVAL;
+use crate::providers::common::{read_session_file, remove_stale_archive};
 use chrono::{DateTime, Utc};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+
+/// Refresh throttle for a live session's end snapshot
+struct SnapshotThrottle {
+    last: Instant,
+    /// A trailing capture is scheduled for the end of the current interval
+    pending: bool,
+}
+
+/// Live sessions whose end snapshot was refreshed within the last interval
+static LAST_SNAPSHOT: LazyLock<Mutex<HashMap<String, SnapshotThrottle>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
 
 /// Type alias for timing data tuple returned from JSONL parsing
 type TimingResult = Result<
@@ -20,6 +37,114 @@ fn get_existing_git_data(session_id: &str) -> Option<(Option<String>, Option<Str
     }).ok()
 }
 
+/// Capture working-tree snapshots for a live session
+///
+/// The first capture records both the start and end snapshot; later captures
+/// only move the end snapshot forward, at most once per
+/// `GIT_SNAPSHOT_REFRESH_INTERVAL` since each one walks the whole working tree.
+/// Writes inside the interval schedule one trailing capture at its end, so the
+/// end snapshot always reflects the agent's last write. Captures run on the
+/// blocking pool and failures (e.g. cwd is not a git repo) never block session tracking.
+fn update_git_snapshots(provider_id: &str, session_id: &str, cwd: &str) {
+    let delay = {
+        let Ok(mut throttle) = LAST_SNAPSHOT.lock() else {
+            return;
+        };
+        // Entries past the interval no longer throttle anything
+        throttle.retain(|_, t| t.pending || t.last.elapsed() < GIT_SNAPSHOT_REFRESH_INTERVAL);
+        match throttle.get_mut(session_id) {
+            Some(t) if t.pending => return,
+            Some(t) => {
+                t.pending = true;
+                Some(GIT_SNAPSHOT_REFRESH_INTERVAL.saturating_sub(t.last.elapsed()))
+            }
+            None => {
+                throttle.insert(
+                    session_id.to_string(),
+                    SnapshotThrottle {
+                        last: Instant::now(),
+                        pending: false,
+                    },
+                );
+                None
+            }
+        }
+    };
+
+    let provider_id = provider_id.to_string();
+    let session_id = session_id.to_string();
+    let cwd = cwd.to_string();
+    match delay {
+        None => {
+            tauri::async_runtime::spawn_blocking(move || {
+                capture_git_snapshots(&provider_id, &session_id, &cwd)
+            });
+        }
+        Some(delay) => {
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(delay).await;
+                if let Ok(mut throttle) = LAST_SNAPSHOT.lock() {
+                    if let Some(t) = throttle.get_mut(&session_id) {
+                        t.last = Instant::now();
+                        t.pending = false;
+                    }
+                }
+                let _ = tauri::async_runtime::spawn_blocking(move || {
+                    capture_git_snapshots(&provider_id, &session_id, &cwd)
+                })
+                .await;
+            });
+        }
+    }
+}
+
+/// Take the start snapshot if missing, then move the end snapshot forward
+fn capture_git_snapshots(provider_id: &str, session_id: &str, cwd: &str) {
+    use crate::git_snapshot::{capture_session_snapshot, SnapshotKind};
+
+    let has_start = matches!(
+        crate::database::get_session_snapshots(session_id),
+        Ok((Some(_), _))
+    );
+
+    let kinds: &[SnapshotKind] = if has_start {
+        &[SnapshotKind::End]
+    } else {
+        &[SnapshotKind::Start, SnapshotKind::End]
+    };
+
+    for &kind in kinds {
+        match capture_session_snapshot(cwd, session_id, kind) {
+            Ok(tree_id) => {
+                if let Err(e) = crate::database::save_session_snapshot(session_id, kind, &tree_id) {
+                    let _ = log_warn(
+                        provider_id,
+                        &format!("⚠ Failed to save {} snapshot: {}", kind.as_str(), e),
+                    );
+                }
+            }
+            Err(e) => {
+                let _ = log_debug(
+                    provider_id,
+                    &format!("Skipping git snapshot for session {}: {}", session_id, e),
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// Stop refreshing a completed session's end snapshot
+///
+/// The end snapshot stays at the last capture taken while the agent was
+/// writing, so edits made during the idle window before completion are not
+/// attributed to the session.
+pub fn finish_git_snapshots(session_id: &str) {
+    if let Ok(mut throttle) = LAST_SNAPSHOT.lock() {
+        throttle.remove(session_id);
+    }
+}
+
 /// Insert or update a session in the local database immediately (called by all provider watchers)
 ///
 /// # Parameters
@@ -29,7 +154,7 @@ pub fn insert_session_immediately(
     provider_id: &str,
     project_name: &str,
     session_id: &str,
-    file_path: &PathBuf,
+    file_path: &Path,
     file_size: u64,
     file_hash: Option<String>,
     is_historical: bool,
@@ -167,8 +292,40 @@ pub fn insert_session_immediately(
         }
     }
 
+    // A revived compacted session now points at the plain file again
+    match remove_stale_archive(file_path) {
+        Ok(true) => {
+            let _ = log_info(
+                provider_id,
+                &format!("🗜 Removed stale archive for revived session {}", session_id),
+            );
+        }
+        Ok(false) => {}
+        Err(e) => {
+            let _ = log_warn(
+                provider_id,
+                &format!("⚠ Failed to remove stale archive for {}: {}", session_id, e),
+            );
+        }
+    }
+
+    // Rebuild the subagent/sidechain tree for this session
+    update_session_relations(provider_id, session_id, file_path);
+
+    // Record the model for analytics grouping
+    update_session_model(provider_id, session_id, file_path);
+
+    // Snapshot the working tree while the session is live so its diff survives later edits
+    if !is_historical {
+        if let Some(ref cwd_path) = cwd {
+            update_git_snapshots(provider_id, session_id, cwd_path);
+        }
+    }
+
     // Extract and link project if CWD is available
     if let Some(ref cwd_path) = cwd {
+        // Worktrees and subdirectories belong to the project at the repository root
+        let identity = crate::project_identity::resolve_project_identity(cwd_path);
         match crate::project_metadata::extract_project_metadata(cwd_path) {
             Ok(metadata) => {
                 // Insert or update project
@@ -177,6 +334,8 @@ pub fn insert_session_immediately(
                     metadata.git_remote_url.as_deref(),
                     &metadata.cwd,
                     &metadata.detected_project_type,
+                    Some(&identity.key),
+                    metadata.workspace_kind.as_deref(),
                 ) {
                     Ok(project_id) => {
                         // Attach session to project
@@ -200,6 +359,17 @@ pub fn insert_session_immediately(
                                 );
                             }
 
+                            // Record which monorepo package the session worked in
+                            if let Err(e) = crate::database::update_session_package(
+                                session_id,
+                                metadata.package.as_ref(),
+                            ) {
+                                let _ = log_warn(
+                                    provider_id,
+                                    &format!("⚠ Failed to update session package: {}", e),
+                                );
+                            }
+
                             let _ = log_debug(
                                 provider_id,
                                 &format!(
@@ -234,14 +404,65 @@ pub fn insert_session_immediately(
     Ok(())
 }
 
+/// Record the model most assistant messages in the canonical JSONL used
+fn update_session_model(provider_id: &str, session_id: &str, file_path: &Path) {
+    let Ok(content) = read_session_file(file_path) else {
+        return;
+    };
+
+    let Some(model) =
+        crate::providers::common::canonical_path::extract_model_from_canonical_content(&content)
+    else {
+        return;
+    };
+    if let Err(e) = crate::database::update_session_model(session_id, &model) {
+        let _ = log_warn(
+            provider_id,
+            &format!("⚠ Failed to update session model for {}: {}", session_id, e),
+        );
+    }
+}
+
+/// Rebuild a session's tree from its canonical JSONL and persist it to session_relations
+fn update_session_relations(provider_id: &str, session_id: &str, file_path: &Path) {
+    let content = match read_session_file(file_path) {
+        Ok(content) => content,
+        Err(e) => {
+            let _ = log_debug(
+                provider_id,
+                &format!("Could not read session {} for relations: {}", session_id, e),
+            );
+            return;
+        }
+    };
+
+    let tree = build_session_tree_from_content(session_id, &content);
+    if let Err(e) = crate::database::save_session_tree(&tree) {
+        let _ = log_warn(
+            provider_id,
+            &format!(
+                "⚠ Failed to save session relations for {}: {}",
+                session_id, e
+            ),
+        );
+    } else if tree.has_subagents() {
+        let _ = log_debug(
+            provider_id,
+            &format!(
+                "🌳 Session {} has {} subagent branch(es)",
+                session_id,
+                tree.branches.len() - 1
+            ),
+        );
+    }
+}
+
 /// Extract session timing from JSONL file (works for all providers)
 /// Extract timing information from session file (start time, end time, duration)
 /// All providers now use JSONL format (including github-copilot snapshots)
-fn extract_session_timing(_provider_id: &str, file_path: &PathBuf) -> TimingResult {
-    use std::fs;
-
+fn extract_session_timing(_provider_id: &str, file_path: &Path) -> TimingResult {
     // Read JSONL and extract timestamps
-    let content = fs::read_to_string(file_path).map_err(|e| {
+    let content = read_session_file(file_path).map_err(|e| {
         let _ = log_warn(
             "database",
             &format!("⚠ Failed to read file for timing extraction: {}", e),
@@ -307,11 +528,9 @@ fn extract_session_timing(_provider_id: &str, file_path: &PathBuf) -> TimingResu
 }
 
 /// Extract CWD from session file (provider-specific logic)
-fn extract_cwd_from_file(_provider_id: &str, file_path: &PathBuf) -> Option<String> {
-    use std::fs;
-
+fn extract_cwd_from_file(_provider_id: &str, file_path: &Path) -> Option<String> {
     // Read file content
-    let content = fs::read_to_string(file_path).ok()?;
+    let content = read_session_file(file_path).ok()?;
 
     // Use shared utility to extract CWD from canonical content
     // (All providers now use canonical format with cwd at top level)
@@ -1,4 +1,6 @@
-use std::path::Path;
+use flate2::read::GzDecoder;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 /// Check if a file should be filtered out (hidden files, temp files)
 pub fn should_skip_file(path: &Path) -> bool {
@@ -30,6 +32,62 @@ pub fn extract_session_id_from_filename(path: &Path) -> String {
         .to_string()
 }
 
+/// Resolve a session file path, following it to its `.gz` archive once compacted
+pub fn resolve_session_file(path: &Path) -> PathBuf {
+    if path.exists() || has_extension(path, "gz") {
+        return path.to_path_buf();
+    }
+
+    let mut archived = path.as_os_str().to_owned();
+    archived.push(".gz");
+    let archived = PathBuf::from(archived);
+    if archived.exists() {
+        archived
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Remove the `.gz` archive left behind when a compacted session is written again
+///
+/// Returns true if an archive was removed. The plain file must exist, so the
+/// archive is never the only copy of the session.
+pub fn remove_stale_archive(path: &Path) -> Result<bool, std::io::Error> {
+    if has_extension(path, "gz") || !path.exists() {
+        return Ok(false);
+    }
+
+    let mut archived = path.as_os_str().to_owned();
+    archived.push(".gz");
+    let archived = PathBuf::from(archived);
+    if !archived.exists() {
+        return Ok(false);
+    }
+
+    std::fs::remove_file(&archived)?;
+    Ok(true)
+}
+
+/// Read a session file, transparently decompressing `.jsonl.gz` archives
+pub fn read_session_bytes(path: &Path) -> Result<Vec<u8>, std::io::Error> {
+    let path = resolve_session_file(path);
+    let raw = std::fs::read(&path)?;
+
+    if !has_extension(&path, "gz") {
+        return Ok(raw);
+    }
+
+    let mut content = Vec::new();
+    GzDecoder::new(raw.as_slice()).read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// Read a session file as UTF-8, transparently decompressing `.jsonl.gz` archives
+pub fn read_session_file(path: &Path) -> Result<String, std::io::Error> {
+    String::from_utf8(read_session_bytes(path)?)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,4 +123,46 @@ mod tests {
             "nosuffix"
         );
     }
+
+    #[test]
+    fn test_read_session_file_follows_gz_archive() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let plain = dir.path().join("session.jsonl");
+        std::fs::write(&plain, "{\"a\":1}\n").unwrap();
+        assert_eq!(read_session_file(&plain).unwrap(), "{\"a\":1}\n");
+
+        let archived = dir.path().join("old.jsonl.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"{\"b\":2}\n").unwrap();
+        std::fs::write(&archived, encoder.finish().unwrap()).unwrap();
+
+        // Both the archive path and the original path stored in the database work
+        assert_eq!(read_session_file(&archived).unwrap(), "{\"b\":2}\n");
+        assert_eq!(
+            read_session_file(&dir.path().join("old.jsonl")).unwrap(),
+            "{\"b\":2}\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_stale_archive_after_revival() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = dir.path().join("session.jsonl");
+        let archived = dir.path().join("session.jsonl.gz");
+        std::fs::write(&archived, b"stale").unwrap();
+
+        // Archive is the only copy until the session is written again
+        assert!(!remove_stale_archive(&plain).unwrap());
+        assert!(!remove_stale_archive(&archived).unwrap());
+        assert!(archived.exists());
+
+        std::fs::write(&plain, "{\"a\":1}\n").unwrap();
+        assert!(remove_stale_archive(&plain).unwrap());
+        assert!(!archived.exists());
+        assert_eq!(read_session_file(&plain).unwrap(), "{\"a\":1}\n");
+    }
 }
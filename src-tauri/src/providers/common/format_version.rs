@@ -0,0 +1,288 @@
+//! Provider format versions
+//!
+//! Each parser declares the on-disk format versions it understands as a
+//! `FormatSpec`, mapping version ranges to a decoder. Sessions written by a
+//! version outside every range are refused instead of being half converted,
+//! and recorded here so watchers can report them through `WatcherStatus`.
+//!
+//! Versions past the newest known range are only refused when the version
+//! is a schema version. Tool versions (a CLI's own release number) bump far
+//! more often than their format changes, so those sessions are decoded with
+//! the newest decoder and logged once; fidelity stats catch actual drift.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+
+/// Versions in `[min, max)` are decoded by `decoder`; `max: None` is open-ended
+#[derive(Debug, Clone, Copy)]
+pub struct FormatVersionRange<D: 'static> {
+    pub min: &'static str,
+    pub max: Option<&'static str>,
+    pub decoder: D,
+}
+
+/// What to do with versions newer than every range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NewerVersions {
+    /// The version is a schema version; a bump means an unknown format
+    Refuse,
+    /// Decode with the newest decoder (or `unversioned`) and log a warning
+    Warn,
+}
+
+/// Format versions a provider parser supports
+#[derive(Debug)]
+pub struct FormatSpec<D: 'static> {
+    pub provider_id: &'static str,
+    /// Where the version comes from, for error messages (e.g. `session_meta.cli_version`)
+    pub version_field: &'static str,
+    pub ranges: &'static [FormatVersionRange<D>],
+    /// Decoder for files that predate the version field; `None` refuses them
+    pub unversioned: Option<D>,
+    pub newer: NewerVersions,
+}
+
+/// A session refused because of its format version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnsupportedFormat {
+    pub session_id: String,
+    pub version: Option<String>,
+    pub supported: String,
+    pub detected_at: String,
+}
+
+static UNSUPPORTED: LazyLock<Mutex<HashMap<String, BTreeMap<String, UnsupportedFormat>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// `(provider, session)` pairs already warned about a newer version
+static WARNED_NEWER: LazyLock<Mutex<HashSet<(String, String)>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+impl<D: Copy> FormatSpec<D> {
+    /// Decoder for `version`, without recording anything
+    pub fn decoder_for(&self, version: Option<&str>) -> Option<D> {
+        let Some(version) = version else {
+            return self.unversioned;
+        };
+
+        self.ranges
+            .iter()
+            .find(|range| {
+                compare_versions(version, range.min) != Ordering::Less
+                    && range
+                        .max
+                        .is_none_or(|max| compare_versions(version, max) == Ordering::Less)
+            })
+            .map(|range| range.decoder)
+    }
+
+    /// Decoder for a version past every range, if this spec accepts those
+    fn newer_decoder(&self, version: &str) -> Option<D> {
+        if self.newer == NewerVersions::Refuse {
+            return None;
+        }
+
+        let Some(newest) = self
+            .ranges
+            .iter()
+            .max_by(|a, b| compare_versions(a.min, b.min))
+        else {
+            return self.unversioned;
+        };
+        newest
+            .max
+            .filter(|max| compare_versions(version, max) != Ordering::Less)
+            .map(|_| newest.decoder)
+    }
+
+    /// Pick the decoder for a session, recording it as unsupported if none fits
+    pub fn resolve(&self, session_id: &str, version: Option<&str>) -> Result<D, String> {
+        if let Some(version) = version.filter(|v| self.decoder_for(Some(v)).is_none()) {
+            if let Some(decoder) = self.newer_decoder(version) {
+                self.warn_newer(session_id, version);
+                return Ok(decoder);
+            }
+        }
+
+        let mut unsupported = UNSUPPORTED.lock().map_err(|e| e.to_string())?;
+        let sessions = unsupported.entry(self.provider_id.to_string()).or_default();
+
+        match self.decoder_for(version) {
+            Some(decoder) => {
+                sessions.remove(session_id);
+                Ok(decoder)
+            }
+            None => {
+                let supported = self.describe();
+                sessions.insert(
+                    session_id.to_string(),
+                    UnsupportedFormat {
+                        session_id: session_id.to_string(),
+                        version: version.map(str::to_string),
+                        supported: supported.clone(),
+                        detected_at: Utc::now().to_rfc3339(),
+                    },
+                );
+                Err(format!(
+                    "Unsupported {} format version {} ({}; supported: {})",
+                    self.provider_id,
+                    version.unwrap_or("(none)"),
+                    self.version_field,
+                    supported
+                ))
+            }
+        }
+    }
+
+    /// Log the first time a session is decoded despite a newer version
+    fn warn_newer(&self, session_id: &str, version: &str) {
+        let first = WARNED_NEWER
+            .lock()
+            .map(|mut warned| warned.insert((self.provider_id.to_string(), session_id.to_string())))
+            .unwrap_or(false);
+        if first {
+            let known = match self.describe() {
+                ranges if ranges.is_empty() => "only unversioned files".to_string(),
+                ranges => ranges,
+            };
+            let _ = crate::logging::log_warn(
+                self.provider_id,
+                &format!(
+                    "⚠ Session {} has {} {} (known: {}); converting it with the newest known format",
+                    session_id, self.version_field, version, known
+                ),
+            );
+        }
+    }
+
+    /// Human-readable list of supported ranges, e.g. `>=1.0.0 <3.0.0`
+    pub fn describe(&self) -> String {
+        self.ranges
+            .iter()
+            .map(|range| match range.max {
+                Some(max) => format!(">={} <{}", range.min, max),
+                None => format!(">={}", range.min),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Sessions of a provider refused because of their format version
+pub fn unsupported_formats(provider_id: &str) -> Vec<UnsupportedFormat> {
+    UNSUPPORTED
+        .lock()
+        .map(|unsupported| {
+            unsupported
+                .get(provider_id)
+                .map(|sessions| sessions.values().cloned().collect())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default()
+}
+
+/// Compare dotted numeric versions; pre-release/build suffixes are ignored
+/// and missing components count as zero (`2` == `2.0.0`)
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<u64> {
+        v.trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or("")
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+
+    let (a, b) = (parts(a), parts(b));
+    (0..a.len().max(b.len()))
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum TestFormat {
+        Legacy,
+        Current,
+    }
+
+    static SPEC: FormatSpec<TestFormat> = FormatSpec {
+        provider_id: "format-version-test",
+        version_field: "version",
+        ranges: &[
+            FormatVersionRange {
+                min: "0.1.0",
+                max: Some("1.0.0"),
+                decoder: TestFormat::Legacy,
+            },
+            FormatVersionRange {
+                min: "1.0.0",
+                max: Some("3.0.0"),
+                decoder: TestFormat::Current,
+            },
+        ],
+        unversioned: None,
+        newer: NewerVersions::Refuse,
+    };
+
+    static TOOL_SPEC: FormatSpec<TestFormat> = FormatSpec {
+        provider_id: "format-version-tool-test",
+        version_field: "version",
+        ranges: &[FormatVersionRange {
+            min: "1.0.0",
+            max: Some("3.0.0"),
+            decoder: TestFormat::Current,
+        }],
+        unversioned: None,
+        newer: NewerVersions::Warn,
+    };
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("2.0.21", "2.0.3"), Ordering::Greater);
+        assert_eq!(compare_versions("2", "2.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("v1.2.0-beta.1", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.45.0", "1.0.0"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_resolve_dispatches_and_records_unsupported() {
+        assert_eq!(SPEC.resolve("a", Some("0.45.0")), Ok(TestFormat::Legacy));
+        assert_eq!(SPEC.resolve("b", Some("2.0.21")), Ok(TestFormat::Current));
+
+        let error = SPEC.resolve("c", Some("3.1.0")).unwrap_err();
+        assert!(error.contains("3.1.0"));
+        assert!(SPEC.resolve("d", None).is_err());
+
+        let unsupported = unsupported_formats("format-version-test");
+        assert_eq!(unsupported.len(), 2);
+        assert_eq!(unsupported[0].session_id, "c");
+        assert_eq!(unsupported[0].supported, ">=0.1.0 <1.0.0, >=1.0.0 <3.0.0");
+
+        // A later supported run clears the entry
+        assert!(SPEC.resolve("c", Some("2.5.0")).is_ok());
+        assert_eq!(unsupported_formats("format-version-test").len(), 1);
+    }
+
+    #[test]
+    fn test_newer_tool_versions_decode_with_a_warning() {
+        assert_eq!(
+            TOOL_SPEC.resolve("next-major", Some("3.1.0")),
+            Ok(TestFormat::Current)
+        );
+        assert!(TOOL_SPEC.resolve("too-old", Some("0.9.0")).is_err());
+
+        let unsupported = unsupported_formats("format-version-tool-test");
+        assert_eq!(unsupported.len(), 1);
+        assert_eq!(unsupported[0].session_id, "too-old");
+    }
+}
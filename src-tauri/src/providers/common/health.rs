@@ -0,0 +1,469 @@
+//! Watcher health
+//!
+//! Every provider watcher thread carries a [`WatcherHeartbeat`] and beats it
+//! once per loop iteration. The supervisor reads heartbeats to spot watchers
+//! that exited, panicked, stalled or lost their watched directory, restarts
+//! them with [`RestartBackoff`], and moves providers that hit the OS watch
+//! limit over to polling through [`FsWatcher`].
+
+use crate::logging::log_warn;
+use crate::providers::common::{HEARTBEAT_STALE_AFTER, RESTART_BACKOFF_BASE, RESTART_BACKOFF_MAX};
+use chrono::Utc;
+use notify::{
+    Config, ErrorKind, EventHandler, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher,
+    WatcherKind,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Providers that exhausted the native watch limit and now poll
+static POLLING: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Last trouble a watcher reported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum WatchFailure {
+    /// The OS refused more watches (inotify limit, too many open files)
+    WatchLimit(String),
+    Error(String),
+}
+
+impl WatchFailure {
+    pub fn from_notify(error: &notify::Error) -> Self {
+        if is_watch_limit_error(error) {
+            WatchFailure::WatchLimit(error.to_string())
+        } else {
+            WatchFailure::Error(error.to_string())
+        }
+    }
+}
+
+#[derive(Debug)]
+struct HeartbeatState {
+    root: PathBuf,
+    polling: bool,
+    last_beat: Mutex<Instant>,
+    last_failure: Mutex<Option<WatchFailure>>,
+    exited: AtomicBool,
+    panicked: AtomicBool,
+}
+
+/// Liveness signal shared between a watcher, its thread and the supervisor
+#[derive(Debug, Clone)]
+pub struct WatcherHeartbeat {
+    provider_id: String,
+    state: Arc<HeartbeatState>,
+}
+
+/// Point-in-time view of a heartbeat
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeartbeatSnapshot {
+    pub root: PathBuf,
+    pub polling: bool,
+    pub since_beat: Duration,
+    pub exited: bool,
+    pub panicked: bool,
+    pub last_failure: Option<WatchFailure>,
+}
+
+impl WatcherHeartbeat {
+    /// `root` is the directory whose disappearance makes the watcher useless
+    pub fn new(provider_id: &str, root: &Path, polling: bool) -> Self {
+        Self {
+            provider_id: provider_id.to_string(),
+            state: Arc::new(HeartbeatState {
+                root: root.to_path_buf(),
+                polling,
+                last_beat: Mutex::new(Instant::now()),
+                last_failure: Mutex::new(None),
+                exited: AtomicBool::new(false),
+                panicked: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub fn beat(&self) {
+        if let Ok(mut last_beat) = self.state.last_beat.lock() {
+            *last_beat = Instant::now();
+        }
+    }
+
+    /// Record an error delivered on the watcher's event channel
+    pub fn report(&self, error: &notify::Error) {
+        let failure = WatchFailure::from_notify(error);
+        if matches!(failure, WatchFailure::WatchLimit(_)) {
+            enable_polling(&self.provider_id);
+        }
+        if let Ok(mut last_failure) = self.state.last_failure.lock() {
+            *last_failure = Some(failure);
+        }
+    }
+
+    /// Guard for the watcher thread: marks the heartbeat exited when the
+    /// thread returns or unwinds
+    pub fn exit_guard(&self) -> ExitGuard {
+        ExitGuard(self.clone())
+    }
+
+    /// Whether both heartbeats belong to the same watcher instance
+    pub fn same_watcher(&self, other: &WatcherHeartbeat) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+
+    /// Thread still running (it may still be stalled, see [`HeartbeatSnapshot::problem`])
+    pub fn is_alive(&self) -> bool {
+        !self.state.exited.load(Ordering::SeqCst)
+    }
+
+    pub fn snapshot(&self) -> HeartbeatSnapshot {
+        HeartbeatSnapshot {
+            root: self.state.root.clone(),
+            polling: self.state.polling,
+            since_beat: self
+                .state
+                .last_beat
+                .lock()
+                .map(|last_beat| last_beat.elapsed())
+                .unwrap_or_default(),
+            exited: self.state.exited.load(Ordering::SeqCst),
+            panicked: self.state.panicked.load(Ordering::SeqCst),
+            last_failure: self
+                .state
+                .last_failure
+                .lock()
+                .map(|failure| failure.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+pub struct ExitGuard(WatcherHeartbeat);
+
+impl Drop for ExitGuard {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.state.panicked.store(true, Ordering::SeqCst);
+        }
+        self.0.state.exited.store(true, Ordering::SeqCst);
+    }
+}
+
+impl HeartbeatSnapshot {
+    /// What is wrong with the watcher, if anything, most severe first
+    pub fn problem(&self, provider_id: &str) -> Option<HealthEventKind> {
+        if self.panicked {
+            Some(HealthEventKind::Panicked)
+        } else if self.exited {
+            Some(HealthEventKind::Exited)
+        } else if !self.polling && use_polling(provider_id) {
+            Some(HealthEventKind::WatchLimit)
+        } else if !self.root.exists() {
+            Some(HealthEventKind::RootMissing)
+        } else if self.since_beat >= HEARTBEAT_STALE_AFTER {
+            Some(HealthEventKind::Stalled)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether the OS refused a watch because a watch/descriptor limit was hit
+pub fn is_watch_limit_error(error: &notify::Error) -> bool {
+    match &error.kind {
+        ErrorKind::MaxFilesWatch => true,
+        // ENOSPC (inotify max_user_watches), EMFILE (max_user_instances)
+        ErrorKind::Io(io) => matches!(io.raw_os_error(), Some(28) | Some(24)),
+        ErrorKind::Generic(message) => {
+            message.contains("No space left on device") || message.contains("Too many open files")
+        }
+        _ => false,
+    }
+}
+
+pub fn use_polling(provider_id: &str) -> bool {
+    POLLING
+        .lock()
+        .map(|polling| polling.contains(provider_id))
+        .unwrap_or(false)
+}
+
+/// Switch a provider to polling from its next watcher start on
+pub fn enable_polling(provider_id: &str) {
+    if let Ok(mut polling) = POLLING.lock() {
+        if polling.insert(provider_id.to_string()) {
+            let _ = log_warn(
+                provider_id,
+                "⚠ OS file watch limit reached, falling back to polling",
+            );
+        }
+    }
+}
+
+/// Native watcher, or a poll watcher once the provider hit the watch limit
+#[derive(Debug)]
+pub struct FsWatcher {
+    provider_id: String,
+    backend: FsBackend,
+}
+
+#[derive(Debug)]
+enum FsBackend {
+    Native(RecommendedWatcher),
+    Polling(PollWatcher),
+}
+
+impl FsWatcher {
+    pub fn for_provider<F: EventHandler + Clone>(
+        provider_id: &str,
+        event_handler: F,
+        config: Config,
+    ) -> notify::Result<Self> {
+        let backend = if use_polling(provider_id) {
+            FsBackend::Polling(PollWatcher::new(event_handler, config)?)
+        } else {
+            match RecommendedWatcher::new(event_handler.clone(), config) {
+                Ok(watcher) => FsBackend::Native(watcher),
+                Err(e) if is_watch_limit_error(&e) => {
+                    enable_polling(provider_id);
+                    FsBackend::Polling(PollWatcher::new(event_handler, config)?)
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        Ok(Self {
+            provider_id: provider_id.to_string(),
+            backend,
+        })
+    }
+
+    pub fn is_polling(&self) -> bool {
+        matches!(self.backend, FsBackend::Polling(_))
+    }
+}
+
+impl Watcher for FsWatcher {
+    fn new<F: EventHandler>(event_handler: F, config: Config) -> notify::Result<Self> {
+        Ok(Self {
+            provider_id: String::new(),
+            backend: FsBackend::Native(RecommendedWatcher::new(event_handler, config)?),
+        })
+    }
+
+    fn watch(&mut self, path: &Path, recursive_mode: RecursiveMode) -> notify::Result<()> {
+        let result = match &mut self.backend {
+            FsBackend::Native(watcher) => watcher.watch(path, recursive_mode),
+            FsBackend::Polling(watcher) => watcher.watch(path, recursive_mode),
+        };
+        if let Err(e) = &result {
+            if is_watch_limit_error(e) && !self.provider_id.is_empty() {
+                enable_polling(&self.provider_id);
+            }
+        }
+        result
+    }
+
+    fn unwatch(&mut self, path: &Path) -> notify::Result<()> {
+        match &mut self.backend {
+            FsBackend::Native(watcher) => watcher.unwatch(path),
+            FsBackend::Polling(watcher) => watcher.unwatch(path),
+        }
+    }
+
+    /// The native backend; instances may poll instead, see [`FsWatcher::is_polling`]
+    fn kind() -> WatcherKind {
+        RecommendedWatcher::kind()
+    }
+}
+
+/// Exponential delay between restarts of a failing watcher
+#[derive(Debug, Clone, Default)]
+pub struct RestartBackoff {
+    attempts: u32,
+    next_attempt: Option<Instant>,
+}
+
+impl RestartBackoff {
+    /// `base * 2^(attempt - 1)`, capped at [`RESTART_BACKOFF_MAX`]
+    pub fn delay_for(attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        RESTART_BACKOFF_BASE
+            .saturating_mul(factor)
+            .min(RESTART_BACKOFF_MAX)
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn ready(&self, now: Instant) -> bool {
+        self.next_attempt.is_none_or(|next| now >= next)
+    }
+
+    /// Count an attempt and hold the next one back; returns the delay
+    pub fn schedule(&mut self, now: Instant) -> Duration {
+        self.attempts += 1;
+        let delay = Self::delay_for(self.attempts);
+        self.next_attempt = Some(now + delay);
+        delay
+    }
+
+    pub fn next_attempt_in(&self, now: Instant) -> Option<Duration> {
+        self.next_attempt
+            .map(|next| next.saturating_duration_since(now))
+            .filter(|delay| !delay.is_zero())
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthEventKind {
+    Panicked,
+    Exited,
+    Stalled,
+    RootMissing,
+    WatchLimit,
+    PollingFallback,
+    Restarted,
+    RestartFailed,
+    Recovered,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthEvent {
+    pub provider_id: String,
+    pub kind: HealthEventKind,
+    pub detail: String,
+    pub at: String,
+}
+
+/// Most recent health events across providers, oldest dropped first
+#[derive(Debug)]
+pub struct HealthHistory {
+    events: VecDeque<HealthEvent>,
+    limit: usize,
+}
+
+impl HealthHistory {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            limit,
+        }
+    }
+
+    pub fn record(&mut self, provider_id: &str, kind: HealthEventKind, detail: impl Into<String>) {
+        if self.events.len() >= self.limit {
+            self.events.pop_front();
+        }
+        self.events.push_back(HealthEvent {
+            provider_id: provider_id.to_string(),
+            kind,
+            detail: detail.into(),
+            at: Utc::now().to_rfc3339(),
+        });
+    }
+
+    /// Newest first, optionally for one provider
+    pub fn recent(&self, provider_id: Option<&str>) -> Vec<HealthEvent> {
+        self.events
+            .iter()
+            .rev()
+            .filter(|event| provider_id.is_none_or(|id| event.provider_id == id))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_restart_backoff_doubles_up_to_cap() {
+        assert_eq!(RestartBackoff::delay_for(1), Duration::from_secs(2));
+        assert_eq!(RestartBackoff::delay_for(4), Duration::from_secs(16));
+        assert_eq!(RestartBackoff::delay_for(40), RESTART_BACKOFF_MAX);
+
+        let now = Instant::now();
+        let mut backoff = RestartBackoff::default();
+        assert!(backoff.ready(now));
+        assert_eq!(backoff.schedule(now), Duration::from_secs(2));
+        assert!(!backoff.ready(now));
+        assert!(backoff.ready(now + Duration::from_secs(2)));
+        assert_eq!(backoff.schedule(now), Duration::from_secs(4));
+        backoff.reset();
+        assert_eq!(backoff.attempts(), 0);
+    }
+
+    #[test]
+    fn test_heartbeat_problems() {
+        let dir = tempdir().unwrap();
+        let provider_id = "health-test";
+        let heartbeat = WatcherHeartbeat::new(provider_id, dir.path(), false);
+        assert_eq!(heartbeat.snapshot().problem(provider_id), None);
+
+        // The watch limit flips the provider to polling and flags the native watcher
+        heartbeat.report(&notify::Error::new(ErrorKind::MaxFilesWatch));
+        assert!(use_polling(provider_id));
+        assert_eq!(
+            heartbeat.snapshot().problem(provider_id),
+            Some(HealthEventKind::WatchLimit)
+        );
+
+        let missing = WatcherHeartbeat::new(provider_id, &dir.path().join("gone"), true);
+        assert_eq!(
+            missing.snapshot().problem(provider_id),
+            Some(HealthEventKind::RootMissing)
+        );
+
+        assert!(heartbeat.same_watcher(&heartbeat.clone()));
+        assert!(!heartbeat.same_watcher(&missing));
+
+        let worker = heartbeat.clone();
+        let result = thread::spawn(move || {
+            let _guard = worker.exit_guard();
+            panic!("watcher thread panicked");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(!heartbeat.is_alive());
+        assert_eq!(
+            heartbeat.snapshot().problem(provider_id),
+            Some(HealthEventKind::Panicked)
+        );
+    }
+
+    #[test]
+    fn test_health_history_is_capped() {
+        let mut history = HealthHistory::new(3);
+        for kind in [
+            HealthEventKind::Exited,
+            HealthEventKind::Restarted,
+            HealthEventKind::Stalled,
+            HealthEventKind::RestartFailed,
+        ] {
+            history.record("codex", kind, "");
+        }
+        history.record("claude-code", HealthEventKind::Recovered, "");
+
+        let all = history.recent(None);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].kind, HealthEventKind::Recovered);
+        let codex = history.recent(Some("codex"));
+        assert_eq!(
+            codex.iter().map(|e| e.kind).collect::<Vec<_>>(),
+            vec![HealthEventKind::RestartFailed, HealthEventKind::Stalled]
+        );
+    }
+}
@@ -8,9 +8,15 @@ pub mod canonical_path;
 pub mod constants;
 pub mod db_helpers;
 pub mod file_utils;
+pub mod format_version;
+pub mod health;
 pub mod session_info;
+pub mod session_lifecycle;
 pub mod session_state;
+pub mod session_tree;
 pub mod timing;
+pub mod watch_set;
+pub mod watch_task;
 pub mod watcher_status;
 
 // Re-export commonly used types
@@ -18,6 +24,13 @@ pub use agent_merger::*;
 pub use canonical_path::*;
 pub use constants::*;
 pub use file_utils::*;
+pub use health::{FsWatcher, WatcherHeartbeat};
 pub use session_info::SessionInfo;
+pub use session_lifecycle::SessionLifecycle;
 pub use session_state::SessionStateManager;
+pub use session_tree::SessionTree;
+pub use watch_set::WatchSet;
+pub use watch_task::{
+    fs_event_channel, run_blocking, Debouncer, FsEventReceiver, WatchCancellation, WatchTask,
+};
 pub use watcher_status::WatcherStatus;
@@ -0,0 +1,576 @@
+//! Session lifecycle tracking shared by all provider watchers
+//!
+//! Sessions move through `discovered → active → idle → completed → archived`:
+//!
+//! * file activity makes a session active (and re-opens a completed one that
+//!   was resumed)
+//! * an active session with no activity for `idle_after` becomes idle
+//! * an idle session with no activity for `complete_after` (the provider's
+//!   `idleTimeoutMinutes`) completes, as does one whose file ends with a
+//!   provider end marker
+//! * completed sessions are archived (forgotten) after `archive_after`
+//!
+//! Completion publishes `SessionEventPayload::Completed` with the session's
+//! real start/end time. States are persisted, so sessions that were still open
+//! when the app quit complete after a restart.
+
+use crate::config::ProviderConfig;
+use crate::database::{self, LifecycleRecord};
+use crate::events::{EventBus, SessionEventPayload};
+use crate::logging::{log_debug, log_info, log_warn};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// How much of the end of a file is checked for end markers
+const END_MARKER_TAIL_BYTES: u64 = 16 * 1024;
+
+/// Activity timestamps are persisted at most this often between state changes
+const PERSIST_INTERVAL_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleState {
+    Discovered,
+    Active,
+    Idle,
+    Completed,
+    Archived,
+}
+
+impl LifecycleState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LifecycleState::Discovered => "discovered",
+            LifecycleState::Active => "active",
+            LifecycleState::Idle => "idle",
+            LifecycleState::Completed => "completed",
+            LifecycleState::Archived => "archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "discovered" => Some(LifecycleState::Discovered),
+            "active" => Some(LifecycleState::Active),
+            "idle" => Some(LifecycleState::Idle),
+            "completed" => Some(LifecycleState::Completed),
+            "archived" => Some(LifecycleState::Archived),
+            _ => None,
+        }
+    }
+}
+
+/// Timeouts driving idle detection
+#[derive(Debug, Clone, Copy)]
+pub struct LifecycleConfig {
+    pub idle_after: Duration,
+    pub complete_after: Duration,
+    pub archive_after: Duration,
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        Self {
+            idle_after: Duration::minutes(5),
+            complete_after: Duration::minutes(30),
+            archive_after: Duration::hours(24),
+        }
+    }
+}
+
+impl LifecycleConfig {
+    /// Defaults, with the completion timeout taken from the provider's `idleTimeoutMinutes`
+    pub fn for_provider(provider_id: &str) -> Self {
+        crate::config_service::provider_config(provider_id)
+            .map(|config| Self::from_provider_config(&config))
+            .unwrap_or_default()
+    }
+
+    pub fn from_provider_config(provider_config: &ProviderConfig) -> Self {
+        let mut config = Self::default();
+        if let Some(minutes) = provider_config.idle_timeout_minutes.filter(|m| *m > 0) {
+            config.complete_after = Duration::minutes(i64::from(minutes));
+            config.idle_after = config.idle_after.min(config.complete_after / 2);
+        }
+        config
+    }
+}
+
+/// A state change produced by the tracker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transition {
+    pub session_id: String,
+    pub from: LifecycleState,
+    pub to: LifecycleState,
+    pub first_activity: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct TrackedSession {
+    state: LifecycleState,
+    first_activity: DateTime<Utc>,
+    last_activity: DateTime<Utc>,
+    /// When the session entered its current state
+    entered_at: DateTime<Utc>,
+}
+
+/// Pure state machine; callers supply the clock
+#[derive(Debug)]
+pub struct LifecycleTracker {
+    config: LifecycleConfig,
+    sessions: HashMap<String, TrackedSession>,
+}
+
+impl LifecycleTracker {
+    pub fn new(config: LifecycleConfig) -> Self {
+        Self {
+            config,
+            sessions: HashMap::new(),
+        }
+    }
+
+    pub fn set_config(&mut self, config: LifecycleConfig) {
+        self.config = config;
+    }
+
+    /// Resume tracking a session known from a previous run
+    pub fn restore(
+        &mut self,
+        session_id: &str,
+        state: LifecycleState,
+        first_activity: DateTime<Utc>,
+        last_activity: DateTime<Utc>,
+    ) {
+        self.sessions.insert(
+            session_id.to_string(),
+            TrackedSession {
+                state,
+                first_activity,
+                last_activity,
+                entered_at: last_activity,
+            },
+        );
+    }
+
+    pub fn state(&self, session_id: &str) -> Option<LifecycleState> {
+        self.sessions.get(session_id).map(|s| s.state)
+    }
+
+    fn transition(
+        session_id: &str,
+        session: &mut TrackedSession,
+        to: LifecycleState,
+        now: DateTime<Utc>,
+    ) -> Transition {
+        let from = session.state;
+        session.state = to;
+        session.entered_at = now;
+        Transition {
+            session_id: session_id.to_string(),
+            from,
+            to,
+            first_activity: session.first_activity,
+            last_activity: session.last_activity,
+        }
+    }
+
+    /// The session's file changed; `ended` is set when it carries an end marker
+    pub fn record_activity(
+        &mut self,
+        session_id: &str,
+        now: DateTime<Utc>,
+        ended: bool,
+    ) -> Vec<Transition> {
+        let session = self
+            .sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| TrackedSession {
+                state: LifecycleState::Discovered,
+                first_activity: now,
+                last_activity: now,
+                entered_at: now,
+            });
+        session.last_activity = now;
+
+        // Trailing writes after an end marker don't re-open the session
+        if ended && session.state == LifecycleState::Completed {
+            return Vec::new();
+        }
+
+        let mut transitions = Vec::new();
+        if session.state != LifecycleState::Active {
+            transitions.push(Self::transition(
+                session_id,
+                session,
+                LifecycleState::Active,
+                now,
+            ));
+        }
+        if ended {
+            transitions.push(Self::transition(
+                session_id,
+                session,
+                LifecycleState::Completed,
+                now,
+            ));
+        }
+        transitions
+    }
+
+    /// Advance sessions whose timeouts have passed
+    pub fn tick(&mut self, now: DateTime<Utc>) -> Vec<Transition> {
+        let mut transitions = Vec::new();
+        let config = self.config;
+
+        for (session_id, session) in self.sessions.iter_mut() {
+            let quiet = now - session.last_activity;
+
+            if matches!(
+                session.state,
+                LifecycleState::Discovered | LifecycleState::Active
+            ) && quiet >= config.idle_after
+            {
+                transitions.push(Self::transition(
+                    session_id,
+                    session,
+                    LifecycleState::Idle,
+                    now,
+                ));
+            }
+            if session.state == LifecycleState::Idle && quiet >= config.complete_after {
+                transitions.push(Self::transition(
+                    session_id,
+                    session,
+                    LifecycleState::Completed,
+                    now,
+                ));
+            }
+            if session.state == LifecycleState::Completed
+                && now - session.entered_at >= config.archive_after
+            {
+                transitions.push(Self::transition(
+                    session_id,
+                    session,
+                    LifecycleState::Archived,
+                    now,
+                ));
+            }
+        }
+
+        self.sessions
+            .retain(|_, session| session.state != LifecycleState::Archived);
+        transitions
+    }
+}
+
+/// Whether the end of a session file shows the session was explicitly ended
+///
+/// Only Claude Code writes a recognizable marker (the `/exit` command); other
+/// providers complete through the idle timeout.
+pub fn has_end_marker(provider_id: &str, path: &Path) -> bool {
+    let markers: &[&str] = match provider_id {
+        "claude-code" => &[
+            "<command-name>/exit</command-name>",
+            "<command-name>/quit</command-name>",
+        ],
+        _ => return false,
+    };
+
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if file
+        .seek(SeekFrom::Start(len.saturating_sub(END_MARKER_TAIL_BYTES)))
+        .is_err()
+    {
+        return false;
+    }
+    let mut tail = Vec::new();
+    if file.read_to_end(&mut tail).is_err() {
+        return false;
+    }
+
+    // The marker has to be in one of the last few entries, not anywhere in the session
+    String::from_utf8_lossy(&tail)
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .take(3)
+        .any(|line| markers.iter().any(|marker| line.contains(marker)))
+}
+
+fn millis_to_datetime(ms: i64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(ms).single()
+}
+
+/// Lifecycle tracking for one provider watcher: persists transitions and
+/// publishes `Completed` events
+pub struct SessionLifecycle {
+    provider_id: String,
+    event_bus: EventBus,
+    tracker: LifecycleTracker,
+    last_persisted: HashMap<String, DateTime<Utc>>,
+}
+
+impl SessionLifecycle {
+    /// Create a tracker for the provider, restoring sessions left open by a previous run
+    pub fn new(provider_id: &str, event_bus: EventBus) -> Self {
+        let mut lifecycle = Self {
+            provider_id: provider_id.to_string(),
+            event_bus,
+            tracker: LifecycleTracker::new(LifecycleConfig::for_provider(provider_id)),
+            last_persisted: HashMap::new(),
+        };
+
+        match database::get_open_lifecycle_sessions(provider_id) {
+            Ok(records) => {
+                let restored = records
+                    .iter()
+                    .filter(|record| lifecycle.restore(record))
+                    .count();
+                if restored > 0 {
+                    let _ = log_info(
+                        provider_id,
+                        &format!(
+                            "⏳ Resumed lifecycle tracking for {} open sessions",
+                            restored
+                        ),
+                    );
+                }
+            }
+            Err(e) => {
+                let _ = log_warn(
+                    provider_id,
+                    &format!("⚠ Failed to restore session lifecycles: {}", e),
+                );
+            }
+        }
+
+        lifecycle
+    }
+
+    fn restore(&mut self, record: &LifecycleRecord) -> bool {
+        let Some(state) = LifecycleState::parse(&record.state) else {
+            return false;
+        };
+        let Some(last) = record.last_activity_at.and_then(millis_to_datetime) else {
+            return false;
+        };
+        let first = record
+            .session_start_time
+            .and_then(millis_to_datetime)
+            .unwrap_or(last);
+        self.tracker.restore(&record.session_id, state, first, last);
+        true
+    }
+
+    /// Record a file change for a session
+    pub fn record_activity(&mut self, session_id: &str, file_path: &Path) {
+        let now = Utc::now();
+        let ended = has_end_marker(&self.provider_id, file_path);
+        let transitions = self.tracker.record_activity(session_id, now, ended);
+
+        if transitions.is_empty() {
+            // Keep last_activity_at fresh enough to survive a restart
+            let stale = self
+                .last_persisted
+                .get(session_id)
+                .is_none_or(|at| now - *at >= Duration::seconds(PERSIST_INTERVAL_SECS));
+            // The state may not be Active, e.g. writes after an end marker
+            if let (true, Some(state)) = (stale, self.tracker.state(session_id)) {
+                self.persist(session_id, state, now);
+            }
+        }
+        self.apply(transitions);
+    }
+
+    /// Apply a reloaded provider config (timeouts only; tracked sessions are kept)
+    pub fn set_config(&mut self, provider_config: &ProviderConfig) {
+        self.tracker
+            .set_config(LifecycleConfig::from_provider_config(provider_config));
+    }
+
+    /// Advance idle sessions; returns the ids of sessions that completed
+    pub fn tick(&mut self) -> Vec<String> {
+        let transitions = self.tracker.tick(Utc::now());
+        self.apply(transitions)
+    }
+
+    fn persist(&mut self, session_id: &str, state: LifecycleState, last_activity: DateTime<Utc>) {
+        match database::update_session_lifecycle(
+            session_id,
+            state.as_str(),
+            last_activity.timestamp_millis(),
+        ) {
+            Ok(()) => {
+                self.last_persisted
+                    .insert(session_id.to_string(), Utc::now());
+            }
+            Err(e) => {
+                let _ = log_warn(
+                    &self.provider_id,
+                    &format!("⚠ Failed to save lifecycle of {}: {}", session_id, e),
+                );
+            }
+        }
+    }
+
+    fn apply(&mut self, transitions: Vec<Transition>) -> Vec<String> {
+        let mut completed = Vec::new();
+
+        for transition in transitions {
+            let _ = log_debug(
+                &self.provider_id,
+                &format!(
+                    "Session {} {} → {}",
+                    transition.session_id,
+                    transition.from.as_str(),
+                    transition.to.as_str()
+                ),
+            );
+
+            match transition.to {
+                LifecycleState::Completed => {
+                    self.publish_completed(&transition);
+                    completed.push(transition.session_id.clone());
+                }
+                LifecycleState::Archived => {
+                    self.last_persisted.remove(&transition.session_id);
+                    continue;
+                }
+                _ => {}
+            }
+            self.persist(
+                &transition.session_id,
+                transition.to,
+                transition.last_activity,
+            );
+        }
+
+        completed
+    }
+
+    /// Publish `Completed`, preferring the message timestamps recorded for the session
+    fn publish_completed(&self, transition: &Transition) {
+        let (db_start, db_end) =
+            database::get_session_times(&transition.session_id).unwrap_or((None, None));
+        let start_time = db_start
+            .and_then(millis_to_datetime)
+            .unwrap_or(transition.first_activity);
+        let end_time = db_end
+            .and_then(millis_to_datetime)
+            .filter(|end| *end >= start_time)
+            .unwrap_or(transition.last_activity)
+            .max(start_time);
+        let duration_ms = (end_time - start_time).num_milliseconds();
+
+        let payload = SessionEventPayload::Completed {
+            session_id: transition.session_id.clone(),
+            start_time,
+            end_time,
+            duration_ms,
+        };
+        if let Err(e) = self.event_bus.publish(&self.provider_id, payload) {
+            let _ = log_warn(
+                &self.provider_id,
+                &format!("⚠ Failed to publish completion event: {}", e),
+            );
+            return;
+        }
+
+        let _ = log_info(
+            &self.provider_id,
+            &format!(
+                "✅ Session {} completed ({}s)",
+                transition.session_id,
+                duration_ms / 1000
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    fn states(transitions: &[Transition]) -> Vec<(LifecycleState, LifecycleState)> {
+        transitions.iter().map(|t| (t.from, t.to)).collect()
+    }
+
+    #[test]
+    fn test_lifecycle_transitions() {
+        use LifecycleState::*;
+        let mut tracker = LifecycleTracker::new(LifecycleConfig::default());
+
+        assert_eq!(
+            states(&tracker.record_activity("s1", at(0), false)),
+            vec![(Discovered, Active)]
+        );
+        assert!(tracker.record_activity("s1", at(3), false).is_empty());
+        assert!(tracker.tick(at(7)).is_empty());
+
+        assert_eq!(states(&tracker.tick(at(8))), vec![(Active, Idle)]);
+
+        // Activity while idle resumes the session
+        assert_eq!(
+            states(&tracker.record_activity("s1", at(10), false)),
+            vec![(Idle, Active)]
+        );
+
+        assert_eq!(states(&tracker.tick(at(20))), vec![(Active, Idle)]);
+        let completed = tracker.tick(at(40));
+        assert_eq!(states(&completed), vec![(Idle, Completed)]);
+        assert_eq!(completed[0].first_activity, at(0));
+        assert_eq!(completed[0].last_activity, at(10));
+
+        // Completed sessions are archived and forgotten after a day
+        assert!(tracker.tick(at(60)).is_empty());
+        assert_eq!(
+            states(&tracker.tick(at(40 + 24 * 60))),
+            vec![(Completed, Archived)]
+        );
+        assert_eq!(tracker.state("s1"), None);
+    }
+
+    #[test]
+    fn test_end_marker_and_restore() {
+        use LifecycleState::*;
+        let mut tracker = LifecycleTracker::new(LifecycleConfig::default());
+
+        assert_eq!(
+            states(&tracker.record_activity("s1", at(0), true)),
+            vec![(Discovered, Active), (Active, Completed)]
+        );
+        assert!(tracker.record_activity("s1", at(1), true).is_empty());
+        // What record_activity persists for writes without a transition
+        assert_eq!(tracker.state("s1"), Some(Completed));
+
+        // A session left active before a restart completes once its timeout has passed
+        tracker.restore("s2", Active, at(-120), at(-60));
+        assert_eq!(
+            states(&tracker.tick(at(0))),
+            vec![(Active, Idle), (Idle, Completed)]
+        );
+
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, r#"{{"type":"user","message":{{"content":"hello"}}}}"#).unwrap();
+        assert!(!has_end_marker("claude-code", file.path()));
+        writeln!(
+            file,
+            r#"{{"type":"user","message":{{"content":"<command-name>/exit</command-name>"}}}}"#
+        )
+        .unwrap();
+        assert!(has_end_marker("claude-code", file.path()));
+        assert!(!has_end_marker("codex", file.path()));
+    }
+}
@@ -60,6 +60,13 @@ impl SessionStateManager {
             .or_insert_with(|| SessionState::new(file_size))
     }
 
+    /// Mark a session inactive once its lifecycle has completed
+    pub fn mark_inactive(&mut self, session_id: &str) {
+        if let Some(state) = self.states.get_mut(session_id) {
+            state.is_active = false;
+        }
+    }
+
     /// Check if a session exists in the manager
     pub fn contains(&self, session_id: &str) -> bool {
         self.states.contains_key(session_id)
@@ -133,4 +140,17 @@ mod tests {
         assert!(manager.contains("session1"));
         assert!(!manager.contains("session2"));
     }
+
+    #[test]
+    fn test_mark_inactive() {
+        let mut manager = SessionStateManager::new();
+        manager.get_or_create("session1", 1024);
+
+        manager.mark_inactive("session1");
+        assert!(!manager.get_or_create("session1", 1024).is_active);
+
+        // Activity makes it active again
+        manager.get_or_create("session1", 1024).update(2048);
+        assert!(manager.get_or_create("session1", 2048).is_active);
+    }
 }
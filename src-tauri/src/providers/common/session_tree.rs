@@ -0,0 +1,349 @@
+//! Session tree reconstruction for subagents and sidechains
+//!
+//! Canonical sessions are flat lists, but several providers run subagents:
+//! Claude Code inlines `agent-*.jsonl` sidechains after the Task tool result
+//! (see `agent_merger`), while OpenCode task-tool children and Codex sub-agent
+//! threads are separate sessions that name their parent session. This module
+//! rebuilds the tree from `parent_uuid` / `is_sidechain`, records which tool
+//! call spawned each branch, and computes per-branch metrics.
+//!
+//! Cursor agent mode is not linked: its `store.db` metadata only records the
+//! `mode`, with no parent agent or spawning tool call, so every Cursor session
+//! is a single-branch tree.
+
+use crate::providers::canonical::{CanonicalMessage, ContentBlock, ContentValue, MessageType};
+use chrono::DateTime;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How a branch relates to its parent
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationType {
+    /// The main conversation of a top-level session
+    Main,
+    /// Subagent messages inlined into the parent session (Claude Code agents)
+    Sidechain,
+    /// A separate session spawned by another session (OpenCode task tool)
+    ChildSession,
+}
+
+impl RelationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationType::Main => "main",
+            RelationType::Sidechain => "sidechain",
+            RelationType::ChildSession => "child_session",
+        }
+    }
+}
+
+/// Metrics for the messages of a single branch
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchMetrics {
+    pub message_count: usize,
+    pub user_messages: usize,
+    pub assistant_messages: usize,
+    pub tool_calls: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+/// A branch of a session tree: the main conversation or one subagent run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionBranch {
+    /// Session ID for the main branch, agent ID (or first message UUID) for sidechains
+    pub branch_id: String,
+    pub relation_type: RelationType,
+    /// Branch (or, for child sessions, session) this branch was spawned from
+    pub parent_branch_id: Option<String>,
+    /// Tool call that spawned this branch, if known
+    pub spawning_tool_use_id: Option<String>,
+    /// Message carrying the spawning tool result, if known
+    pub spawning_message_uuid: Option<String>,
+    pub metrics: BranchMetrics,
+}
+
+/// Tree of branches reconstructed from one canonical session
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTree {
+    pub session_id: String,
+    /// Parent session for sessions that were themselves spawned as subagents
+    pub parent_session_id: Option<String>,
+    /// The main branch is always first
+    pub branches: Vec<SessionBranch>,
+}
+
+impl SessionTree {
+    pub fn main_branch(&self) -> &SessionBranch {
+        &self.branches[0]
+    }
+
+    pub fn children_of(&self, branch_id: &str) -> Vec<&SessionBranch> {
+        self.branches
+            .iter()
+            .filter(|b| b.parent_branch_id.as_deref() == Some(branch_id))
+            .collect()
+    }
+
+    pub fn has_subagents(&self) -> bool {
+        self.branches.len() > 1 || self.parent_session_id.is_some()
+    }
+}
+
+/// Codex `session_meta.source` of a sub-agent spawned by another thread
+const CODEX_PARENT_THREAD_POINTER: &str =
+    "/SessionMeta/source/subagent/thread_spawn/parent_thread_id";
+
+/// Tool result that announced a subagent run (Claude `toolUseResult.agentId`)
+struct PendingSpawn {
+    agent_id: String,
+    tool_use_id: Option<String>,
+    message_uuid: String,
+}
+
+/// Build the session tree from canonical messages in file order
+pub fn build_session_tree(session_id: &str, messages: &[CanonicalMessage]) -> SessionTree {
+    let parent_session_id = messages.iter().find_map(parent_session_of);
+
+    let mut branches = vec![SessionBranch {
+        branch_id: session_id.to_string(),
+        relation_type: if parent_session_id.is_some() {
+            RelationType::ChildSession
+        } else {
+            RelationType::Main
+        },
+        parent_branch_id: parent_session_id.clone(),
+        spawning_tool_use_id: None,
+        spawning_message_uuid: None,
+        metrics: BranchMetrics::default(),
+    }];
+
+    let mut branch_of_uuid: HashMap<&str, usize> = HashMap::new();
+    let mut pending_spawn: Option<PendingSpawn> = None;
+
+    for message in messages {
+        let index = if message.is_sidechain == Some(true) {
+            let parent_index = message
+                .parent_uuid
+                .as_deref()
+                .and_then(|parent| branch_of_uuid.get(parent).copied());
+
+            match parent_index {
+                Some(i) if branches[i].relation_type == RelationType::Sidechain => i,
+                _ => {
+                    let spawn = pending_spawn.take();
+                    let parent_branch_id = parent_index.unwrap_or(0);
+                    branches.push(SessionBranch {
+                        branch_id: spawn
+                            .as_ref()
+                            .map(|s| s.agent_id.clone())
+                            .unwrap_or_else(|| message.uuid.clone()),
+                        relation_type: RelationType::Sidechain,
+                        parent_branch_id: Some(branches[parent_branch_id].branch_id.clone()),
+                        spawning_tool_use_id: spawn.as_ref().and_then(|s| s.tool_use_id.clone()),
+                        spawning_message_uuid: spawn.map(|s| s.message_uuid),
+                        metrics: BranchMetrics::default(),
+                    });
+                    branches.len() - 1
+                }
+            }
+        } else {
+            0
+        };
+
+        branch_of_uuid.insert(message.uuid.as_str(), index);
+        record_message(&mut branches[index].metrics, message);
+
+        if let Some(spawn) = spawn_marker(message) {
+            pending_spawn = Some(spawn);
+        }
+    }
+
+    for branch in &mut branches {
+        branch.metrics.duration_ms = duration_ms(&branch.metrics);
+    }
+
+    SessionTree {
+        session_id: session_id.to_string(),
+        parent_session_id,
+        branches,
+    }
+}
+
+/// Build the session tree from canonical JSONL content, skipping unparseable lines
+pub fn build_session_tree_from_content(session_id: &str, content: &str) -> SessionTree {
+    let messages: Vec<CanonicalMessage> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    build_session_tree(session_id, &messages)
+}
+
+/// Parent session named by an OpenCode child session or a Codex sub-agent
+fn parent_session_of(message: &CanonicalMessage) -> Option<String> {
+    let metadata = message.provider_metadata.as_ref()?;
+    metadata
+        .get("parent_session_id")
+        .or_else(|| metadata.pointer(CODEX_PARENT_THREAD_POINTER))?
+        .as_str()
+        .map(|s| s.to_string())
+}
+
+/// Detect a tool result that announces a subagent run
+fn spawn_marker(message: &CanonicalMessage) -> Option<PendingSpawn> {
+    let agent_id = message
+        .tool_use_result
+        .as_ref()?
+        .get("agentId")?
+        .as_str()?
+        .to_string();
+
+    let tool_use_id = match &message.message.content {
+        ContentValue::Structured(blocks) => blocks.iter().find_map(|block| match block {
+            ContentBlock::ToolResult { tool_use_id, .. } => Some(tool_use_id.clone()),
+            _ => None,
+        }),
+        ContentValue::Text(_) => None,
+    };
+
+    Some(PendingSpawn {
+        agent_id,
+        tool_use_id,
+        message_uuid: message.uuid.clone(),
+    })
+}
+
+fn record_message(metrics: &mut BranchMetrics, message: &CanonicalMessage) {
+    metrics.message_count += 1;
+
+    match message.message_type {
+        MessageType::User => metrics.user_messages += 1,
+        MessageType::Assistant => metrics.assistant_messages += 1,
+        MessageType::Meta => {}
+    }
+
+    if let ContentValue::Structured(blocks) = &message.message.content {
+        metrics.tool_calls += blocks
+            .iter()
+            .filter(|block| matches!(block, ContentBlock::ToolUse { .. }))
+            .count();
+    }
+
+    if let Some(usage) = &message.message.usage {
+        metrics.input_tokens += usage.input_tokens.unwrap_or(0) as u64;
+        metrics.output_tokens += usage.output_tokens.unwrap_or(0) as u64;
+    }
+
+    if metrics.start_time.is_none() {
+        metrics.start_time = Some(message.timestamp.clone());
+    }
+    metrics.end_time = Some(message.timestamp.clone());
+}
+
+fn duration_ms(metrics: &BranchMetrics) -> Option<i64> {
+    let start = DateTime::parse_from_rfc3339(metrics.start_time.as_deref()?).ok()?;
+    let end = DateTime::parse_from_rfc3339(metrics.end_time.as_deref()?).ok()?;
+    Some((end - start).num_milliseconds())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Claude session with one Task agent inlined after its tool result
+    const CLAUDE_SESSION: &str = r#"{"uuid":"m1","timestamp":"2025-01-01T10:00:00Z","type":"user","sessionId":"s1","provider":"claude-code","isSidechain":false,"message":{"role":"user","content":"Explore the repo"}}
+{"uuid":"m2","parentUuid":"m1","timestamp":"2025-01-01T10:00:05Z","type":"assistant","sessionId":"s1","provider":"claude-code","isSidechain":false,"message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Task","input":{"prompt":"Find tests"}}],"usage":{"input_tokens":100,"output_tokens":20}}}
+{"uuid":"m3","parentUuid":"m2","timestamp":"2025-01-01T10:01:00Z","type":"user","sessionId":"s1","provider":"claude-code","isSidechain":false,"toolUseResult":{"agentId":"abc123","status":"completed"},"message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"Found 3 tests"}]}}
+{"uuid":"a1","timestamp":"2025-01-01T10:00:10Z","type":"user","sessionId":"s1","provider":"claude-code","isSidechain":true,"message":{"role":"user","content":"Find tests"}}
+{"uuid":"a2","parentUuid":"a1","timestamp":"2025-01-01T10:00:40Z","type":"assistant","sessionId":"s1","provider":"claude-code","isSidechain":true,"message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_2","name":"Glob","input":{"pattern":"**/*test*"}}],"usage":{"input_tokens":50,"output_tokens":10}}}
+{"uuid":"m4","parentUuid":"m3","timestamp":"2025-01-01T10:01:05Z","type":"assistant","sessionId":"s1","provider":"claude-code","isSidechain":false,"message":{"role":"assistant","content":"There are 3 tests"}}"#;
+
+    #[test]
+    fn test_claude_sidechain_becomes_branch() {
+        let tree = build_session_tree_from_content("s1", CLAUDE_SESSION);
+
+        assert_eq!(tree.branches.len(), 2);
+        assert!(tree.has_subagents());
+
+        let main = tree.main_branch();
+        assert_eq!(main.relation_type, RelationType::Main);
+        assert_eq!(main.metrics.message_count, 4);
+        assert_eq!(main.metrics.tool_calls, 1);
+        assert_eq!(main.metrics.duration_ms, Some(65_000));
+
+        let children = tree.children_of("s1");
+        assert_eq!(children.len(), 1);
+        let agent = children[0];
+        assert_eq!(agent.branch_id, "abc123");
+        assert_eq!(agent.relation_type, RelationType::Sidechain);
+        assert_eq!(agent.spawning_tool_use_id.as_deref(), Some("toolu_1"));
+        assert_eq!(agent.spawning_message_uuid.as_deref(), Some("m3"));
+        assert_eq!(agent.metrics.message_count, 2);
+        assert_eq!(agent.metrics.input_tokens, 50);
+        assert_eq!(agent.metrics.duration_ms, Some(30_000));
+    }
+
+    #[test]
+    fn test_sidechain_without_spawn_marker() {
+        let content = r#"{"uuid":"m1","timestamp":"2025-01-01T10:00:00Z","type":"user","sessionId":"s2","provider":"codex","message":{"role":"user","content":"Hi"}}
+{"uuid":"x1","parentUuid":"m1","timestamp":"2025-01-01T10:00:01Z","type":"assistant","sessionId":"s2","provider":"codex","isSidechain":true,"message":{"role":"assistant","content":"sub-task"}}"#;
+
+        let tree = build_session_tree_from_content("s2", content);
+
+        assert_eq!(tree.branches.len(), 2);
+        assert_eq!(tree.branches[1].branch_id, "x1");
+        assert_eq!(tree.branches[1].parent_branch_id.as_deref(), Some("s2"));
+        assert_eq!(tree.branches[1].spawning_tool_use_id, None);
+    }
+
+    #[test]
+    fn test_child_session_links_to_parent() {
+        let content = r#"{"uuid":"c1","timestamp":"2025-01-01T10:00:00Z","type":"user","sessionId":"child","provider":"opencode","providerMetadata":{"opencode_type":"user","parent_session_id":"parent"},"message":{"role":"user","content":"Sub task"}}"#;
+
+        let tree = build_session_tree_from_content("child", content);
+
+        assert_eq!(tree.parent_session_id.as_deref(), Some("parent"));
+        assert_eq!(tree.main_branch().relation_type, RelationType::ChildSession);
+        assert_eq!(
+            tree.main_branch().parent_branch_id.as_deref(),
+            Some("parent")
+        );
+    }
+
+    #[test]
+    fn test_codex_subagent_links_to_parent_thread() {
+        let rollout = r#"{"timestamp":"2025-10-20T06:46:43.215Z","type":"session_meta","payload":{"id":"child-thread","timestamp":"2025-10-20T06:46:43.196Z","cwd":"/work","originator":"codex_cli_rs","cli_version":"0.60.0","source":{"subagent":{"thread_spawn":{"parent_thread_id":"parent-thread","depth":1}}}}}
+{"timestamp":"2025-10-20T06:46:47.990Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Review the diff"}]}}"#;
+        let (messages, _) = crate::providers::codex::converter::convert_session_lines(
+            rollout.lines(),
+            "child-thread",
+        );
+
+        let tree = build_session_tree("child-thread", &messages);
+
+        assert_eq!(tree.parent_session_id.as_deref(), Some("parent-thread"));
+        assert_eq!(tree.main_branch().relation_type, RelationType::ChildSession);
+        assert_eq!(tree.main_branch().metrics.user_messages, 1);
+    }
+
+    #[test]
+    fn test_codex_cli_session_has_no_parent() {
+        let rollout = r#"{"timestamp":"2025-10-20T06:46:43.215Z","type":"session_meta","payload":{"id":"s3","timestamp":"2025-10-20T06:46:43.196Z","cwd":"/work","originator":"codex_cli_rs","cli_version":"0.60.0","source":"cli"}}"#;
+        let (messages, _) =
+            crate::providers::codex::converter::convert_session_lines(rollout.lines(), "s3");
+
+        let tree = build_session_tree("s3", &messages);
+
+        assert_eq!(tree.parent_session_id, None);
+        assert!(!tree.has_subagents());
+    }
+}
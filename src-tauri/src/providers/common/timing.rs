@@ -3,8 +3,8 @@
 //! This module provides functions to extract start time, end time, and duration
 //! from canonical JSONL session files.
 
+use crate::providers::common::read_session_file;
 use chrono::{DateTime, Utc};
-use std::fs;
 use std::path::Path;
 
 /// Type alias for timing data tuple
@@ -48,8 +48,8 @@ pub type TimingData = (
 /// ```
 #[allow(dead_code)]
 pub fn extract_timing_from_jsonl(file_path: &Path) -> Result<TimingData, String> {
-    let content = fs::read_to_string(file_path)
-        .map_err(|e| format!("Failed to read snapshot file: {}", e))?;
+    let content =
+        read_session_file(file_path).map_err(|e| format!("Failed to read snapshot file: {}", e))?;
 
     let lines: Vec<&str> = content
         .lines()
@@ -0,0 +1,323 @@
+//! Dynamic set of per-project watch subscriptions
+//!
+//! Providers that keep one directory per project under a common root
+//! (Claude Code `projects/{name}`, Gemini `tmp/{hash}/chats`) watch that root
+//! non-recursively so new projects are noticed, and add or drop the
+//! per-project subscriptions as directories come and go or as the provider's
+//! project selection changes.
+
+use crate::config::ProviderConfig;
+use crate::logging::{log_info, log_warn};
+use crate::providers::common::WATCH_SET_RESCAN_INTERVAL;
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Projects added and removed by a single [`WatchSet::sync`]
+#[derive(Debug, Default, PartialEq)]
+pub struct WatchSetChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl WatchSetChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub struct WatchSet {
+    provider_id: String,
+    root: PathBuf,
+    /// Sub-directory inside each project dir that holds the session files
+    leaf: Option<&'static str>,
+    mode: RecursiveMode,
+    excluded: &'static [&'static str],
+    watched: BTreeMap<String, PathBuf>,
+    last_scan: Instant,
+}
+
+impl WatchSet {
+    pub fn new(
+        provider_id: &str,
+        root: PathBuf,
+        leaf: Option<&'static str>,
+        mode: RecursiveMode,
+    ) -> Self {
+        Self {
+            provider_id: provider_id.to_string(),
+            root,
+            leaf,
+            mode,
+            excluded: &[],
+            watched: BTreeMap::new(),
+            last_scan: Instant::now(),
+        }
+    }
+
+    /// Directory names under the root that are never projects (e.g. Gemini's `bin`)
+    pub fn excluding(mut self, names: &'static [&'static str]) -> Self {
+        self.excluded = names;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Subscribe to the root itself so new project directories produce events
+    pub fn watch_root<W: Watcher>(&self, watcher: &mut W) -> notify::Result<()> {
+        watcher.watch(&self.root, RecursiveMode::NonRecursive)
+    }
+
+    /// Projects that should be watched right now, keyed by directory name
+    pub fn desired(&self, config: &ProviderConfig) -> BTreeMap<String, PathBuf> {
+        let mut desired = BTreeMap::new();
+        let Ok(entries) = std::fs::read_dir(&self.root) else {
+            return desired;
+        };
+
+        for entry in entries.flatten() {
+            let Some(name) = entry.file_name().to_str().map(|s| s.to_string()) else {
+                continue;
+            };
+            if name.starts_with('.') || self.excluded.contains(&name.as_str()) {
+                continue;
+            }
+            if config.project_selection != "ALL" && !config.selected_projects.contains(&name) {
+                continue;
+            }
+
+            let mut path = entry.path();
+            if let Some(leaf) = self.leaf {
+                path = path.join(leaf);
+            }
+            if path.is_dir() {
+                desired.insert(name, path);
+            }
+        }
+
+        desired
+    }
+
+    /// Bring the watcher's subscriptions in line with [`WatchSet::desired`]
+    ///
+    /// Callers should feed `file_events(&changes.added)` to their event
+    /// pipeline: files created before the watch was registered raise no event.
+    pub fn sync<W: Watcher>(
+        &mut self,
+        watcher: &mut W,
+        config: &ProviderConfig,
+    ) -> WatchSetChanges {
+        self.last_scan = Instant::now();
+        let desired = self.desired(config);
+        let mut changes = WatchSetChanges::default();
+
+        let stale: Vec<String> = self
+            .watched
+            .keys()
+            .filter(|name| !desired.contains_key(*name))
+            .cloned()
+            .collect();
+        for name in stale {
+            if let Some(path) = self.watched.remove(&name) {
+                // The OS drops the watch itself when the directory is deleted
+                let _ = watcher.unwatch(&path);
+                let _ = log_info(
+                    &self.provider_id,
+                    &format!("📁 Stopped watching project: {}", path.display()),
+                );
+                changes.removed.push(name);
+            }
+        }
+
+        for (name, path) in desired {
+            if self.watched.contains_key(&name) {
+                continue;
+            }
+            match watcher.watch(&path, self.mode) {
+                Ok(()) => {
+                    let _ = log_info(
+                        &self.provider_id,
+                        &format!("📂 Watching project: {}", path.display()),
+                    );
+                    self.watched.insert(name.clone(), path);
+                    changes.added.push(name);
+                }
+                Err(e) => {
+                    let _ = log_warn(
+                        &self.provider_id,
+                        &format!("⚠ Failed to watch {}: {}", path.display(), e),
+                    );
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Whether an event touches a direct child of the root (project created or removed)
+    pub fn is_root_event(&self, event: &Event) -> bool {
+        matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_)
+        ) && event
+            .paths
+            .iter()
+            .any(|path| path.parent() == Some(self.root.as_path()))
+    }
+
+    /// Modify events for the files already in the given projects
+    ///
+    /// Covers files the watcher never got events for, such as everything
+    /// written while the event channel was overflowing.
+    pub fn file_events(&self, names: &[String]) -> Vec<Event> {
+        names
+            .iter()
+            .filter_map(|name| self.watched.get(name))
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .map(|path| Event::new(EventKind::Modify(ModifyKind::Any)).add_path(path))
+            .collect()
+    }
+
+    /// Names of the watched projects
+    pub fn names(&self) -> Vec<String> {
+        self.watched.keys().cloned().collect()
+    }
+
+    /// Periodic rescan catches late leaf dirs and selection changes
+    pub fn rescan_due(&self) -> bool {
+        self.last_scan.elapsed() >= WATCH_SET_RESCAN_INTERVAL
+    }
+
+    pub fn len(&self) -> usize {
+        self.watched.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.watched.is_empty()
+    }
+
+    pub fn watched_paths(&self) -> Vec<String> {
+        self.watched
+            .values()
+            .map(|path| path.display().to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::{Config, RecommendedWatcher};
+    use tempfile::TempDir;
+
+    fn config(selection: &str, selected: &[&str]) -> ProviderConfig {
+        ProviderConfig {
+            project_selection: selection.to_string(),
+            selected_projects: selected.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_desired_respects_selection_leaf_and_exclusions() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("aaa/chats")).unwrap();
+        std::fs::create_dir_all(temp.path().join("bbb")).unwrap();
+        std::fs::create_dir_all(temp.path().join("bin/chats")).unwrap();
+
+        let set = WatchSet::new(
+            "test",
+            temp.path().to_path_buf(),
+            Some("chats"),
+            RecursiveMode::NonRecursive,
+        )
+        .excluding(&["bin"]);
+
+        let all: Vec<String> = set.desired(&config("ALL", &[])).into_keys().collect();
+        assert_eq!(all, vec!["aaa".to_string()]);
+
+        let selected = set.desired(&config("SELECTED", &["bbb"]));
+        assert!(selected.is_empty());
+    }
+
+    #[test]
+    fn test_desired_lists_project_directories_only() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("project1")).unwrap();
+        std::fs::create_dir_all(temp.path().join("project2")).unwrap();
+        std::fs::create_dir_all(temp.path().join(".hidden")).unwrap();
+        std::fs::write(temp.path().join("not_a_project.txt"), "content").unwrap();
+
+        let set = WatchSet::new(
+            "test",
+            temp.path().to_path_buf(),
+            None,
+            RecursiveMode::Recursive,
+        );
+
+        let all: Vec<String> = set.desired(&config("ALL", &[])).into_keys().collect();
+        assert_eq!(all, vec!["project1".to_string(), "project2".to_string()]);
+    }
+
+    #[test]
+    fn test_sync_adds_and_removes_projects() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("one")).unwrap();
+
+        let mut watcher = RecommendedWatcher::new(|_| {}, Config::default()).unwrap();
+        let mut set = WatchSet::new(
+            "test",
+            temp.path().to_path_buf(),
+            None,
+            RecursiveMode::Recursive,
+        );
+
+        let changes = set.sync(&mut watcher, &config("ALL", &[]));
+        assert_eq!(changes.added, vec!["one".to_string()]);
+
+        // A project that already holds a session when it is first seen
+        std::fs::create_dir_all(temp.path().join("two")).unwrap();
+        std::fs::write(temp.path().join("two/first.jsonl"), "{}").unwrap();
+        let changes = set.sync(&mut watcher, &config("SELECTED", &["two"]));
+        assert_eq!(changes.added, vec!["two".to_string()]);
+        let events = set.file_events(&changes.added);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].paths, vec![temp.path().join("two/first.jsonl")]);
+        assert_eq!(changes.removed, vec!["one".to_string()]);
+        assert_eq!(set.len(), 1);
+
+        let changes = set.sync(&mut watcher, &config("SELECTED", &["two"]));
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_file_events_cover_watched_projects_only() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("one/nested")).unwrap();
+        std::fs::create_dir_all(temp.path().join("two")).unwrap();
+        std::fs::write(temp.path().join("one/a.jsonl"), "{}").unwrap();
+        std::fs::write(temp.path().join("two/b.jsonl"), "{}").unwrap();
+
+        let mut watcher = RecommendedWatcher::new(|_| {}, Config::default()).unwrap();
+        let mut set = WatchSet::new(
+            "test",
+            temp.path().to_path_buf(),
+            None,
+            RecursiveMode::NonRecursive,
+        );
+        set.sync(&mut watcher, &config("SELECTED", &["one"]));
+
+        let events = set.file_events(&set.names());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].paths, vec![temp.path().join("one/a.jsonl")]);
+        assert!(matches!(events[0].kind, EventKind::Modify(_)));
+    }
+}
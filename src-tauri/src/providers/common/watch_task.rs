@@ -0,0 +1,433 @@
+//! Async watcher tasks
+//!
+//! Provider watchers run as tokio tasks fed by notify through a tokio
+//! channel. A task stops when its own token is cancelled (`stop()`) or the
+//! parent token shared by all watchers is, and processes the events it is
+//! still debouncing before it exits, so nothing seen before shutdown is lost.
+//!
+//! The parent token is separate from the [`ShutdownCoordinator`] on purpose:
+//! the event handlers must keep running until every watcher has flushed.
+//!
+//! [`ShutdownCoordinator`]: crate::shutdown::ShutdownCoordinator
+
+use crate::logging::{log_info, log_warn};
+use crate::providers::common::{
+    WATCH_DEBOUNCE, WATCH_DEBOUNCE_CAPACITY, WATCH_DEBOUNCE_MAX, WATCH_EVENT_CHANNEL_CAPACITY,
+};
+use notify::{Event, EventHandler};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// notify event handler that forwards into a bounded tokio channel
+///
+/// notify calls the handler from its own thread, which must never block, so
+/// events arriving while the channel is full are dropped and counted, and the
+/// receiver is told to rescan.
+#[derive(Debug, Clone)]
+pub struct FsEventSender {
+    provider_id: String,
+    tx: mpsc::Sender<notify::Result<Event>>,
+    dropped: Arc<AtomicU64>,
+    overflowed: Arc<AtomicBool>,
+}
+
+impl EventHandler for FsEventSender {
+    fn handle_event(&mut self, event: notify::Result<Event>) {
+        match self.tx.try_send(event) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                self.overflowed.store(true, Ordering::Relaxed);
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped == 1 || dropped.is_multiple_of(1000) {
+                    let _ = log_warn(
+                        &self.provider_id,
+                        &format!("⚠ Watch event channel full, {} events dropped", dropped),
+                    );
+                }
+            }
+            // The receiver is gone once the task exited; late events are moot
+            Err(mpsc::error::TrySendError::Closed(_)) => {}
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FsEventReceiver {
+    rx: mpsc::Receiver<notify::Result<Event>>,
+    dropped: Arc<AtomicU64>,
+    overflowed: Arc<AtomicBool>,
+}
+
+impl FsEventReceiver {
+    pub async fn recv(&mut self) -> Option<notify::Result<Event>> {
+        self.rx.recv().await
+    }
+
+    /// Events dropped because the channel was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Whether events were dropped since the last call; the caller should rescan
+    pub fn take_overflow(&self) -> bool {
+        self.overflowed.swap(false, Ordering::Relaxed)
+    }
+}
+
+pub fn fs_event_channel(provider_id: &str) -> (FsEventSender, FsEventReceiver) {
+    fs_event_channel_with_capacity(provider_id, WATCH_EVENT_CHANNEL_CAPACITY)
+}
+
+fn fs_event_channel_with_capacity(
+    provider_id: &str,
+    capacity: usize,
+) -> (FsEventSender, FsEventReceiver) {
+    let (tx, rx) = mpsc::channel(capacity);
+    let dropped = Arc::new(AtomicU64::new(0));
+    let overflowed = Arc::new(AtomicBool::new(false));
+    let sender = FsEventSender {
+        provider_id: provider_id.to_string(),
+        tx,
+        dropped: Arc::clone(&dropped),
+        overflowed: Arc::clone(&overflowed),
+    };
+    (
+        sender,
+        FsEventReceiver {
+            rx,
+            dropped,
+            overflowed,
+        },
+    )
+}
+
+/// Resolves when the owning watcher is stopped or all watchers are shut down
+#[derive(Debug)]
+pub struct WatchCancellation {
+    token: CancellationToken,
+}
+
+impl WatchCancellation {
+    pub async fn cancelled(&mut self) {
+        self.token.cancelled().await
+    }
+
+    /// Stop from inside the task; the next wait resolves and pending events flush
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Stop because the provider was disabled in config
+    pub fn stop_disabled(&self, provider_id: &str, is_running: &Mutex<bool>) {
+        let _ = log_info(
+            provider_id,
+            "🛑 Provider disabled in config, stopping file monitoring",
+        );
+        if let Ok(mut running) = is_running.lock() {
+            *running = false;
+        }
+        self.cancel();
+    }
+}
+
+/// Handle to a running watcher task
+#[derive(Debug)]
+pub struct WatchTask {
+    token: CancellationToken,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl WatchTask {
+    /// Spawn `run`, cancelled by `stop()` or by cancelling `shutdown`
+    pub fn spawn<F, Fut>(shutdown: &CancellationToken, run: F) -> Self
+    where
+        F: FnOnce(WatchCancellation) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let token = shutdown.child_token();
+        let cancellation = WatchCancellation {
+            token: token.clone(),
+        };
+        let handle = tauri::async_runtime::spawn(run(cancellation));
+
+        Self {
+            token,
+            handle: Mutex::new(Some(handle)),
+        }
+    }
+
+    /// Ask the task to flush and exit; returns immediately
+    pub fn stop(&self) {
+        self.token.cancel();
+    }
+
+    /// Wait for the task to finish flushing, at most `timeout`
+    pub async fn join(&self, timeout: Duration) -> Result<(), String> {
+        let handle = self.handle.lock().map_err(|e| e.to_string())?.take();
+        let Some(handle) = handle else {
+            return Ok(());
+        };
+
+        match tokio::time::timeout(timeout, handle).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(format!("Watcher task failed: {}", e)),
+            Err(_) => Err(format!(
+                "Watcher task did not stop within {}s",
+                timeout.as_secs()
+            )),
+        }
+    }
+}
+
+/// Run blocking file work (reads, conversion) on the blocking pool so a large
+/// session doesn't stall the async workers
+///
+/// A panic is re-raised on the calling task, where the heartbeat reports it.
+pub async fn run_blocking<T, F>(work: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(work).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => panic!("Blocking watcher work was cancelled: {}", e),
+    }
+}
+
+#[derive(Debug)]
+struct PendingEvent {
+    event: Event,
+    first_seen: Instant,
+    last_seen: Instant,
+}
+
+/// Coalesces bursts of events per path
+///
+/// An event is released once its path has been quiet for `quiet`, or
+/// `max_delay` after it was first seen however busy the path stays. At most
+/// `capacity` paths are held; beyond that the oldest are released early.
+#[derive(Debug)]
+pub struct Debouncer {
+    quiet: Duration,
+    max_delay: Duration,
+    capacity: usize,
+    pending: HashMap<PathBuf, PendingEvent>,
+}
+
+impl Default for Debouncer {
+    fn default() -> Self {
+        Self::new(WATCH_DEBOUNCE, WATCH_DEBOUNCE_MAX, WATCH_DEBOUNCE_CAPACITY)
+    }
+}
+
+impl Debouncer {
+    pub fn new(quiet: Duration, max_delay: Duration, capacity: usize) -> Self {
+        Self {
+            quiet,
+            max_delay,
+            capacity,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Queue an event, replacing an earlier one for the same path
+    pub fn push(&mut self, event: Event, now: Instant) {
+        let key = event.paths.first().cloned().unwrap_or_default();
+        match self.pending.get_mut(&key) {
+            Some(pending) => {
+                pending.event = event;
+                pending.last_seen = now;
+            }
+            None => {
+                self.pending.insert(
+                    key,
+                    PendingEvent {
+                        event,
+                        first_seen: now,
+                        last_seen: now,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Events due at `now`, oldest first
+    pub fn ready(&mut self, now: Instant) -> Vec<Event> {
+        let overflow = self.pending.len().saturating_sub(self.capacity);
+        let mut by_age: Vec<(PathBuf, Instant)> = self
+            .pending
+            .iter()
+            .map(|(path, pending)| (path.clone(), pending.first_seen))
+            .collect();
+        by_age.sort_by_key(|(_, first_seen)| *first_seen);
+
+        let due: Vec<PathBuf> = by_age
+            .into_iter()
+            .enumerate()
+            .filter(|(index, (path, _))| {
+                *index < overflow
+                    || self
+                        .pending
+                        .get(path)
+                        .is_some_and(|pending| now >= self.due_at(pending))
+            })
+            .map(|(_, (path, _))| path)
+            .collect();
+
+        due.iter()
+            .filter_map(|path| self.pending.remove(path))
+            .map(|pending| pending.event)
+            .collect()
+    }
+
+    /// Everything still pending, oldest first (used when stopping)
+    pub fn drain(&mut self) -> Vec<Event> {
+        let mut pending: Vec<PendingEvent> = self.pending.drain().map(|(_, p)| p).collect();
+        pending.sort_by_key(|p| p.first_seen);
+        pending.into_iter().map(|p| p.event).collect()
+    }
+
+    /// When to wake up next: the earliest due event, or `idle` from now
+    pub fn wake_at(&self, now: Instant, idle: Duration) -> tokio::time::Instant {
+        let due = self
+            .pending
+            .values()
+            .map(|pending| self.due_at(pending))
+            .min()
+            .unwrap_or(now + idle)
+            .min(now + idle);
+        tokio::time::Instant::from_std(due)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    fn due_at(&self, pending: &PendingEvent) -> Instant {
+        (pending.last_seen + self.quiet).min(pending.first_seen + self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, ModifyKind};
+    use notify::EventKind;
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn test_debouncer_coalesces_until_quiet_or_max_delay() {
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        let mut debouncer = Debouncer::new(ms(100), ms(250), 16);
+
+        debouncer.push(
+            event(EventKind::Create(CreateKind::File), "/a.jsonl"),
+            start,
+        );
+        debouncer.push(
+            event(EventKind::Modify(ModifyKind::Any), "/a.jsonl"),
+            start + ms(50),
+        );
+        debouncer.push(
+            event(EventKind::Create(CreateKind::File), "/b.jsonl"),
+            start + ms(60),
+        );
+        assert_eq!(debouncer.len(), 2);
+        assert!(debouncer.ready(start + ms(120)).is_empty());
+
+        // a is quiet at 150, b at 160; the latest event for a wins
+        let ready = debouncer.ready(start + ms(155));
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].kind, EventKind::Modify(ModifyKind::Any));
+
+        // A path that never goes quiet is still released after max_delay
+        for step in 0..5 {
+            debouncer.push(
+                event(EventKind::Modify(ModifyKind::Any), "/c.jsonl"),
+                start + ms(200 + step * 60),
+            );
+        }
+        let ready = debouncer.ready(start + ms(455));
+        assert_eq!(ready.len(), 2);
+        assert!(debouncer.is_empty());
+    }
+
+    #[test]
+    fn test_debouncer_capacity_releases_oldest() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_secs(10), Duration::from_secs(60), 2);
+        for (i, path) in ["/1", "/2", "/3"].iter().enumerate() {
+            debouncer.push(
+                event(EventKind::Modify(ModifyKind::Any), path),
+                start + Duration::from_millis(i as u64),
+            );
+        }
+
+        let ready = debouncer.ready(start);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].paths[0], PathBuf::from("/1"));
+        assert_eq!(debouncer.drain().len(), 2);
+    }
+
+    #[test]
+    fn test_fs_event_channel_drops_when_full() {
+        let (mut tx, mut rx) = fs_event_channel_with_capacity("test", 2);
+        for path in ["/1", "/2", "/3", "/4"] {
+            tx.handle_event(Ok(event(EventKind::Modify(ModifyKind::Any), path)));
+        }
+
+        assert_eq!(rx.dropped(), 2);
+        assert!(rx.take_overflow());
+        assert!(!rx.take_overflow());
+        assert!(rx.rx.try_recv().is_ok());
+        assert!(rx.rx.try_recv().is_ok());
+        assert!(rx.rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watch_task_flushes_on_shutdown() {
+        let shutdown = CancellationToken::new();
+        let (mut tx, mut rx) = fs_event_channel("test");
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+
+        let task = WatchTask::spawn(&shutdown, move |mut cancellation| async move {
+            let mut debouncer =
+                Debouncer::new(Duration::from_secs(60), Duration::from_secs(60), 16);
+            loop {
+                tokio::select! {
+                    _ = cancellation.cancelled() => break,
+                    Some(Ok(event)) = rx.recv() => debouncer.push(event, Instant::now()),
+                }
+            }
+            for event in debouncer.drain() {
+                let _ = done_tx.send(event.paths[0].clone());
+            }
+        });
+
+        tx.handle_event(Ok(event(
+            EventKind::Modify(ModifyKind::Any),
+            "/pending.jsonl",
+        )));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        shutdown.cancel();
+
+        task.join(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(done_rx.recv().await, Some(PathBuf::from("/pending.jsonl")));
+    }
+}
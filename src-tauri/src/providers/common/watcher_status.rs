@@ -1,3 +1,4 @@
+use super::format_version::UnsupportedFormat;
 use serde::{Deserialize, Serialize};
 
 /// Generic watcher status that all providers share
@@ -8,4 +9,10 @@ pub struct WatcherStatus {
     pub pending_uploads: usize,
     pub processing_uploads: usize,
     pub failed_uploads: usize,
+    /// Directories currently subscribed to (providers with a dynamic watch set)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub watched_paths: Vec<String>,
+    /// Sessions refused because their format version isn't supported
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unsupported_formats: Vec<UnsupportedFormat>,
 }
@@ -1,7 +1,7 @@
+use super::parser::CopilotEvent;
 use crate::providers::canonical::{
-    CanonicalMessage, ContentBlock, ContentValue, MessageContent, MessageType,
+    CanonicalMessage, ContentBlock, ContentValue, MessageContent, MessageType, CANONICAL_VERSION,
 };
-use super::parser::CopilotEvent;
 use anyhow::Result;
 use serde_json::Value;
 
@@ -40,6 +40,7 @@ fn convert_session_start(
     cwd: Option<&str>,
 ) -> Result<CanonicalMessage> {
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: event.id.clone(),
         timestamp: event.timestamp.clone(),
         message_type: MessageType::Meta,
@@ -78,6 +79,7 @@ fn convert_user_message(
         .to_string();
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: event.id.clone(),
         timestamp: event.timestamp.clone(),
         message_type: MessageType::User,
@@ -122,6 +124,7 @@ fn convert_assistant_message(
         .map(String::from);
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: id,
         timestamp,
         message_type: MessageType::Assistant,
@@ -182,6 +185,7 @@ fn convert_info_message(
     }
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: id,
         timestamp,
         message_type: MessageType::Meta,
@@ -225,6 +229,7 @@ fn convert_abort_message(
     let message_text = format!("Session aborted: {}", reason);
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: id,
         timestamp,
         message_type: MessageType::Meta,
@@ -313,6 +318,7 @@ fn convert_tool_use(
         .map(String::from);
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: call_id,
         timestamp,
         message_type: MessageType::Assistant,
@@ -394,6 +400,7 @@ fn convert_tool_result(
     };
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: format!("{}_result", id),
         timestamp,
         message_type: MessageType::User,  // Tool results are USER messages
@@ -432,6 +439,7 @@ fn convert_unknown_message(
     let event_type = &event.event_type;
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: id,
         timestamp,
         message_type: MessageType::Meta,
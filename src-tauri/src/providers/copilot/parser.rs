@@ -1,3 +1,6 @@
+use crate::providers::canonical::fidelity::ConversionStats;
+use crate::providers::canonical::CanonicalMessage;
+use crate::providers::common::format_version::{FormatSpec, FormatVersionRange, NewerVersions};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use shellexpand::tilde;
@@ -54,8 +57,44 @@ pub struct ParsedSession {
     pub duration_ms: Option<i64>,
     pub jsonl_content: String,
     pub cwd: Option<String>,
+    pub stats: ConversionStats,
 }
 
+/// Event types `convert_event_to_canonical` maps explicitly
+const KNOWN_EVENT_TYPES: &[&str] = &[
+    "session.start",
+    "user.message",
+    "assistant.message",
+    "tool.execution_start",
+    "tool.execution_complete",
+    "session.info",
+    "abort",
+];
+
+/// Keys read from each event (`data` is kept whole in provider metadata)
+const KNOWN_EVENT_KEYS: &[&str] = &["type", "data", "id", "timestamp", "parentId"];
+
+/// Copilot session formats this parser can decode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CopilotFormat {
+    /// Event-based JSONL (`session.start`, `user.message`, `tool.execution_*`, ...)
+    EventsV1,
+}
+
+/// Versioned by `session.start` `data.version`
+pub static COPILOT_FORMATS: FormatSpec<CopilotFormat> = FormatSpec {
+    provider_id: "github-copilot",
+    version_field: "session.start data.version",
+    ranges: &[FormatVersionRange {
+        min: "1",
+        max: Some("2"),
+        decoder: CopilotFormat::EventsV1,
+    }],
+    unversioned: None,
+    // A schema version: v2 is a different event format
+    newer: NewerVersions::Refuse,
+};
+
 pub struct CopilotParser {
     #[allow(dead_code)]
     storage_path: PathBuf,
@@ -174,6 +213,43 @@ fn match_trusted_folder(path: &str, trusted_folders: &[String]) -> Option<String
     match_trusted_folder_with_cwd(path, trusted_folders).map(|(name, _cwd)| name)
 }
 
+/// Convert version 1 events to canonical messages
+fn convert_events(
+    content: &str,
+    events: &[CopilotEvent],
+    session_id: &str,
+    cwd: Option<&str>,
+) -> (Vec<CanonicalMessage>, ConversionStats) {
+    use crate::providers::copilot::converter::convert_event_to_canonical;
+
+    let mut canonical_messages = Vec::new();
+    let mut stats = ConversionStats::new();
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        if let Ok(raw) = serde_json::from_str::<serde_json::Value>(line) {
+            stats.observe_keys(&raw, "", KNOWN_EVENT_KEYS);
+        }
+    }
+    for event in events {
+        if !KNOWN_EVENT_TYPES.contains(&event.event_type.as_str()) {
+            stats.note_unknown_event_type(&event.event_type);
+        }
+        match convert_event_to_canonical(event, session_id, cwd) {
+            Ok(messages) if messages.is_empty() => stats.record_skipped(&event.event_type),
+            Ok(mut messages) => {
+                stats.record_converted(messages.len());
+                canonical_messages.append(&mut messages);
+            }
+            Err(e) => {
+                // Log error but continue processing other events
+                stats.record_failed();
+                eprintln!("Warning: Failed to convert event: {}", e);
+            }
+        }
+    }
+
+    (canonical_messages, stats)
+}
+
 impl CopilotParser {
     #[allow(dead_code)]
     pub fn new(storage_path: PathBuf) -> Self {
@@ -246,19 +322,14 @@ impl CopilotParser {
             .map(|s| s.to_string())
             .unwrap_or_else(|| session_start_data.session_id.clone());
 
-        // Convert events to canonical format
-        use crate::providers::copilot::converter::convert_event_to_canonical;
-
-        let mut canonical_messages = Vec::new();
-        for event in &events {
-            match convert_event_to_canonical(event, &session_id, cwd.as_deref()) {
-                Ok(mut messages) => canonical_messages.append(&mut messages),
-                Err(e) => {
-                    // Log error but continue processing other events
-                    eprintln!("Warning: Failed to convert event: {}", e);
+        // Convert events with the decoder for the session's format version
+        let version = session_start_data.version.to_string();
+        let (canonical_messages, stats) =
+            match COPILOT_FORMATS.resolve(&session_id, Some(&version))? {
+                CopilotFormat::EventsV1 => {
+                    convert_events(&content, &events, &session_id, cwd.as_deref())
                 }
-            }
-        }
+            };
 
         // Convert canonical messages to JSONL
         let jsonl_content = canonical_messages
@@ -275,6 +346,7 @@ impl CopilotParser {
             duration_ms,
             jsonl_content,
             cwd,
+            stats,
         })
     }
 
@@ -349,6 +421,12 @@ mod tests {
         assert!(result.session_start_time.is_some());
         assert!(result.session_end_time.is_some());
 
+        // Every event is known and fully read
+        assert_eq!(result.stats.records_seen, 3);
+        assert_eq!(result.stats.failed, 0);
+        assert!(result.stats.unknown_event_types.is_empty());
+        assert!(result.stats.unknown_keys.is_empty());
+
         // Verify it's canonical format (should have multiple lines, each a CanonicalMessage)
         let lines: Vec<&str> = result.jsonl_content.lines().collect();
         assert!(lines.len() >= 3, "Expected at least 3 canonical messages");
@@ -362,6 +440,27 @@ mod tests {
         assert_eq!(first_msg.get("provider").and_then(|v| v.as_str()), Some("github-copilot"));
     }
 
+    #[test]
+    fn test_unsupported_format_version_is_refused() {
+        let temp_dir = tempdir().unwrap();
+        let session_file = temp_dir.path().join("future-session.jsonl");
+        fs::write(
+            &session_file,
+            r#"{"type":"session.start","data":{"sessionId":"future-session","version":2,"producer":"copilot-agent","copilotVersion":"1.0.0","startTime":"2025-01-01T10:00:00.000Z"},"id":"event-1","timestamp":"2025-01-01T10:00:00.000Z","parentId":null}"#,
+        )
+        .unwrap();
+
+        let parser = CopilotParser::new(temp_dir.path().to_path_buf());
+        let error = parser.parse_session(&session_file).unwrap_err();
+        assert!(error.contains("Unsupported github-copilot format version 2"));
+
+        let unsupported =
+            crate::providers::common::format_version::unsupported_formats("github-copilot");
+        assert!(unsupported
+            .iter()
+            .any(|u| u.session_id == "future-session" && u.version.as_deref() == Some("2")));
+    }
+
     #[test]
     fn test_get_all_sessions() {
         let temp_dir = tempdir().unwrap();
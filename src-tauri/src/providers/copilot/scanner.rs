@@ -92,6 +92,7 @@ fn parse_copilot_session(
 
     fs::write(&cache_path, &parsed.jsonl_content)
         .map_err(|e| format!("Failed to write canonical cache file: {}", e))?;
+    parsed.stats.persist("github-copilot", &parsed.session_id);
 
     // Get file size of canonical cache file
     let file_size = fs::metadata(&cache_path).map(|m| m.len()).unwrap_or(0);
@@ -1,17 +1,21 @@
 use crate::config::load_provider_config;
+use crate::config_service::ProviderConfigUpdates;
 use crate::events::{EventBus, SessionEventPayload};
 use crate::logging::{log_error, log_info};
 use crate::providers::common::{
-    extract_session_id_from_filename, get_canonical_path, get_file_size, has_extension,
-    should_skip_file, SessionStateManager, WatcherStatus, EVENT_TIMEOUT, FILE_WATCH_POLL_INTERVAL,
+    extract_session_id_from_filename, format_version, fs_event_channel, get_canonical_path,
+    get_file_size, has_extension, run_blocking, should_skip_file, Debouncer, FsEventReceiver,
+    FsWatcher, SessionLifecycle, SessionStateManager, WatchCancellation, WatchTask,
+    WatcherHeartbeat, WatcherStatus, EVENT_TIMEOUT, FILE_WATCH_POLL_INTERVAL,
     MIN_SIZE_CHANGE_BYTES,
 };
 use crate::upload_queue::UploadQueue;
-use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Config, Event, EventKind, RecursiveMode, Watcher};
 use shellexpand::tilde;
 use std::path::{Path, PathBuf};
-use std::sync::{mpsc, Arc, Mutex};
-use std::thread;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 const PROVIDER_ID: &str = "github-copilot";
 
@@ -25,10 +29,11 @@ pub struct FileChangeEvent {
 
 #[derive(Debug)]
 pub struct CopilotWatcher {
-    _watcher: RecommendedWatcher,
-    _thread_handle: thread::JoinHandle<()>,
+    _watcher: FsWatcher,
+    task: WatchTask,
     upload_queue: Arc<UploadQueue>,
     is_running: Arc<Mutex<bool>>,
+    heartbeat: WatcherHeartbeat,
 }
 
 impl CopilotWatcher {
@@ -36,6 +41,7 @@ impl CopilotWatcher {
         _projects: Vec<String>,
         upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
+        shutdown: CancellationToken,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         if let Err(e) = log_info(PROVIDER_ID, "🔍 Starting GitHub Copilot file monitoring") {
             eprintln!("Logging error: {}", e);
@@ -81,10 +87,11 @@ impl CopilotWatcher {
         }
 
         // Create file system event channel
-        let (tx, rx) = mpsc::channel();
+        let (tx, rx) = fs_event_channel(PROVIDER_ID);
 
         // Create the file watcher
-        let mut watcher = RecommendedWatcher::new(
+        let mut watcher = FsWatcher::for_provider(
+            PROVIDER_ID,
             tx,
             Config::default().with_poll_interval(FILE_WATCH_POLL_INTERVAL),
         )?;
@@ -101,134 +108,176 @@ impl CopilotWatcher {
             eprintln!("Logging error: {}", e);
         }
 
+        let heartbeat = WatcherHeartbeat::new(PROVIDER_ID, &session_dir, watcher.is_polling());
         let is_running = Arc::new(Mutex::new(true));
         let is_running_clone = Arc::clone(&is_running);
         let upload_queue_clone = Arc::clone(&upload_queue);
         let event_bus_clone = event_bus.clone();
+        let heartbeat_clone = heartbeat.clone();
 
-        // Start background thread to handle file events
-        let thread_handle = thread::spawn(move || {
+        // Start background task to handle file events
+        let task = WatchTask::spawn(&shutdown, move |cancellation| async move {
+            let _exit_guard = heartbeat_clone.exit_guard();
             Self::file_event_processor(
                 rx,
                 session_dir,
                 upload_queue_clone,
                 event_bus_clone,
                 is_running_clone,
-            );
+                heartbeat_clone,
+                cancellation,
+            )
+            .await;
         });
 
         Ok(CopilotWatcher {
             _watcher: watcher,
-            _thread_handle: thread_handle,
+            task,
             upload_queue,
             is_running,
+            heartbeat,
         })
     }
 
-    fn file_event_processor(
-        rx: mpsc::Receiver<Result<Event, notify::Error>>,
+    async fn file_event_processor(
+        mut rx: FsEventReceiver,
         session_dir: PathBuf,
         _upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
         is_running: Arc<Mutex<bool>>,
+        heartbeat: WatcherHeartbeat,
+        mut cancellation: WatchCancellation,
     ) {
         let mut session_states = SessionStateManager::new();
+        let mut lifecycle = SessionLifecycle::new(PROVIDER_ID, event_bus.clone());
+        let mut config_updates = ProviderConfigUpdates::new(PROVIDER_ID);
+        let mut debouncer = Debouncer::default();
 
         loop {
-            // Check if we should continue running
-            {
-                if let Ok(running) = is_running.lock() {
-                    if !*running {
-                        break;
-                    }
+            heartbeat.beat();
+
+            // Move quiet sessions towards idle/completed
+            for session_id in lifecycle.tick() {
+                session_states.mark_inactive(&session_id);
+            }
+
+            // Apply config edits without restarting the watcher
+            if let Some(config) = config_updates.poll() {
+                if !config.enabled {
+                    cancellation.stop_disabled(PROVIDER_ID, &is_running);
+                    continue;
                 }
+                lifecycle.set_config(&config);
             }
 
-            // Process file system events with timeout
-            match rx.recv_timeout(EVENT_TIMEOUT) {
-                Ok(Ok(event)) => {
-                    if let Some(file_event) = Self::process_file_event(&event, &session_dir) {
-                        // Check if this is a new session (before get_or_create)
-                        let is_new_session = !session_states.contains(&file_event.session_id);
-
-                        // Get or create session state
-                        let state = session_states
-                            .get_or_create(&file_event.session_id, file_event.file_size);
-                        let should_log = state.should_log(
-                            file_event.file_size,
-                            MIN_SIZE_CHANGE_BYTES,
-                            is_new_session,
-                        );
-
-                        // Publish SessionChanged event to event bus
-                        let payload = SessionEventPayload::SessionChanged {
-                            session_id: file_event.session_id.clone(),
-                            project_name: file_event.project_name.clone(),
-                            file_path: file_event.path.clone(),
-                            file_size: file_event.file_size,
-                        };
-
-                        if let Err(e) = event_bus.publish(PROVIDER_ID, payload) {
-                            if let Err(log_err) = log_error(
-                                PROVIDER_ID,
-                                &format!("Failed to publish session event: {}", e),
-                            ) {
-                                eprintln!("Logging error: {}", log_err);
-                            }
+            // Wait for file system events, debouncing bursts per path
+            let wake_at = debouncer.wake_at(Instant::now(), EVENT_TIMEOUT);
+            let stopping = tokio::select! {
+                _ = cancellation.cancelled() => true,
+                received = rx.recv() => match received {
+                    Some(Ok(event)) => {
+                        debouncer.push(event, Instant::now());
+                        false
+                    }
+                    Some(Err(error)) => {
+                        heartbeat.report(&error);
+                        if let Err(e) =
+                            log_error(PROVIDER_ID, &format!("File watcher error: {:?}", error))
+                        {
+                            eprintln!("Logging error: {}", e);
                         }
-
-                        // Update session state immediately to prevent duplicate events
-                        state.update(file_event.file_size);
-
-                        // Mark session as seen so it's not treated as new again
-                        if is_new_session {
-                            state.mark_as_seen();
+                        false
+                    }
+                    None => {
+                        if let Err(e) = log_error(PROVIDER_ID, "File watcher channel disconnected") {
+                            eprintln!("Logging error: {}", e);
                         }
+                        true
+                    }
+                },
+                _ = tokio::time::sleep_until(wake_at) => false,
+            };
+
+            // Stopping flushes whatever is still being debounced
+            let events = if stopping {
+                debouncer.drain()
+            } else {
+                debouncer.ready(Instant::now())
+            };
+            for event in events {
+                let session_dir = session_dir.clone();
+                let file_event =
+                    run_blocking(move || Self::process_file_event(&event, &session_dir)).await;
+                if let Some(file_event) = file_event {
+                    // Check if this is a new session (before get_or_create)
+                    let is_new_session = !session_states.contains(&file_event.session_id);
+
+                    // Get or create session state
+                    let state =
+                        session_states.get_or_create(&file_event.session_id, file_event.file_size);
+                    let should_log = state.should_log(
+                        file_event.file_size,
+                        MIN_SIZE_CHANGE_BYTES,
+                        is_new_session,
+                    );
+
+                    // Publish SessionChanged event to event bus
+                    let payload = SessionEventPayload::SessionChanged {
+                        session_id: file_event.session_id.clone(),
+                        project_name: file_event.project_name.clone(),
+                        file_path: file_event.path.clone(),
+                        file_size: file_event.file_size,
+                    };
 
-                        // Log events
-                        if should_log {
-                            if is_new_session {
-                                if let Err(e) = log_info(
-                                    PROVIDER_ID,
-                                    &format!(
-                                        "🆕 New Copilot session detected: {}",
-                                        file_event.session_id
-                                    ),
-                                ) {
-                                    eprintln!("Logging error: {}", e);
-                                }
-                            } else {
-                                // Log session updates at info level
-                                if let Err(e) = log_info(
-                                    PROVIDER_ID,
-                                    &format!(
-                                        "📝 Copilot session changed: {} (size: {} bytes)",
-                                        file_event.session_id, file_event.file_size
-                                    ),
-                                ) {
-                                    eprintln!("Logging error: {}", e);
-                                }
-                            }
+                    if let Err(e) = event_bus.publish(PROVIDER_ID, payload) {
+                        if let Err(log_err) = log_error(
+                            PROVIDER_ID,
+                            &format!("Failed to publish session event: {}", e),
+                        ) {
+                            eprintln!("Logging error: {}", log_err);
                         }
                     }
-                }
-                Ok(Err(error)) => {
-                    if let Err(e) =
-                        log_error(PROVIDER_ID, &format!("File watcher error: {:?}", error))
-                    {
-                        eprintln!("Logging error: {}", e);
+
+                    // Update session state immediately to prevent duplicate events
+                    state.update(file_event.file_size);
+                    lifecycle.record_activity(&file_event.session_id, &file_event.path);
+
+                    // Mark session as seen so it's not treated as new again
+                    if is_new_session {
+                        state.mark_as_seen();
                     }
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // Timeout is normal, continue to check pending files
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    if let Err(e) = log_error(PROVIDER_ID, "File watcher channel disconnected") {
-                        eprintln!("Logging error: {}", e);
+
+                    // Log events
+                    if should_log {
+                        if is_new_session {
+                            if let Err(e) = log_info(
+                                PROVIDER_ID,
+                                &format!(
+                                    "🆕 New Copilot session detected: {}",
+                                    file_event.session_id
+                                ),
+                            ) {
+                                eprintln!("Logging error: {}", e);
+                            }
+                        } else {
+                            // Log session updates at info level
+                            if let Err(e) = log_info(
+                                PROVIDER_ID,
+                                &format!(
+                                    "📝 Copilot session changed: {} (size: {} bytes)",
+                                    file_event.session_id, file_event.file_size
+                                ),
+                            ) {
+                                eprintln!("Logging error: {}", e);
+                            }
+                        }
                     }
-                    break;
                 }
             }
+
+            if stopping {
+                break;
+            }
         }
 
         if let Err(e) = log_info(PROVIDER_ID, "🛑 GitHub Copilot file monitoring stopped") {
@@ -258,6 +307,7 @@ impl CopilotWatcher {
 
         // Write canonical JSONL to project-organized path
         fs::write(&canonical_path, parsed.jsonl_content)?;
+        parsed.stats.persist(PROVIDER_ID, session_id);
 
         Ok(canonical_path)
     }
@@ -324,15 +374,25 @@ impl CopilotWatcher {
         if let Ok(mut running) = self.is_running.lock() {
             *running = false;
         }
+        self.task.stop();
 
         if let Err(e) = log_info(PROVIDER_ID, "🛑 Stopping GitHub Copilot file monitoring") {
             eprintln!("Logging error: {}", e);
         }
     }
 
+    pub fn task(&self) -> &WatchTask {
+        &self.task
+    }
+
+    pub fn heartbeat(&self) -> &WatcherHeartbeat {
+        &self.heartbeat
+    }
+
     pub fn get_status(&self) -> WatcherStatus {
+        // A panicked task never clears the flag, so ask the heartbeat too
         let is_running = if let Ok(running) = self.is_running.lock() {
-            *running
+            *running && self.heartbeat.is_alive()
         } else {
             false
         };
@@ -344,6 +404,8 @@ impl CopilotWatcher {
             pending_uploads: upload_status.pending,
             processing_uploads: upload_status.processing,
             failed_uploads: upload_status.failed,
+            watched_paths: Vec::new(),
+            unsupported_formats: format_version::unsupported_formats(PROVIDER_ID),
         }
     }
 }
@@ -1,9 +1,10 @@
 /// Converter from Cursor protobuf format to canonical JSONL
-use super::protobuf::{CursorBlob, ContentBlock as CursorContentBlock, CursorMessage};
+use super::protobuf::{ContentBlock as CursorContentBlock, CursorBlob, CursorMessage};
+use crate::providers::canonical::converter::ToCanonical;
+use crate::providers::canonical::fidelity::ConversionStats;
 use crate::providers::canonical::{
-    CanonicalMessage, ContentBlock, ContentValue, MessageContent, MessageType,
+    CanonicalMessage, ContentBlock, ContentValue, MessageContent, MessageType, CANONICAL_VERSION,
 };
-use crate::providers::canonical::converter::ToCanonical;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde_json::{json, Value};
@@ -44,6 +45,35 @@ impl<'a> CursorMessageWithRaw<'a> {
         message_timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
     }
 
+    /// `to_canonical_split` that also records the blob in `stats`
+    ///
+    /// Each blob is one record. Keys of JSON blobs beyond `id`/`role`/`content`
+    /// count as unknown; protobuf fields are not checked since prost drops them.
+    pub fn to_canonical_split_with_stats(
+        &self,
+        stats: &mut ConversionStats,
+    ) -> Result<Vec<CanonicalMessage>> {
+        let source = match self.message {
+            CursorMessage::Protobuf(_) => "protobuf",
+            CursorMessage::Json(_) => {
+                if let Ok(raw) = serde_json::from_slice::<Value>(self.raw_data) {
+                    stats.observe_keys(&raw, "", &["id", "role", "content"]);
+                }
+                "json"
+            }
+        };
+
+        let result = self.to_canonical_split();
+        match &result {
+            Ok(messages) if messages.is_empty() => {
+                stats.record_skipped(&format!("{}:empty", source))
+            }
+            Ok(messages) => stats.record_converted(messages.len()),
+            Err(_) => stats.record_failed(),
+        }
+        result
+    }
+
     /// Convert to multiple canonical messages (split by content block type)
     pub fn to_canonical_split(&self) -> Result<Vec<CanonicalMessage>> {
         match self.message {
@@ -261,6 +291,7 @@ fn create_json_text_message(
     let unique_uuid = format!("{}-{}", json_msg.id, uuid::Uuid::new_v4());
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: unique_uuid,
         timestamp: timestamp.to_string(),
         message_type,
@@ -307,6 +338,7 @@ fn create_json_structured_message(
     let unique_uuid = format!("{}-{}", json_msg.id, uuid::Uuid::new_v4());
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: unique_uuid,
         timestamp: timestamp.to_string(),
         message_type,
@@ -487,6 +519,7 @@ fn create_text_message(
     let unique_uuid = format!("{}-{}", base_uuid, uuid::Uuid::new_v4());
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: unique_uuid,
         timestamp: timestamp.to_string(),
         message_type,
@@ -532,6 +565,7 @@ fn create_structured_message(
     let unique_uuid = format!("{}-{}", base_uuid, uuid::Uuid::new_v4());
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: unique_uuid,
         timestamp: timestamp.to_string(),
         message_type,
@@ -613,6 +647,7 @@ impl CursorBlob {
             .map(|_| "default".to_string());
 
         Ok(Some(CanonicalMessage {
+            canonical_version: CANONICAL_VERSION.to_string(),
             uuid: self.uuid.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
             timestamp: timestamp.to_string(),
             message_type,
@@ -963,6 +998,7 @@ fn convert_json_message_with_timestamp(
     };
 
     Ok(Some(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid: if json_msg.id.is_empty() {
             uuid::Uuid::new_v4().to_string()
         } else {
@@ -1035,6 +1071,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_blobs_convert_cleanly() {
+        use super::super::protobuf::ContentWrapper;
+        use prost::Message;
+
+        let blob = CursorBlob {
+            content_wrapper: Some(ContentWrapper {
+                text: Some("Test message".to_string()),
+            }),
+            uuid: Some("test-uuid".to_string()),
+            metadata: Some(String::new()),
+            complex_data: None,
+            additional_content: None,
+            blob_references: None,
+        };
+        let blobs = [
+            blob.encode_to_vec(),
+            json!({"id": "msg-1", "role": "user", "content": "Hi", "providerOptions": {}})
+                .to_string()
+                .into_bytes(),
+        ];
+
+        let mut stats = ConversionStats::new();
+        for (index, data) in blobs.iter().enumerate() {
+            let message = CursorMessage::decode_from_bytes(data).unwrap();
+            CursorMessageWithRaw::new(&message, data, 1_700_000_000_000, index)
+                .to_canonical_split_with_stats(&mut stats)
+                .unwrap();
+        }
+
+        assert_eq!(stats.records_seen, 2);
+        assert_eq!(stats.failed, 0);
+        assert!(stats.unknown_event_types.is_empty());
+        // Protobuf fields are not checked; JSON blobs report extra keys
+        assert_eq!(
+            stats.unknown_keys,
+            std::collections::BTreeMap::from([("providerOptions".to_string(), 1)])
+        );
+    }
+
     #[test]
     fn test_skip_empty_messages() {
         let blob = CursorBlob {
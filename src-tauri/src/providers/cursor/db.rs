@@ -1,9 +1,26 @@
 /// SQLite database utilities for Cursor sessions
 use super::protobuf::CursorBlob;
 use super::types::SessionMetadata;
+use crate::providers::common::format_version::{FormatSpec, NewerVersions};
 use rusqlite::{Connection, OpenFlags};
 use std::path::Path;
 
+/// Cursor store.db formats this module can decode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CursorFormat {
+    /// `meta` row `0` pointing at a protobuf/JSON blob tree in `blobs`
+    BlobStore,
+}
+
+/// Cursor doesn't stamp a version in `meta` yet; one appearing is logged as drift
+pub static CURSOR_FORMATS: FormatSpec<CursorFormat> = FormatSpec {
+    provider_id: "cursor",
+    version_field: "meta.version",
+    ranges: &[],
+    unversioned: Some(CursorFormat::BlobStore),
+    newer: NewerVersions::Warn,
+};
+
 /// Open a Cursor database in read-only mode
 ///
 /// This is safe for concurrent access while Cursor is writing due to WAL mode.
@@ -37,6 +54,26 @@ pub fn get_session_metadata(conn: &Connection) -> Result<SessionMetadata, Box<dy
     Ok(metadata)
 }
 
+/// Check a session's store format against `CURSOR_FORMATS`
+pub fn resolve_format(conn: &Connection, session_id: &str) -> Result<CursorFormat, String> {
+    let meta_hex: String = conn
+        .query_row("SELECT value FROM meta WHERE key = '0'", [], |row| {
+            row.get(0)
+        })
+        .map_err(|e| e.to_string())?;
+    let meta: serde_json::Value = hex::decode(&meta_hex)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .unwrap_or_default();
+    let version = match meta.get("version") {
+        Some(serde_json::Value::String(version)) => Some(version.clone()),
+        Some(serde_json::Value::Number(version)) => Some(version.to_string()),
+        _ => None,
+    };
+
+    CURSOR_FORMATS.resolve(session_id, version.as_deref())
+}
+
 /// Get all blobs from the blobs table
 pub fn get_all_blobs(
     conn: &Connection,
@@ -120,6 +157,58 @@ pub fn get_decoded_messages(
     Ok(decoded)
 }
 
+/// Get the current conversation's messages in tree order
+///
+/// Walks the blob tree from `latestRootBlobId` (see `tree::decode_tree`).
+/// Falls back to `get_decoded_messages()` (rowid order) when the root is
+/// missing or reaches no messages, e.g. while Cursor is still writing it.
+#[allow(clippy::type_complexity)]
+pub fn get_conversation_messages(
+    conn: &Connection,
+) -> Result<Vec<(String, Vec<u8>, super::protobuf::CursorMessage)>, Box<dyn std::error::Error>> {
+    let metadata = get_session_metadata(conn)?;
+
+    match super::tree::decode_tree(conn, &metadata.latest_root_blob_id) {
+        Ok(tree) if !tree.messages.is_empty() => {
+            tracing::debug!(
+                "Walked blob tree from {}: {} messages, {} branches, {} orphans, {} missing",
+                tree.root_id,
+                tree.messages.len(),
+                tree.branches.len(),
+                tree.orphans.len(),
+                tree.missing.len()
+            );
+            Ok(tree
+                .messages
+                .into_iter()
+                .map(|m| (m.blob_id, m.data, m.message))
+                .collect())
+        }
+        Ok(_) => {
+            tracing::debug!(
+                "Root blob {} reaches no messages, using rowid order",
+                metadata.latest_root_blob_id
+            );
+            get_decoded_messages(conn)
+        }
+        Err(e) => {
+            tracing::debug!("Failed to walk blob tree ({:?}), using rowid order", e);
+            get_decoded_messages(conn)
+        }
+    }
+}
+
+/// Walk the blob tree from `latestRootBlobId`, keeping branches and orphans
+pub fn get_conversation_tree(
+    conn: &Connection,
+) -> Result<super::tree::BlobTree, Box<dyn std::error::Error>> {
+    let metadata = get_session_metadata(conn)?;
+    Ok(super::tree::decode_tree(
+        conn,
+        &metadata.latest_root_blob_id,
+    )?)
+}
+
 /// Get the count of blobs in the database
 pub fn get_blob_count(conn: &Connection) -> Result<i64, rusqlite::Error> {
     conn.query_row("SELECT COUNT(*) FROM blobs", [], |row| row.get(0))
@@ -109,6 +109,22 @@ pub fn inspect_session_db(db_path: &Path) -> Result<(), Box<dyn std::error::Erro
             println!("Created: {}", metadata.created_at);
             println!("Last Model: {}", metadata.last_used_model);
             println!("Latest Root Blob: {}", metadata.latest_root_blob_id);
+
+            if let Ok(tree) = super::tree::decode_tree(&conn, &metadata.latest_root_blob_id) {
+                println!("\n=== Blob Tree ===");
+                println!("Messages from root: {}", tree.messages.len());
+                println!("Branches (edits/regenerations): {}", tree.branches.len());
+                for branch in &tree.branches {
+                    println!(
+                        "  {} → {} messages after {}",
+                        branch.root_id,
+                        branch.messages.len(),
+                        branch.forked_after.as_deref().unwrap_or("start")
+                    );
+                }
+                println!("Orphaned message blobs: {}", tree.orphans.len());
+                println!("Missing referenced blobs: {}", tree.missing.len());
+            }
         }
         Err(e) => {
             println!("Failed to get metadata: {:?}", e);
@@ -5,18 +5,20 @@
 /// Architecture:
 /// - SQLite databases (one per session) at ~/.cursor/chats/{hash}/{uuid}/store.db
 /// - Protocol Buffer encoded messages in blobs table
-/// - Content-addressable storage with SHA-256 blob IDs
+/// - Content-addressable storage with SHA-256 blob IDs, walked from the
+///   session's latest root blob (see `tree`)
 /// - WAL mode for safe concurrent access
 pub mod converter;
 pub mod db;
 pub mod debug;
 pub mod protobuf;
 pub mod scanner;
+pub mod tree;
 pub mod types;
 pub mod watcher;
 
 pub use scanner::scan_existing_sessions;
-pub use types::CursorSession;
+pub use types::{CursorSession, SessionBranch, SessionBranches};
 
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -242,6 +244,73 @@ pub fn get_db_path_for_session(session_id: &str) -> Result<PathBuf, String> {
         .ok_or_else(|| format!("Session not found: {}", session_id))
 }
 
+/// Branches and orphan blobs of a session, converted to canonical messages
+pub fn get_session_branches(session_id: &str) -> Result<SessionBranches, String> {
+    let session = discover_sessions()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|s| s.session_id == session_id)
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+    let conn = db::open_cursor_db(&session.db_path).map_err(|e| e.to_string())?;
+    let tree = db::get_conversation_tree(&conn).map_err(|e| e.to_string())?;
+
+    Ok(branches_to_canonical(tree, &session))
+}
+
+/// Convert the branches of a decoded tree
+///
+/// Branch messages are timed as if they followed their fork point, the same
+/// way the current conversation is timed from its message index.
+pub fn branches_to_canonical(tree: tree::BlobTree, session: &CursorSession) -> SessionBranches {
+    let branches = tree
+        .branches
+        .into_iter()
+        .map(|branch| {
+            let first_index = branch
+                .forked_after
+                .as_ref()
+                .and_then(|id| tree.messages.iter().position(|m| &m.blob_id == id))
+                .map_or(0, |index| index + 1);
+
+            let messages = branch
+                .messages
+                .iter()
+                .enumerate()
+                .filter_map(|(offset, m)| {
+                    converter::CursorMessageWithRaw::new(
+                        &m.message,
+                        &m.data,
+                        session.metadata.created_at,
+                        first_index + offset,
+                    )
+                    .to_canonical_split()
+                    .ok()
+                })
+                .flatten()
+                .map(|mut canonical| {
+                    canonical.session_id = session.session_id.clone();
+                    if canonical.cwd.is_none() {
+                        canonical.cwd = session.cwd.clone();
+                    }
+                    canonical
+                })
+                .collect();
+
+            SessionBranch {
+                root_id: branch.root_id,
+                forked_after: branch.forked_after,
+                messages,
+            }
+        })
+        .collect();
+
+    SessionBranches {
+        branches,
+        orphans: tree.orphans,
+    }
+}
+
 /// Find the CWD for a Cursor session by checking the projects directory
 ///
 /// Cursor stores projects in ~/.cursor/projects with folder names that are
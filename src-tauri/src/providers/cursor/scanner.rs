@@ -4,6 +4,7 @@
 /// Cursor sessions that may not have been previously imported.
 use super::{converter::CursorMessageWithRaw, db, discover_sessions, CursorSession};
 use crate::events::{EventBus, SessionEventPayload};
+use crate::providers::canonical::fidelity::ConversionStats;
 use crate::providers::canonical::CanonicalMessage;
 use crate::providers::common::get_canonical_path;
 use std::fs;
@@ -116,9 +117,10 @@ fn process_session(
 ) -> Result<usize, Box<dyn std::error::Error>> {
     // Open database
     let conn = db::open_cursor_db(&session.db_path)?;
+    let db::CursorFormat::BlobStore = db::resolve_format(&conn, &session.session_id)?;
 
     // Get decoded messages (supports both protobuf and JSON)
-    let decoded_messages = db::get_decoded_messages(&conn)?;
+    let decoded_messages = db::get_conversation_messages(&conn)?;
 
     if decoded_messages.is_empty() {
         return Ok(0); // Empty session, skip
@@ -127,6 +129,7 @@ fn process_session(
     // Convert to canonical messages
     let mut canonical_messages: Vec<CanonicalMessage> = Vec::new();
     let mut stats = MessageStats::default();
+    let mut fidelity = ConversionStats::new();
 
     for (message_index, (_msg_id, raw_data, msg)) in decoded_messages.iter().enumerate() {
         // Track message source type
@@ -144,7 +147,7 @@ fn process_session(
         );
 
         // Use split conversion to prevent UUID collisions
-        match msg_with_raw.to_canonical_split() {
+        match msg_with_raw.to_canonical_split_with_stats(&mut fidelity) {
             Ok(messages) => {
                 // Process each split message
                 for mut canonical in messages {
@@ -216,6 +219,8 @@ fn process_session(
         stats.failed_count
     );
 
+    fidelity.persist(PROVIDER_ID, &session.session_id);
+
     if canonical_messages.is_empty() {
         return Ok(0); // No valid messages, skip
     }
@@ -326,7 +331,8 @@ fn scan_single_cursor_session(
 
     // Open database and get decoded messages (supports both protobuf and JSON)
     let conn = db::open_cursor_db(&session.db_path).map_err(|e| e.to_string())?;
-    let decoded_messages = db::get_decoded_messages(&conn).map_err(|e| e.to_string())?;
+    let db::CursorFormat::BlobStore = db::resolve_format(&conn, &session.session_id)?;
+    let decoded_messages = db::get_conversation_messages(&conn).map_err(|e| e.to_string())?;
 
     if decoded_messages.is_empty() {
         return Err("Empty session (no messages)".to_string());
@@ -334,6 +340,7 @@ fn scan_single_cursor_session(
 
     // Convert messages to canonical format (decoded_messages is Vec<(String, Vec<u8>, CursorMessage)>)
     let mut canonical_messages = Vec::new();
+    let mut fidelity = ConversionStats::new();
 
     for (message_index, (_blob_id, raw_data, msg)) in decoded_messages.iter().enumerate() {
         // Wrap message with raw data and session metadata for timestamp calculation
@@ -345,7 +352,7 @@ fn scan_single_cursor_session(
         );
 
         // Use to_canonical_split() to properly separate tool calls and tool results
-        if let Ok(messages) = msg_with_raw.to_canonical_split() {
+        if let Ok(messages) = msg_with_raw.to_canonical_split_with_stats(&mut fidelity) {
             for mut canonical_msg in messages {
                 // Set session ID (required for UI parser)
                 canonical_msg.session_id = session.session_id.clone();
@@ -402,6 +409,7 @@ fn scan_single_cursor_session(
 
     fs::write(&canonical_path, canonical_content)
         .map_err(|e| format!("Failed to write canonical file: {}", e))?;
+    fidelity.persist(PROVIDER_ID, &session.session_id);
 
     // Extract timing from messages
     let session_start_time = canonical_messages
@@ -453,7 +461,9 @@ mod tests {
 
     #[test]
     fn test_write_canonical_file() {
-        use crate::providers::canonical::{ContentValue, MessageContent, MessageType};
+        use crate::providers::canonical::{
+            ContentValue, MessageContent, MessageType, CANONICAL_VERSION,
+        };
         use chrono::Utc;
         use tempfile::tempdir;
 
@@ -461,6 +471,7 @@ mod tests {
         let file_path = dir.path().join("test.jsonl");
 
         let messages = vec![CanonicalMessage {
+            canonical_version: CANONICAL_VERSION.to_string(),
             uuid: "test-1".to_string(),
             timestamp: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
             message_type: MessageType::User,
@@ -0,0 +1,429 @@
+//! Cursor blob tree decoder
+//!
+//! `store.db` is a content-addressed store: every blob id is the hex SHA-256
+//! of its data, and tree blobs list their children as 32-byte hashes in
+//! protobuf field 8. `SessionMetadata.latest_root_blob_id` names the root of
+//! the current conversation, so walking the tree from there gives the exact
+//! message order instead of relying on `rowid`.
+//!
+//! Each edit or regeneration writes a new root. Earlier roots that reach
+//! messages the current root does not are reported as branches; message blobs
+//! that no root reaches are orphans.
+
+use super::protobuf::CursorMessage;
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+
+/// Protobuf field holding child blob hashes
+const REFERENCE_FIELD: u64 = 8;
+const HASH_LEN: usize = 32;
+
+/// A message blob reached while walking the tree
+#[derive(Debug, Clone)]
+pub struct TreeMessage {
+    pub blob_id: String,
+    pub data: Vec<u8>,
+    pub message: CursorMessage,
+}
+
+/// Messages only reachable from a superseded root (an edit or regeneration)
+#[derive(Debug, Clone)]
+pub struct Branch {
+    pub root_id: String,
+    /// Last message shared with the current conversation, if any
+    pub forked_after: Option<String>,
+    pub messages: Vec<TreeMessage>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BlobTree {
+    pub root_id: String,
+    /// Current conversation in order
+    pub messages: Vec<TreeMessage>,
+    pub branches: Vec<Branch>,
+    /// Message blobs no root reaches
+    pub orphans: Vec<String>,
+    /// Referenced blob ids that are not (yet) in the store
+    pub missing: Vec<String>,
+}
+
+enum Node {
+    Message(CursorMessage),
+    Tree,
+}
+
+struct Store {
+    data: HashMap<String, Vec<u8>>,
+    /// Each blob decoded once up front; the walks revisit them per root
+    nodes: HashMap<String, Node>,
+    /// Blob ids in rowid order, for deterministic output
+    order: Vec<String>,
+    references: HashMap<String, Vec<String>>,
+}
+
+impl Store {
+    fn load(conn: &Connection) -> Result<Self, rusqlite::Error> {
+        let blobs = super::db::get_all_blobs(conn)?;
+        let mut store = Store {
+            data: HashMap::with_capacity(blobs.len()),
+            nodes: HashMap::with_capacity(blobs.len()),
+            order: Vec::with_capacity(blobs.len()),
+            references: HashMap::new(),
+        };
+
+        for (id, data) in blobs {
+            let id = id.to_lowercase();
+            store.references.insert(id.clone(), blob_references(&data));
+            store.nodes.insert(id.clone(), decode_node(&data));
+            store.order.push(id.clone());
+            store.data.insert(id, data);
+        }
+
+        Ok(store)
+    }
+
+    fn node(&self, id: &str) -> Option<&Node> {
+        self.nodes.get(id)
+    }
+
+    /// Depth-first walk in reference order, collecting message blobs
+    fn walk(
+        &self,
+        root_id: &str,
+        visited: &mut HashSet<String>,
+        missing: &mut Vec<String>,
+    ) -> Vec<TreeMessage> {
+        let mut messages = Vec::new();
+        let mut stack = vec![root_id.to_lowercase()];
+
+        while let Some(id) = stack.pop() {
+            // Content addressing makes cycles impossible in a sane store; guard anyway
+            if !visited.insert(id.clone()) {
+                continue;
+            }
+
+            match self.node(&id) {
+                None => {
+                    if !missing.contains(&id) {
+                        missing.push(id);
+                    }
+                    continue;
+                }
+                Some(Node::Message(message)) => messages.push(TreeMessage {
+                    blob_id: id.clone(),
+                    data: self.data[&id].clone(),
+                    message: message.clone(),
+                }),
+                Some(Node::Tree) => {}
+            }
+
+            if let Some(children) = self.references.get(&id) {
+                stack.extend(children.iter().rev().cloned());
+            }
+        }
+
+        messages
+    }
+}
+
+fn decode_node(data: &[u8]) -> Node {
+    match CursorMessage::decode_from_bytes(data) {
+        Ok(CursorMessage::Protobuf(blob)) if !blob.is_message_blob() => Node::Tree,
+        Ok(message) => Node::Message(message),
+        Err(_) => Node::Tree,
+    }
+}
+
+/// Child hashes stored in field 8, hex-encoded to match blob ids
+///
+/// Field 8 may repeat (one hash each) or hold several hashes back to back.
+/// JSON message blobs never carry references.
+pub fn blob_references(data: &[u8]) -> Vec<String> {
+    let mut references = Vec::new();
+    if data.first() == Some(&b'{') {
+        return references;
+    }
+
+    let mut pos = 0;
+    while pos < data.len() {
+        let Some((key, next)) = read_varint(data, pos) else {
+            break;
+        };
+        pos = next;
+        match key & 0x7 {
+            0 => match read_varint(data, pos) {
+                Some((_, next)) => pos = next,
+                None => break,
+            },
+            1 => pos += 8,
+            2 => {
+                let Some((len, next)) = read_varint(data, pos) else {
+                    break;
+                };
+                let end = next.saturating_add(len as usize);
+                if end > data.len() {
+                    break;
+                }
+                let value = &data[next..end];
+                if key >> 3 == REFERENCE_FIELD
+                    && !value.is_empty()
+                    && value.len().is_multiple_of(HASH_LEN)
+                {
+                    references.extend(value.chunks(HASH_LEN).map(hex::encode));
+                }
+                pos = end;
+            }
+            5 => pos += 4,
+            // Groups and unknown wire types: not something we can walk
+            _ => break,
+        }
+    }
+
+    references
+}
+
+fn read_varint(data: &[u8], mut pos: usize) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(pos)?;
+        pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some((value, pos));
+        }
+    }
+    None
+}
+
+/// Walk the tree rooted at `root_id` and classify every other blob
+pub fn decode_tree(conn: &Connection, root_id: &str) -> Result<BlobTree, rusqlite::Error> {
+    let store = Store::load(conn)?;
+    let root_id = root_id.to_lowercase();
+
+    let mut reachable = HashSet::new();
+    let mut missing = Vec::new();
+    let messages = store.walk(&root_id, &mut reachable, &mut missing);
+    let main_ids: Vec<&str> = messages.iter().map(|m| m.blob_id.as_str()).collect();
+
+    // Superseded roots: tree blobs that nothing references
+    let referenced: HashSet<&String> = store.references.values().flatten().collect();
+    let mut covered = reachable.clone();
+    let mut branches = Vec::new();
+    for id in &store.order {
+        if *id == root_id || referenced.contains(id) || reachable.contains(id) {
+            continue;
+        }
+        if !matches!(store.node(id), Some(Node::Tree)) {
+            continue;
+        }
+
+        let mut seen = HashSet::new();
+        let branch_messages = store.walk(id, &mut seen, &mut Vec::new());
+        covered.extend(seen);
+
+        let shared = branch_messages
+            .iter()
+            .zip(&main_ids)
+            .take_while(|(message, main_id)| message.blob_id == **main_id)
+            .count();
+        let unique: Vec<TreeMessage> = branch_messages[shared..]
+            .iter()
+            .filter(|message| !main_ids.contains(&message.blob_id.as_str()))
+            .cloned()
+            .collect();
+
+        // An earlier root of a linear conversation is just a prefix
+        if unique.is_empty() {
+            continue;
+        }
+
+        branches.push(Branch {
+            root_id: id.clone(),
+            forked_after: shared.checked_sub(1).map(|i| main_ids[i].to_string()),
+            messages: unique,
+        });
+    }
+
+    let orphans = store
+        .order
+        .iter()
+        .filter(|id| !covered.contains(*id))
+        .filter(|id| matches!(store.node(id), Some(Node::Message(_))))
+        .cloned()
+        .collect();
+
+    Ok(BlobTree {
+        root_id,
+        messages,
+        branches,
+        orphans,
+        missing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::cursor::protobuf::{ContentWrapper, CursorBlob};
+    use prost::Message;
+    use sha2::{Digest, Sha256};
+    use tempfile::TempDir;
+
+    /// Synthetic store.db: blobs keyed by their SHA-256, plus the meta row
+    struct Fixture {
+        _dir: TempDir,
+        conn: Connection,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let dir = TempDir::new().unwrap();
+            let conn = Connection::open(dir.path().join("store.db")).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE blobs (id TEXT PRIMARY KEY, data BLOB);
+                 CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT);",
+            )
+            .unwrap();
+            Fixture { _dir: dir, conn }
+        }
+
+        fn put(&self, data: Vec<u8>) -> String {
+            let id = hex::encode(Sha256::digest(&data));
+            self.conn
+                .execute(
+                    "INSERT OR IGNORE INTO blobs (id, data) VALUES (?1, ?2)",
+                    rusqlite::params![id, data],
+                )
+                .unwrap();
+            id
+        }
+
+        fn user(&self, uuid: &str) -> String {
+            let blob = CursorBlob {
+                uuid: Some(uuid.to_string()),
+                ..Default::default()
+            };
+            self.put(blob.encode_to_vec())
+        }
+
+        fn assistant(&self, text: &str) -> String {
+            let blob = CursorBlob {
+                content_wrapper: Some(ContentWrapper {
+                    text: Some(text.to_string()),
+                }),
+                ..Default::default()
+            };
+            self.put(blob.encode_to_vec())
+        }
+
+        fn tree(&self, children: &[&str]) -> String {
+            let mut data = Vec::new();
+            for child in children {
+                data.push(((REFERENCE_FIELD << 3) | 2) as u8);
+                data.push(HASH_LEN as u8);
+                data.extend(hex::decode(child).unwrap());
+            }
+            self.put(data)
+        }
+    }
+
+    fn ids(messages: &[TreeMessage]) -> Vec<&str> {
+        messages.iter().map(|m| m.blob_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tree_order_wins_over_rowid_order() {
+        let fx = Fixture::new();
+        // Written out of order: the answer lands before the question
+        let answer = fx.assistant("Hi there");
+        let question = fx.user("u-1");
+        let follow_up = fx.user("u-2");
+        let turn = fx.tree(&[&question, &answer]);
+        let root = fx.tree(&[&turn, &follow_up]);
+
+        let tree = decode_tree(&fx.conn, &root).unwrap();
+        assert_eq!(ids(&tree.messages), vec![&question, &answer, &follow_up]);
+        assert!(tree.branches.is_empty());
+        assert!(tree.orphans.is_empty());
+        assert!(tree.missing.is_empty());
+    }
+
+    #[test]
+    fn test_regeneration_becomes_branch_and_stray_blob_orphan() {
+        let fx = Fixture::new();
+        let question = fx.user("u-1");
+        let first_answer = fx.assistant("First try");
+        let old_root = fx.tree(&[&question, &first_answer]);
+        let second_answer = fx.assistant("Second try");
+        let root = fx.tree(&[&question, &second_answer]);
+        let stray = fx.assistant("Never linked");
+
+        let tree = decode_tree(&fx.conn, &root).unwrap();
+        assert_eq!(ids(&tree.messages), vec![&question, &second_answer]);
+
+        assert_eq!(tree.branches.len(), 1);
+        let branch = &tree.branches[0];
+        assert_eq!(branch.root_id, old_root);
+        assert_eq!(branch.forked_after.as_deref(), Some(question.as_str()));
+        assert_eq!(ids(&branch.messages), vec![&first_answer]);
+
+        assert_eq!(tree.orphans, vec![stray]);
+    }
+
+    #[test]
+    fn test_branches_convert_after_fork_point() {
+        let fx = Fixture::new();
+        let question = fx.user("u-1");
+        let first_answer = fx.assistant("First try");
+        fx.tree(&[&question, &first_answer]);
+        let second_answer = fx.assistant("Second try");
+        let root = fx.tree(&[&question, &second_answer]);
+
+        let session = crate::providers::cursor::CursorSession {
+            session_id: "cursor-session".to_string(),
+            db_path: Default::default(),
+            metadata: crate::providers::cursor::types::SessionMetadata {
+                agent_id: "agent".to_string(),
+                latest_root_blob_id: root.clone(),
+                name: "Test".to_string(),
+                mode: "default".to_string(),
+                created_at: 1_700_000_000_000,
+                last_used_model: "default".to_string(),
+            },
+            hash: "hash".to_string(),
+            cwd: Some("/work/project".to_string()),
+        };
+
+        let tree = decode_tree(&fx.conn, &root).unwrap();
+        let converted = crate::providers::cursor::branches_to_canonical(tree, &session);
+
+        assert_eq!(converted.branches.len(), 1);
+        let branch = &converted.branches[0];
+        assert_eq!(branch.forked_after.as_deref(), Some(question.as_str()));
+        assert_eq!(branch.messages.len(), 1);
+        let message = &branch.messages[0];
+        assert_eq!(message.session_id, "cursor-session");
+        assert_eq!(message.cwd.as_deref(), Some("/work/project"));
+        // Timed as the second message, right after the shared question
+        assert_eq!(message.timestamp, "2023-11-14T22:13:21.000Z");
+    }
+
+    #[test]
+    fn test_missing_children_and_linear_history() {
+        let fx = Fixture::new();
+        let question = fx.user("u-1");
+        // Earlier root of an append-only conversation is not a branch
+        fx.tree(&[&question]);
+        let absent = hex::encode([7u8; 32]);
+        let root = fx.tree(&[&question, &absent]);
+
+        let tree = decode_tree(&fx.conn, &root.to_uppercase()).unwrap();
+        assert_eq!(ids(&tree.messages), vec![&question]);
+        assert!(tree.branches.is_empty());
+        assert_eq!(tree.missing, vec![absent]);
+        assert_eq!(
+            blob_references(b"{\"role\":\"user\"}"),
+            Vec::<String>::new()
+        );
+    }
+}
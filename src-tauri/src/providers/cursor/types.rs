@@ -56,6 +56,26 @@ impl CursorSession {
     }
 }
 
+/// Conversation left behind by an edit or regeneration
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionBranch {
+    /// Superseded root blob the branch hangs off
+    pub root_id: String,
+    /// Blob id of the last message shared with the current conversation
+    pub forked_after: Option<String>,
+    pub messages: Vec<crate::providers::canonical::CanonicalMessage>,
+}
+
+/// What the canonical file (current conversation only) leaves out of a session
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionBranches {
+    pub branches: Vec<SessionBranch>,
+    /// Message blobs no root reaches
+    pub orphans: Vec<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -6,17 +6,24 @@
 /// - Polls active sessions (from our database) using PRAGMA data_version
 /// - Only polls sessions updated in last hour (automatic pruning)
 use crate::config::load_provider_config;
+use crate::config_service::ProviderConfigUpdates;
 use crate::database::with_connection_mut;
 use crate::events::{EventBus, SessionEventPayload};
-use crate::providers::cursor::{db, discover_sessions, get_db_path_for_session, scan_existing_sessions};
-use crate::providers::common::get_canonical_path;
+use crate::providers::common::format_version::{self, UnsupportedFormat};
+use crate::providers::common::{
+    fs_event_channel, get_canonical_path, run_blocking, Debouncer, FsEventReceiver, FsWatcher,
+    SessionLifecycle, WatchCancellation, WatchTask, WatcherHeartbeat,
+};
+use crate::providers::cursor::{
+    db, discover_sessions, get_db_path_for_session, scan_existing_sessions,
+};
 use crate::upload_queue::UploadQueue;
-use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Config, RecursiveMode, Watcher};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::{mpsc, Arc, Mutex};
-use std::thread;
-use std::time::{Duration, SystemTime};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio_util::sync::CancellationToken;
 
 const PROVIDER_ID: &str = "cursor";
 const POLL_INTERVAL: Duration = Duration::from_secs(5);
@@ -28,14 +35,25 @@ struct SessionTracker {
     session_id: String,
     db_path: PathBuf,
     last_data_version: i64,
+    /// Conversation state last written to the canonical file
+    last_processed: Option<ConversationState>,
     last_checked: SystemTime,
 }
 
+/// Root blob of the conversation plus the blob count, since children can land
+/// after the root that names them
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ConversationState {
+    root_blob_id: String,
+    blob_count: i64,
+}
+
 #[derive(Debug)]
 pub struct CursorWatcher {
-    _watcher: RecommendedWatcher,
-    _poll_thread: thread::JoinHandle<()>,
+    _watcher: FsWatcher,
+    task: WatchTask,
     is_running: Arc<Mutex<bool>>,
+    heartbeat: WatcherHeartbeat,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -43,6 +61,7 @@ pub struct CursorWatcher {
 pub struct CursorWatcherStatus {
     pub is_running: bool,
     pub active_sessions: usize,
+    pub unsupported_formats: Vec<UnsupportedFormat>,
 }
 
 
@@ -51,6 +70,7 @@ impl CursorWatcher {
         _projects: Vec<String>,
         upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
+        shutdown: CancellationToken,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         tracing::info!("🔍 Starting Cursor session monitoring");
 
@@ -87,8 +107,9 @@ impl CursorWatcher {
 
         tracing::info!("📁 Watching Cursor chats directory: {}", chats_path);
 
-        let (tx, rx) = mpsc::channel();
-        let mut watcher = RecommendedWatcher::new(
+        let (tx, rx) = fs_event_channel(PROVIDER_ID);
+        let mut watcher = FsWatcher::for_provider(
+            PROVIDER_ID,
             tx,
             Config::default().with_poll_interval(Duration::from_secs(2)),
         )?;
@@ -97,73 +118,141 @@ impl CursorWatcher {
         watcher.watch(chats_dir, RecursiveMode::Recursive)?;
 
         // Part 3: Start hybrid event loop (filesystem + database polling)
+        let heartbeat = WatcherHeartbeat::new(PROVIDER_ID, chats_dir, watcher.is_polling());
         let is_running = Arc::new(Mutex::new(true));
         let is_running_clone = is_running.clone();
         let upload_queue_clone = upload_queue.clone();
         let event_bus_clone = event_bus.clone();
-
-        let poll_thread = thread::spawn(move || {
-            Self::hybrid_event_loop(rx, is_running_clone, upload_queue_clone, event_bus_clone);
+        let heartbeat_clone = heartbeat.clone();
+
+        let task = WatchTask::spawn(&shutdown, move |cancellation| async move {
+            let _exit_guard = heartbeat_clone.exit_guard();
+            Self::hybrid_event_loop(
+                rx,
+                is_running_clone,
+                upload_queue_clone,
+                event_bus_clone,
+                heartbeat_clone,
+                cancellation,
+            )
+            .await;
         });
 
         Ok(CursorWatcher {
             _watcher: watcher,
-            _poll_thread: poll_thread,
+            task,
             is_running,
+            heartbeat,
         })
     }
 
     /// Hybrid event loop: handles both filesystem events and database polling
-    fn hybrid_event_loop(
-        rx: mpsc::Receiver<notify::Result<notify::Event>>,
+    async fn hybrid_event_loop(
+        mut rx: FsEventReceiver,
         is_running: Arc<Mutex<bool>>,
         _upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
+        heartbeat: WatcherHeartbeat,
+        mut cancellation: WatchCancellation,
     ) {
         let mut session_trackers: HashMap<String, SessionTracker> = HashMap::new();
         let mut last_poll = SystemTime::now();
+        let mut lifecycle = SessionLifecycle::new(PROVIDER_ID, event_bus.clone());
+        let mut config_updates = ProviderConfigUpdates::new(PROVIDER_ID);
+        let mut debouncer = Debouncer::default();
 
         loop {
-            // Check shutdown
-            if !*is_running.lock().unwrap() {
-                tracing::info!("🛑 Cursor watcher shutting down");
-                break;
+            heartbeat.beat();
+
+            // Move quiet sessions towards idle/completed
+            lifecycle.tick();
+
+            // Apply config edits without restarting the watcher
+            if let Some(config) = config_updates.poll() {
+                if !config.enabled {
+                    cancellation.stop_disabled(PROVIDER_ID, &is_running);
+                    continue;
+                }
+                lifecycle.set_config(&config);
             }
 
-            // Part 1: Handle filesystem events (new session detection)
-            match rx.recv_timeout(Duration::from_millis(100)) {
-                Ok(Ok(event)) => {
-                    if let Some(session_id) = Self::handle_filesystem_event(event) {
+            // Part 1: Handle filesystem events (new session detection), waking
+            // up in time for the next database poll
+            let until_poll =
+                POLL_INTERVAL.saturating_sub(last_poll.elapsed().unwrap_or(Duration::ZERO));
+            let wake_at = debouncer.wake_at(Instant::now(), until_poll);
+            let stopping = tokio::select! {
+                _ = cancellation.cancelled() => true,
+                received = rx.recv() => match received {
+                    Some(Ok(event)) => {
+                        debouncer.push(event, Instant::now());
+                        false
+                    }
+                    Some(Err(e)) => {
+                        heartbeat.report(&e);
+                        tracing::error!("Filesystem watch error: {:?}", e);
+                        false
+                    }
+                    None => {
+                        tracing::error!("Filesystem watch channel disconnected");
+                        true
+                    }
+                },
+                _ = tokio::time::sleep_until(wake_at) => false,
+            };
+
+            // Stopping flushes whatever is still being debounced
+            let events = if stopping {
+                debouncer.drain()
+            } else {
+                debouncer.ready(Instant::now())
+            };
+            let new_sessions: Vec<String> = events
+                .into_iter()
+                .filter_map(Self::handle_filesystem_event)
+                .collect();
+            if !new_sessions.is_empty() {
+                // Database reads and conversion run on the blocking pool
+                let event_bus = event_bus.clone();
+                lifecycle = run_blocking(move || {
+                    for session_id in new_sessions {
                         tracing::info!("🆕 New Cursor session detected: {}", session_id);
 
                         // Try to process immediately
-                        match Self::process_new_session(&session_id, &event_bus) {
+                        match Self::process_new_session(&session_id, &event_bus, &mut lifecycle) {
                             Ok(()) => {
                                 tracing::debug!("✅ Processed new session: {}", session_id);
                             }
                             Err(e) => {
-                                tracing::warn!("❌ Failed to process new session {}: {:?}", session_id, e);
+                                tracing::warn!(
+                                    "❌ Failed to process new session {}: {:?}",
+                                    session_id,
+                                    e
+                                );
                             }
                         }
                     }
-                }
-                Ok(Err(e)) => {
-                    tracing::error!("Filesystem watch error: {:?}", e);
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // No filesystem events, continue to polling
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    tracing::error!("Filesystem watch channel disconnected");
-                    break;
-                }
+                    lifecycle
+                })
+                .await;
             }
 
-            // Part 2: Smart polling (only active sessions from our database)
-            if last_poll.elapsed().unwrap_or(Duration::ZERO) >= POLL_INTERVAL {
-                Self::poll_active_sessions(&mut session_trackers, &event_bus);
+            // Part 2: Smart polling (only active sessions from our database);
+            // a final poll on stop picks up edits made since the last one
+            if stopping || last_poll.elapsed().unwrap_or(Duration::ZERO) >= POLL_INTERVAL {
+                let event_bus = event_bus.clone();
+                (session_trackers, lifecycle) = run_blocking(move || {
+                    Self::poll_active_sessions(&mut session_trackers, &event_bus, &mut lifecycle);
+                    (session_trackers, lifecycle)
+                })
+                .await;
                 last_poll = SystemTime::now();
             }
+
+            if stopping {
+                tracing::info!("🛑 Cursor watcher shutting down");
+                break;
+            }
         }
     }
 
@@ -189,6 +278,7 @@ impl CursorWatcher {
     fn process_new_session(
         session_id: &str,
         event_bus: &EventBus,
+        lifecycle: &mut SessionLifecycle,
     ) -> Result<(), Box<dyn std::error::Error>> {
         // Re-discover sessions to find the new one
         let sessions = discover_sessions()?;
@@ -199,14 +289,17 @@ impl CursorWatcher {
             .ok_or_else(|| format!("Session {} not found after discovery", session_id))?;
 
         // Use scanner logic to process single session
+        use crate::providers::canonical::fidelity::ConversionStats;
         use crate::providers::cursor::converter::CursorMessageWithRaw;
         use crate::providers::cursor::scanner;
         
 
         let conn = db::open_cursor_db(&session.db_path)?;
-        let decoded_messages = db::get_decoded_messages(&conn)?;
+        let db::CursorFormat::BlobStore = db::resolve_format(&conn, &session.session_id)?;
+        let decoded_messages = db::get_conversation_messages(&conn)?;
 
         let mut canonical_messages = Vec::new();
+        let mut fidelity = ConversionStats::new();
         for (message_index, (_msg_id, raw_data, msg)) in decoded_messages.iter().enumerate() {
             // Wrap message with raw data and session metadata for timestamp calculation
             let msg_with_raw = CursorMessageWithRaw::new(
@@ -217,7 +310,7 @@ impl CursorWatcher {
             );
 
             // Use split conversion to prevent UUID collisions
-            if let Ok(messages) = msg_with_raw.to_canonical_split() {
+            if let Ok(messages) = msg_with_raw.to_canonical_split_with_stats(&mut fidelity) {
                 for mut canonical in messages {
                     canonical.session_id = session.session_id.clone();
                     if canonical.cwd.is_none() {
@@ -241,9 +334,11 @@ impl CursorWatcher {
         .map_err(|e| -> Box<dyn std::error::Error> { Box::new(std::io::Error::other(e.to_string())) })?;
 
         scanner::write_canonical_file(&canonical_path, &canonical_messages)?;
+        fidelity.persist(PROVIDER_ID, &session.session_id);
 
         // Get file size and publish event
         let file_size = std::fs::metadata(&canonical_path)?.len();
+        lifecycle.record_activity(&session.session_id, &canonical_path);
 
         event_bus.publish(
             PROVIDER_ID,
@@ -262,6 +357,7 @@ impl CursorWatcher {
     fn poll_active_sessions(
         session_trackers: &mut HashMap<String, SessionTracker>,
         event_bus: &EventBus,
+        lifecycle: &mut SessionLifecycle,
     ) {
         // Query OUR database for recently active Cursor sessions
         let active_sessions = match Self::get_active_sessions_from_db() {
@@ -295,11 +391,13 @@ impl CursorWatcher {
                 } else {
                     0
                 };
+                let initial_state = Self::conversation_state(&db_path).ok();
 
                 SessionTracker {
                     session_id: session_id.clone(),
                     db_path,
                     last_data_version: initial_version,
+                    last_processed: initial_state,
                     last_checked: SystemTime::now(),
                 }
             });
@@ -309,8 +407,12 @@ impl CursorWatcher {
                 Ok(true) => {
                     // Phase 3 Enhancement: Verify content actually changed before reprocessing
                     // Prevents redundant processing when only SQLite metadata changed
-                    let should_reprocess = match Self::verify_content_changed(&canonical_path, &tracker.db_path) {
-                        Ok(changed) => changed,
+                    let current_state = Self::conversation_state(&tracker.db_path);
+                    let should_reprocess = match &current_state {
+                        Ok(state) => {
+                            !std::path::Path::new(&canonical_path).exists()
+                                || tracker.last_processed.as_ref() != Some(state)
+                        }
                         Err(e) => {
                             tracing::debug!("Could not verify content change for {}: {:?}, will reprocess", session_id, e);
                             true // Default to reprocessing if verification fails
@@ -321,8 +423,15 @@ impl CursorWatcher {
                         tracing::info!("🔄 Session {} has content changes, reprocessing", session_id);
 
                         // Reprocess session
-                        if let Err(e) = Self::process_new_session(&session_id, event_bus) {
-                            tracing::warn!("Failed to reprocess session {}: {:?}", session_id, e);
+                        match Self::process_new_session(&session_id, event_bus, lifecycle) {
+                            Ok(()) => tracker.last_processed = current_state.ok(),
+                            Err(e) => {
+                                tracing::warn!(
+                                    "Failed to reprocess session {}: {:?}",
+                                    session_id,
+                                    e
+                                );
+                            }
                         }
                     } else {
                         tracing::debug!("Session {} data_version changed but content unchanged, skipping", session_id);
@@ -388,39 +497,37 @@ impl CursorWatcher {
         }
     }
 
-    /// Verify content actually changed by comparing message count
-    /// Returns true if content changed, false if unchanged
-    fn verify_content_changed(
-        canonical_path: &str,
-        db_path: &Path,
-    ) -> Result<bool, Box<dyn std::error::Error>> {
-        // If canonical file doesn't exist, definitely need to create it
-        if !std::path::Path::new(canonical_path).exists() {
-            return Ok(true);
-        }
-
-        // Count messages in Cursor database
+    /// Read the conversation state without decoding any blobs
+    ///
+    /// Edits and regenerations write a new root, so this catches changes that
+    /// leave the message count as it was.
+    fn conversation_state(db_path: &Path) -> Result<ConversationState, Box<dyn std::error::Error>> {
         let conn = db::open_cursor_db(db_path)?;
-        let db_message_count = db::get_decoded_messages(&conn)?.len();
-
-        // Count lines in canonical JSONL file (each line = 1 message)
-        let canonical_message_count = std::fs::read_to_string(canonical_path)?
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .count();
-
-        // If counts differ, content changed
-        Ok(db_message_count != canonical_message_count)
+        Ok(ConversationState {
+            root_blob_id: db::get_session_metadata(&conn)?.latest_root_blob_id,
+            blob_count: db::get_blob_count(&conn)?,
+        })
     }
 
     pub fn stop(&self) -> Result<(), String> {
         let mut is_running = self.is_running.lock().map_err(|e| e.to_string())?;
         *is_running = false;
+        self.task.stop();
         Ok(())
     }
 
+    pub fn task(&self) -> &WatchTask {
+        &self.task
+    }
+
+    pub fn heartbeat(&self) -> &WatcherHeartbeat {
+        &self.heartbeat
+    }
+
     pub fn get_status(&self) -> Result<CursorWatcherStatus, String> {
-        let is_running = *self.is_running.lock().map_err(|e| e.to_string())?;
+        // A panicked task never clears the flag, so ask the heartbeat too
+        let is_running =
+            *self.is_running.lock().map_err(|e| e.to_string())? && self.heartbeat.is_alive();
 
         // Query active sessions count
         let active_sessions = Self::get_active_sessions_from_db()
@@ -430,6 +537,7 @@ impl CursorWatcher {
         Ok(CursorWatcherStatus {
             is_running,
             active_sessions,
+            unsupported_formats: format_version::unsupported_formats("cursor"),
         })
     }
 }
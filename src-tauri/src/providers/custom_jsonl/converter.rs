@@ -0,0 +1,530 @@
+//! Mapping engine for custom JSONL providers
+//!
+//! Applies a `CustomJsonlMapping` to each raw JSONL record and produces
+//! canonical messages. Records without a timestamp or any content are skipped.
+
+use super::selector::{select, select_string, top_level_keys};
+use crate::config::CustomJsonlMapping;
+use crate::providers::canonical::fidelity::ConversionStats;
+use crate::providers::canonical::{
+    converter::ToCanonical, CanonicalMessage, ContentBlock, ContentValue, MessageContent,
+    MessageType, CANONICAL_VERSION,
+};
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use serde_json::Value;
+use std::collections::VecDeque;
+
+/// A single raw record from a custom JSONL log, paired with its mapping
+pub struct CustomJsonlRecord<'a> {
+    provider_id: &'a str,
+    mapping: &'a CustomJsonlMapping,
+    session_id: &'a str,
+    line_number: usize,
+    raw: Value,
+    /// Tool call this record's result answers when the mapping has no `toolUseId`
+    answered_tool_use_id: Option<String>,
+}
+
+/// Result of converting a whole custom JSONL file
+#[derive(Debug, Clone)]
+pub struct ConvertedSession {
+    pub session_id: String,
+    pub cwd: Option<String>,
+    pub messages: Vec<CanonicalMessage>,
+    pub stats: ConversionStats,
+}
+
+impl<'a> CustomJsonlRecord<'a> {
+    pub fn new(
+        provider_id: &'a str,
+        mapping: &'a CustomJsonlMapping,
+        session_id: &'a str,
+        line_number: usize,
+        raw: Value,
+    ) -> Self {
+        Self {
+            provider_id,
+            mapping,
+            session_id,
+            line_number,
+            raw,
+            answered_tool_use_id: None,
+        }
+    }
+
+    /// Pair an unmapped tool result with the tool call it answers
+    pub fn answering(mut self, tool_use_id: Option<String>) -> Self {
+        self.answered_tool_use_id = tool_use_id;
+        self
+    }
+
+    fn field(&self, selector: &Option<String>) -> Option<String> {
+        selector
+            .as_deref()
+            .and_then(|s| select_string(&self.raw, s))
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Map the provider's role name to "user", "assistant" or "system"
+    fn normalized_role(&self) -> String {
+        let raw_role = select_string(&self.raw, &self.mapping.role).unwrap_or_default();
+        let mapped = self
+            .mapping
+            .role_map
+            .get(&raw_role)
+            .cloned()
+            .unwrap_or(raw_role);
+
+        match mapped.to_lowercase().as_str() {
+            "user" | "human" | "input" => "user".to_string(),
+            "system" | "meta" | "developer" => "system".to_string(),
+            "tool" | "function" => "tool".to_string(),
+            _ => "assistant".to_string(),
+        }
+    }
+
+    fn timestamp(&self) -> Option<String> {
+        select(&self.raw, &self.mapping.timestamp).and_then(normalize_timestamp)
+    }
+}
+
+impl ToCanonical for CustomJsonlRecord<'_> {
+    fn to_canonical(&self) -> Result<Option<CanonicalMessage>> {
+        let Some(timestamp) = self.timestamp() else {
+            return Ok(None);
+        };
+
+        let role = self.normalized_role();
+        let mut blocks = Vec::new();
+
+        if let Some(text) = self.field(&self.mapping.text) {
+            blocks.push(ContentBlock::Text { text });
+        }
+
+        let mapped_tool_use_id = self.field(&self.mapping.tool_use_id);
+
+        if let Some(name) = self.field(&self.mapping.tool_name) {
+            let input = self
+                .mapping
+                .tool_input
+                .as_deref()
+                .and_then(|s| select(&self.raw, s))
+                .cloned()
+                .unwrap_or(Value::Object(Default::default()));
+            blocks.push(ContentBlock::ToolUse {
+                id: mapped_tool_use_id
+                    .clone()
+                    .unwrap_or_else(|| self.generated_tool_use_id()),
+                name,
+                input,
+            });
+        }
+
+        if let Some(content) = self.field(&self.mapping.tool_result) {
+            let tool_use_id = mapped_tool_use_id
+                .or_else(|| self.answered_tool_use_id.clone())
+                .unwrap_or_else(|| self.generated_tool_use_id());
+            blocks.push(ContentBlock::ToolResult {
+                tool_use_id,
+                content,
+                is_error: None,
+            });
+        }
+
+        if blocks.is_empty() {
+            return Ok(None);
+        }
+
+        // Tool results are delivered back to the model as user turns (Claude convention)
+        let only_results = blocks
+            .iter()
+            .all(|b| matches!(b, ContentBlock::ToolResult { .. }));
+        let has_tool_use = blocks
+            .iter()
+            .any(|b| matches!(b, ContentBlock::ToolUse { .. }));
+
+        let (message_type, canonical_role, is_meta) = if only_results || role == "tool" {
+            (MessageType::User, "user", None)
+        } else if has_tool_use {
+            (MessageType::Assistant, "assistant", None)
+        } else {
+            match role.as_str() {
+                "user" => (MessageType::User, "user", None),
+                "system" => (MessageType::Meta, "user", Some(true)),
+                _ => (MessageType::Assistant, "assistant", None),
+            }
+        };
+
+        let content = match blocks.as_slice() {
+            [ContentBlock::Text { text }] => ContentValue::Text(text.clone()),
+            _ => ContentValue::Structured(blocks),
+        };
+
+        let model = if canonical_role == "assistant" {
+            self.field(&self.mapping.model)
+        } else {
+            None
+        };
+
+        Ok(Some(CanonicalMessage {
+            canonical_version: CANONICAL_VERSION.to_string(),
+            uuid: self.uuid(),
+            timestamp,
+            message_type,
+            session_id: self.session_id.to_string(),
+            provider: self.provider_name().to_string(),
+            cwd: self.extract_cwd(),
+            git_branch: None,
+            version: None,
+            parent_uuid: None,
+            is_sidechain: None,
+            user_type: Some("external".to_string()),
+            message: MessageContent {
+                role: canonical_role.to_string(),
+                content,
+                model,
+                usage: None,
+            },
+            provider_metadata: Some(self.raw.clone()),
+            is_meta,
+            request_id: None,
+            tool_use_result: None,
+        }))
+    }
+
+    fn provider_name(&self) -> &str {
+        self.provider_id
+    }
+
+    fn extract_cwd(&self) -> Option<String> {
+        self.field(&self.mapping.cwd)
+    }
+}
+
+impl CustomJsonlRecord<'_> {
+    /// Mapped UUID, or a stable one derived from session and line number
+    fn uuid(&self) -> String {
+        self.field(&self.mapping.uuid)
+            .unwrap_or_else(|| generate_uuid(self.session_id, self.line_number))
+    }
+
+    /// Tool call ID for records whose mapping has no `toolUseId`
+    fn generated_tool_use_id(&self) -> String {
+        format!("{}-tool", self.uuid())
+    }
+
+    /// Unmapped tool call or result that must be paired by position
+    fn unpaired_tool_kind(&self) -> Option<UnpairedTool> {
+        if self.field(&self.mapping.tool_use_id).is_some() {
+            return None;
+        }
+        match (
+            self.field(&self.mapping.tool_name).is_some(),
+            self.field(&self.mapping.tool_result).is_some(),
+        ) {
+            (true, false) => Some(UnpairedTool::Call),
+            (false, true) => Some(UnpairedTool::Result),
+            _ => None,
+        }
+    }
+}
+
+enum UnpairedTool {
+    Call,
+    Result,
+}
+
+/// Top-level record keys the mapping reads, or `None` if it reads whole records
+fn mapped_keys(mapping: &CustomJsonlMapping) -> Option<Vec<String>> {
+    [
+        Some(&mapping.timestamp),
+        Some(&mapping.role),
+        mapping.text.as_ref(),
+        mapping.tool_name.as_ref(),
+        mapping.tool_input.as_ref(),
+        mapping.tool_result.as_ref(),
+        mapping.tool_use_id.as_ref(),
+        mapping.session_id.as_ref(),
+        mapping.cwd.as_ref(),
+        mapping.uuid.as_ref(),
+        mapping.model.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|selector| top_level_keys(selector))
+    .collect::<Option<Vec<_>>>()
+    .map(|keys| keys.concat())
+}
+
+/// Convert the full content of a custom JSONL file
+///
+/// The session ID comes from the first record matching the `sessionId` selector
+/// (falling back to `fallback_session_id`), and the first CWD seen is applied to
+/// messages that don't carry one. Messages are threaded via `parent_uuid`.
+/// Without a `toolUseId` mapping, tool results answer the oldest unanswered
+/// tool call in file order.
+pub fn convert_content(
+    provider_id: &str,
+    mapping: &CustomJsonlMapping,
+    content: &str,
+    fallback_session_id: &str,
+) -> ConvertedSession {
+    let mut stats = ConversionStats::new();
+    let records: Vec<(usize, Value)> = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(index, line)| match serde_json::from_str::<Value>(line) {
+            Ok(value) => Some((index + 1, value)),
+            Err(e) => {
+                stats.record_failed();
+                stats.trace(index, 0, Some(format!("Invalid JSON: {}", e)));
+                None
+            }
+        })
+        .collect();
+
+    let session_id = mapping
+        .session_id
+        .as_deref()
+        .and_then(|selector| {
+            records
+                .iter()
+                .find_map(|(_, value)| select_string(value, selector))
+        })
+        .unwrap_or_else(|| fallback_session_id.to_string());
+
+    let known_keys = mapped_keys(mapping);
+    let mut messages: Vec<CanonicalMessage> = Vec::new();
+    let mut unanswered: VecDeque<String> = VecDeque::new();
+    for (line_number, raw) in records {
+        if let Some(ref known_keys) = known_keys {
+            let known: Vec<&str> = known_keys.iter().map(String::as_str).collect();
+            stats.observe_keys(&raw, "", &known);
+        }
+        let mut record =
+            CustomJsonlRecord::new(provider_id, mapping, &session_id, line_number, raw);
+        let unpaired = record.unpaired_tool_kind();
+        if let Some(UnpairedTool::Result) = unpaired {
+            record = record.answering(unanswered.pop_front());
+        }
+
+        let result = record.to_canonical();
+        stats.record_result(&result, "unmapped");
+        stats.trace(
+            line_number - 1,
+            usize::from(matches!(result, Ok(Some(_)))),
+            result.as_ref().err().map(|e| e.to_string()),
+        );
+        if let Ok(Some(mut message)) = result {
+            if let Some(UnpairedTool::Call) = unpaired {
+                unanswered.push_back(record.generated_tool_use_id());
+            }
+            message.parent_uuid = messages.last().map(|prev| prev.uuid.clone());
+            messages.push(message);
+        }
+    }
+
+    let cwd = messages.iter().find_map(|m| m.cwd.clone());
+    if let Some(ref session_cwd) = cwd {
+        for message in messages.iter_mut().filter(|m| m.cwd.is_none()) {
+            message.cwd = Some(session_cwd.clone());
+        }
+    }
+
+    ConvertedSession {
+        session_id,
+        cwd,
+        messages,
+        stats,
+    }
+}
+
+/// Normalize RFC 3339 strings and epoch seconds/milliseconds to RFC 3339 UTC
+fn normalize_timestamp(value: &Value) -> Option<String> {
+    let parsed: Option<DateTime<Utc>> = match value {
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok()
+            .or_else(|| s.parse::<i64>().ok().and_then(epoch_to_datetime)),
+        Value::Number(n) => n
+            .as_i64()
+            .or_else(|| n.as_f64().map(|f| f as i64))
+            .and_then(epoch_to_datetime),
+        _ => None,
+    };
+
+    parsed.map(|dt| dt.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+fn epoch_to_datetime(epoch: i64) -> Option<DateTime<Utc>> {
+    // Anything past ~year 5138 in seconds is assumed to be milliseconds
+    if epoch.abs() >= 100_000_000_000 {
+        Utc.timestamp_millis_opt(epoch).single()
+    } else {
+        Utc.timestamp_opt(epoch, 0).single()
+    }
+}
+
+fn generate_uuid(session_id: &str, line_number: usize) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    session_id.hash(&mut hasher);
+    line_number.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        (hash >> 32) as u32,
+        (hash >> 16) as u16,
+        hash as u16,
+        (hash >> 48) as u16,
+        hash & 0xFFFFFFFFFFFF
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn test_mapping() -> CustomJsonlMapping {
+        CustomJsonlMapping {
+            timestamp: "$.ts".to_string(),
+            role: "$.speaker".to_string(),
+            text: Some("$.body.text".to_string()),
+            tool_name: Some("$.tool.name".to_string()),
+            tool_input: Some("$.tool.args".to_string()),
+            tool_result: Some("$.tool.output".to_string()),
+            tool_use_id: Some("$.tool.call_id".to_string()),
+            session_id: Some("$.run_id".to_string()),
+            cwd: Some("$.env.cwd".to_string()),
+            uuid: None,
+            model: Some("$.model".to_string()),
+            role_map: HashMap::from([("bot".to_string(), "assistant".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_convert_text_and_tool_records() {
+        let content = r#"{"ts":"2025-03-01T10:00:00Z","speaker":"human","run_id":"run-1","env":{"cwd":"/work/agent"},"body":{"text":"List files"}}
+{"ts":"2025-03-01T10:00:01Z","speaker":"bot","model":"internal-1","tool":{"name":"ls","args":{"path":"."},"call_id":"call-1"}}
+{"ts":"2025-03-01T10:00:02Z","speaker":"tool","tool":{"output":"a.txt","call_id":"call-1"}}
+{"ts":"2025-03-01T10:00:03Z","speaker":"bot","body":{"text":"Found a.txt"}}"#;
+
+        let session = convert_content("custom-agent", &test_mapping(), content, "fallback");
+
+        assert_eq!(session.session_id, "run-1");
+        assert_eq!(session.cwd.as_deref(), Some("/work/agent"));
+        assert_eq!(session.messages.len(), 4);
+        assert_eq!(session.stats.failed, 0);
+        assert!(session.stats.unknown_event_types.is_empty());
+        assert!(session.stats.unknown_keys.is_empty());
+
+        let first = &session.messages[0];
+        assert_eq!(first.message_type, MessageType::User);
+        assert_eq!(first.provider, "custom-agent");
+        assert!(first.parent_uuid.is_none());
+        assert!(matches!(&first.message.content, ContentValue::Text(t) if t == "List files"));
+
+        let tool_use = &session.messages[1];
+        assert_eq!(tool_use.message_type, MessageType::Assistant);
+        assert_eq!(tool_use.message.model.as_deref(), Some("internal-1"));
+        assert_eq!(tool_use.parent_uuid.as_deref(), Some(first.uuid.as_str()));
+        assert_eq!(tool_use.cwd.as_deref(), Some("/work/agent"));
+        match &tool_use.message.content {
+            ContentValue::Structured(blocks) => match &blocks[0] {
+                ContentBlock::ToolUse { id, name, input } => {
+                    assert_eq!(id, "call-1");
+                    assert_eq!(name, "ls");
+                    assert_eq!(input["path"], ".");
+                }
+                _ => panic!("Expected tool_use block"),
+            },
+            _ => panic!("Expected structured content"),
+        }
+
+        let tool_result = &session.messages[2];
+        assert_eq!(tool_result.message_type, MessageType::User);
+        match &tool_result.message.content {
+            ContentValue::Structured(blocks) => match &blocks[0] {
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    ..
+                } => {
+                    assert_eq!(tool_use_id, "call-1");
+                    assert_eq!(content, "a.txt");
+                }
+                _ => panic!("Expected tool_result block"),
+            },
+            _ => panic!("Expected structured content"),
+        }
+    }
+
+    #[test]
+    fn test_unmapped_tool_results_pair_with_calls_in_order() {
+        let mapping = CustomJsonlMapping {
+            tool_use_id: None,
+            ..test_mapping()
+        };
+        let content = r#"{"ts":"2025-03-01T10:00:01Z","speaker":"bot","tool":{"name":"ls","args":{}}}
+{"ts":"2025-03-01T10:00:02Z","speaker":"bot","tool":{"name":"cat","args":{}}}
+{"ts":"2025-03-01T10:00:03Z","speaker":"tool","tool":{"output":"a.txt"}}
+{"ts":"2025-03-01T10:00:04Z","speaker":"tool","tool":{"output":"hello"}}
+{"ts":"2025-03-01T10:00:05Z","speaker":"tool","tool":{"output":"stray"}}"#;
+
+        let session = convert_content("custom-agent", &mapping, content, "s1");
+
+        let ids: Vec<&str> = session
+            .messages
+            .iter()
+            .map(|m| match &m.message.content {
+                ContentValue::Structured(blocks) => match &blocks[0] {
+                    ContentBlock::ToolUse { id, .. } => id.as_str(),
+                    ContentBlock::ToolResult { tool_use_id, .. } => tool_use_id.as_str(),
+                    _ => panic!("Expected tool block"),
+                },
+                _ => panic!("Expected structured content"),
+            })
+            .collect();
+
+        assert_eq!(ids[2], ids[0]);
+        assert_eq!(ids[3], ids[1]);
+        assert_ne!(ids[0], ids[1]);
+        // A result with nothing left to answer keeps its own ID
+        assert!(!ids[..2].contains(&ids[4]));
+    }
+
+    #[test]
+    fn test_skips_records_without_timestamp_or_content() {
+        let content = r#"{"speaker":"human","body":{"text":"no timestamp"}}
+{"ts":"2025-03-01T10:00:00Z","speaker":"human"}
+not json
+{"ts":1740823200000,"speaker":"human","body":{"text":"epoch millis"}}"#;
+
+        let session = convert_content("custom-agent", &test_mapping(), content, "fallback");
+
+        assert_eq!(session.session_id, "fallback");
+        assert_eq!(session.messages.len(), 1);
+        assert_eq!(session.messages[0].timestamp, "2025-03-01T10:00:00.000Z");
+        assert_eq!(session.stats.records_seen, 4);
+        assert_eq!(session.stats.skipped.get("unmapped"), Some(&2));
+        assert_eq!(session.stats.failed, 1);
+    }
+
+    #[test]
+    fn test_generated_uuids_are_stable() {
+        let content = r#"{"ts":"2025-03-01T10:00:00Z","speaker":"system","body":{"text":"boot"}}"#;
+
+        let first = convert_content("custom-agent", &test_mapping(), content, "s1");
+        let second = convert_content("custom-agent", &test_mapping(), content, "s1");
+
+        assert_eq!(first.messages[0].uuid, second.messages[0].uuid);
+        assert_eq!(first.messages[0].message_type, MessageType::Meta);
+        assert_eq!(first.messages[0].is_meta, Some(true));
+    }
+}
@@ -0,0 +1,315 @@
+//! Declarative "custom JSONL" providers
+//!
+//! Any provider whose ID starts with `custom-` is driven entirely by its
+//! `ProviderConfig.customJsonl` block: a session glob relative to the home
+//! directory plus a mapping spec that turns each JSONL record into a
+//! `CanonicalMessage`. Scanning and watching reuse `providers/common`.
+
+use super::sort_projects_by_modified;
+use crate::config::{
+    get_providers_dir, load_provider_config, CustomJsonlConfig, ProjectInfo, ProviderConfig,
+};
+use crate::providers::common::format_version::{FormatSpec, FormatVersionRange, NewerVersions};
+use chrono::{DateTime, Utc};
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::WalkBuilder;
+use shellexpand::tilde;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub mod converter;
+pub mod scanner;
+pub mod selector;
+pub mod watcher;
+
+pub use converter::{convert_content, ConvertedSession, CustomJsonlRecord};
+
+/// Provider ID prefix that marks a provider as custom JSONL
+pub const CUSTOM_PROVIDER_PREFIX: &str = "custom-";
+
+/// Mapping semantics this converter implements
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CustomJsonlFormat {
+    /// One record per line, fields picked by `CustomJsonlMapping` selectors
+    SelectorMapping,
+}
+
+/// Versioned by `customJsonl.formatVersion`, checked once per config load
+pub static CUSTOM_JSONL_FORMATS: FormatSpec<CustomJsonlFormat> = FormatSpec {
+    provider_id: "custom-jsonl",
+    version_field: "customJsonl.formatVersion",
+    ranges: &[FormatVersionRange {
+        min: "1",
+        max: Some("2"),
+        decoder: CustomJsonlFormat::SelectorMapping,
+    }],
+    unversioned: Some(CustomJsonlFormat::SelectorMapping),
+    // A mapping written for newer semantics would be misread
+    newer: NewerVersions::Refuse,
+};
+
+/// Check whether a provider ID refers to a custom JSONL provider
+pub fn is_custom_provider(provider_id: &str) -> bool {
+    provider_id.starts_with(CUSTOM_PROVIDER_PREFIX)
+        && provider_id.len() > CUSTOM_PROVIDER_PREFIX.len()
+}
+
+/// List the IDs of all configured custom providers (from ~/.guideai/providers)
+pub fn list_custom_provider_ids() -> Vec<String> {
+    let Ok(providers_dir) = get_providers_dir() else {
+        return Vec::new();
+    };
+
+    let mut ids: Vec<String> = fs::read_dir(providers_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                        return None;
+                    }
+                    path.file_stem()
+                        .and_then(|s| s.to_str())
+                        .filter(|id| is_custom_provider(id))
+                        .map(|id| id.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    ids.sort();
+    ids
+}
+
+/// Load and validate the config of a custom provider
+pub fn load_custom_config(
+    provider_id: &str,
+) -> Result<(ProviderConfig, CustomJsonlConfig), String> {
+    if !is_custom_provider(provider_id) {
+        return Err(format!("Not a custom provider: {}", provider_id));
+    }
+
+    let config = load_provider_config(provider_id)
+        .map_err(|e| format!("Failed to load provider config: {}", e))?;
+
+    let spec = config
+        .custom_jsonl
+        .clone()
+        .ok_or_else(|| format!("Provider '{}' has no customJsonl mapping", provider_id))?;
+
+    validate_spec(&spec)?;
+
+    Ok((config, spec))
+}
+
+/// Check that the glob and every configured selector parse
+pub fn validate_spec(spec: &CustomJsonlConfig) -> Result<(), String> {
+    if spec.session_glob.trim().is_empty() {
+        return Err("customJsonl.sessionGlob must not be empty".to_string());
+    }
+
+    let format_version = spec.format_version.as_deref();
+    let CustomJsonlFormat::SelectorMapping = CUSTOM_JSONL_FORMATS
+        .decoder_for(format_version)
+        .ok_or_else(|| {
+            format!(
+                "Unsupported customJsonl.formatVersion {} (supported: {})",
+                format_version.unwrap_or_default(),
+                CUSTOM_JSONL_FORMATS.describe()
+            )
+        })?;
+
+    let mapping = &spec.mapping;
+    let required = [("timestamp", &mapping.timestamp), ("role", &mapping.role)];
+    for (name, selector) in required {
+        if !selector::is_valid_selector(selector) {
+            return Err(format!("Invalid selector for '{}': '{}'", name, selector));
+        }
+    }
+
+    let optional = [
+        ("text", &mapping.text),
+        ("toolName", &mapping.tool_name),
+        ("toolInput", &mapping.tool_input),
+        ("toolResult", &mapping.tool_result),
+        ("toolUseId", &mapping.tool_use_id),
+        ("sessionId", &mapping.session_id),
+        ("cwd", &mapping.cwd),
+        ("uuid", &mapping.uuid),
+        ("model", &mapping.model),
+    ];
+    for (name, selector) in optional {
+        if let Some(selector) = selector {
+            if !selector::is_valid_selector(selector) {
+                return Err(format!("Invalid selector for '{}': '{}'", name, selector));
+            }
+        }
+    }
+
+    if mapping.text.is_none() && mapping.tool_name.is_none() && mapping.tool_result.is_none() {
+        return Err("Mapping must define at least one of text, toolName or toolResult".to_string());
+    }
+
+    Ok(())
+}
+
+/// Build a matcher for the session glob, rooted at the provider home directory
+pub fn session_glob_matcher(base_path: &Path, session_glob: &str) -> Result<Override, String> {
+    let mut builder = OverrideBuilder::new(base_path);
+    builder
+        .add(session_glob)
+        .map_err(|e| format!("Invalid session glob '{}': {}", session_glob, e))?;
+    builder
+        .build()
+        .map_err(|e| format!("Invalid session glob '{}': {}", session_glob, e))
+}
+
+/// Check whether a path matches the compiled session glob
+pub fn matches_session_glob(matcher: &Override, path: &Path) -> bool {
+    matcher.matched(path, false).is_whitelist()
+}
+
+/// Find every file under `base_path` that matches the session glob
+pub fn find_session_files(base_path: &Path, session_glob: &str) -> Result<Vec<PathBuf>, String> {
+    let matcher = session_glob_matcher(base_path, session_glob)?;
+
+    let walker = WalkBuilder::new(base_path)
+        .standard_filters(false)
+        .overrides(matcher)
+        .build();
+
+    let mut files: Vec<PathBuf> = walker
+        .flatten()
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .map(|entry| entry.into_path())
+        .collect();
+
+    files.sort();
+    Ok(files)
+}
+
+/// Project name for a custom session: the basename of its CWD
+pub fn project_name_from_cwd(cwd: Option<&str>) -> String {
+    cwd.and_then(|c| Path::new(c).file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// List projects for a custom provider by collecting session CWDs
+pub fn scan_projects(provider_id: &str, home_directory: &str) -> Result<Vec<ProjectInfo>, String> {
+    let (_, spec) = load_custom_config(provider_id)?;
+    let base_path = PathBuf::from(tilde(home_directory).into_owned());
+
+    if !base_path.exists() {
+        return Err(format!(
+            "Custom provider home directory not found: {}",
+            home_directory
+        ));
+    }
+
+    let mut latest_by_cwd: HashMap<String, DateTime<Utc>> = HashMap::new();
+
+    for file_path in find_session_files(&base_path, &spec.session_glob)? {
+        let Ok(content) = fs::read_to_string(&file_path) else {
+            continue;
+        };
+
+        let fallback_id = crate::providers::common::extract_session_id_from_filename(&file_path);
+        let session = convert_content(provider_id, &spec.mapping, &content, &fallback_id);
+        let Some(cwd) = session.cwd else {
+            continue;
+        };
+
+        let modified = fs::metadata(&file_path)
+            .and_then(|m| m.modified())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| DateTime::<Utc>::from(SystemTime::UNIX_EPOCH));
+
+        let entry = latest_by_cwd.entry(cwd).or_insert(modified);
+        if modified > *entry {
+            *entry = modified;
+        }
+    }
+
+    let projects = latest_by_cwd
+        .into_iter()
+        .map(|(cwd, modified)| {
+            (
+                modified,
+                ProjectInfo {
+                    name: project_name_from_cwd(Some(&cwd)),
+                    path: cwd,
+                    last_modified: modified.to_rfc3339(),
+                },
+            )
+        })
+        .collect();
+
+    Ok(sort_projects_by_modified(projects))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CustomJsonlMapping;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_custom_provider() {
+        assert!(is_custom_provider("custom-my-agent"));
+        assert!(!is_custom_provider("custom-"));
+        assert!(!is_custom_provider("codex"));
+    }
+
+    #[test]
+    fn test_validate_spec() {
+        let mut spec = CustomJsonlConfig {
+            session_glob: "**/*.jsonl".to_string(),
+            mapping: CustomJsonlMapping {
+                timestamp: "$.ts".to_string(),
+                role: "$.role".to_string(),
+                text: Some("$.text".to_string()),
+                ..Default::default()
+            },
+            format_version: None,
+        };
+        assert!(validate_spec(&spec).is_ok());
+
+        spec.format_version = Some("1".to_string());
+        assert!(validate_spec(&spec).is_ok());
+        spec.format_version = Some("2".to_string());
+        assert!(validate_spec(&spec).unwrap_err().contains("formatVersion"));
+        spec.format_version = None;
+
+        spec.mapping.cwd = Some("a..b".to_string());
+        assert!(validate_spec(&spec).unwrap_err().contains("cwd"));
+
+        spec.mapping.cwd = None;
+        spec.mapping.text = None;
+        assert!(validate_spec(&spec).is_err());
+    }
+
+    #[test]
+    fn test_find_session_files_respects_glob() {
+        let temp_dir = tempdir().unwrap();
+        let runs = temp_dir.path().join("runs").join("2025");
+        fs::create_dir_all(&runs).unwrap();
+        fs::write(runs.join("a.jsonl"), "{}").unwrap();
+        fs::write(runs.join("b.log"), "{}").unwrap();
+        fs::write(temp_dir.path().join("top.jsonl"), "{}").unwrap();
+
+        let files = find_session_files(temp_dir.path(), "runs/**/*.jsonl").unwrap();
+        assert_eq!(files, vec![runs.join("a.jsonl")]);
+
+        let matcher = session_glob_matcher(temp_dir.path(), "runs/**/*.jsonl").unwrap();
+        assert!(matches_session_glob(&matcher, &runs.join("new.jsonl")));
+        assert!(!matches_session_glob(
+            &matcher,
+            &temp_dir.path().join("top.jsonl")
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+//! Custom JSONL session scanner - finds files matching the session glob and
+//! converts them to canonical JSONL
+
+use super::{
+    convert_content, find_session_files, load_custom_config, project_name_from_cwd,
+    ConvertedSession,
+};
+use crate::config::CustomJsonlConfig;
+use crate::logging::{log_info, log_warn};
+use crate::providers::common::{extract_session_id_from_filename, get_canonical_path, SessionInfo};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Scan all sessions of a custom provider
+pub fn scan_sessions_filtered(
+    provider_id: &str,
+    base_path: &Path,
+    selected_projects: Option<&[String]>,
+) -> Result<Vec<SessionInfo>, String> {
+    let (_, spec) = load_custom_config(provider_id)?;
+
+    let mut sessions = Vec::new();
+
+    for file_path in find_session_files(base_path, &spec.session_glob)? {
+        match parse_custom_session(provider_id, &spec, &file_path, selected_projects) {
+            Ok(Some(session_info)) => sessions.push(session_info),
+            Ok(None) => {
+                // Session filtered out - skipped
+            }
+            Err(e) => {
+                if let Err(log_err) = log_warn(
+                    provider_id,
+                    &format!(
+                        "Failed to parse custom session {}: {}",
+                        file_path.display(),
+                        e
+                    ),
+                ) {
+                    eprintln!("Logging error: {}", log_err);
+                }
+            }
+        }
+    }
+
+    if let Err(e) = log_info(
+        provider_id,
+        &format!("📊 Found {} {} sessions", sessions.len(), provider_id),
+    ) {
+        eprintln!("Logging error: {}", e);
+    }
+
+    Ok(sessions)
+}
+
+/// Read and convert a raw custom JSONL file
+pub fn convert_file(
+    provider_id: &str,
+    spec: &CustomJsonlConfig,
+    file_path: &Path,
+) -> Result<ConvertedSession, String> {
+    let content =
+        fs::read_to_string(file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let fallback_id = extract_session_id_from_filename(file_path);
+    let session = convert_content(provider_id, &spec.mapping, &content, &fallback_id);
+
+    if session.messages.is_empty() {
+        return Err("No records matched the mapping".to_string());
+    }
+
+    Ok(session)
+}
+
+/// Write a converted session to its project-organized canonical path
+pub fn write_canonical_file(
+    provider_id: &str,
+    session: &ConvertedSession,
+) -> Result<PathBuf, String> {
+    let canonical_lines: Vec<String> = session
+        .messages
+        .iter()
+        .filter_map(|msg| serde_json::to_string(msg).ok())
+        .collect();
+
+    let cache_path = get_canonical_path(provider_id, session.cwd.as_deref(), &session.session_id)
+        .map_err(|e| format!("Failed to get canonical path: {}", e))?;
+
+    fs::write(&cache_path, canonical_lines.join("\n"))
+        .map_err(|e| format!("Failed to write canonical JSONL: {}", e))?;
+    session.stats.persist(provider_id, &session.session_id);
+
+    Ok(cache_path)
+}
+
+fn parse_custom_session(
+    provider_id: &str,
+    spec: &CustomJsonlConfig,
+    file_path: &Path,
+    selected_projects: Option<&[String]>,
+) -> Result<Option<SessionInfo>, String> {
+    let session = convert_file(provider_id, spec, file_path)?;
+    let project_name = project_name_from_cwd(session.cwd.as_deref());
+
+    // Filter projects BEFORE writing the canonical cache
+    if let Some(selected) = selected_projects {
+        if !selected.contains(&project_name) {
+            return Ok(None);
+        }
+    }
+
+    let cache_path = write_canonical_file(provider_id, &session)?;
+
+    let parse_ts = |ts: &str| {
+        DateTime::parse_from_rfc3339(ts)
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc))
+    };
+    let session_start_time = session
+        .messages
+        .first()
+        .and_then(|m| parse_ts(&m.timestamp));
+    let session_end_time = session.messages.last().and_then(|m| parse_ts(&m.timestamp));
+
+    let duration_ms = match (session_start_time, session_end_time) {
+        (Some(start), Some(end)) => Some((end - start).num_milliseconds()),
+        _ => None,
+    };
+
+    let file_size = fs::metadata(&cache_path).map(|m| m.len()).unwrap_or(0);
+
+    let file_name = cache_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("unknown.jsonl")
+        .to_string();
+
+    Ok(Some(SessionInfo {
+        provider: provider_id.to_string(),
+        project_name,
+        session_id: session.session_id,
+        file_path: cache_path,
+        file_name,
+        session_start_time,
+        session_end_time,
+        duration_ms,
+        file_size,
+        content: None,
+        cwd: session.cwd,
+        project_hash: None,
+    }))
+}
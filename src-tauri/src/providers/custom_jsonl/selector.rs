@@ -0,0 +1,177 @@
+//! JSONPath-like selectors used by custom JSONL mappings
+//!
+//! Supported syntax is intentionally small:
+//! - `$.a.b` / `a.b` - object keys (leading `$` is optional)
+//! - `items[0]` / `items.0` - array indexes
+//! - `a.b | c.d` - alternatives, the first selector that resolves wins
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse a single (non-alternative) selector into path segments
+fn parse_path(selector: &str) -> Option<Vec<Segment>> {
+    let trimmed = selector.trim();
+    let trimmed = trimmed.strip_prefix('$').unwrap_or(trimmed);
+    let trimmed = trimmed.strip_prefix('.').unwrap_or(trimmed);
+
+    let mut segments = Vec::new();
+    if trimmed.is_empty() {
+        return Some(segments);
+    }
+
+    for part in trimmed.split('.') {
+        if part.is_empty() {
+            return None;
+        }
+
+        // Split "name[0][1]" into the key and its bracketed indexes
+        let (key, mut rest) = match part.find('[') {
+            Some(pos) => (&part[..pos], &part[pos..]),
+            None => (part, ""),
+        };
+
+        if !key.is_empty() {
+            match key.parse::<usize>() {
+                Ok(index) => segments.push(Segment::Index(index)),
+                Err(_) => segments.push(Segment::Key(key.to_string())),
+            }
+        }
+
+        while !rest.is_empty() {
+            let close = rest.find(']')?;
+            let inner = rest[1..close].trim_matches(|c| c == '"' || c == '\'');
+            match inner.parse::<usize>() {
+                Ok(index) => segments.push(Segment::Index(index)),
+                Err(_) => segments.push(Segment::Key(inner.to_string())),
+            }
+            rest = &rest[close + 1..];
+            if !rest.is_empty() && !rest.starts_with('[') {
+                return None;
+            }
+        }
+    }
+
+    Some(segments)
+}
+
+fn resolve<'a>(value: &'a Value, segments: &[Segment]) -> Option<&'a Value> {
+    segments
+        .iter()
+        .try_fold(value, |current, segment| match segment {
+            Segment::Key(key) => current.get(key.as_str()),
+            Segment::Index(index) => current.get(*index),
+        })
+}
+
+/// Resolve a selector against a JSON value
+///
+/// Returns the first non-null match across `|`-separated alternatives.
+pub fn select<'a>(value: &'a Value, selector: &str) -> Option<&'a Value> {
+    selector
+        .split('|')
+        .filter_map(parse_path)
+        .filter_map(|segments| resolve(value, &segments))
+        .find(|found| !found.is_null())
+}
+
+/// Resolve a selector and render the result as a string
+///
+/// Strings are returned as-is, numbers and booleans are formatted, and
+/// objects/arrays are serialized to compact JSON.
+pub fn select_string(value: &Value, selector: &str) -> Option<String> {
+    select(value, selector).map(|found| match found {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    })
+}
+
+/// Top-level keys a selector reads, or `None` if it selects the whole record
+pub fn top_level_keys(selector: &str) -> Option<Vec<String>> {
+    selector
+        .split('|')
+        .filter_map(parse_path)
+        .map(|segments| match segments.first() {
+            Some(Segment::Key(key)) => Some(key.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Check that every alternative in a selector parses
+pub fn is_valid_selector(selector: &str) -> bool {
+    !selector.trim().is_empty() && selector.split('|').all(|alt| parse_path(alt).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_top_level_keys() {
+        assert_eq!(
+            top_level_keys("$.payload.message.role | meta.role"),
+            Some(vec!["payload".to_string(), "meta".to_string()])
+        );
+        assert_eq!(top_level_keys("$"), None);
+    }
+
+    #[test]
+    fn test_select_nested_keys() {
+        let value = json!({"payload": {"message": {"role": "user"}}});
+        assert_eq!(
+            select_string(&value, "$.payload.message.role"),
+            Some("user".to_string())
+        );
+        assert_eq!(
+            select_string(&value, "payload.message.role"),
+            Some("user".to_string())
+        );
+        assert_eq!(select(&value, "payload.missing"), None);
+    }
+
+    #[test]
+    fn test_select_array_indexes() {
+        let value = json!({"content": [{"text": "first"}, {"text": "second"}]});
+        assert_eq!(
+            select_string(&value, "$.content[1].text"),
+            Some("second".to_string())
+        );
+        assert_eq!(
+            select_string(&value, "content.0.text"),
+            Some("first".to_string())
+        );
+        assert_eq!(select(&value, "content[5].text"), None);
+    }
+
+    #[test]
+    fn test_select_alternatives() {
+        let value = json!({"ts": null, "time": "2025-01-01T00:00:00Z"});
+        assert_eq!(
+            select_string(&value, "$.ts | $.time"),
+            Some("2025-01-01T00:00:00Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_select_string_formats_non_strings() {
+        let value = json!({"n": 42, "obj": {"a": 1}});
+        assert_eq!(select_string(&value, "n"), Some("42".to_string()));
+        assert_eq!(select_string(&value, "obj"), Some(r#"{"a":1}"#.to_string()));
+        assert_eq!(select_string(&value, "$"), Some(value.to_string()));
+    }
+
+    #[test]
+    fn test_is_valid_selector() {
+        assert!(is_valid_selector("$.a.b[0]"));
+        assert!(is_valid_selector("a | b.c"));
+        assert!(!is_valid_selector(""));
+        assert!(!is_valid_selector("a..b"));
+        assert!(!is_valid_selector("a[0"));
+    }
+}
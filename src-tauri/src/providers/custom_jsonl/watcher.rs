@@ -0,0 +1,419 @@
+use super::scanner::{convert_file, write_canonical_file};
+use super::{
+    load_custom_config, matches_session_glob, project_name_from_cwd, session_glob_matcher,
+};
+use crate::config::{CustomJsonlConfig, ProviderConfig};
+use crate::config_service::ProviderConfigUpdates;
+use crate::events::{EventBus, SessionEventPayload};
+use crate::logging::{log_error, log_info, log_warn};
+use crate::providers::common::{
+    fs_event_channel, get_file_size, run_blocking, should_skip_file, Debouncer, FsEventReceiver,
+    FsWatcher, SessionLifecycle, SessionStateManager, WatchCancellation, WatchTask,
+    WatcherHeartbeat, WatcherStatus, EVENT_TIMEOUT, FILE_WATCH_POLL_INTERVAL,
+    MIN_SIZE_CHANGE_BYTES,
+};
+use crate::upload_queue::UploadQueue;
+use ignore::overrides::Override;
+use notify::{Config, Event, EventKind, RecursiveMode, Watcher};
+use shellexpand::tilde;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// Watcher for a declarative custom JSONL provider
+///
+/// Watches the provider home directory recursively and converts every file
+/// that matches the configured session glob.
+#[derive(Debug)]
+pub struct CustomJsonlWatcher {
+    provider_id: String,
+    _watcher: FsWatcher,
+    task: WatchTask,
+    upload_queue: Arc<UploadQueue>,
+    is_running: Arc<Mutex<bool>>,
+    heartbeat: WatcherHeartbeat,
+}
+
+impl CustomJsonlWatcher {
+    pub fn new(
+        provider_id: String,
+        upload_queue: Arc<UploadQueue>,
+        event_bus: EventBus,
+        shutdown: CancellationToken,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        if let Err(e) = log_info(&provider_id, "🔍 Starting custom JSONL file monitoring") {
+            eprintln!("Logging error: {}", e);
+        }
+
+        let (config, spec) = load_custom_config(&provider_id)?;
+
+        if !config.enabled {
+            return Err(format!("Custom provider '{}' is not enabled", provider_id).into());
+        }
+
+        let expanded_home = tilde(&config.home_directory);
+        let base_path = PathBuf::from(expanded_home.as_ref());
+
+        if !base_path.exists() {
+            return Err(format!(
+                "Custom provider home directory does not exist: {}",
+                base_path.display()
+            )
+            .into());
+        }
+
+        let matcher = session_glob_matcher(&base_path, &spec.session_glob)?;
+
+        let selected_projects = if config.project_selection == "ALL" {
+            None
+        } else {
+            Some(config.selected_projects.clone())
+        };
+
+        let (tx, rx) = fs_event_channel(&provider_id);
+
+        let mut watcher = FsWatcher::for_provider(
+            &provider_id,
+            tx,
+            Config::default().with_poll_interval(FILE_WATCH_POLL_INTERVAL),
+        )?;
+        watcher.watch(&base_path, RecursiveMode::Recursive)?;
+
+        if let Err(e) = log_info(
+            &provider_id,
+            &format!(
+                "📁 Watching {} for sessions matching '{}'",
+                base_path.display(),
+                spec.session_glob
+            ),
+        ) {
+            eprintln!("Logging error: {}", e);
+        }
+
+        let heartbeat = WatcherHeartbeat::new(&provider_id, &base_path, watcher.is_polling());
+        let is_running = Arc::new(Mutex::new(true));
+        let is_running_clone = Arc::clone(&is_running);
+        let provider_id_clone = provider_id.clone();
+        let heartbeat_clone = heartbeat.clone();
+
+        let task = WatchTask::spawn(&shutdown, move |cancellation| async move {
+            let _exit_guard = heartbeat_clone.exit_guard();
+            Self::file_event_processor(
+                rx,
+                provider_id_clone,
+                base_path,
+                spec,
+                matcher,
+                selected_projects,
+                event_bus,
+                is_running_clone,
+                heartbeat_clone,
+                cancellation,
+            )
+            .await;
+        });
+
+        Ok(CustomJsonlWatcher {
+            provider_id,
+            _watcher: watcher,
+            task,
+            upload_queue,
+            is_running,
+            heartbeat,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn file_event_processor(
+        mut rx: FsEventReceiver,
+        provider_id: String,
+        base_path: PathBuf,
+        mut spec: CustomJsonlConfig,
+        mut matcher: Override,
+        mut selected_projects: Option<Vec<String>>,
+        event_bus: EventBus,
+        is_running: Arc<Mutex<bool>>,
+        heartbeat: WatcherHeartbeat,
+        mut cancellation: WatchCancellation,
+    ) {
+        let mut session_states = SessionStateManager::new();
+        let mut lifecycle = SessionLifecycle::new(&provider_id, event_bus.clone());
+        let mut config_updates = ProviderConfigUpdates::new(&provider_id);
+        let mut debouncer = Debouncer::default();
+
+        loop {
+            heartbeat.beat();
+
+            // Move quiet sessions towards idle/completed
+            for session_id in lifecycle.tick() {
+                session_states.mark_inactive(&session_id);
+            }
+
+            // Apply config edits without restarting the watcher
+            if let Some(config) = config_updates.poll() {
+                if !config.enabled {
+                    cancellation.stop_disabled(&provider_id, &is_running);
+                    continue;
+                }
+                lifecycle.set_config(&config);
+                match Self::reconfigure(&base_path, &config) {
+                    Ok((new_spec, new_matcher, new_selection)) => {
+                        spec = new_spec;
+                        matcher = new_matcher;
+                        selected_projects = new_selection;
+                    }
+                    Err(e) => {
+                        let _ = log_warn(&provider_id, &format!("Keeping previous mapping: {}", e));
+                    }
+                }
+            }
+
+            // Wait for file system events, debouncing bursts per path
+            let wake_at = debouncer.wake_at(Instant::now(), EVENT_TIMEOUT);
+            let stopping = tokio::select! {
+                _ = cancellation.cancelled() => true,
+                received = rx.recv() => match received {
+                    Some(Ok(event)) => {
+                        debouncer.push(event, Instant::now());
+                        false
+                    }
+                    Some(Err(error)) => {
+                        heartbeat.report(&error);
+                        if let Err(e) = log_error(
+                            &provider_id,
+                            &format!("Custom JSONL file watcher error: {:?}", error),
+                        ) {
+                            eprintln!("Logging error: {}", e);
+                        }
+                        false
+                    }
+                    None => {
+                        if let Err(e) = log_error(
+                            &provider_id,
+                            "Custom JSONL file watcher channel disconnected",
+                        ) {
+                            eprintln!("Logging error: {}", e);
+                        }
+                        true
+                    }
+                },
+                _ = tokio::time::sleep_until(wake_at) => false,
+            };
+
+            // Stopping flushes whatever is still being debounced
+            let events = if stopping {
+                debouncer.drain()
+            } else {
+                debouncer.ready(Instant::now())
+            };
+            for event in events {
+                for path in Self::matching_paths(&event, &matcher) {
+                    Self::handle_session_file(
+                        &provider_id,
+                        &spec,
+                        &path,
+                        selected_projects.as_deref(),
+                        &event_bus,
+                        &mut session_states,
+                        &mut lifecycle,
+                    )
+                    .await;
+                }
+            }
+
+            if stopping {
+                break;
+            }
+        }
+
+        if let Err(e) = log_info(&provider_id, "🛑 Custom JSONL file monitoring stopped") {
+            eprintln!("Logging error: {}", e);
+        }
+    }
+
+    /// Rebuild the mapping, glob matcher and project filter from a reloaded config
+    fn reconfigure(
+        base_path: &Path,
+        config: &ProviderConfig,
+    ) -> Result<(CustomJsonlConfig, Override, Option<Vec<String>>), String> {
+        let spec = config
+            .custom_jsonl
+            .clone()
+            .ok_or("Provider has no customJsonl mapping")?;
+        let matcher = session_glob_matcher(base_path, &spec.session_glob)?;
+        let selected_projects = if config.project_selection == "ALL" {
+            None
+        } else {
+            Some(config.selected_projects.clone())
+        };
+        Ok((spec, matcher, selected_projects))
+    }
+
+    fn matching_paths(event: &Event, matcher: &Override) -> Vec<PathBuf> {
+        match &event.kind {
+            EventKind::Create(_) | EventKind::Modify(_) => event
+                .paths
+                .iter()
+                .filter(|path| !should_skip_file(path))
+                .filter(|path| path.is_file())
+                .filter(|path| matches_session_glob(matcher, path))
+                .cloned()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    async fn handle_session_file(
+        provider_id: &str,
+        spec: &CustomJsonlConfig,
+        path: &Path,
+        selected_projects: Option<&[String]>,
+        event_bus: &EventBus,
+        session_states: &mut SessionStateManager,
+        lifecycle: &mut SessionLifecycle,
+    ) {
+        let converted = {
+            let (provider_id, spec, path) =
+                (provider_id.to_string(), spec.clone(), path.to_path_buf());
+            run_blocking(move || convert_file(&provider_id, &spec, &path)).await
+        };
+        let session = match converted {
+            Ok(session) => session,
+            Err(e) => {
+                if let Err(log_err) = log_error(
+                    provider_id,
+                    &format!("Failed to convert {}: {}", path.display(), e),
+                ) {
+                    eprintln!("Logging error: {}", log_err);
+                }
+                return;
+            }
+        };
+
+        let project_name = project_name_from_cwd(session.cwd.as_deref());
+        if let Some(selected) = selected_projects {
+            if !selected.contains(&project_name) {
+                return;
+            }
+        }
+
+        let file_size = get_file_size(path).unwrap_or(0);
+        let is_new_session = !session_states.contains(&session.session_id);
+        let state = session_states.get_or_create(&session.session_id, file_size);
+        let should_log = state.should_log(file_size, MIN_SIZE_CHANGE_BYTES, is_new_session);
+
+        let (session, written) = {
+            let provider_id = provider_id.to_string();
+            run_blocking(move || {
+                let written = write_canonical_file(&provider_id, &session);
+                (session, written)
+            })
+            .await
+        };
+        match written {
+            Ok(canonical_path) => {
+                let canonical_size = get_file_size(&canonical_path).unwrap_or(0);
+
+                let payload = SessionEventPayload::SessionChanged {
+                    session_id: session.session_id.clone(),
+                    project_name,
+                    file_path: canonical_path,
+                    file_size: canonical_size,
+                };
+
+                if let Err(e) = event_bus.publish(provider_id, payload) {
+                    if let Err(log_err) = log_error(
+                        provider_id,
+                        &format!("Failed to publish session event: {}", e),
+                    ) {
+                        eprintln!("Logging error: {}", log_err);
+                    }
+                }
+            }
+            Err(e) => {
+                if let Err(log_err) = log_error(
+                    provider_id,
+                    &format!(
+                        "Failed to convert session {} to canonical format: {}",
+                        session.session_id, e
+                    ),
+                ) {
+                    eprintln!("Logging error: {}", log_err);
+                }
+            }
+        }
+
+        state.update(file_size);
+        lifecycle.record_activity(&session.session_id, path);
+        if is_new_session {
+            state.mark_as_seen();
+        }
+
+        if should_log {
+            let log_message = if is_new_session {
+                format!("🆕 New session detected: {}", session.session_id)
+            } else {
+                format!(
+                    "📝 Session changed: {} (size: {} bytes)",
+                    session.session_id, file_size
+                )
+            };
+            if let Err(e) = log_info(provider_id, &log_message) {
+                eprintln!("Logging error: {}", e);
+            }
+        }
+    }
+
+    pub fn provider_id(&self) -> &str {
+        &self.provider_id
+    }
+
+    pub fn stop(&self) {
+        if let Ok(mut running) = self.is_running.lock() {
+            *running = false;
+        }
+        self.task.stop();
+
+        if let Err(e) = log_info(
+            &self.provider_id,
+            "🛑 Stopping custom JSONL file monitoring",
+        ) {
+            eprintln!("Logging error: {}", e);
+        }
+    }
+
+    pub fn task(&self) -> &WatchTask {
+        &self.task
+    }
+
+    pub fn heartbeat(&self) -> &WatcherHeartbeat {
+        &self.heartbeat
+    }
+
+    pub fn get_status(&self) -> WatcherStatus {
+        // A panicked task never clears the flag, so ask the heartbeat too
+        let is_running = if let Ok(running) = self.is_running.lock() {
+            *running && self.heartbeat.is_alive()
+        } else {
+            false
+        };
+
+        let upload_status = self.upload_queue.get_status();
+
+        WatcherStatus {
+            is_running,
+            pending_uploads: upload_status.pending,
+            processing_uploads: upload_status.processing,
+            failed_uploads: upload_status.failed,
+            watched_paths: Vec::new(),
+            unsupported_formats: Vec::new(),
+        }
+    }
+}
+
+impl Drop for CustomJsonlWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
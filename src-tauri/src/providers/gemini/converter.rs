@@ -1,14 +1,33 @@
+use super::parser::{GeminiMessage, GeminiSession};
+use super::registry::GeminiProjectRegistry;
+use super::utils::resolve_session_cwd;
 use crate::providers::canonical::{
-    converter::ToCanonical, CanonicalMessage, ContentBlock, ContentValue, MessageContent,
-    MessageType, TokenUsage,
+    converter::ToCanonical, fidelity::ConversionStats, CanonicalMessage, ContentBlock,
+    ContentValue, MessageContent, MessageType, TokenUsage, CANONICAL_VERSION,
 };
+use crate::providers::common::format_version::{FormatSpec, NewerVersions};
 use crate::providers::common::get_canonical_path;
-use super::parser::{GeminiMessage, GeminiSession};
 use anyhow::{Context, Result};
 use serde_json::Value;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Gemini CLI session formats this converter can decode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeminiFormat {
+    /// One JSON document with a `messages` array
+    SessionJson,
+}
+
+/// Gemini CLI doesn't stamp a version yet; one appearing is logged as drift
+pub static GEMINI_FORMATS: FormatSpec<GeminiFormat> = FormatSpec {
+    provider_id: "gemini-code",
+    version_field: "version",
+    ranges: &[],
+    unversioned: Some(GeminiFormat::SessionJson),
+    newer: NewerVersions::Warn,
+};
+
 /// Implement ToCanonical for GeminiMessage
 ///
 /// Gemini messages need special handling:
@@ -75,6 +94,7 @@ impl ToCanonical for GeminiMessage {
         });
 
         Ok(Some(CanonicalMessage {
+            canonical_version: CANONICAL_VERSION.to_string(),
             uuid: self.id.clone(),
             timestamp: self.timestamp.clone(),
             message_type,
@@ -118,9 +138,56 @@ pub fn convert_session_to_canonical(
     session: &GeminiSession,
     cwd: Option<String>,
 ) -> Result<Vec<CanonicalMessage>> {
+    convert_session_with_stats(session, cwd).map(|(messages, _)| messages)
+}
+
+/// Session-level keys read from a Gemini session file
+const KNOWN_SESSION_KEYS: &[&str] = &[
+    "sessionId",
+    "projectHash",
+    "startTime",
+    "lastUpdated",
+    "messages",
+];
+
+/// Message keys read by `GeminiMessage`
+const KNOWN_MESSAGE_KEYS: &[&str] = &[
+    "id",
+    "timestamp",
+    "type",
+    "content",
+    "toolCalls",
+    "thoughts",
+    "tokens",
+    "model",
+];
+
+/// Count session and message keys the converter does not read
+fn observe_session_keys(raw: &Value, stats: &mut ConversionStats) {
+    stats.observe_keys(raw, "", KNOWN_SESSION_KEYS);
+    for raw_message in raw
+        .get("messages")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        stats.observe_keys(raw_message, "messages[]", KNOWN_MESSAGE_KEYS);
+    }
+}
+
+/// `convert_session_to_canonical` plus per-message fidelity stats
+///
+/// Each Gemini message is one record; messages without content, thoughts or
+/// tool calls are counted as skipped.
+pub fn convert_session_with_stats(
+    session: &GeminiSession,
+    cwd: Option<String>,
+) -> Result<(Vec<CanonicalMessage>, ConversionStats)> {
     let mut canonical_messages = Vec::new();
+    let mut stats = ConversionStats::new();
 
     for message in &session.messages {
+        let produced_before = canonical_messages.len();
         // First, handle tool calls if present
         if let Some(ref tool_calls) = message.tool_calls {
             for tool_call in tool_calls {
@@ -132,6 +199,7 @@ pub fn convert_session_to_canonical(
                 };
 
                 let tool_use_msg = CanonicalMessage {
+                    canonical_version: CANONICAL_VERSION.to_string(),
                     uuid: tool_call.id.clone(),
                     timestamp: message.timestamp.clone(),
                     message_type: MessageType::Assistant,
@@ -200,6 +268,7 @@ pub fn convert_session_to_canonical(
                     };
 
                     let tool_result_msg = CanonicalMessage {
+                        canonical_version: CANONICAL_VERSION.to_string(),
                         uuid: format!("{}_result", tool_call.id),
                         timestamp: message.timestamp.clone(),
                         message_type: MessageType::User,  // Tool results are USER messages
@@ -253,9 +322,14 @@ pub fn convert_session_to_canonical(
                 canonical_messages.push(canonical_msg);
             }
         }
+
+        match canonical_messages.len() - produced_before {
+            0 => stats.record_skipped(&format!("empty:{}", message.message_type)),
+            produced => stats.record_converted(produced),
+        }
     }
 
-    Ok(canonical_messages)
+    Ok((canonical_messages, stats))
 }
 
 /// Convert Gemini JSON file to canonical JSONL and cache it
@@ -266,6 +340,7 @@ pub fn convert_session_to_canonical(
 /// # Arguments
 /// * `json_file_path` - Path to the original Gemini session JSON file
 /// * `session_id` - Session identifier (from filename)
+/// * `registry` - Project registry, loaded once by the caller
 ///
 /// # Returns
 /// The path to the cached canonical JSONL file
@@ -279,6 +354,7 @@ pub fn convert_session_to_canonical(
 pub fn convert_to_canonical_file(
     json_file_path: &Path,
     session_id: &str,
+    registry: &GeminiProjectRegistry,
 ) -> Result<PathBuf> {
     const PROVIDER_ID: &str = "gemini-code";
 
@@ -289,12 +365,23 @@ pub fn convert_to_canonical_file(
     // Parse the Gemini session
     let session = GeminiSession::from_json(&content)
         .context("Failed to parse Gemini session JSON")?;
-
-    // Try to infer CWD from message content using shared utility
-    let cwd = infer_cwd_from_session(&session);
+    let raw = serde_json::from_str::<Value>(&content).ok();
+    let version = raw
+        .as_ref()
+        .and_then(|raw| raw.get("version"))
+        .and_then(Value::as_str);
+    let GeminiFormat::SessionJson = GEMINI_FORMATS
+        .resolve(session_id, version)
+        .map_err(anyhow::Error::msg)?;
+
+    // Resolve CWD from the project registry, falling back to message content
+    let cwd = resolve_session_cwd(&session, registry);
 
     // Convert to canonical format
-    let canonical_messages = convert_session_to_canonical(&session, cwd.clone())?;
+    let (canonical_messages, mut stats) = convert_session_with_stats(&session, cwd.clone())?;
+    if let Some(raw) = &raw {
+        observe_session_keys(raw, &mut stats);
+    }
 
     // Serialize each message to JSONL
     let mut canonical_lines = Vec::new();
@@ -315,17 +402,11 @@ pub fn convert_to_canonical_file(
     // Write to project-organized path
     fs::write(&canonical_path, canonical_content)
         .context(format!("Failed to write canonical JSONL to {:?}", canonical_path))?;
+    stats.persist(PROVIDER_ID, session_id);
 
     Ok(canonical_path)
 }
 
-/// Infer working directory from Gemini session messages
-///
-/// Uses the shared CWD extraction function from gemini_utils.rs
-fn infer_cwd_from_session(session: &GeminiSession) -> Option<String> {
-    use super::utils::infer_cwd_from_session as shared_infer_cwd;
-    shared_infer_cwd(session, &session.project_hash)
-}
 
 #[cfg(test)]
 mod tests {
@@ -423,11 +504,16 @@ mod tests {
             }],
         };
 
-        let canonical = convert_session_to_canonical(&session, Some("/test/path".to_string()))
-            .unwrap();
+        let (canonical, mut stats) =
+            convert_session_with_stats(&session, Some("/test/path".to_string())).unwrap();
+        observe_session_keys(&serde_json::to_value(&session).unwrap(), &mut stats);
 
         // Should have 2 messages: tool_use + tool_result
         assert_eq!(canonical.len(), 2);
+        assert_eq!(stats.converted, 1);
+        assert_eq!(stats.failed, 0);
+        assert!(stats.unknown_event_types.is_empty());
+        assert!(stats.unknown_keys.is_empty());
 
         // First message: tool_use
         assert_eq!(canonical[0].uuid, "call-1");
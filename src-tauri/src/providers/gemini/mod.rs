@@ -1,6 +1,7 @@
 pub mod converter;
 pub mod parser;
 pub mod registry;
+pub mod resolver;
 pub mod scanner;
 pub mod utils;
 pub mod watcher;
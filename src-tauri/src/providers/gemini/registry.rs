@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Registry entry for a Gemini project
 /// Maps project hash to its working directory and metadata
@@ -43,24 +43,37 @@ impl GeminiProjectRegistry {
 
     /// Save the registry to disk
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let path = Self::get_registry_path()?;
+        self.save_to(&Self::get_registry_path()?)
+    }
 
+    /// Write to a temporary file and rename it over `path`, so a crash or a
+    /// concurrent scan never leaves a truncated registry behind
+    fn save_to(&self, path: &Path) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
 
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(format!(".{}.tmp", std::process::id()));
+        let tmp_path = PathBuf::from(tmp_path);
+
         let content = serde_json::to_string_pretty(self)?;
-        fs::write(&path, content)?;
+        fs::write(&tmp_path, content)?;
 
         // Set permissions to 600 (read/write for owner only) on Unix systems
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
-            let metadata = fs::metadata(&path)?;
+            let metadata = fs::metadata(&tmp_path)?;
             let mut permissions = metadata.permissions();
             permissions.set_mode(0o600);
-            fs::set_permissions(&path, permissions)?;
+            fs::set_permissions(&tmp_path, permissions)?;
+        }
+
+        if let Err(e) = fs::rename(&tmp_path, path) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e.into());
         }
 
         Ok(())
@@ -153,4 +166,20 @@ mod tests {
         assert_eq!(entry.cwd, "/test/path");
         assert_eq!(entry.name, "testproj");
     }
+
+    #[test]
+    fn test_save_replaces_file_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gemini-code-projects.json");
+        fs::write(&path, "{ not json").unwrap();
+
+        let mut registry = GeminiProjectRegistry::default();
+        registry.update_project("h".to_string(), "/p".to_string(), "p".to_string());
+        registry.save_to(&path).unwrap();
+
+        let saved: GeminiProjectRegistry =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.get_project("h").unwrap().cwd, "/p");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
 }
@@ -0,0 +1,503 @@
+//! Resolve Gemini project hashes to directories
+//!
+//! Gemini stores each project under `tmp/{sha256(cwd)}`, so instead of guessing
+//! the cwd from message content we hash candidate directories and look the
+//! hashes up. Candidates come from git repos under the configured project
+//! roots, cwds other providers have recorded, and `cd` targets in shell
+//! history (plus their parent directories). Hashes none of them match are
+//! left alone for `UNRESOLVED_RETRY` so every scan doesn't repeat the walk.
+
+use super::registry::GeminiProjectRegistry;
+use super::utils::verify_hash;
+use crate::logging::log_warn;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+const PROVIDER_ID: &str = "gemini-code";
+
+/// Roots searched for git repos when the provider config doesn't set `projectRoots`
+pub const DEFAULT_PROJECT_ROOTS: &[&str] = &[
+    "~/work",
+    "~/code",
+    "~/projects",
+    "~/src",
+    "~/dev",
+    "~/Developer",
+    "~/repos",
+    "~/git",
+];
+
+/// How deep below a root to look for `.git` directories
+const MAX_REPO_DEPTH: usize = 4;
+
+/// How long an unresolved hash is skipped before candidates are collected again
+const UNRESOLVED_RETRY: Duration = Duration::from_secs(10 * 60);
+
+/// Hashes no candidate matched, with when that was last checked
+static UNRESOLVED: LazyLock<Mutex<HashMap<String, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Directories never worth descending into while looking for repos
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateSource {
+    GitRepo,
+    KnownCwd,
+    ShellHistory,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Candidate {
+    pub path: String,
+    pub source: CandidateSource,
+}
+
+/// A registry entry that disagrees with what the hash resolves to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RegistryConflict {
+    pub hash: String,
+    pub registered_cwd: String,
+    pub resolved_cwd: Option<String>,
+}
+
+/// Outcome of resolving every `tmp/{hash}` directory
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResolveReport {
+    pub candidates: usize,
+    /// Hashes newly added to (or corrected in) the registry
+    pub resolved: Vec<String>,
+    pub unresolved: Vec<String>,
+    pub conflicts: Vec<RegistryConflict>,
+}
+
+/// Precomputed `sha256(path) -> path` lookup
+#[derive(Debug, Default)]
+pub struct HashResolver {
+    candidates: HashMap<String, Candidate>,
+}
+
+impl HashResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gather candidates from all sources
+    pub fn collect(project_roots: &[String]) -> Self {
+        let mut resolver = Self::new();
+
+        for root in project_roots {
+            let root = shellexpand::tilde(root).to_string();
+            for repo in find_git_repos(Path::new(&root), MAX_REPO_DEPTH) {
+                resolver.add(&repo.to_string_lossy(), CandidateSource::GitRepo);
+            }
+        }
+
+        for cwd in known_cwds() {
+            resolver.add_with_ancestors(&cwd, CandidateSource::KnownCwd);
+        }
+
+        if let Some(home) = dirs::home_dir() {
+            for dir in shell_history_dirs(&home) {
+                resolver.add_with_ancestors(&dir, CandidateSource::ShellHistory);
+            }
+        }
+
+        resolver
+    }
+
+    /// Add a candidate; the first source to provide a path wins
+    pub fn add(&mut self, path: &str, source: CandidateSource) {
+        let path = normalize_path(path);
+        if path.is_empty() || path == "/" {
+            return;
+        }
+
+        self.candidates
+            .entry(hash_path(&path))
+            .or_insert(Candidate { path, source });
+    }
+
+    /// Add a candidate and each of its parents (a project root may be above it)
+    pub fn add_with_ancestors(&mut self, path: &str, source: CandidateSource) {
+        let path = normalize_path(path);
+        for ancestor in Path::new(&path).ancestors() {
+            self.add(&ancestor.to_string_lossy(), source);
+        }
+    }
+
+    pub fn resolve(&self, hash: &str) -> Option<&Candidate> {
+        self.candidates.get(hash)
+    }
+
+    pub fn len(&self) -> usize {
+        self.candidates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+}
+
+/// Resolve every project directory under `tmp_path` into `registry`
+///
+/// Candidates are only collected when some hash lacks a registry entry whose
+/// cwd hashes back to it and wasn't found unresolvable within
+/// `UNRESOLVED_RETRY`. Entries that don't verify are reported as conflicts
+/// and replaced when a candidate matches. The caller saves the registry.
+pub fn resolve_registry(
+    tmp_path: &Path,
+    registry: &mut GeminiProjectRegistry,
+    project_roots: &[String],
+) -> Result<ResolveReport, String> {
+    let mut pending = pending_hashes(tmp_path, registry)?;
+    let now = Instant::now();
+    {
+        let unresolved = UNRESOLVED.lock().map_err(|e| e.to_string())?;
+        pending.retain(|hash| retry_due(&unresolved, hash, now));
+    }
+    if pending.is_empty() {
+        return Ok(ResolveReport::default());
+    }
+
+    let resolver = HashResolver::collect(project_roots);
+    let report = resolve_hashes(&pending, registry, &resolver);
+
+    let mut unresolved = UNRESOLVED.lock().map_err(|e| e.to_string())?;
+    record_unresolved(&mut unresolved, &report, now);
+    Ok(report)
+}
+
+/// Forget unresolved hashes so the next `resolve_registry` retries them all
+pub fn clear_unresolved() {
+    if let Ok(mut unresolved) = UNRESOLVED.lock() {
+        unresolved.clear();
+    }
+}
+
+fn retry_due(unresolved: &HashMap<String, Instant>, hash: &str, now: Instant) -> bool {
+    unresolved
+        .get(hash)
+        .is_none_or(|checked| now.duration_since(*checked) >= UNRESOLVED_RETRY)
+}
+
+fn record_unresolved(
+    unresolved: &mut HashMap<String, Instant>,
+    report: &ResolveReport,
+    now: Instant,
+) {
+    for hash in &report.resolved {
+        unresolved.remove(hash);
+    }
+    for hash in &report.unresolved {
+        unresolved.insert(hash.clone(), now);
+    }
+}
+
+/// Roots from the provider config, or `DEFAULT_PROJECT_ROOTS`
+pub fn project_roots() -> Vec<String> {
+    crate::config::load_provider_config(PROVIDER_ID)
+        .map(|config| config.project_roots)
+        .ok()
+        .filter(|roots| !roots.is_empty())
+        .unwrap_or_else(|| {
+            DEFAULT_PROJECT_ROOTS
+                .iter()
+                .map(|r| r.to_string())
+                .collect()
+        })
+}
+
+/// Project hashes under `tmp_path` without a verified registry entry
+fn pending_hashes(
+    tmp_path: &Path,
+    registry: &GeminiProjectRegistry,
+) -> Result<Vec<String>, String> {
+    let entries =
+        fs::read_dir(tmp_path).map_err(|e| format!("Failed to read tmp directory: {}", e))?;
+
+    let mut hashes: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| is_project_hash(name))
+        .filter(|hash| {
+            registry
+                .get_project(hash)
+                .is_none_or(|entry| !verify_hash(&entry.cwd, hash))
+        })
+        .collect();
+    hashes.sort();
+    Ok(hashes)
+}
+
+fn resolve_hashes(
+    hashes: &[String],
+    registry: &mut GeminiProjectRegistry,
+    resolver: &HashResolver,
+) -> ResolveReport {
+    let mut report = ResolveReport {
+        candidates: resolver.len(),
+        ..Default::default()
+    };
+
+    for hash in hashes {
+        let resolved = resolver.resolve(hash);
+        if let Some(entry) = registry.get_project(hash) {
+            report.conflicts.push(RegistryConflict {
+                hash: hash.clone(),
+                registered_cwd: entry.cwd.clone(),
+                resolved_cwd: resolved.map(|c| c.path.clone()),
+            });
+        }
+
+        match resolved {
+            Some(candidate) => {
+                let name = Path::new(&candidate.path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(&candidate.path)
+                    .to_string();
+                registry.update_project(hash.clone(), candidate.path.clone(), name);
+                report.resolved.push(hash.clone());
+            }
+            None => report.unresolved.push(hash.clone()),
+        }
+    }
+
+    for conflict in &report.conflicts {
+        let _ = log_warn(
+            PROVIDER_ID,
+            &format!(
+                "Project registry entry {} points to {} which does not match its hash (resolved: {})",
+                conflict.hash,
+                conflict.registered_cwd,
+                conflict.resolved_cwd.as_deref().unwrap_or("none")
+            ),
+        );
+    }
+
+    report
+}
+
+/// Git repositories (directories containing `.git`) under `root`
+pub fn find_git_repos(root: &Path, max_depth: usize) -> Vec<PathBuf> {
+    let mut repos = Vec::new();
+    let mut walker = WalkDir::new(root)
+        .max_depth(max_depth)
+        .follow_links(false)
+        .into_iter();
+
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy();
+        if entry.depth() > 0 && (name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref())) {
+            walker.skip_current_dir();
+            continue;
+        }
+
+        if entry.path().join(".git").exists() {
+            repos.push(entry.path().to_path_buf());
+            // Nested repos (submodules, vendored checkouts) aren't projects
+            walker.skip_current_dir();
+        }
+    }
+
+    repos
+}
+
+/// Working directories recorded by other providers
+fn known_cwds() -> Vec<String> {
+    let rows = crate::database::execute_sql_query(
+        "SELECT DISTINCT cwd FROM agent_sessions WHERE cwd IS NOT NULL AND provider != ?
+         UNION SELECT cwd FROM projects",
+        vec![serde_json::Value::String(PROVIDER_ID.to_string())],
+    )
+    .unwrap_or_default();
+
+    rows.iter()
+        .filter_map(|row| row.get("cwd").and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Absolute `cd`/`pushd` targets from bash, zsh and fish history
+pub fn shell_history_dirs(home: &Path) -> Vec<String> {
+    let files = [
+        home.join(".bash_history"),
+        home.join(".zsh_history"),
+        home.join(".local/share/fish/fish_history"),
+    ];
+
+    let home = home.to_string_lossy();
+    let mut dirs = Vec::new();
+    for file in files {
+        // zsh "metafies" non-ASCII bytes, so don't insist on UTF-8
+        let Ok(bytes) = fs::read(&file) else {
+            continue;
+        };
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            dirs.extend(parse_history_line(line, &home));
+        }
+    }
+
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Directories changed into by one history line
+fn parse_history_line(line: &str, home: &str) -> Vec<String> {
+    // zsh extended history (`: 1700000000:0;cmd`) and fish (`- cmd: cmd`)
+    let command = if line.starts_with(": ") {
+        line.split_once(';').map(|(_, cmd)| cmd).unwrap_or("")
+    } else {
+        line.trim_start().strip_prefix("- cmd: ").unwrap_or(line)
+    };
+
+    command
+        .split(['&', ';', '|'])
+        .filter_map(|segment| {
+            let mut words = segment.split_whitespace();
+            match words.next() {
+                Some("cd") | Some("pushd") => words.next(),
+                _ => None,
+            }
+        })
+        .map(|target| target.trim_matches(|c| c == '"' || c == '\''))
+        .filter_map(|target| {
+            if let Some(rest) = target.strip_prefix('~') {
+                Some(format!("{}{}", home, rest))
+            } else if target.starts_with('/') {
+                Some(target.to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn normalize_path(path: &str) -> String {
+    let trimmed = path.trim();
+    if trimmed.len() > 1 {
+        trimmed.trim_end_matches('/').to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn hash_path(path: &str) -> String {
+    hex::encode(Sha256::digest(path.as_bytes()))
+}
+
+fn is_project_hash(name: &str) -> bool {
+    name.len() == 64 && name.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_history_line() {
+        let home = "/Users/test";
+        assert_eq!(
+            parse_history_line(": 1700000000:0;cd ~/work/guideai && gemini", home),
+            vec!["/Users/test/work/guideai"]
+        );
+        assert_eq!(
+            parse_history_line("- cmd: cd \"/opt/project\"", home),
+            vec!["/opt/project"]
+        );
+        assert_eq!(
+            parse_history_line("git status; pushd /srv/app; cd src", home),
+            vec!["/srv/app"]
+        );
+        assert!(parse_history_line("ls -la", home).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_hashes_uses_candidates_and_reports_conflicts() {
+        let tmp = tempdir().unwrap();
+        let resolvable = hash_path("/Users/test/work/guideai");
+        let unknown = hash_path("/somewhere/else");
+        let stale = hash_path("/Users/test/work/api");
+        for hash in [&resolvable, &unknown, &stale] {
+            fs::create_dir(tmp.path().join(hash)).unwrap();
+        }
+        fs::create_dir(tmp.path().join("bin")).unwrap();
+
+        let mut resolver = HashResolver::new();
+        resolver.add_with_ancestors(
+            "/Users/test/work/guideai/src/",
+            CandidateSource::ShellHistory,
+        );
+        resolver.add("/Users/test/work/api", CandidateSource::GitRepo);
+
+        let mut registry = GeminiProjectRegistry::default();
+        registry.update_project(stale.clone(), "/wrong/path".to_string(), "path".to_string());
+
+        let pending = pending_hashes(tmp.path(), &registry).unwrap();
+        let report = resolve_hashes(&pending, &mut registry, &resolver);
+
+        assert_eq!(report.unresolved, vec![unknown]);
+        assert_eq!(report.resolved.len(), 2);
+        assert_eq!(
+            registry.get_project(&resolvable).unwrap().cwd,
+            "/Users/test/work/guideai"
+        );
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].registered_cwd, "/wrong/path");
+        assert_eq!(registry.get_project(&stale).unwrap().name, "api");
+    }
+
+    #[test]
+    fn test_unresolved_hashes_wait_for_retry() {
+        let now = Instant::now();
+        let mut unresolved = HashMap::new();
+        let report = ResolveReport {
+            resolved: vec!["found".to_string()],
+            unresolved: vec!["missing".to_string()],
+            ..Default::default()
+        };
+        unresolved.insert("found".to_string(), now);
+        record_unresolved(&mut unresolved, &report, now);
+
+        assert!(retry_due(&unresolved, "found", now));
+        assert!(retry_due(&unresolved, "new", now));
+        assert!(!retry_due(&unresolved, "missing", now));
+        assert!(retry_due(&unresolved, "missing", now + UNRESOLVED_RETRY));
+    }
+
+    #[test]
+    fn test_find_git_repos_stops_at_repo_and_skips_hidden() {
+        let root = tempdir().unwrap();
+        for dir in [
+            "a/.git",
+            "a/nested/.git",
+            "b/c/.git",
+            ".cache/d/.git",
+            "node_modules/e/.git",
+        ] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+
+        let mut repos = find_git_repos(root.path(), MAX_REPO_DEPTH);
+        repos.sort();
+        assert_eq!(repos, vec![root.path().join("a"), root.path().join("b/c")]);
+    }
+}
@@ -1,5 +1,6 @@
 //! Gemini session scanner - discovers and parses Gemini sessions from ~/.gemini/tmp/
 
+use super::registry::GeminiProjectRegistry;
 use crate::logging::{log_info, log_warn};
 use crate::providers::common::SessionInfo;
 use chrono::{DateTime, Utc};
@@ -18,6 +19,8 @@ pub fn scan_sessions_filtered(
     }
 
     let mut sessions = Vec::new();
+    // Read once; every session looks its project hash up in it
+    let registry = GeminiProjectRegistry::load().unwrap_or_default();
 
     // Recursively scan project hash directories
     let entries = fs::read_dir(&tmp_path)
@@ -67,7 +70,7 @@ pub fn scan_sessions_filtered(
                 // Only process session JSON files
                 if let Some(filename) = file_path.file_name().and_then(|n| n.to_str()) {
                     if filename.starts_with("session-") && filename.ends_with(".json") {
-                        match parse_gemini_session(&file_path) {
+                        match parse_gemini_session(&file_path, &registry) {
                             Ok(session_info) => {
                                 sessions.push(session_info);
                             }
@@ -100,7 +103,10 @@ pub fn scan_sessions_filtered(
     Ok(sessions)
 }
 
-fn parse_gemini_session(file_path: &Path) -> Result<SessionInfo, String> {
+fn parse_gemini_session(
+    file_path: &Path,
+    registry: &GeminiProjectRegistry,
+) -> Result<SessionInfo, String> {
     use super::super::common::extract_session_id_from_filename;
     use super::converter::convert_to_canonical_file;
     use super::parser::GeminiSession;
@@ -139,7 +145,7 @@ fn parse_gemini_session(file_path: &Path) -> Result<SessionInfo, String> {
     // - Serializing to JSONL
     // - Getting project-organized canonical path
     // - Writing to cache
-    let cached_file_path = convert_to_canonical_file(file_path, &session_id)
+    let cached_file_path = convert_to_canonical_file(file_path, &session_id, registry)
         .map_err(|e| format!("Failed to convert to canonical format: {}", e))?;
 
     let file_name = format!("{}.jsonl", session_id);
@@ -151,7 +157,7 @@ fn parse_gemini_session(file_path: &Path) -> Result<SessionInfo, String> {
         .unwrap_or(0);
 
     // Extract CWD from the cached file for project name determination
-    let cwd = extract_cwd_from_gemini_session(&session);
+    let cwd = extract_cwd_from_gemini_session(&session, registry);
 
     // Determine project name from CWD or use hash
     let project_name = if let Some(cwd_path) = &cwd {
@@ -183,7 +189,7 @@ fn parse_gemini_session(file_path: &Path) -> Result<SessionInfo, String> {
 /// Extract CWD from Gemini session using shared extraction logic
 fn extract_cwd_from_gemini_session(
     session: &super::parser::GeminiSession,
+    registry: &GeminiProjectRegistry,
 ) -> Option<String> {
-    use super::utils::infer_cwd_from_session;
-    infer_cwd_from_session(session, &session.project_hash)
+    super::utils::resolve_session_cwd(session, registry)
 }
@@ -1,6 +1,7 @@
-use crate::providers::sort_projects_by_modified;
-use crate::config::ProjectInfo;
 use super::parser::GeminiSession;
+use super::registry::GeminiProjectRegistry;
+use crate::config::ProjectInfo;
+use crate::providers::sort_projects_by_modified;
 use chrono::{DateTime, Utc};
 use sha2::{Digest, Sha256};
 use shellexpand::tilde;
@@ -8,7 +9,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 pub fn scan_projects(home_directory: &str) -> Result<Vec<ProjectInfo>, String> {
-    use super::registry::GeminiProjectRegistry;
+    use super::resolver::{project_roots, resolve_registry};
 
     let expanded = tilde(home_directory);
     let base_path = PathBuf::from(expanded.into_owned());
@@ -29,6 +30,17 @@ pub fn scan_projects(home_directory: &str) -> Result<Vec<ProjectInfo>, String> {
     let mut registry = GeminiProjectRegistry::load()
         .map_err(|e| format!("Failed to load project registry: {}", e))?;
 
+    // Reverse-map hashes from candidate directories first; reading session
+    // content below is only a fallback for what this can't resolve
+    match resolve_registry(&tmp_path, &mut registry, &project_roots()) {
+        Ok(report) if !report.conflicts.is_empty() => eprintln!(
+            "Warning: {} Gemini project registry entries did not match their hash",
+            report.conflicts.len()
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("Warning: Could not resolve Gemini project hashes: {}", e),
+    }
+
     let entries =
         fs::read_dir(&tmp_path).map_err(|e| format!("Failed to read tmp directory: {}", e))?;
 
@@ -230,6 +242,19 @@ pub fn verify_hash(workdir: &str, expected_hash: &str) -> bool {
     computed_hash == expected_hash
 }
 
+/// Working directory of a session: its registry entry when that hashes back
+/// to the project hash, otherwise inferred from the messages
+pub fn resolve_session_cwd(
+    session: &GeminiSession,
+    registry: &GeminiProjectRegistry,
+) -> Option<String> {
+    registry
+        .get_project(&session.project_hash)
+        .map(|entry| entry.cwd.clone())
+        .filter(|cwd| verify_hash(cwd, &session.project_hash))
+        .or_else(|| infer_cwd_from_session(session, &session.project_hash))
+}
+
 /// Infer working directory from Gemini session messages
 /// This is the canonical CWD extraction function used by both watcher and scanner
 /// Priority order: 1) Tool call arguments (most reliable), 2) Extended Thinking, 3) Message content
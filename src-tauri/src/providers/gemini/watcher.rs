@@ -1,19 +1,24 @@
 use crate::config::load_provider_config;
+use crate::config_service::ProviderConfigUpdates;
 use crate::events::{EventBus, SessionEventPayload};
 use crate::logging::{log_error, log_info, log_warn};
 use crate::providers::common::{
-    extract_session_id_from_filename, get_file_size, has_extension,
-    should_skip_file, SessionStateManager, WatcherStatus, EVENT_TIMEOUT, FILE_WATCH_POLL_INTERVAL,
+    extract_session_id_from_filename, format_version, fs_event_channel, get_file_size,
+    has_extension, run_blocking, should_skip_file, Debouncer, FsEventReceiver, FsWatcher,
+    SessionLifecycle, SessionStateManager, WatchCancellation, WatchSet, WatchTask,
+    WatcherHeartbeat, WatcherStatus, EVENT_TIMEOUT, FILE_WATCH_POLL_INTERVAL,
     MIN_SIZE_CHANGE_BYTES,
 };
 use crate::providers::gemini::converter::convert_to_canonical_file;
+use crate::providers::gemini::registry::GeminiProjectRegistry;
 use crate::upload_queue::UploadQueue;
-use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Config, Event, EventKind, RecursiveMode, Watcher};
 use shellexpand::tilde;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{mpsc, Arc, Mutex};
-use std::thread;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 const PROVIDER_ID: &str = "gemini-code";
 
@@ -26,10 +31,12 @@ pub struct FileChangeEvent {
 
 #[derive(Debug)]
 pub struct GeminiWatcher {
-    _watcher: RecommendedWatcher,
-    _thread_handle: thread::JoinHandle<()>,
+    _watcher: Arc<Mutex<FsWatcher>>,
+    task: WatchTask,
     upload_queue: Arc<UploadQueue>,
     is_running: Arc<Mutex<bool>>,
+    watch_set: Arc<Mutex<WatchSet>>,
+    heartbeat: WatcherHeartbeat,
 }
 
 impl GeminiWatcher {
@@ -37,6 +44,7 @@ impl GeminiWatcher {
         project_hashes: Vec<String>, // Project hashes to watch
         upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
+        shutdown: CancellationToken,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         if let Err(e) = log_info(PROVIDER_ID, "🔍 Starting Gemini Code file monitoring") {
             eprintln!("Logging error: {}", e);
@@ -50,8 +58,7 @@ impl GeminiWatcher {
             return Err("Gemini Code provider is not enabled".into());
         }
 
-        let home_directory = config.home_directory;
-        let expanded_home = tilde(&home_directory);
+        let expanded_home = tilde(&config.home_directory);
         let base_path = Path::new(expanded_home.as_ref());
 
         if !base_path.exists() {
@@ -71,228 +78,284 @@ impl GeminiWatcher {
             .into());
         }
 
-        // Use the provided project hashes directly
-        let projects_to_watch = project_hashes;
-
-        if let Err(e) = log_info(
-            PROVIDER_ID,
-            &format!(
-                "📁 Monitoring {} Gemini Code projects",
-                projects_to_watch.len()
-            ),
-        ) {
-            eprintln!("Logging error: {}", e);
+        if config.project_selection != "ALL" && config.selected_projects.is_empty() {
+            return Err("No valid projects selected for watching".into());
         }
 
         // Create file system event channel
-        let (tx, rx) = mpsc::channel();
+        let (tx, rx) = fs_event_channel(PROVIDER_ID);
 
         // Create the file watcher
-        let mut watcher = RecommendedWatcher::new(
+        let mut watcher = FsWatcher::for_provider(
+            PROVIDER_ID,
             tx,
             Config::default().with_poll_interval(FILE_WATCH_POLL_INTERVAL),
         )?;
 
-        // Watch each project's chats directory
-        for project_hash in &projects_to_watch {
-            let chats_path = tmp_path.join(project_hash).join("chats");
-            if chats_path.exists() && chats_path.is_dir() {
-                watcher.watch(&chats_path, RecursiveMode::NonRecursive)?;
-                if let Err(e) = log_info(
-                    PROVIDER_ID,
-                    &format!("📂 Watching Gemini project: {}", &project_hash[..8]),
-                ) {
-                    eprintln!("Logging error: {}", e);
-                }
-            } else if let Err(e) = log_warn(
-                PROVIDER_ID,
-                &format!(
-                    "⚠ Project chats directory not found: {}",
-                    chats_path.display()
-                ),
-            ) {
-                eprintln!("Logging error: {}", e);
-            }
+        // Watch tmp/ so new project hashes are noticed, plus each project's chats
+        // directory. `selected_projects` holds hashes, so the config drives the set.
+        let mut watch_set = WatchSet::new(
+            PROVIDER_ID,
+            tmp_path.clone(),
+            Some("chats"),
+            RecursiveMode::NonRecursive,
+        )
+        .excluding(&["bin"]);
+        watch_set.watch_root(&mut watcher)?;
+        watch_set.sync(&mut watcher, &config);
+
+        if let Err(e) = log_info(
+            PROVIDER_ID,
+            &format!(
+                "📁 Monitoring {} Gemini Code projects ({} requested)",
+                watch_set.len(),
+                project_hashes.len()
+            ),
+        ) {
+            eprintln!("Logging error: {}", e);
         }
 
+        let heartbeat = WatcherHeartbeat::new(PROVIDER_ID, &tmp_path, watcher.is_polling());
+        let watcher = Arc::new(Mutex::new(watcher));
+        let watch_set = Arc::new(Mutex::new(watch_set));
         let is_running = Arc::new(Mutex::new(true));
         let is_running_clone = Arc::clone(&is_running);
         let upload_queue_clone = Arc::clone(&upload_queue);
         let tmp_path_clone = tmp_path.clone();
         let event_bus_clone = event_bus.clone();
+        let watcher_clone = Arc::clone(&watcher);
+        let watch_set_clone = Arc::clone(&watch_set);
+        let heartbeat_clone = heartbeat.clone();
 
-        // Start background thread to handle file events
-        let thread_handle = thread::spawn(move || {
+        // Start background task to handle file events
+        let task = WatchTask::spawn(&shutdown, move |cancellation| async move {
+            let _exit_guard = heartbeat_clone.exit_guard();
             Self::file_event_processor(
                 rx,
                 tmp_path_clone,
+                watcher_clone,
+                watch_set_clone,
                 upload_queue_clone,
                 event_bus_clone,
                 is_running_clone,
-            );
+                heartbeat_clone,
+                cancellation,
+            )
+            .await;
         });
 
         Ok(GeminiWatcher {
             _watcher: watcher,
-            _thread_handle: thread_handle,
+            task,
             upload_queue,
             is_running,
+            watch_set,
+            heartbeat,
         })
     }
 
-    #[allow(dead_code)]
-    fn discover_all_projects(
-        tmp_path: &Path,
-    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-        let entries = std::fs::read_dir(tmp_path)
-            .map_err(|e| format!("Failed to read tmp directory: {}", e))?;
+    #[allow(clippy::too_many_arguments)]
+    async fn file_event_processor(
+        mut rx: FsEventReceiver,
+        tmp_path: PathBuf,
+        watcher: Arc<Mutex<FsWatcher>>,
+        watch_set: Arc<Mutex<WatchSet>>,
+        _upload_queue: Arc<UploadQueue>,
+        event_bus: EventBus,
+        is_running: Arc<Mutex<bool>>,
+        heartbeat: WatcherHeartbeat,
+        mut cancellation: WatchCancellation,
+    ) {
+        let mut session_states = SessionStateManager::new();
+        let mut lifecycle = SessionLifecycle::new(PROVIDER_ID, event_bus.clone());
+        let mut config_updates = ProviderConfigUpdates::new(PROVIDER_ID);
+        let mut debouncer = Debouncer::default();
 
-        let mut projects = Vec::new();
-        for entry in entries.flatten() {
-            let path = entry.path();
+        loop {
+            heartbeat.beat();
 
-            // Skip 'bin' directory
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if name == "bin" {
-                    continue;
-                }
+            // Move quiet sessions towards idle/completed
+            for session_id in lifecycle.tick() {
+                session_states.mark_inactive(&session_id);
             }
 
-            if path.is_dir() {
-                // Check if chats directory exists
-                let chats_path = path.join("chats");
-                if chats_path.exists() && chats_path.is_dir() {
-                    if let Some(hash) = path.file_name().and_then(|n| n.to_str()) {
-                        projects.push(hash.to_string());
-                    }
+            // Apply config edits without restarting the watcher
+            if let Some(config) = config_updates.poll() {
+                if !config.enabled {
+                    cancellation.stop_disabled(PROVIDER_ID, &is_running);
+                    continue;
                 }
+                lifecycle.set_config(&config);
+                Self::resync_watch_set(&watcher, &watch_set, &mut debouncer);
             }
-        }
 
-        Ok(projects)
-    }
+            let rescan_due = watch_set
+                .lock()
+                .map(|set| set.rescan_due())
+                .unwrap_or(false);
+            if rescan_due {
+                Self::resync_watch_set(&watcher, &watch_set, &mut debouncer);
+            }
 
-    fn file_event_processor(
-        rx: mpsc::Receiver<Result<Event, notify::Error>>,
-        tmp_path: PathBuf,
-        _upload_queue: Arc<UploadQueue>,
-        event_bus: EventBus,
-        is_running: Arc<Mutex<bool>>,
-    ) {
-        let mut session_states = SessionStateManager::new();
+            // Events were dropped, so look at every watched file again
+            if rx.take_overflow() {
+                let events = watch_set
+                    .lock()
+                    .map(|set| set.file_events(&set.names()))
+                    .unwrap_or_default();
+                let _ = log_warn(
+                    PROVIDER_ID,
+                    &format!(
+                        "⚠ Rescanning {} watched files after dropped events",
+                        events.len()
+                    ),
+                );
+                let now = Instant::now();
+                for event in events {
+                    debouncer.push(event, now);
+                }
+            }
 
-        loop {
-            // Check if we should continue running
-            {
-                if let Ok(running) = is_running.lock() {
-                    if !*running {
-                        break;
+            // Wait for file system events, debouncing bursts per path
+            let wake_at = debouncer.wake_at(Instant::now(), EVENT_TIMEOUT);
+            let stopping = tokio::select! {
+                _ = cancellation.cancelled() => true,
+                received = rx.recv() => match received {
+                    Some(Ok(event)) => {
+                        debouncer.push(event, Instant::now());
+                        false
+                    }
+                    Some(Err(error)) => {
+                        heartbeat.report(&error);
+                        if let Err(e) =
+                            log_error(PROVIDER_ID, &format!("File watcher error: {:?}", error))
+                        {
+                            eprintln!("Logging error: {}", e);
+                        }
+                        false
                     }
+                    None => {
+                        if let Err(e) = log_error(PROVIDER_ID, "File watcher channel disconnected") {
+                            eprintln!("Logging error: {}", e);
+                        }
+                        true
+                    }
+                },
+                _ = tokio::time::sleep_until(wake_at) => false,
+            };
+
+            // Stopping flushes whatever is still being debounced
+            let events = if stopping {
+                debouncer.drain()
+            } else {
+                debouncer.ready(Instant::now())
+            };
+            for event in events {
+                let is_root_event = watch_set
+                    .lock()
+                    .map(|set| set.is_root_event(&event))
+                    .unwrap_or(false);
+                if is_root_event {
+                    Self::resync_watch_set(&watcher, &watch_set, &mut debouncer);
                 }
-            }
 
-            // Process file system events with timeout
-            match rx.recv_timeout(EVENT_TIMEOUT) {
-                Ok(Ok(event)) => {
-                    if let Some(file_event) = Self::process_file_event(&event, &tmp_path) {
-                        // Convert Gemini JSON to canonical JSONL and cache it using shared function
-                        let canonical_path = match convert_to_canonical_file(
-                            &file_event.path,
-                            &file_event.session_id,
-                        ) {
-                            Ok(path) => path,
-                            Err(e) => {
-                                if let Err(log_err) = log_error(
-                                    PROVIDER_ID,
-                                    &format!("Failed to convert Gemini session {} to canonical format: {}", file_event.session_id, e),
-                                ) {
-                                    eprintln!("Logging error: {}", log_err);
-                                }
-                                continue; // Skip this event - don't crash on conversion errors
-                            }
-                        };
-
-                        // Get file size of canonical JSONL
-                        let canonical_size = get_file_size(&canonical_path).unwrap_or(0);
-
-                        // Check if this is a new session (before get_or_create)
-                        let is_new_session = !session_states.contains(&file_event.session_id);
-
-                        // Get or create session state
-                        let state =
-                            session_states.get_or_create(&file_event.session_id, canonical_size);
-                        let should_log =
-                            state.should_log(canonical_size, MIN_SIZE_CHANGE_BYTES, is_new_session);
-
-                        // Extract real project name from canonical JSONL (CWD -> project name)
-                        // Fallback to shortened hash if CWD extraction fails
-                        let project_name = Self::extract_project_name_from_jsonl(&canonical_path)
-                            .unwrap_or_else(|| format!("gemini-{}", &file_event.project_hash[..8]));
-
-                        // Publish SessionChanged event with CANONICAL path
-                        // DatabaseEventHandler will call db_helpers which does smart insert-or-update
-                        let payload = SessionEventPayload::SessionChanged {
-                            session_id: file_event.session_id.clone(),
-                            project_name, // Real project name extracted from CWD
-                            file_path: canonical_path.clone(), // Use canonical path (not original JSON)
-                            file_size: canonical_size,         // Use canonical file size
-                        };
-
-                        if let Err(e) = event_bus.publish(PROVIDER_ID, payload) {
+                let tmp_path = tmp_path.clone();
+                let file_event =
+                    run_blocking(move || Self::process_file_event(&event, &tmp_path)).await;
+                if let Some(file_event) = file_event {
+                    // Convert Gemini JSON to canonical JSONL and cache it using shared function
+                    let (path, session_id) =
+                        (file_event.path.clone(), file_event.session_id.clone());
+                    let converted = run_blocking(move || {
+                        let registry = GeminiProjectRegistry::load().unwrap_or_default();
+                        convert_to_canonical_file(&path, &session_id, &registry)
+                    })
+                    .await;
+                    let canonical_path = match converted {
+                        Ok(path) => path,
+                        Err(e) => {
                             if let Err(log_err) = log_error(
                                 PROVIDER_ID,
-                                &format!("Failed to publish session event: {}", e),
+                                &format!(
+                                    "Failed to convert Gemini session {} to canonical format: {}",
+                                    file_event.session_id, e
+                                ),
                             ) {
                                 eprintln!("Logging error: {}", log_err);
                             }
+                            continue; // Skip this event - don't crash on conversion errors
                         }
+                    };
+
+                    // Get file size of canonical JSONL
+                    let canonical_size = get_file_size(&canonical_path).unwrap_or(0);
+
+                    // Check if this is a new session (before get_or_create)
+                    let is_new_session = !session_states.contains(&file_event.session_id);
+
+                    // Get or create session state
+                    let state =
+                        session_states.get_or_create(&file_event.session_id, canonical_size);
+                    let should_log =
+                        state.should_log(canonical_size, MIN_SIZE_CHANGE_BYTES, is_new_session);
+
+                    // Extract real project name from canonical JSONL (CWD -> project name)
+                    // Fallback to shortened hash if CWD extraction fails
+                    let project_name = Self::extract_project_name_from_jsonl(&canonical_path)
+                        .unwrap_or_else(|| format!("gemini-{}", &file_event.project_hash[..8]));
+
+                    // Publish SessionChanged event with CANONICAL path
+                    // DatabaseEventHandler will call db_helpers which does smart insert-or-update
+                    let payload = SessionEventPayload::SessionChanged {
+                        session_id: file_event.session_id.clone(),
+                        project_name, // Real project name extracted from CWD
+                        file_path: canonical_path.clone(), // Use canonical path (not original JSON)
+                        file_size: canonical_size, // Use canonical file size
+                    };
+
+                    if let Err(e) = event_bus.publish(PROVIDER_ID, payload) {
+                        if let Err(log_err) = log_error(
+                            PROVIDER_ID,
+                            &format!("Failed to publish session event: {}", e),
+                        ) {
+                            eprintln!("Logging error: {}", log_err);
+                        }
+                    }
 
-                        // Update session state immediately to prevent duplicate events
-                        state.update(canonical_size);
+                    // Update session state immediately to prevent duplicate events
+                    state.update(canonical_size);
+                    lifecycle.record_activity(&file_event.session_id, &file_event.path);
 
-                        // Mark session as seen so it's not treated as new again
-                        if is_new_session {
-                            state.mark_as_seen();
-                        }
+                    // Mark session as seen so it's not treated as new again
+                    if is_new_session {
+                        state.mark_as_seen();
+                    }
 
-                        if should_log {
-                            if is_new_session {
-                                let log_message = format!(
-                                    "🆕 New Gemini session detected: {}",
-                                    file_event.session_id
-                                );
-                                if let Err(e) = log_info(PROVIDER_ID, &log_message) {
-                                    eprintln!("Logging error: {}", e);
-                                }
-                            } else {
-                                // Log session updates at info level
-                                let log_message = format!(
-                                    "📝 Gemini session changed: {} (size: {} bytes)",
-                                    file_event.session_id, canonical_size
-                                );
-                                if let Err(e) = log_info(PROVIDER_ID, &log_message) {
-                                    eprintln!("Logging error: {}", e);
-                                }
+                    if should_log {
+                        if is_new_session {
+                            let log_message = format!(
+                                "🆕 New Gemini session detected: {}",
+                                file_event.session_id
+                            );
+                            if let Err(e) = log_info(PROVIDER_ID, &log_message) {
+                                eprintln!("Logging error: {}", e);
+                            }
+                        } else {
+                            // Log session updates at info level
+                            let log_message = format!(
+                                "📝 Gemini session changed: {} (size: {} bytes)",
+                                file_event.session_id, canonical_size
+                            );
+                            if let Err(e) = log_info(PROVIDER_ID, &log_message) {
+                                eprintln!("Logging error: {}", e);
                             }
                         }
                     }
                 }
-                Ok(Err(error)) => {
-                    if let Err(e) =
-                        log_error(PROVIDER_ID, &format!("File watcher error: {:?}", error))
-                    {
-                        eprintln!("Logging error: {}", e);
-                    }
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // Timeout is normal, continue
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    if let Err(e) = log_error(PROVIDER_ID, "File watcher channel disconnected") {
-                        eprintln!("Logging error: {}", e);
-                    }
-                    break;
-                }
+            }
+
+            if stopping {
+                break;
             }
         }
 
@@ -301,6 +364,45 @@ impl GeminiWatcher {
         }
     }
 
+    /// Update project subscriptions from the current provider config
+    ///
+    /// Files already inside newly watched projects are queued too; they were
+    /// written before the watch existed and would otherwise wait for their next write.
+    fn resync_watch_set(
+        watcher: &Arc<Mutex<FsWatcher>>,
+        watch_set: &Arc<Mutex<WatchSet>>,
+        debouncer: &mut Debouncer,
+    ) {
+        // Last validated config, so a broken edit never empties the watch set
+        let config = match crate::config_service::provider_config(PROVIDER_ID) {
+            Ok(config) => config,
+            Err(e) => {
+                let _ = log_warn(PROVIDER_ID, &e);
+                return;
+            }
+        };
+
+        if let (Ok(mut watcher), Ok(mut set)) = (watcher.lock(), watch_set.lock()) {
+            let changes = set.sync(&mut *watcher, &config);
+            if !changes.is_empty() {
+                let _ = log_info(
+                    PROVIDER_ID,
+                    &format!(
+                        "📁 Watch set updated: +{} -{} ({} projects)",
+                        changes.added.len(),
+                        changes.removed.len(),
+                        set.len()
+                    ),
+                );
+            }
+
+            let now = Instant::now();
+            for event in set.file_events(&changes.added) {
+                debouncer.push(event, now);
+            }
+        }
+    }
+
     fn process_file_event(event: &Event, tmp_path: &Path) -> Option<FileChangeEvent> {
         // Only process write events for session JSON files
         match &event.kind {
@@ -386,15 +488,25 @@ impl GeminiWatcher {
         if let Ok(mut running) = self.is_running.lock() {
             *running = false;
         }
+        self.task.stop();
 
         if let Err(e) = log_info(PROVIDER_ID, "🛑 Stopping Gemini Code file monitoring") {
             eprintln!("Logging error: {}", e);
         }
     }
 
+    pub fn task(&self) -> &WatchTask {
+        &self.task
+    }
+
+    pub fn heartbeat(&self) -> &WatcherHeartbeat {
+        &self.heartbeat
+    }
+
     pub fn get_status(&self) -> WatcherStatus {
+        // A panicked task never clears the flag, so ask the heartbeat too
         let is_running = if let Ok(running) = self.is_running.lock() {
-            *running
+            *running && self.heartbeat.is_alive()
         } else {
             false
         };
@@ -406,6 +518,12 @@ impl GeminiWatcher {
             pending_uploads: upload_status.pending,
             processing_uploads: upload_status.processing,
             failed_uploads: upload_status.failed,
+            watched_paths: self
+                .watch_set
+                .lock()
+                .map(|set| set.watched_paths())
+                .unwrap_or_default(),
+            unsupported_formats: format_version::unsupported_formats(PROVIDER_ID),
         }
     }
 }
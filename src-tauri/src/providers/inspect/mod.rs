@@ -0,0 +1,493 @@
+//! Provider-agnostic session inspection
+//!
+//! Shows each raw source record next to the `CanonicalMessage`s it produced,
+//! together with records the converter dropped or failed on and raw fields
+//! that never made it into the canonical output. Claude, Codex and custom
+//! JSONL sessions go through the same session-level converters as the
+//! watchers, lined up by the converters' per-record provenance; the other
+//! providers are converted one record at a time.
+//!
+//! `export_fixture` writes a self-contained JSON fixture (raw records, the
+//! resolved session ID/CWD, and the expected canonical output) that can be
+//! attached to bug reports and replayed with `replay_fixture`.
+
+mod records;
+
+use crate::config::CustomJsonlConfig;
+use crate::providers::canonical::CanonicalMessage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bumped when the fixture layout changes incompatibly
+pub const FIXTURE_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum RecordStatus {
+    /// Produced one or more canonical messages
+    Mapped,
+    /// Skipped by the converter (e.g. snapshots, duplicate events)
+    Dropped,
+    /// The converter returned an error or the record did not parse
+    Failed { error: String },
+}
+
+/// One raw source record and what the converter made of it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordInspection {
+    /// Position in the source (line number - 1, message index, or blob order)
+    pub index: usize,
+    /// Blob ID for Cursor records
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+    pub raw: Value,
+    pub canonical: Vec<CanonicalMessage>,
+    #[serde(flatten)]
+    pub status: RecordStatus,
+    /// Raw leaf fields whose values do not appear in the canonical output
+    pub unmapped_fields: Vec<String>,
+}
+
+impl RecordInspection {
+    fn new(
+        index: usize,
+        source_id: Option<String>,
+        raw: Value,
+        result: Result<Vec<CanonicalMessage>, String>,
+    ) -> Self {
+        let (canonical, status) = match result {
+            Ok(messages) if messages.is_empty() => (messages, RecordStatus::Dropped),
+            Ok(messages) => (messages, RecordStatus::Mapped),
+            Err(error) => (Vec::new(), RecordStatus::Failed { error }),
+        };
+        let unmapped_fields = if status == RecordStatus::Mapped {
+            unmapped_fields(&raw, &canonical)
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            index,
+            source_id,
+            raw,
+            canonical,
+            status,
+            unmapped_fields,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InspectionSummary {
+    pub records: usize,
+    pub mapped: usize,
+    pub dropped: usize,
+    pub failed: usize,
+    pub canonical_messages: usize,
+    /// Unmapped field path -> number of mapped records missing it
+    pub unmapped_fields: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInspection {
+    pub provider: String,
+    pub session_id: String,
+    pub cwd: Option<String>,
+    pub source_name: String,
+    pub records: Vec<RecordInspection>,
+    pub summary: InspectionSummary,
+}
+
+impl SessionInspection {
+    fn new(fixture: &InspectionFixture, records: Vec<RecordInspection>) -> Self {
+        let mut summary = InspectionSummary {
+            records: records.len(),
+            ..Default::default()
+        };
+        for record in &records {
+            match record.status {
+                RecordStatus::Mapped => summary.mapped += 1,
+                RecordStatus::Dropped => summary.dropped += 1,
+                RecordStatus::Failed { .. } => summary.failed += 1,
+            }
+            summary.canonical_messages += record.canonical.len();
+            for field in &record.unmapped_fields {
+                *summary.unmapped_fields.entry(field.clone()).or_default() += 1;
+            }
+        }
+
+        Self {
+            provider: fixture.provider.clone(),
+            session_id: fixture.session_id.clone(),
+            cwd: fixture.cwd.clone(),
+            source_name: fixture.source_name.clone(),
+            records,
+            summary,
+        }
+    }
+
+    /// Canonical output in conversion order
+    pub fn canonical_messages(&self) -> Vec<CanonicalMessage> {
+        self.records
+            .iter()
+            .flat_map(|record| record.canonical.iter().cloned())
+            .collect()
+    }
+}
+
+/// Raw session data as stored in a fixture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FixtureSource {
+    /// JSONL providers (one record per line)
+    Lines {
+        lines: Vec<String>,
+        /// Claude agent sidechains (`agent-<id>.jsonl` lines) by agent ID
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        sidechains: BTreeMap<String, Vec<String>>,
+    },
+    /// Single-document providers (Gemini)
+    Document { document: Value },
+    /// Cursor message blobs in conversation order
+    #[serde(rename_all = "camelCase")]
+    CursorBlobs {
+        created_at: i64,
+        blobs: Vec<FixtureBlob>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureBlob {
+    pub id: String,
+    /// Base64-encoded blob bytes
+    pub data: String,
+}
+
+/// Reproducible snapshot of a session for bug reports
+///
+/// Contains the raw session content; review it before sharing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InspectionFixture {
+    pub format_version: u32,
+    pub provider: String,
+    pub session_id: String,
+    pub cwd: Option<String>,
+    pub source_name: String,
+    pub source: FixtureSource,
+    /// Mapping used for custom JSONL providers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_jsonl: Option<CustomJsonlConfig>,
+    /// Canonical output at export time
+    pub expected: Vec<CanonicalMessage>,
+}
+
+/// Result of replaying a fixture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureReplay {
+    pub inspection: SessionInspection,
+    /// Whether today's converters reproduce the exported output
+    pub matches_expected: bool,
+}
+
+/// Inspect a session file (a `store.db` or session directory for Cursor)
+pub fn inspect_session(provider_id: &str, path: &Path) -> Result<SessionInspection, String> {
+    let fixture = records::load_fixture(provider_id, path)?;
+    let records = records::convert_records(&fixture)?;
+    Ok(SessionInspection::new(&fixture, records))
+}
+
+/// Write a replayable fixture for a session and return its path
+pub fn export_fixture(provider_id: &str, path: &Path, output: &Path) -> Result<PathBuf, String> {
+    let mut fixture = records::load_fixture(provider_id, path)?;
+    let inspection = SessionInspection::new(&fixture, records::convert_records(&fixture)?);
+    fixture.expected = inspection.canonical_messages();
+
+    let output = if output.is_dir() {
+        output.join(format!(
+            "{}-{}.fixture.json",
+            provider_id, fixture.session_id
+        ))
+    } else {
+        output.to_path_buf()
+    };
+    let content = serde_json::to_string_pretty(&fixture)
+        .map_err(|e| format!("Failed to serialize fixture: {}", e))?;
+    fs::write(&output, content).map_err(|e| format!("Failed to write fixture: {}", e))?;
+
+    Ok(output)
+}
+
+pub fn read_fixture(path: &Path) -> Result<InspectionFixture, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read fixture: {}", e))?;
+    let fixture: InspectionFixture =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid fixture: {}", e))?;
+
+    if fixture.format_version > FIXTURE_FORMAT_VERSION {
+        return Err(format!(
+            "Fixture format {} is newer than supported ({})",
+            fixture.format_version, FIXTURE_FORMAT_VERSION
+        ));
+    }
+
+    Ok(fixture)
+}
+
+/// Re-run the converters on a fixture without touching the local environment
+pub fn replay_fixture(fixture: &InspectionFixture) -> Result<FixtureReplay, String> {
+    let inspection = SessionInspection::new(fixture, records::convert_records(fixture)?);
+    let replayed = serde_json::to_value(inspection.canonical_messages()).ok();
+    let expected = serde_json::to_value(&fixture.expected).ok();
+
+    Ok(FixtureReplay {
+        matches_expected: replayed.is_some() && replayed == expected,
+        inspection,
+    })
+}
+
+/// Raw leaf fields whose value is not found anywhere in the canonical output
+///
+/// Heuristic: string leaves count as mapped when some canonical string
+/// contains them, numbers when the same number appears. Booleans, nulls and
+/// empty strings are ignored. Array indices collapse to `[]`.
+fn unmapped_fields(raw: &Value, canonical: &[CanonicalMessage]) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut numbers = HashSet::new();
+    for message in canonical {
+        if let Ok(value) = serde_json::to_value(message) {
+            collect_canonical_values(&value, &mut strings, &mut numbers);
+        }
+    }
+
+    let mut leaves = Vec::new();
+    collect_leaves(raw, String::new(), &mut leaves);
+
+    let mut unmapped: Vec<String> = leaves
+        .into_iter()
+        .filter(|(_, value)| match value {
+            Value::String(s) => !strings.iter().any(|c| c.contains(s.as_str())),
+            Value::Number(n) => !numbers.contains(&n.to_string()),
+            _ => false,
+        })
+        .map(|(path, _)| path)
+        .collect();
+    unmapped.sort();
+    unmapped.dedup();
+    unmapped
+}
+
+fn collect_canonical_values(
+    value: &Value,
+    strings: &mut Vec<String>,
+    numbers: &mut HashSet<String>,
+) {
+    match value {
+        Value::String(s) => {
+            // Nested JSON (tool inputs stored as strings) can hold raw numbers too
+            if let Ok(nested @ (Value::Object(_) | Value::Array(_))) = serde_json::from_str(s) {
+                collect_canonical_values(&nested, strings, numbers);
+            }
+            strings.push(s.clone());
+        }
+        Value::Number(n) => {
+            numbers.insert(n.to_string());
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_canonical_values(item, strings, numbers)),
+        Value::Object(map) => map
+            .values()
+            .for_each(|item| collect_canonical_values(item, strings, numbers)),
+        _ => {}
+    }
+}
+
+fn collect_leaves(value: &Value, path: String, leaves: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                collect_leaves(child, child_path, leaves);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_leaves(item, format!("{}[]", path), leaves);
+            }
+        }
+        Value::String(s) if s.is_empty() => {}
+        Value::String(_) | Value::Number(_) => leaves.push((path, value.clone())),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn claude_session(dir: &TempDir) -> PathBuf {
+        let lines = [
+            json!({"type": "file-history-snapshot", "messageId": "snap-1"}),
+            json!({
+                "type": "user",
+                "uuid": "u-1",
+                "sessionId": "sess-1",
+                "timestamp": "2025-01-01T00:00:00Z",
+                "cwd": "/work/project",
+                "experimentFlag": "shadow-mode",
+                "message": {"role": "user", "content": "Hello there"}
+            }),
+            json!({
+                "type": "assistant",
+                "uuid": "a-1",
+                "parentUuid": "u-1",
+                "sessionId": "sess-1",
+                "timestamp": "2025-01-01T00:00:01Z",
+                "message": {"role": "assistant", "content": [{"type": "text", "text": "Hi!"}]}
+            }),
+        ];
+        let content: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        let path = dir.path().join("sess-1.jsonl");
+        fs::write(&path, content.join("\n") + "\n{not json").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_inspect_reports_dropped_failed_and_unmapped() {
+        let dir = TempDir::new().unwrap();
+        let inspection = inspect_session("claude-code", &claude_session(&dir)).unwrap();
+
+        assert_eq!(inspection.session_id, "sess-1");
+        assert_eq!(inspection.cwd.as_deref(), Some("/work/project"));
+        assert_eq!(inspection.summary.records, 4);
+        assert_eq!(inspection.summary.mapped, 2);
+        assert_eq!(inspection.summary.dropped, 1);
+        assert_eq!(inspection.summary.failed, 1);
+        assert_eq!(inspection.records[0].status, RecordStatus::Dropped);
+        assert!(matches!(
+            inspection.records[3].status,
+            RecordStatus::Failed { .. }
+        ));
+
+        let user = &inspection.records[1];
+        assert_eq!(user.canonical.len(), 1);
+        assert!(user.unmapped_fields.contains(&"experimentFlag".to_string()));
+        assert!(!user
+            .unmapped_fields
+            .contains(&"message.content".to_string()));
+        assert_eq!(
+            inspection.summary.unmapped_fields.get("experimentFlag"),
+            Some(&1)
+        );
+    }
+
+    #[test]
+    fn test_exported_fixture_replays_without_source() {
+        let dir = TempDir::new().unwrap();
+        let source = claude_session(&dir);
+        let output = export_fixture("claude-code", &source, dir.path()).unwrap();
+        assert_eq!(output, dir.path().join("claude-code-sess-1.fixture.json"));
+
+        // The fixture must not depend on the original file
+        fs::remove_file(&source).unwrap();
+        let fixture = read_fixture(&output).unwrap();
+        assert_eq!(fixture.expected.len(), 2);
+
+        let replay = replay_fixture(&fixture).unwrap();
+        assert!(replay.matches_expected);
+        assert_eq!(replay.inspection.summary.dropped, 1);
+
+        let mut tampered = fixture.clone();
+        tampered.expected.pop();
+        assert!(!replay_fixture(&tampered).unwrap().matches_expected);
+    }
+
+    #[test]
+    fn test_agent_sidechains_belong_to_their_tool_result() {
+        let dir = TempDir::new().unwrap();
+        let lines = [
+            json!({
+                "type": "assistant",
+                "uuid": "a-1",
+                "sessionId": "sess-2",
+                "timestamp": "2025-01-01T00:00:00Z",
+                "message": {"role": "assistant", "content": [
+                    {"type": "tool_use", "id": "toolu_1", "name": "Task", "input": {"prompt": "look"}}
+                ]}
+            }),
+            json!({
+                "type": "user",
+                "uuid": "u-1",
+                "parentUuid": "a-1",
+                "sessionId": "sess-2",
+                "timestamp": "2025-01-01T00:00:05Z",
+                "toolUseResult": {"agentId": "ab12cd34"},
+                "message": {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "done"}
+                ]}
+            }),
+        ];
+        let agent = json!({
+            "type": "assistant",
+            "uuid": "agent-a-1",
+            "sessionId": "sess-2",
+            "isSidechain": true,
+            "timestamp": "2025-01-01T00:00:02Z",
+            "message": {"role": "assistant", "content": [{"type": "text", "text": "Looking"}]}
+        });
+        let source = dir.path().join("sess-2.jsonl");
+        let content: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        fs::write(&source, content.join("\n")).unwrap();
+        let agent_file = dir.path().join("agent-ab12cd34.jsonl");
+        fs::write(&agent_file, agent.to_string()).unwrap();
+
+        let inspection = inspect_session("claude-code", &source).unwrap();
+        assert_eq!(inspection.summary.records, 2);
+        assert_eq!(inspection.summary.canonical_messages, 3);
+        let result = &inspection.records[1];
+        assert_eq!(result.index, 1);
+        assert_eq!(result.canonical.len(), 2);
+        assert_eq!(result.canonical[1].uuid, "agent-a-1");
+
+        // The fixture carries the sidechain, so it replays without the agent file
+        let output = export_fixture("claude-code", &source, dir.path()).unwrap();
+        fs::remove_file(&agent_file).unwrap();
+        let replay = replay_fixture(&read_fixture(&output).unwrap()).unwrap();
+        assert!(replay.matches_expected);
+    }
+
+    #[test]
+    fn test_unmapped_fields_follow_nested_paths() {
+        let raw = json!({
+            "id": 42,
+            "payload": {"items": [{"text": "kept"}, {"text": "lost"}], "flag": true}
+        });
+        let message = CanonicalMessage::new_text_message(
+            "uuid-1".to_string(),
+            "2025-01-01T00:00:00Z".to_string(),
+            crate::providers::canonical::MessageType::User,
+            "sess".to_string(),
+            "test".to_string(),
+            "user".to_string(),
+            "prefix kept suffix".to_string(),
+        );
+
+        assert_eq!(
+            unmapped_fields(&raw, &[message]),
+            vec!["id", "payload.items[].text"]
+        );
+    }
+}
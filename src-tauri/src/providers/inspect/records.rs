@@ -0,0 +1,443 @@
+//! Per-provider source loading and record-by-record conversion
+//!
+//! `load_fixture` captures everything a conversion depends on (raw records
+//! plus the session ID and CWD the scanners would resolve from the
+//! environment), so `convert_records` gives the same result on any machine.
+
+use super::{
+    FixtureBlob, FixtureSource, InspectionFixture, RecordInspection, FIXTURE_FORMAT_VERSION,
+};
+use crate::providers::canonical::fidelity::{ConversionStats, RecordOrigin};
+use crate::providers::canonical::CanonicalMessage;
+use crate::providers::common::{
+    extract_session_id_from_filename, load_agent_messages, read_session_file,
+};
+use crate::providers::cursor::protobuf::CursorMessage;
+use crate::providers::{claude, codex, copilot, cursor, custom_jsonl, gemini, opencode};
+use base64::Engine;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+type Conversion = Result<Vec<CanonicalMessage>, String>;
+
+/// Read a session from disk into a replayable fixture (without `expected`)
+pub(super) fn load_fixture(provider_id: &str, path: &Path) -> Result<InspectionFixture, String> {
+    let source_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut fixture = InspectionFixture {
+        format_version: FIXTURE_FORMAT_VERSION,
+        provider: provider_id.to_string(),
+        session_id: session_stem(path),
+        cwd: None,
+        source_name,
+        source: FixtureSource::Lines {
+            lines: Vec::new(),
+            sidechains: BTreeMap::new(),
+        },
+        custom_jsonl: None,
+        expected: Vec::new(),
+    };
+
+    match provider_id {
+        "claude-code" => {
+            let lines = read_lines(path)?;
+            fixture.cwd = lines.iter().find_map(|line| string_at(line, &["cwd"]));
+            let source_dir = path.parent().unwrap_or(Path::new(""));
+            let sidechains = lines
+                .iter()
+                .filter_map(|line| string_at(line, &["toolUseResult", "agentId"]))
+                .filter_map(|agent_id| {
+                    load_agent_messages(source_dir, &agent_id, &fixture.session_id)
+                        .ok()
+                        .map(|agent_lines| (agent_id, agent_lines))
+                })
+                .collect();
+            fixture.source = FixtureSource::Lines { lines, sidechains };
+        }
+        "codex" => {
+            let lines = read_lines(path)?;
+            // Only the session_meta record carries the session ID and CWD
+            if let Some(id) = lines
+                .iter()
+                .find_map(|line| string_at(line, &["payload", "id"]))
+            {
+                fixture.session_id = id;
+            }
+            fixture.cwd = lines
+                .iter()
+                .find_map(|line| string_at(line, &["payload", "cwd"]));
+            fixture.source = FixtureSource::Lines {
+                lines,
+                sidechains: BTreeMap::new(),
+            };
+        }
+        "github-copilot" => {
+            let lines = read_lines(path)?;
+            let events: Vec<copilot::parser::CopilotEvent> = lines
+                .iter()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect();
+            fixture.cwd = copilot::parser::load_copilot_config()
+                .ok()
+                .and_then(|config| {
+                    copilot::parser::detect_project_and_cwd_from_events(
+                        &events,
+                        &config.trusted_folders,
+                    )
+                })
+                .map(|(_, cwd)| cwd);
+            fixture.source = FixtureSource::Lines {
+                lines,
+                sidechains: BTreeMap::new(),
+            };
+        }
+        "opencode" => {
+            // OpenCode spreads a session over session/message/part files; inspect
+            // the per-message entries the parser assembles from them
+            let storage = path
+                .ancestors()
+                .find(|dir| dir.file_name().is_some_and(|name| name == "storage"))
+                .ok_or_else(|| {
+                    format!(
+                        "{} is not inside an OpenCode storage directory",
+                        path.display()
+                    )
+                })?;
+            let parsed = opencode::OpenCodeParser::new(storage.to_path_buf())
+                .parse_session(&fixture.session_id)?;
+            let lines: Vec<String> = parsed.jsonl_content.lines().map(str::to_string).collect();
+            fixture.cwd = lines.iter().find_map(|line| string_at(line, &["cwd"]));
+            fixture.source = FixtureSource::Lines {
+                lines,
+                sidechains: BTreeMap::new(),
+            };
+        }
+        "gemini-code" => {
+            let content =
+                read_session_file(path).map_err(|e| format!("Failed to read file: {}", e))?;
+            let document: Value = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse session: {}", e))?;
+            let session: gemini::parser::GeminiSession =
+                serde_json::from_value(document.clone())
+                    .map_err(|e| format!("Failed to parse session: {}", e))?;
+            fixture.session_id = session.session_id.clone();
+            fixture.cwd = gemini::utils::infer_cwd_from_session(&session, &session.project_hash);
+            fixture.source = FixtureSource::Document { document };
+        }
+        "cursor" => {
+            // Accept either the session directory or its store.db
+            let db_path = if path.is_dir() {
+                path.join("store.db")
+            } else {
+                path.to_path_buf()
+            };
+            let session_dir = db_path.parent().unwrap_or(Path::new(""));
+            if let Some(id) = session_dir.file_name().and_then(|name| name.to_str()) {
+                fixture.session_id = id.to_string();
+            }
+            fixture.source_name = fixture.session_id.clone();
+            fixture.cwd = session_dir
+                .parent()
+                .and_then(|hash_dir| hash_dir.file_name())
+                .and_then(|hash| hash.to_str())
+                .and_then(cursor::find_cwd_for_session);
+
+            let conn = cursor::db::open_cursor_db(&db_path).map_err(|e| e.to_string())?;
+            let metadata = cursor::db::get_session_metadata(&conn).map_err(|e| e.to_string())?;
+            let blobs = cursor::db::get_conversation_messages(&conn)
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .map(|(id, data, _)| FixtureBlob {
+                    id,
+                    data: base64::engine::general_purpose::STANDARD.encode(data),
+                })
+                .collect();
+            fixture.source = FixtureSource::CursorBlobs {
+                created_at: metadata.created_at,
+                blobs,
+            };
+        }
+        other if custom_jsonl::is_custom_provider(other) => {
+            let (_, spec) = custom_jsonl::load_custom_config(other)?;
+            let content =
+                read_session_file(path).map_err(|e| format!("Failed to read file: {}", e))?;
+            let converted =
+                custom_jsonl::convert_content(other, &spec.mapping, &content, &fixture.session_id);
+            fixture.session_id = converted.session_id;
+            fixture.cwd = converted.cwd;
+            fixture.custom_jsonl = Some(spec);
+            fixture.source = FixtureSource::Lines {
+                lines: content.lines().map(str::to_string).collect(),
+                sidechains: BTreeMap::new(),
+            };
+        }
+        other => return Err(format!("Unsupported provider: {}", other)),
+    }
+
+    Ok(fixture)
+}
+
+/// Convert a fixture and attribute the output to its raw records
+pub(super) fn convert_records(
+    fixture: &InspectionFixture,
+) -> Result<Vec<RecordInspection>, String> {
+    let provider = fixture.provider.as_str();
+    let session_id = fixture.session_id.as_str();
+    let cwd = fixture.cwd.as_deref();
+    let finish = |messages: Vec<CanonicalMessage>| stamp(messages, session_id, cwd);
+
+    let records = match (&fixture.source, provider) {
+        (FixtureSource::Lines { lines, sidechains }, "claude-code") => {
+            let mut cwd_value = cwd.map(str::to_string);
+            let mut stats = ConversionStats::new();
+            let canonical_lines = claude::converter_utils::convert_content(
+                &lines.join("\n"),
+                |agent_id| sidechains.get(agent_id).cloned(),
+                session_id,
+                &mut cwd_value,
+                &mut stats,
+            )
+            .map_err(|e| e.to_string())?;
+            let messages = canonical_lines
+                .iter()
+                .map(|line| serde_json::from_str(line))
+                .collect::<Result<Vec<CanonicalMessage>, _>>()
+                .map_err(|e| format!("Converter wrote an invalid message: {}", e))?;
+            traced_lines(lines, messages, &stats.origins)
+        }
+        (FixtureSource::Lines { lines, .. }, "codex") => {
+            let lines_ref: Vec<&str> = lines.iter().map(String::as_str).collect();
+            let (messages, stats) = codex::converter::convert_session(&lines_ref, session_id)?;
+            traced_lines(lines, messages, &stats.origins)
+        }
+        (FixtureSource::Lines { lines, .. }, "github-copilot") => {
+            convert_lines(lines, |_, line| {
+                let event: copilot::parser::CopilotEvent = parse(line)?;
+                copilot::converter::convert_event_to_canonical(&event, session_id, cwd)
+                    .map(finish)
+                    .map_err(|e| e.to_string())
+            })
+        }
+        (FixtureSource::Lines { lines, .. }, "opencode") => convert_lines(lines, |_, line| {
+            let entry: opencode::parser::OpenCodeJsonLEntry = parse(line)?;
+            opencode::converter::convert_entry_to_canonical(&entry)
+                .map(|message| finish(vec![message]))
+                .map_err(|e| e.to_string())
+        }),
+        (FixtureSource::Lines { lines, .. }, other) if custom_jsonl::is_custom_provider(other) => {
+            let spec = fixture
+                .custom_jsonl
+                .as_ref()
+                .ok_or_else(|| format!("Fixture for '{}' has no customJsonl mapping", other))?;
+            let converted =
+                custom_jsonl::convert_content(other, &spec.mapping, &lines.join("\n"), session_id);
+            traced_lines(lines, converted.messages, &converted.stats.origins)
+        }
+        (FixtureSource::Document { document }, "gemini-code") => {
+            let mut session: gemini::parser::GeminiSession =
+                serde_json::from_value(document.clone())
+                    .map_err(|e| format!("Failed to parse session: {}", e))?;
+            let messages = std::mem::take(&mut session.messages);
+            let raw_messages = document
+                .get("messages")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+
+            messages
+                .into_iter()
+                .zip(raw_messages)
+                .enumerate()
+                .map(|(index, (message, raw))| {
+                    let mut single = session.clone();
+                    single.messages = vec![message];
+                    let result = gemini::converter::convert_session_to_canonical(
+                        &single,
+                        cwd.map(str::to_string),
+                    )
+                    .map(finish)
+                    .map_err(|e| e.to_string());
+                    RecordInspection::new(index, None, raw, result)
+                })
+                .collect()
+        }
+        (FixtureSource::CursorBlobs { created_at, blobs }, "cursor") => blobs
+            .iter()
+            .enumerate()
+            .map(|(index, blob)| {
+                let data = match base64::engine::general_purpose::STANDARD.decode(&blob.data) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        return RecordInspection::new(
+                            index,
+                            Some(blob.id.clone()),
+                            Value::Null,
+                            Err(format!("Invalid base64 blob: {}", e)),
+                        )
+                    }
+                };
+                match CursorMessage::decode_from_bytes(&data) {
+                    Ok(message) => {
+                        let result = cursor::converter::CursorMessageWithRaw::new(
+                            &message,
+                            &data,
+                            *created_at,
+                            index,
+                        )
+                        .to_canonical_split()
+                        .map(finish)
+                        .map_err(|e| e.to_string());
+                        RecordInspection::new(
+                            index,
+                            Some(blob.id.clone()),
+                            cursor_raw(&message, &data),
+                            result,
+                        )
+                    }
+                    Err(e) => RecordInspection::new(
+                        index,
+                        Some(blob.id.clone()),
+                        Value::Null,
+                        Err(format!("Failed to decode blob: {}", e)),
+                    ),
+                }
+            })
+            .collect(),
+        (_, other) => {
+            return Err(format!(
+                "Fixture source does not match provider '{}'",
+                other
+            ));
+        }
+    };
+
+    Ok(records)
+}
+
+/// Hand each traced record the messages it produced, in the converter's order
+fn traced_lines(
+    lines: &[String],
+    messages: Vec<CanonicalMessage>,
+    origins: &[RecordOrigin],
+) -> Vec<RecordInspection> {
+    let mut messages = messages.into_iter();
+    let mut records: Vec<RecordInspection> = origins
+        .iter()
+        .map(|origin| {
+            let canonical: Vec<CanonicalMessage> =
+                messages.by_ref().take(origin.messages).collect();
+            let raw = lines.get(origin.index).map_or(Value::Null, |line| {
+                serde_json::from_str(line).unwrap_or_else(|_| Value::String(line.clone()))
+            });
+            let result = match &origin.error {
+                Some(error) => Err(error.clone()),
+                None => Ok(canonical),
+            };
+            RecordInspection::new(origin.index, None, raw, result)
+        })
+        .collect();
+    records.sort_by_key(|record| record.index);
+    records
+}
+
+/// Inspect each non-empty line; unparseable JSON is reported as a failed record
+fn convert_lines<F>(lines: &[String], mut convert: F) -> Vec<RecordInspection>
+where
+    F: FnMut(usize, &str) -> Conversion,
+{
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| match serde_json::from_str::<Value>(line) {
+            Ok(raw) => RecordInspection::new(index, None, raw, convert(index, line)),
+            Err(e) => RecordInspection::new(
+                index,
+                None,
+                Value::String(line.clone()),
+                Err(format!("Invalid JSON: {}", e)),
+            ),
+        })
+        .collect()
+}
+
+/// Apply the session ID and CWD the scanners would set after conversion
+fn stamp(
+    mut messages: Vec<CanonicalMessage>,
+    session_id: &str,
+    cwd: Option<&str>,
+) -> Vec<CanonicalMessage> {
+    for message in &mut messages {
+        message.session_id = session_id.to_string();
+        if message.cwd.is_none() {
+            message.cwd = cwd.map(str::to_string);
+        }
+    }
+    messages
+}
+
+fn parse<T: serde::de::DeserializeOwned>(line: &str) -> Result<T, String> {
+    serde_json::from_str(line).map_err(|e| format!("Unrecognized record: {}", e))
+}
+
+/// JSON view of a Cursor blob: JSON blobs as-is, protobuf blobs by field
+fn cursor_raw(message: &CursorMessage, data: &[u8]) -> Value {
+    let blob = match message {
+        CursorMessage::Json(json) => {
+            return serde_json::from_slice(data)
+                .unwrap_or_else(|_| serde_json::to_value(json).unwrap_or(Value::Null))
+        }
+        CursorMessage::Protobuf(blob) => blob,
+    };
+
+    let mut fields = Map::new();
+    let content = blob.get_content_with_fallback(data);
+    if !content.is_empty() {
+        fields.insert("content".to_string(), Value::String(content));
+    }
+    if let Some(ref uuid) = blob.uuid {
+        fields.insert("uuid".to_string(), json!(uuid));
+    }
+    if let Some(ref metadata) = blob.metadata {
+        fields.insert("metadata".to_string(), json!(metadata));
+    }
+    if let Some(ref complex) = blob.complex_data {
+        let value = serde_json::from_str(complex).unwrap_or_else(|_| json!(complex));
+        fields.insert("complexData".to_string(), value);
+    }
+    if let Some(ref additional) = blob.additional_content {
+        let value = blob
+            .parse_additional_content()
+            .unwrap_or_else(|| json!(additional));
+        fields.insert("additionalContent".to_string(), value);
+    }
+    Value::Object(fields)
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>, String> {
+    let content = read_session_file(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(content.lines().map(str::to_string).collect())
+}
+
+/// String at `keys` in a JSON line, if the line parses and the value is non-empty
+fn string_at(line: &str, keys: &[&str]) -> Option<String> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    keys.iter()
+        .try_fold(&value, |value, key| value.get(key))?
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Session ID from a file name, ignoring `.jsonl.gz` archive suffixes
+fn session_stem(path: &Path) -> String {
+    let stem = extract_session_id_from_filename(path);
+    stem.strip_suffix(".jsonl")
+        .map(str::to_string)
+        .unwrap_or(stem)
+}
@@ -7,7 +7,9 @@ pub mod codex; // Codex converter (public for canonical format migration)
 pub mod common;
 pub mod copilot; // Copilot converter (public for canonical format migration)
 pub mod cursor; // Cursor converter
+pub mod custom_jsonl; // Declarative custom JSONL providers ("custom-*")
 pub mod gemini; // Gemini converter (public for canonical format migration)
+pub mod inspect; // Record-level conversion inspection and fixtures
 pub mod opencode; // OpenCode converter (public for canonical format migration)
 mod session_scanner;
 
@@ -17,6 +19,7 @@ pub use codex::watcher::{CodexWatcher, CodexWatcherStatus};
 pub use common::SessionInfo;
 pub use copilot::watcher::{CopilotWatcher, CopilotWatcherStatus};
 pub use cursor::watcher::{CursorWatcher, CursorWatcherStatus};
+pub use custom_jsonl::watcher::CustomJsonlWatcher;
 pub use gemini::watcher::{GeminiWatcher, GeminiWatcherStatus};
 pub use opencode::watcher::{OpenCodeWatcher, OpenCodeWatcherStatus};
 pub use session_scanner::scan_all_sessions_filtered;
@@ -29,6 +32,9 @@ pub fn scan_projects(provider_id: &str, home_directory: &str) -> Result<Vec<Proj
         "codex" => codex::scan_projects(home_directory),
         "gemini-code" => gemini::utils::scan_projects(home_directory),
         "cursor" => cursor::scan_projects(home_directory),
+        other if custom_jsonl::is_custom_provider(other) => {
+            custom_jsonl::scan_projects(other, home_directory)
+        }
         other => Err(format!("Unsupported provider: {}", other)),
     }
 }
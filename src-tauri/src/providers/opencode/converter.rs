@@ -1,7 +1,7 @@
+use super::parser::{OpenCodeJsonLContent, OpenCodeJsonLEntry};
 use crate::providers::canonical::{
-    CanonicalMessage, ContentBlock, ContentValue, MessageContent, MessageType,
+    CanonicalMessage, ContentBlock, ContentValue, MessageContent, MessageType, CANONICAL_VERSION,
 };
-use super::parser::{OpenCodeJsonLContent, OpenCodeJsonLEntry};
 use anyhow::{Context, Result};
 use uuid::Uuid;
 
@@ -30,15 +30,15 @@ pub fn convert_entry_to_canonical(entry: &OpenCodeJsonLEntry) -> Result<Canonica
     // Convert content blocks
     let content = convert_content_blocks(&entry.message.content)?;
 
-    // Generate UUID if needed (some entries might not have unique IDs)
-    let uuid = if entry.session_id.is_empty() {
-        Uuid::new_v4().to_string()
-    } else {
-        // Create a deterministic ID from session + timestamp
-        format!("{}-{}", entry.session_id, entry.timestamp)
+    // OpenCode message and part ids are unique; timestamps are not
+    let uuid = match &entry.id {
+        Some(id) => id.clone(),
+        None if entry.session_id.is_empty() => Uuid::new_v4().to_string(),
+        None => format!("{}-{}", entry.session_id, entry.timestamp),
     };
 
     Ok(CanonicalMessage {
+        canonical_version: CANONICAL_VERSION.to_string(),
         uuid,
         timestamp: entry.timestamp.clone(),
         message_type,
@@ -56,9 +56,15 @@ pub fn convert_entry_to_canonical(entry: &OpenCodeJsonLEntry) -> Result<Canonica
             model: None,
             usage: None,
         },
-        provider_metadata: Some(serde_json::json!({
-            "opencode_type": entry.entry_type,
-        })),
+        provider_metadata: Some(match &entry.parent_session_id {
+            Some(parent_session_id) => serde_json::json!({
+                "opencode_type": entry.entry_type,
+                "parent_session_id": parent_session_id,
+            }),
+            None => serde_json::json!({
+                "opencode_type": entry.entry_type,
+            }),
+        }),
         is_meta: None,
         request_id: None,
         tool_use_result: None,
@@ -186,6 +192,7 @@ mod tests {
     #[test]
     fn test_convert_text_entry() {
         let entry = OpenCodeJsonLEntry {
+            id: None,
             session_id: "test-session".to_string(),
             timestamp: "2025-01-01T00:00:00.000Z".to_string(),
             entry_type: "user".to_string(),
@@ -197,6 +204,7 @@ mod tests {
                 }],
             },
             cwd: Some("/test/project".to_string()),
+            parent_session_id: None,
         };
 
         let canonical = convert_entry_to_canonical(&entry).unwrap();
@@ -215,6 +223,7 @@ mod tests {
     #[test]
     fn test_convert_tool_use_entry() {
         let entry = OpenCodeJsonLEntry {
+            id: Some("prt_tool_1".to_string()),
             session_id: "test-session".to_string(),
             timestamp: "2025-01-01T00:00:00.000Z".to_string(),
             entry_type: "tool_use".to_string(),
@@ -228,9 +237,11 @@ mod tests {
                 }],
             },
             cwd: Some("/test/project".to_string()),
+            parent_session_id: None,
         };
 
         let canonical = convert_entry_to_canonical(&entry).unwrap();
+        assert_eq!(canonical.uuid, "prt_tool_1");
 
         assert_eq!(canonical.message_type, MessageType::Assistant);
 
@@ -252,6 +263,7 @@ mod tests {
     #[test]
     fn test_convert_tool_result_entry() {
         let entry = OpenCodeJsonLEntry {
+            id: None,
             session_id: "test-session".to_string(),
             timestamp: "2025-01-01T00:00:00.000Z".to_string(),
             entry_type: "tool_result".to_string(),
@@ -265,6 +277,7 @@ mod tests {
                 }],
             },
             cwd: Some("/test/project".to_string()),
+            parent_session_id: None,
         };
 
         let canonical = convert_entry_to_canonical(&entry).unwrap();
@@ -292,6 +305,7 @@ mod tests {
     #[test]
     fn test_convert_file_entry() {
         let entry = OpenCodeJsonLEntry {
+            id: None,
             session_id: "test-session".to_string(),
             timestamp: "2025-01-01T00:00:00.000Z".to_string(),
             entry_type: "user".to_string(),
@@ -305,6 +319,7 @@ mod tests {
                 }],
             },
             cwd: Some("/test/project".to_string()),
+            parent_session_id: None,
         };
 
         let canonical = convert_entry_to_canonical(&entry).unwrap();
@@ -327,6 +342,7 @@ mod tests {
     #[test]
     fn test_convert_patch_entry() {
         let entry = OpenCodeJsonLEntry {
+            id: None,
             session_id: "test-session".to_string(),
             timestamp: "2025-01-01T00:00:00.000Z".to_string(),
             entry_type: "assistant".to_string(),
@@ -339,6 +355,7 @@ mod tests {
                 }],
             },
             cwd: Some("/test/project".to_string()),
+            parent_session_id: None,
         };
 
         let canonical = convert_entry_to_canonical(&entry).unwrap();
@@ -362,6 +379,7 @@ mod tests {
     #[test]
     fn test_convert_mixed_content() {
         let entry = OpenCodeJsonLEntry {
+            id: None,
             session_id: "test-session".to_string(),
             timestamp: "2025-01-01T00:00:00.000Z".to_string(),
             entry_type: "user".to_string(),
@@ -381,6 +399,7 @@ mod tests {
                 ],
             },
             cwd: Some("/test/project".to_string()),
+            parent_session_id: None,
         };
 
         let canonical = convert_entry_to_canonical(&entry).unwrap();
@@ -1,3 +1,5 @@
+use crate::providers::canonical::fidelity::ConversionStats;
+use crate::providers::common::format_version::{FormatSpec, FormatVersionRange, NewerVersions};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -28,6 +30,9 @@ pub struct OpenCodeSession {
     pub directory: Option<String>,
     pub title: Option<String>,
     pub time: OpenCodeTime,
+    /// Parent session ID for child sessions spawned by the task tool
+    #[serde(rename = "parentID", default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +74,9 @@ pub struct OpenCodePart {
     pub mime: Option<String>,
     pub url: Option<String>,
     pub source: Option<serde_json::Value>,
+    /// Fields not read by the parser, tracked in conversion stats
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,6 +111,9 @@ pub struct OpenCodePartTime {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenCodeJsonLEntry {
+    /// OpenCode message or part id the entry was built from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(rename = "sessionId")]
     pub session_id: String,
     pub timestamp: String,
@@ -111,6 +122,12 @@ pub struct OpenCodeJsonLEntry {
     pub message: OpenCodeJsonLMessage,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cwd: Option<String>,
+    #[serde(
+        rename = "parentSessionId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub parent_session_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -172,9 +189,35 @@ pub struct ParsedSession {
     pub tool_count: usize,
     #[allow(dead_code)]
     pub file_count: usize,
+    /// One record per message part
+    pub stats: ConversionStats,
     pub cwd: Option<String>,
 }
 
+/// OpenCode storage formats this parser can decode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OpenCodeFormat {
+    /// `session/`, `message/` and `part/` JSON files under `storage/`
+    Storage,
+}
+
+/// Versioned by the OpenCode release stamped on `session.version`
+pub static OPENCODE_FORMATS: FormatSpec<OpenCodeFormat> = FormatSpec {
+    provider_id: "opencode",
+    version_field: "session.version",
+    ranges: &[FormatVersionRange {
+        min: "0.0.0",
+        max: Some("2.0.0"),
+        decoder: OpenCodeFormat::Storage,
+    }],
+    unversioned: Some(OpenCodeFormat::Storage),
+    // A release number; drift shows up in the part stats
+    newer: NewerVersions::Warn,
+};
+
+/// Part types the parser skips on purpose
+const KNOWN_IGNORED_PART_TYPES: &[&str] = &["step-start", "snapshot", "reasoning", "agent"];
+
 pub struct OpenCodeParser {
     storage_path: PathBuf,
 }
@@ -187,6 +230,8 @@ impl OpenCodeParser {
     pub fn parse_session(&self, session_id: &str) -> Result<ParsedSession, String> {
         // Load session metadata first to get project ID
         let session = self.load_session(session_id)?;
+        let OpenCodeFormat::Storage =
+            OPENCODE_FORMATS.resolve(session_id, session.version.as_deref())?;
         let project_id = session
             .project_id
             .ok_or_else(|| format!("Session {} has no project ID", session_id))?;
@@ -205,6 +250,7 @@ impl OpenCodeParser {
         let mut total_cost = 0.0;
         let mut tool_count = 0usize;
         let mut file_count = 0usize;
+        let mut stats = ConversionStats::new();
 
         // Load all parts for each message and organize chronologically
         let mut session_entries = Vec::new();
@@ -225,13 +271,21 @@ impl OpenCodeParser {
                 .unwrap_or_else(Utc::now);
 
             for part in parts {
+                for key in part.other.keys() {
+                    *stats.unknown_keys.entry(key.clone()).or_default() += 1;
+                }
+
                 match part.part_type.as_str() {
                     "text" => {
+                        // Text, file and patch parts merge into one entry per message
                         if let Some(text) = part.text {
+                            stats.record_converted(0);
                             text_content.push(OpenCodeJsonLContent::Text {
                                 content_type: "text".to_string(),
                                 text,
                             });
+                        } else {
+                            stats.record_skipped("text:empty");
                         }
                     }
                     "tool" => {
@@ -258,6 +312,7 @@ impl OpenCodeParser {
 
                             // Create separate entry for tool use
                             let tool_use_entry = OpenCodeJsonLEntry {
+                                id: Some(part.id.clone()),
                                 session_id: session_id.to_string(),
                                 timestamp: part_timestamp.to_rfc3339(),
                                 entry_type: "tool_use".to_string(),
@@ -274,6 +329,7 @@ impl OpenCodeParser {
                                     }],
                                 },
                                 cwd: cwd.clone(),
+                                parent_session_id: session.parent_id.clone(),
                             };
                             session_entries.push((part_timestamp, tool_use_entry));
 
@@ -295,6 +351,7 @@ impl OpenCodeParser {
                                     });
 
                                 let tool_result_entry = OpenCodeJsonLEntry {
+                                    id: Some(format!("{}-result", part.id)),
                                     session_id: session_id.to_string(),
                                     timestamp: result_timestamp.to_rfc3339(),
                                     entry_type: "tool_result".to_string(),
@@ -308,9 +365,13 @@ impl OpenCodeParser {
                                         }],
                                     },
                                     cwd: cwd.clone(),
+                                    parent_session_id: session.parent_id.clone(),
                                 };
                                 session_entries.push((result_timestamp, tool_result_entry));
                             }
+                            stats.record_converted(if state.output.is_some() { 2 } else { 1 });
+                        } else {
+                            stats.record_skipped("tool:incomplete");
                         }
                     }
                     "file" => {
@@ -320,28 +381,37 @@ impl OpenCodeParser {
                             part.url.as_ref(),
                         ) {
                             file_count += 1;
+                            stats.record_converted(0);
                             text_content.push(OpenCodeJsonLContent::File {
                                 content_type: "file".to_string(),
                                 filename: filename.clone(),
                                 mime: mime.clone(),
                                 url: url.clone(),
                             });
+                        } else {
+                            stats.record_skipped("file:incomplete");
                         }
                     }
                     "patch" => {
                         if let (Some(files), Some(hash)) = (part.files.as_ref(), part.hash.as_ref())
                         {
                             if !files.is_empty() {
+                                stats.record_converted(0);
                                 text_content.push(OpenCodeJsonLContent::Patch {
                                     content_type: "patch".to_string(),
                                     files: files.clone(),
                                     hash: hash.clone(),
                                 });
+                            } else {
+                                stats.record_skipped("patch:empty");
                             }
+                        } else {
+                            stats.record_skipped("patch:incomplete");
                         }
                     }
                     "step-finish" => {
-                        // Aggregate token usage
+                        // Aggregate token usage (session totals, not messages)
+                        stats.record_skipped("step-finish");
                         if let Some(tokens) = part.tokens.as_ref() {
                             total_input_tokens += tokens.input.unwrap_or(0);
                             total_output_tokens += tokens.output.unwrap_or(0);
@@ -355,8 +425,12 @@ impl OpenCodeParser {
                             total_cost += cost;
                         }
                     }
-                    _ => {
+                    other => {
                         // Skip other types (step-start, snapshot, etc.)
+                        if !KNOWN_IGNORED_PART_TYPES.contains(&other) {
+                            stats.note_unknown_event_type(other);
+                        }
+                        stats.record_skipped(other);
                     }
                 }
             }
@@ -364,6 +438,7 @@ impl OpenCodeParser {
             // Create entry for text/file/patch content if any
             if !text_content.is_empty() {
                 let entry = OpenCodeJsonLEntry {
+                    id: Some(message.id.clone()),
                     session_id: session_id.to_string(),
                     timestamp: base_timestamp.to_rfc3339(),
                     entry_type: message.role.clone(),
@@ -372,9 +447,12 @@ impl OpenCodeParser {
                         content: text_content,
                     },
                     cwd: cwd.clone(),
+                    parent_session_id: session.parent_id.clone(),
                 };
 
                 session_entries.push((base_timestamp, entry));
+                // Credit the merged text/file/patch parts with their message
+                stats.messages_produced += 1;
             }
         }
 
@@ -451,6 +529,7 @@ impl OpenCodeParser {
             tool_count,
             file_count,
             cwd: Some(project.worktree.clone()),
+            stats,
         })
     }
 
@@ -749,6 +828,9 @@ mod tests {
         assert_eq!(result.session_id, "test_session");
         assert_eq!(result.project_name, "project");
         assert!(!result.jsonl_content.is_empty());
+        assert_eq!(result.stats.failed, 0);
+        assert!(result.stats.unknown_event_types.is_empty());
+        assert!(result.stats.unknown_keys.is_empty());
 
         // Print JSONL for debugging
         println!("Generated JSONL:\n{}", result.jsonl_content);
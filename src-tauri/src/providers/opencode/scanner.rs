@@ -99,6 +99,7 @@ fn parse_opencode_session(
     // Write canonical JSONL to project-organized path
     fs::write(&cached_file_path, &canonical_jsonl)
         .map_err(|e| format!("Failed to write cached JSONL: {}", e))?;
+    parsed_session.stats.persist("opencode", session_id);
 
     let file_name = format!("{}.jsonl", session_id);
 
@@ -1,18 +1,23 @@
 use super::converter::convert_opencode_jsonl_to_canonical;
 use super::parser::OpenCodeParser;
 use crate::config::load_provider_config;
+use crate::config_service::ProviderConfigUpdates;
 use crate::events::{EventBus, SessionEventPayload};
 use crate::logging::{log_error, log_info};
-use crate::providers::common::{WatcherStatus, FILE_WATCH_POLL_INTERVAL};
+use crate::providers::common::{
+    format_version, fs_event_channel, run_blocking, Debouncer, FsEventReceiver, FsWatcher,
+    SessionLifecycle, WatchCancellation, WatchTask, WatcherHeartbeat, WatcherStatus,
+    FILE_WATCH_POLL_INTERVAL,
+};
 use crate::upload_queue::UploadQueue;
-use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Config, Event, EventKind, RecursiveMode, Watcher};
 use shellexpand::tilde;
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{mpsc, Arc, Mutex};
-use std::thread;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
 const PROVIDER_ID: &str = "opencode";
 
@@ -38,10 +43,11 @@ pub struct OpenCodeSessionState {
 
 #[derive(Debug)]
 pub struct OpenCodeWatcher {
-    _watcher: RecommendedWatcher,
-    _thread_handle: thread::JoinHandle<()>,
+    _watcher: FsWatcher,
+    task: WatchTask,
     upload_queue: Arc<UploadQueue>,
     is_running: Arc<Mutex<bool>>,
+    heartbeat: WatcherHeartbeat,
 }
 
 impl OpenCodeWatcher {
@@ -49,6 +55,7 @@ impl OpenCodeWatcher {
         _projects: Vec<String>,
         upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
+        shutdown: CancellationToken,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         if let Err(e) = log_info(PROVIDER_ID, "🔍 Starting OpenCode file monitoring") {
             eprintln!("Logging error: {}", e);
@@ -131,10 +138,11 @@ impl OpenCodeWatcher {
         }
 
         // Create file system event channel
-        let (tx, rx) = mpsc::channel();
+        let (tx, rx) = fs_event_channel(PROVIDER_ID);
 
         // Create the file watcher
-        let mut watcher = RecommendedWatcher::new(
+        let mut watcher = FsWatcher::for_provider(
+            PROVIDER_ID,
             tx,
             Config::default().with_poll_interval(FILE_WATCH_POLL_INTERVAL),
         )?;
@@ -149,13 +157,16 @@ impl OpenCodeWatcher {
             eprintln!("Logging error: {}", e);
         }
 
+        let heartbeat = WatcherHeartbeat::new(PROVIDER_ID, &storage_path, watcher.is_polling());
         let is_running = Arc::new(Mutex::new(true));
         let is_running_clone = Arc::clone(&is_running);
         let upload_queue_clone = Arc::clone(&upload_queue);
         let event_bus_clone = event_bus.clone();
+        let heartbeat_clone = heartbeat.clone();
 
-        // Start background thread to handle file events
-        let thread_handle = thread::spawn(move || {
+        // Start background task to handle file events
+        let task = WatchTask::spawn(&shutdown, move |cancellation| async move {
+            let _exit_guard = heartbeat_clone.exit_guard();
             Self::file_event_processor(
                 rx,
                 storage_path,
@@ -164,14 +175,18 @@ impl OpenCodeWatcher {
                 upload_queue_clone,
                 event_bus_clone,
                 is_running_clone,
-            );
+                heartbeat_clone,
+                cancellation,
+            )
+            .await;
         });
 
         Ok(OpenCodeWatcher {
             _watcher: watcher,
-            _thread_handle: thread_handle,
+            task,
             upload_queue,
             is_running,
+            heartbeat,
         })
     }
 
@@ -204,6 +219,7 @@ impl OpenCodeWatcher {
 
         // Write canonical JSONL to project-organized path
         fs::write(&jsonl_path, &canonical_jsonl)?;
+        parsed_session.stats.persist(PROVIDER_ID, session_id);
 
         // Extract real project name from parsed session (not the GUID)
         let project_name = parsed_session.project_name.clone();
@@ -245,74 +261,125 @@ impl OpenCodeWatcher {
         Ok(project_names)
     }
 
-    fn file_event_processor(
-        rx: mpsc::Receiver<Result<Event, notify::Error>>,
+    #[allow(clippy::too_many_arguments)]
+    async fn file_event_processor(
+        mut rx: FsEventReceiver,
         storage_path: PathBuf,
         parser: OpenCodeParser,
         projects_to_watch: Vec<String>,
         _upload_queue: Arc<UploadQueue>,
         event_bus: EventBus,
         is_running: Arc<Mutex<bool>>,
+        heartbeat: WatcherHeartbeat,
+        mut cancellation: WatchCancellation,
     ) {
         let mut session_states: std::collections::HashMap<String, OpenCodeSessionState> =
             std::collections::HashMap::new();
+        let mut lifecycle = SessionLifecycle::new(PROVIDER_ID, event_bus.clone());
+        let mut config_updates = ProviderConfigUpdates::new(PROVIDER_ID);
+        let mut debouncer = Debouncer::default();
+        let parser = Arc::new(parser);
+        let projects_to_watch = Arc::new(projects_to_watch);
 
         loop {
-            // Check if we should continue running
-            {
-                if let Ok(running) = is_running.lock() {
-                    if !*running {
-                        break;
-                    }
+            heartbeat.beat();
+
+            // Move quiet sessions towards idle/completed
+            lifecycle.tick();
+
+            // Apply config edits without restarting the watcher
+            if let Some(config) = config_updates.poll() {
+                if !config.enabled {
+                    cancellation.stop_disabled(PROVIDER_ID, &is_running);
+                    continue;
                 }
+                lifecycle.set_config(&config);
             }
 
-            // Process file system events with short timeout for debouncing
-            match rx.recv_timeout(Duration::from_millis(500)) {
-                Ok(Ok(event)) => {
-                    if let Some(session_event) =
-                        Self::process_file_event(&event, &storage_path, &parser, &projects_to_watch)
-                    {
-                        // PHASE 1: WATCH - Just mark session as needing aggregation
-                        Self::mark_session_for_aggregation(&mut session_states, &session_event);
+            // Wait for file system events, debouncing bursts per path
+            let wake_at = debouncer.wake_at(Instant::now(), Duration::from_millis(500));
+            let stopping = tokio::select! {
+                _ = cancellation.cancelled() => true,
+                received = rx.recv() => match received {
+                    Some(Ok(event)) => {
+                        debouncer.push(event, Instant::now());
+                        false
                     }
-                }
-                Ok(Err(error)) => {
-                    if let Err(e) = log_error(
-                        PROVIDER_ID,
-                        &format!("OpenCode file watcher error: {:?}", error),
-                    ) {
-                        eprintln!("Logging error: {}", e);
+                    Some(Err(error)) => {
+                        heartbeat.report(&error);
+                        if let Err(e) = log_error(
+                            PROVIDER_ID,
+                            &format!("OpenCode file watcher error: {:?}", error),
+                        ) {
+                            eprintln!("Logging error: {}", e);
+                        }
+                        false
                     }
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // Timeout is normal - use it to check for sessions ready to aggregate
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    if let Err(e) =
-                        log_error(PROVIDER_ID, "OpenCode file watcher channel disconnected")
-                    {
-                        eprintln!("Logging error: {}", e);
+                    None => {
+                        if let Err(e) =
+                            log_error(PROVIDER_ID, "OpenCode file watcher channel disconnected")
+                        {
+                            eprintln!("Logging error: {}", e);
+                        }
+                        true
                     }
-                    break;
-                }
+                },
+                _ = tokio::time::sleep_until(wake_at) => false,
+            };
+
+            // Stopping flushes whatever is still being debounced
+            let events = if stopping {
+                debouncer.drain()
+            } else {
+                debouncer.ready(Instant::now())
+            };
+            let session_events = {
+                let storage_path = storage_path.clone();
+                let parser = Arc::clone(&parser);
+                let projects_to_watch = Arc::clone(&projects_to_watch);
+                run_blocking(move || {
+                    events
+                        .iter()
+                        .filter_map(|event| {
+                            Self::process_file_event(
+                                event,
+                                &storage_path,
+                                &parser,
+                                &projects_to_watch,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await
+            };
+            for session_event in session_events {
+                // PHASE 1: WATCH - Just mark session as needing aggregation
+                Self::mark_session_for_aggregation(&mut session_states, &session_event);
             }
 
             // PHASE 2 & 3: AGGREGATE & PROCESS
-            // Check for sessions that have been idle for AGGREGATION_DEBOUNCE
+            // Check for sessions that have been idle for AGGREGATION_DEBOUNCE,
+            // or every pending one when stopping so no conversion is left half done
             let now = Instant::now();
             let sessions_to_aggregate: Vec<(String, String)> = session_states
                 .iter()
                 .filter(|(_, state)| {
                     state.needs_aggregation
-                        && now.duration_since(state.last_modified) >= AGGREGATION_DEBOUNCE
+                        && (stopping
+                            || now.duration_since(state.last_modified) >= AGGREGATION_DEBOUNCE)
                 })
                 .map(|(session_id, state)| (session_id.clone(), state.project_id.clone()))
                 .collect();
 
             for (session_id, project_id) in sessions_to_aggregate {
                 // Aggregate session into virtual JSONL
-                match Self::aggregate_session(&parser, &session_id, &project_id) {
+                let aggregated = {
+                    let parser = Arc::clone(&parser);
+                    let session_id = session_id.clone();
+                    run_blocking(move || Self::aggregate_session(&parser, &session_id, &project_id))
+                        .await
+                };
+                match aggregated {
                     Ok((jsonl_path, project_name)) => {
                         // Get file size
                         let file_size = jsonl_path.metadata().map(|m| m.len()).unwrap_or(0);
@@ -339,6 +406,7 @@ impl OpenCodeWatcher {
                                 state.needs_aggregation = false;
                                 state.last_aggregated = Some(now);
                             }
+                            lifecycle.record_activity(&session_id, &jsonl_path);
                         }
                     }
                     Err(e) => {
@@ -351,6 +419,10 @@ impl OpenCodeWatcher {
                     }
                 }
             }
+
+            if stopping {
+                break;
+            }
         }
 
         if let Err(e) = log_info(PROVIDER_ID, "🛑 OpenCode file monitoring stopped") {
@@ -523,15 +595,25 @@ impl OpenCodeWatcher {
         if let Ok(mut running) = self.is_running.lock() {
             *running = false;
         }
+        self.task.stop();
 
         if let Err(e) = log_info(PROVIDER_ID, "🛑 Stopping OpenCode file monitoring") {
             eprintln!("Logging error: {}", e);
         }
     }
 
+    pub fn task(&self) -> &WatchTask {
+        &self.task
+    }
+
+    pub fn heartbeat(&self) -> &WatcherHeartbeat {
+        &self.heartbeat
+    }
+
     pub fn get_status(&self) -> WatcherStatus {
+        // A panicked task never clears the flag, so ask the heartbeat too
         let is_running = if let Ok(running) = self.is_running.lock() {
-            *running
+            *running && self.heartbeat.is_alive()
         } else {
             false
         };
@@ -543,6 +625,8 @@ impl OpenCodeWatcher {
             pending_uploads: upload_status.pending,
             processing_uploads: upload_status.processing,
             failed_uploads: upload_status.failed,
+            watched_paths: Vec::new(),
+            unsupported_formats: format_version::unsupported_formats(PROVIDER_ID),
         }
     }
 }
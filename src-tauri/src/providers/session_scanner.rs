@@ -34,6 +34,13 @@ pub fn scan_all_sessions_filtered(
         "codex" => super::codex::scanner::scan_sessions_filtered(base_path, selected_projects),
         "gemini-code" => super::gemini::scanner::scan_sessions_filtered(base_path, selected_projects),
         "cursor" => super::cursor::scanner::scan_sessions_filtered(selected_projects),
+        other if super::custom_jsonl::is_custom_provider(other) => {
+            super::custom_jsonl::scanner::scan_sessions_filtered(
+                other,
+                base_path,
+                selected_projects,
+            )
+        }
         _ => Err(format!("Unsupported provider: {}", provider_id)),
     }
 }
@@ -0,0 +1,590 @@
+//! Local retention, archival and compaction of session data
+//!
+//! Canonical sessions under `~/.guideai/sessions` otherwise accumulate forever.
+//! A [`RetentionPolicy`] (stored in `~/.guideai/retention.json`) holds rules per
+//! provider and/or project:
+//!
+//! * sessions older than `keep_days` are deleted (file and database rows),
+//!   unless they are rated or not yet synced and the rule protects those
+//! * sessions older than `compress_after_days` have their JSONL replaced by a
+//!   `.jsonl.gz` archive, which `read_session_file` reads transparently
+//!
+//! Orphans are reported too: canonical files without a database row and rows
+//! whose file is gone, once they are at least a day old (a watcher may have
+//! written the file but not the row yet). Every run can be a dry run that only
+//! reports. Deleted sessions are recorded so a rescan doesn't re-import them.
+
+use crate::database::RetentionSession;
+use crate::logging::{log_info, log_warn};
+use crate::providers::common::{has_extension, resolve_session_file};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const POLICY_FILE: &str = "retention.json";
+const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// Sessions active within this window are never touched
+const MIN_IDLE_MS: i64 = MS_PER_DAY;
+
+fn default_true() -> bool {
+    true
+}
+
+/// Retention rule for a provider and/or project (both empty = every session)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionRule {
+    /// Provider id the rule applies to
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Project name or project id the rule applies to
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Delete sessions older than this many days (None keeps them forever)
+    #[serde(default)]
+    pub keep_days: Option<u32>,
+    /// Never delete sessions that have a rating
+    #[serde(default = "default_true")]
+    pub keep_rated: bool,
+    /// Never delete sessions that have not been synced to the server
+    #[serde(default = "default_true")]
+    pub keep_unsynced: bool,
+    /// Compress session files older than this many days (None never compresses)
+    #[serde(default)]
+    pub compress_after_days: Option<u32>,
+}
+
+impl RetentionRule {
+    fn matches(&self, session: &RetentionSession) -> bool {
+        let provider_ok = self
+            .provider
+            .as_deref()
+            .is_none_or(|provider| provider == session.provider);
+        let project_ok = self.project.as_deref().is_none_or(|project| {
+            project == session.project_name || Some(project) == session.project_id.as_deref()
+        });
+        provider_ok && project_ok
+    }
+
+    /// Project rules beat provider rules, which beat catch-all rules
+    fn specificity(&self) -> u8 {
+        u8::from(self.provider.is_some()) + 2 * u8::from(self.project.is_some())
+    }
+}
+
+/// All retention rules; with no rules nothing is deleted or compressed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    #[serde(default)]
+    pub rules: Vec<RetentionRule>,
+}
+
+impl RetentionPolicy {
+    /// The most specific rule matching a session (first one wins on ties)
+    fn rule_for(&self, session: &RetentionSession) -> Option<&RetentionRule> {
+        self.rules.iter().filter(|rule| rule.matches(session)).fold(
+            None,
+            |best: Option<&RetentionRule>, rule| match best {
+                Some(best) if best.specificity() >= rule.specificity() => Some(best),
+                _ => Some(rule),
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionAction {
+    Delete,
+    Compress,
+}
+
+/// A session the policy acts on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionItem {
+    pub session_id: String,
+    pub provider: String,
+    pub project_name: String,
+    pub file_path: String,
+    pub age_days: i64,
+    pub action: RetentionAction,
+    /// Bytes on disk before the action
+    pub bytes: u64,
+}
+
+/// A canonical file no session row points at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanFile {
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub deleted: Vec<RetentionItem>,
+    pub compressed: Vec<RetentionItem>,
+    pub orphan_files: Vec<OrphanFile>,
+    /// Session ids whose file no longer exists
+    pub orphan_rows: Vec<String>,
+    pub orphans_removed: bool,
+    /// Bytes freed (estimated for dry runs)
+    pub bytes_reclaimed: u64,
+    pub errors: Vec<String>,
+}
+
+fn policy_path() -> Result<PathBuf, String> {
+    crate::config::get_config_dir()
+        .map(|dir| dir.join(POLICY_FILE))
+        .map_err(|e| format!("Failed to locate config directory: {}", e))
+}
+
+/// Load the retention policy (an empty policy if none has been saved)
+pub fn load_retention_policy() -> Result<RetentionPolicy, String> {
+    let path = policy_path()?;
+    if !path.exists() {
+        return Ok(RetentionPolicy::default());
+    }
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read retention policy: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid retention policy: {}", e))
+}
+
+pub fn save_retention_policy(policy: &RetentionPolicy) -> Result<(), String> {
+    crate::config::ensure_config_dir()
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    let content = serde_json::to_string_pretty(policy)
+        .map_err(|e| format!("Failed to serialize retention policy: {}", e))?;
+    fs::write(policy_path()?, content)
+        .map_err(|e| format!("Failed to save retention policy: {}", e))
+}
+
+fn bytes_on_disk(file_path: &str) -> u64 {
+    fs::metadata(resolve_session_file(Path::new(file_path)))
+        .map(|m| m.len())
+        .unwrap_or(0)
+}
+
+/// Decide which sessions to delete or compress
+///
+/// Pure apart from reading file sizes, so it backs both dry runs and real runs.
+pub fn plan_retention(
+    policy: &RetentionPolicy,
+    sessions: &[RetentionSession],
+    now_ms: i64,
+) -> Vec<RetentionItem> {
+    let mut items = Vec::new();
+
+    for session in sessions {
+        let idle_ms = now_ms - session.last_activity;
+        if idle_ms < MIN_IDLE_MS {
+            continue;
+        }
+        let Some(rule) = policy.rule_for(session) else {
+            continue;
+        };
+        let age_days = idle_ms / MS_PER_DAY;
+
+        let protected =
+            (rule.keep_rated && session.rated) || (rule.keep_unsynced && !session.synced);
+        let expired = rule
+            .keep_days
+            .is_some_and(|days| age_days >= i64::from(days));
+
+        let action = if expired && !protected {
+            RetentionAction::Delete
+        } else if rule
+            .compress_after_days
+            .is_some_and(|days| age_days >= i64::from(days))
+            && !has_extension(Path::new(&session.file_path), "gz")
+        {
+            RetentionAction::Compress
+        } else {
+            continue;
+        };
+
+        items.push(RetentionItem {
+            session_id: session.session_id.clone(),
+            provider: session.provider.clone(),
+            project_name: session.project_name.clone(),
+            file_path: session.file_path.clone(),
+            age_days,
+            action,
+            bytes: bytes_on_disk(&session.file_path),
+        });
+    }
+
+    items
+}
+
+/// Find canonical files without a session row, and rows without a file
+///
+/// Anything touched within [`MIN_IDLE_MS`] of `now_ms` is left alone.
+pub fn find_orphans(
+    sessions_root: &Path,
+    sessions: &[RetentionSession],
+    now_ms: i64,
+) -> (Vec<OrphanFile>, Vec<String>) {
+    let known: HashSet<PathBuf> = sessions
+        .iter()
+        .flat_map(|s| {
+            let path = PathBuf::from(&s.file_path);
+            let mut archived = path.as_os_str().to_owned();
+            archived.push(".gz");
+            [path, PathBuf::from(archived)]
+        })
+        .collect();
+
+    let mut orphan_files: Vec<OrphanFile> = WalkDir::new(sessions_root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            name.ends_with(".jsonl") || name.ends_with(".jsonl.gz")
+        })
+        .filter(|entry| !known.contains(entry.path()))
+        .filter(|entry| {
+            entry
+                .metadata()
+                .ok()
+                .and_then(|m| m.modified().ok())
+                .map(|modified| chrono::DateTime::<chrono::Utc>::from(modified).timestamp_millis())
+                .is_some_and(|modified| now_ms - modified >= MIN_IDLE_MS)
+        })
+        .map(|entry| OrphanFile {
+            path: entry.path().to_string_lossy().to_string(),
+            bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+        })
+        .collect();
+    orphan_files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let orphan_rows = sessions
+        .iter()
+        .filter(|s| now_ms - s.last_activity >= MIN_IDLE_MS)
+        .filter(|s| !resolve_session_file(Path::new(&s.file_path)).exists())
+        .map(|s| s.session_id.clone())
+        .collect();
+
+    (orphan_files, orphan_rows)
+}
+
+/// Replace a JSONL file with a `.jsonl.gz` archive and return the archive path
+///
+/// The archive is written to a temporary file, verified and renamed before the
+/// original is removed, so a crash never leaves the session unreadable.
+pub fn compress_session_file(path: &Path) -> Result<PathBuf, String> {
+    let original =
+        fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut archive_path = path.as_os_str().to_owned();
+    archive_path.push(".gz");
+    let archive_path = PathBuf::from(archive_path);
+    let mut tmp_path = archive_path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(&original)
+        .map_err(|e| format!("Failed to compress {}: {}", path.display(), e))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress {}: {}", path.display(), e))?;
+    fs::write(&tmp_path, &compressed)
+        .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+
+    let mut roundtrip = Vec::new();
+    let verified = fs::read(&tmp_path)
+        .and_then(|written| GzDecoder::new(written.as_slice()).read_to_end(&mut roundtrip))
+        .is_ok_and(|_| roundtrip == original);
+    if !verified {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(format!(
+            "Archive verification failed for {}",
+            path.display()
+        ));
+    }
+
+    fs::rename(&tmp_path, &archive_path)
+        .map_err(|e| format!("Failed to move archive into place: {}", e))?;
+    fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+
+    Ok(archive_path)
+}
+
+fn remove_session_file(file_path: &str) -> Result<(), String> {
+    let path = resolve_session_file(Path::new(file_path));
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove {}: {}", path.display(), e)),
+    }
+}
+
+/// Run the retention policy
+///
+/// With `dry_run` nothing is changed and the report lists what would happen.
+/// Orphans are only removed when `remove_orphans` is set.
+pub fn run_retention(dry_run: bool, remove_orphans: bool) -> Result<RetentionReport, String> {
+    let policy = load_retention_policy()?;
+    let sessions = crate::database::get_retention_sessions()
+        .map_err(|e| format!("Failed to load sessions: {}", e))?;
+    let sessions_root = crate::config::get_config_dir()
+        .map_err(|e| format!("Failed to locate config directory: {}", e))?
+        .join("sessions");
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let items = plan_retention(&policy, &sessions, now_ms);
+    let (orphan_files, orphan_rows) = find_orphans(&sessions_root, &sessions, now_ms);
+
+    let mut report = RetentionReport {
+        dry_run,
+        orphans_removed: remove_orphans && !dry_run,
+        ..Default::default()
+    };
+
+    for item in items {
+        match item.action {
+            RetentionAction::Delete => {
+                report.bytes_reclaimed += item.bytes;
+                report.deleted.push(item);
+            }
+            RetentionAction::Compress => report.compressed.push(item),
+        }
+    }
+
+    if remove_orphans {
+        report.bytes_reclaimed += orphan_files.iter().map(|f| f.bytes).sum::<u64>();
+    }
+    report.orphan_files = orphan_files;
+    report.orphan_rows = orphan_rows;
+
+    if dry_run {
+        // Typical JSONL compresses to roughly a tenth of its size
+        report.bytes_reclaimed += report
+            .compressed
+            .iter()
+            .map(|i| i.bytes * 9 / 10)
+            .sum::<u64>();
+        return Ok(report);
+    }
+
+    // Delete expired sessions: rows first so a failure never leaves rows without files
+    let expired: Vec<String> = report
+        .deleted
+        .iter()
+        .map(|i| i.session_id.clone())
+        .collect();
+    crate::database::delete_sessions(&expired)
+        .map_err(|e| format!("Failed to delete expired sessions: {}", e))?;
+    let tombstones: Vec<(String, String)> = report
+        .deleted
+        .iter()
+        .map(|i| (i.session_id.clone(), i.provider.clone()))
+        .collect();
+    if let Err(e) = crate::database::record_deleted_sessions(&tombstones) {
+        report
+            .errors
+            .push(format!("Failed to record deleted sessions: {}", e));
+    }
+    for item in &report.deleted {
+        if let Err(e) = remove_session_file(&item.file_path) {
+            report.errors.push(e);
+        }
+    }
+
+    // Compress old sessions
+    let mut compressed = Vec::new();
+    for item in std::mem::take(&mut report.compressed) {
+        let result = compress_session_file(Path::new(&item.file_path)).and_then(|archive| {
+            crate::database::update_session_file_path(&item.session_id, &archive.to_string_lossy())
+                .map_err(|e| format!("Failed to update path for {}: {}", item.session_id, e))?;
+            Ok(fs::metadata(&archive).map(|m| m.len()).unwrap_or(0))
+        });
+
+        match result {
+            Ok(archive_bytes) => {
+                report.bytes_reclaimed += item.bytes.saturating_sub(archive_bytes);
+                compressed.push(item);
+            }
+            Err(e) => report.errors.push(e),
+        }
+    }
+    report.compressed = compressed;
+
+    if remove_orphans {
+        for orphan in &report.orphan_files {
+            if let Err(e) = fs::remove_file(&orphan.path) {
+                report
+                    .errors
+                    .push(format!("Failed to remove {}: {}", orphan.path, e));
+            }
+        }
+        crate::database::delete_sessions(&report.orphan_rows)
+            .map_err(|e| format!("Failed to delete orphaned rows: {}", e))?;
+    }
+
+    let _ = log_info(
+        "retention",
+        &format!(
+            "🧹 Retention: deleted {} sessions, compressed {}, {} orphan files, {} orphan rows, reclaimed {} bytes",
+            report.deleted.len(),
+            report.compressed.len(),
+            report.orphan_files.len(),
+            report.orphan_rows.len(),
+            report.bytes_reclaimed
+        ),
+    );
+    for error in &report.errors {
+        let _ = log_warn("retention", &format!("⚠ {}", error));
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_800_000_000_000;
+
+    fn session(
+        id: &str,
+        provider: &str,
+        age_days: i64,
+        synced: bool,
+        rated: bool,
+    ) -> RetentionSession {
+        RetentionSession {
+            session_id: id.to_string(),
+            provider: provider.to_string(),
+            project_name: "app".to_string(),
+            project_id: Some("project-1".to_string()),
+            file_path: format!("/nonexistent/{}.jsonl", id),
+            file_size: 100,
+            last_activity: NOW - age_days * MS_PER_DAY,
+            synced,
+            rated,
+        }
+    }
+
+    fn rule(
+        provider: Option<&str>,
+        project: Option<&str>,
+        keep_days: Option<u32>,
+    ) -> RetentionRule {
+        RetentionRule {
+            provider: provider.map(String::from),
+            project: project.map(String::from),
+            keep_days,
+            keep_rated: true,
+            keep_unsynced: true,
+            compress_after_days: Some(7),
+        }
+    }
+
+    #[test]
+    fn test_plan_retention_rules_and_protections() {
+        let policy = RetentionPolicy {
+            rules: vec![
+                rule(None, None, Some(30)),
+                rule(Some("codex"), None, Some(10)),
+                rule(None, Some("project-1"), None),
+            ],
+        };
+        // The project rule (keep forever) is the most specific for these sessions
+        let plan = plan_retention(&policy, &[session("a", "codex", 40, true, false)], NOW);
+        assert_eq!(plan[0].action, RetentionAction::Compress);
+
+        let policy = RetentionPolicy {
+            rules: vec![
+                rule(None, None, Some(30)),
+                rule(Some("codex"), None, Some(10)),
+            ],
+        };
+        let sessions = vec![
+            session("old", "claude-code", 40, true, false),
+            session("young", "claude-code", 8, true, false),
+            session("fresh", "claude-code", 0, true, false),
+            session("rated", "claude-code", 40, true, true),
+            session("unsynced", "claude-code", 40, false, false),
+            session("codex", "codex", 12, true, false),
+        ];
+        let plan = plan_retention(&policy, &sessions, NOW);
+        let action = |id: &str| plan.iter().find(|i| i.session_id == id).map(|i| i.action);
+
+        assert_eq!(action("old"), Some(RetentionAction::Delete));
+        assert_eq!(action("young"), Some(RetentionAction::Compress));
+        assert_eq!(action("fresh"), None);
+        assert_eq!(action("rated"), Some(RetentionAction::Compress));
+        assert_eq!(action("unsynced"), Some(RetentionAction::Compress));
+        assert_eq!(action("codex"), Some(RetentionAction::Delete));
+
+        assert!(plan_retention(&RetentionPolicy::default(), &sessions, NOW).is_empty());
+    }
+
+    #[test]
+    fn test_compress_and_find_orphans() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("claude-code").join("app");
+        fs::create_dir_all(&project_dir).unwrap();
+
+        let tracked = project_dir.join("tracked.jsonl");
+        fs::write(&tracked, "{\"type\":\"user\"}\n".repeat(50)).unwrap();
+        let stray = project_dir.join("stray.jsonl");
+        fs::write(&stray, "{}\n").unwrap();
+
+        let archive = compress_session_file(&tracked).unwrap();
+        assert!(!tracked.exists());
+        assert_eq!(
+            crate::providers::common::read_session_file(&tracked).unwrap(),
+            "{\"type\":\"user\"}\n".repeat(50)
+        );
+
+        let mut tracked_session = session("tracked", "claude-code", 30, true, false);
+        tracked_session.file_path = tracked.to_string_lossy().to_string();
+        let mut missing_session = session("missing", "claude-code", 30, true, false);
+        missing_session.file_path = project_dir
+            .join("missing.jsonl")
+            .to_string_lossy()
+            .to_string();
+
+        let mut fresh_session = session("fresh", "claude-code", 0, true, false);
+        fresh_session.file_path = project_dir
+            .join("fresh.jsonl")
+            .to_string_lossy()
+            .to_string();
+        let sessions = [tracked_session, missing_session, fresh_session];
+
+        let written = chrono::DateTime::<chrono::Utc>::from(
+            fs::metadata(&stray).unwrap().modified().unwrap(),
+        )
+        .timestamp_millis();
+        let (recent_files, _) = find_orphans(dir.path(), &sessions, written);
+        assert!(recent_files.is_empty());
+
+        let (orphan_files, orphan_rows) = find_orphans(dir.path(), &sessions, NOW);
+        assert_eq!(
+            orphan_files
+                .iter()
+                .map(|f| f.path.as_str())
+                .collect::<Vec<_>>(),
+            vec![stray.to_string_lossy()]
+        );
+        assert_eq!(orphan_rows, vec!["missing".to_string()]);
+        assert!(archive.exists());
+    }
+}
@@ -0,0 +1,171 @@
+//! Database schema migrations
+//!
+//! tauri-plugin-sql applies these at startup; the backup code checks snapshot
+//! schema versions against the same list before restoring.
+
+/// One forward-only schema migration
+#[derive(Debug, Clone, Copy)]
+pub struct SchemaMigration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// All migrations, in version order
+pub const MIGRATIONS: &[SchemaMigration] = &[
+    SchemaMigration {
+        version: 1,
+        description: "create_agent_sessions",
+        sql: include_str!("../migrations/001_create_agent_sessions.sql"),
+    },
+    SchemaMigration {
+        version: 2,
+        description: "create_session_metrics",
+        sql: include_str!("../migrations/002_create_session_metrics.sql"),
+    },
+    SchemaMigration {
+        version: 3,
+        description: "add_cwd_column",
+        sql: include_str!("../migrations/003_add_cwd_column.sql"),
+    },
+    SchemaMigration {
+        version: 4,
+        description: "add_sync_failed_reason",
+        sql: include_str!("../migrations/004_add_sync_failed_reason.sql"),
+    },
+    SchemaMigration {
+        version: 5,
+        description: "unique_session_id",
+        sql: include_str!("../migrations/005_unique_session_id.sql"),
+    },
+    SchemaMigration {
+        version: 6,
+        description: "unique_session_metrics",
+        sql: include_str!("../migrations/006_unique_session_id.sql"),
+    },
+    SchemaMigration {
+        version: 7,
+        description: "create_projects",
+        sql: include_str!("../migrations/007_create_projects.sql"),
+    },
+    SchemaMigration {
+        version: 8,
+        description: "add_project_foreign_key",
+        sql: include_str!("../migrations/008_add_project_foreign_key.sql"),
+    },
+    SchemaMigration {
+        version: 9,
+        description: "create_session_assessments",
+        sql: include_str!("../migrations/009_create_session_assessments.sql"),
+    },
+    SchemaMigration {
+        version: 10,
+        description: "add_phase_analysis",
+        sql: include_str!("../migrations/010_add_phase_analysis.sql"),
+    },
+    SchemaMigration {
+        version: 11,
+        description: "add_core_metrics_tracking",
+        sql: include_str!("../migrations/011_add_core_metrics_tracking.sql"),
+    },
+    SchemaMigration {
+        version: 12,
+        description: "add_category_improvement_tips",
+        sql: include_str!("../migrations/012_add_category_improvement_tips.sql"),
+    },
+    SchemaMigration {
+        version: 13,
+        description: "add_file_hash",
+        sql: include_str!("../migrations/013_add_file_hash.sql"),
+    },
+    SchemaMigration {
+        version: 14,
+        description: "add_git_tracking",
+        sql: include_str!("../migrations/014_add_git_tracking.sql"),
+    },
+    SchemaMigration {
+        version: 15,
+        description: "add_git_diff_metrics",
+        sql: include_str!("../migrations/015_add_git_diff_metrics.sql"),
+    },
+    SchemaMigration {
+        version: 16,
+        description: "add_git_diff_improvement_tips",
+        sql: include_str!("../migrations/016_add_git_diff_improvement_tips.sql"),
+    },
+    SchemaMigration {
+        version: 17,
+        description: "add_context_management_metrics",
+        sql: include_str!("../migrations/017_add_context_management_metrics.sql"),
+    },
+    SchemaMigration {
+        version: 18,
+        description: "update_context_metrics_structure",
+        sql: include_str!("../migrations/018_update_context_metrics_structure.sql"),
+    },
+    SchemaMigration {
+        version: 19,
+        description: "remove_per_message_tokens",
+        sql: include_str!("../migrations/019_remove_per_message_tokens.sql"),
+    },
+    SchemaMigration {
+        version: 20,
+        description: "remove_peak_context_tokens",
+        sql: include_str!("../migrations/020_remove_peak_context_tokens.sql"),
+    },
+    SchemaMigration {
+        version: 21,
+        description: "create_session_relations",
+        sql: include_str!("../migrations/021_create_session_relations.sql"),
+    },
+    SchemaMigration {
+        version: 22,
+        description: "add_git_snapshots",
+        sql: include_str!("../migrations/022_add_git_snapshots.sql"),
+    },
+    SchemaMigration {
+        version: 23,
+        description: "add_project_identity",
+        sql: include_str!("../migrations/023_add_project_identity.sql"),
+    },
+    SchemaMigration {
+        version: 24,
+        description: "add_workspace_packages",
+        sql: include_str!("../migrations/024_add_workspace_packages.sql"),
+    },
+    SchemaMigration {
+        version: 25,
+        description: "add_session_model",
+        sql: include_str!("../migrations/025_add_session_model.sql"),
+    },
+    SchemaMigration {
+        version: 26,
+        description: "add_session_lifecycle",
+        sql: include_str!("../migrations/026_add_session_lifecycle.sql"),
+    },
+    SchemaMigration {
+        version: 27,
+        description: "create_session_conversion_stats",
+        sql: include_str!("../migrations/027_create_session_conversion_stats.sql"),
+    },
+    SchemaMigration {
+        version: 28,
+        description: "add_analytics_state",
+        sql: include_str!("../migrations/028_add_analytics_state.sql"),
+    },
+    SchemaMigration {
+        version: 29,
+        description: "create_deleted_sessions",
+        sql: include_str!("../migrations/029_create_deleted_sessions.sql"),
+    },
+];
+
+/// Schema version after all migrations have run
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Whether a version is one of the known migrations
+pub fn is_known_version(version: i64) -> bool {
+    MIGRATIONS.iter().any(|m| m.version == version)
+}
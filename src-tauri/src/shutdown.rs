@@ -1,6 +1,6 @@
 use tokio::sync::broadcast;
 
-/// Coordinates graceful shutdown across event handlers and watchers
+/// Coordinates graceful shutdown across event handlers
 ///
 /// Usage:
 /// ```no_run
@@ -10,7 +10,7 @@ use tokio::sync::broadcast;
 /// # async fn example() {
 /// let coordinator = ShutdownCoordinator::new();
 ///
-/// // In event handlers/watchers:
+/// // In event handlers:
 /// let mut shutdown_rx = coordinator.subscribe();
 /// let (tx, mut event_rx) = mpsc::channel::<String>(10);
 /// loop {
@@ -46,10 +46,6 @@ impl ShutdownCoordinator {
 
     /// Trigger graceful shutdown
     /// All subscribers will receive a shutdown signal
-    ///
-    /// Note: Currently not exposed as a command, but available for future use
-    /// (e.g., graceful app shutdown, restart functionality).
-    #[allow(dead_code)]
     pub fn shutdown(&self) {
         let _ = self.shutdown_tx.send(());
     }
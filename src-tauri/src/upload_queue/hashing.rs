@@ -3,17 +3,14 @@
 //! Provides hash functions for both file and in-memory content.
 
 use sha2::{Digest, Sha256};
-use std::fs::File;
-use std::io::Read;
 use std::path::PathBuf;
 
 /// Calculate SHA256 hash of file content (for v2 upload deduplication)
+///
+/// Compacted `.jsonl.gz` sessions hash as their decompressed content so the
+/// server-side dedup still matches.
 pub fn calculate_file_hash_sha256(file_path: &PathBuf) -> Result<String, String> {
-    let mut file =
-        File::open(file_path).map_err(|e| format!("Failed to open file for hashing: {}", e))?;
-
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)
+    let buffer = crate::providers::common::read_session_bytes(file_path)
         .map_err(|e| format!("Failed to read file for hashing: {}", e))?;
 
     // Calculate SHA256 hash
@@ -79,6 +79,27 @@ impl UploadQueue {
         }
     }
 
+    /// Keep server credentials in step with edits to config.json
+    pub fn spawn_config_listener(self: &Arc<Self>) {
+        use crate::config_service::ConfigChange;
+        use tokio::sync::broadcast::error::RecvError;
+
+        let queue = Arc::clone(self);
+        let mut changes = crate::config_service::subscribe();
+        std::thread::spawn(move || loop {
+            match changes.blocking_recv() {
+                Ok(ConfigChange::Global { config }) => queue.set_config(config),
+                Ok(_) => {}
+                Err(RecvError::Lagged(_)) => {
+                    if let Ok(config) = crate::config::load_config() {
+                        queue.set_config(config);
+                    }
+                }
+                Err(RecvError::Closed) => break,
+            }
+        });
+    }
+
     pub fn set_app_handle(&self, app_handle: tauri::AppHandle) {
         if let Ok(mut handle_guard) = self.app_handle.lock() {
             *handle_guard = Some(app_handle);
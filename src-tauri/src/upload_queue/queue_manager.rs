@@ -9,6 +9,7 @@ use crate::database::{
 };
 use crate::logging::{log_info, log_warn};
 use crate::project_metadata::extract_project_metadata;
+use crate::providers::common::read_session_file;
 use crate::providers::SessionInfo;
 use crate::validation::{validate_session_file, MAX_SESSION_FILE_SIZE};
 use chrono::Utc;
@@ -42,8 +43,8 @@ pub fn add_item(
         .to_string();
 
     // Read and validate file content
-    let file_content = std::fs::read_to_string(&validated_path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let file_content =
+        read_session_file(&validated_path).map_err(|e| format!("Failed to read file: {}", e))?;
 
     let (is_valid, validation_error) = validate_jsonl_timestamps(&file_content);
     if !is_valid {
@@ -149,7 +150,7 @@ pub fn add_historical_session(
                 validate_session_file(&session.file_path).map_err(|e| e.to_string())?;
 
             // Read and validate content
-            let file_content = std::fs::read_to_string(&validated_path)
+            let file_content = read_session_file(&validated_path)
                 .map_err(|e| format!("Failed to read file: {}", e))?;
 
             let (is_valid, validation_error) = validate_jsonl_timestamps(&file_content);
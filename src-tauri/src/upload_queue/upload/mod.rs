@@ -20,6 +20,8 @@ pub use project::upload_project_metadata_static;
 pub use retry::{calculate_backoff, classify_error, schedule_retry, should_retry, ErrorType};
 
 use crate::config::GuideAIConfig;
+use crate::logging::log_warn;
+use crate::providers::canonical::validator::validate_canonical_content;
 use crate::upload_queue::hashing::{calculate_content_hash_sha256, calculate_file_hash_sha256};
 use crate::upload_queue::types::UploadItem;
 
@@ -30,10 +32,9 @@ pub async fn process_upload_item(
 ) -> Result<(), String> {
     let config = config.ok_or("No configuration available")?;
 
-    // Check provider sync mode before uploading
-    use crate::config::load_provider_config;
-    let provider_config = load_provider_config(&item.provider)
-        .map_err(|e| format!("Failed to load provider config: {}", e))?;
+    // Check provider sync mode before uploading (last validated config, so
+    // edits apply to the next item without restarting anything)
+    let provider_config = crate::config_service::provider_config(&item.provider)?;
 
     // Route to appropriate upload function based on sync mode
     match provider_config.sync_mode.as_str() {
@@ -48,6 +49,9 @@ pub async fn process_upload_item(
             let session_id = item.session_id.as_ref()
                 .ok_or("Session ID required for upload")?;
 
+            // Reject malformed canonical transcripts before they reach the server
+            validate_before_upload(item)?;
+
             // Calculate file hash if not already present
             let file_hash = if let Some(ref hash) = item.file_hash {
                 hash.clone()
@@ -71,3 +75,43 @@ pub async fn process_upload_item(
         }
     }
 }
+
+/// Validate the canonical JSONL of an upload item
+///
+/// Errors are returned as "Canonical validation failed: ..." so `classify_error`
+/// treats them as client errors and the session is not retried.
+fn validate_before_upload(item: &UploadItem) -> Result<(), String> {
+    let file_content;
+    let content = match item.content {
+        Some(ref content) => content.as_str(),
+        None => {
+            file_content = crate::providers::common::read_session_file(&item.file_path)
+                .map_err(|e| format!("Failed to read file: {}", e))?;
+            file_content.as_str()
+        }
+    };
+
+    let report = validate_canonical_content(content);
+
+    if !report.warnings.is_empty() {
+        if let Err(e) = log_warn(
+            &item.provider,
+            &format!(
+                "⚠️ Session {} has {} canonical validation warning(s)",
+                item.session_id.as_deref().unwrap_or("unknown"),
+                report.warnings.len()
+            ),
+        ) {
+            eprintln!("Logging error: {}", e);
+        }
+    }
+
+    if report.is_valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Canonical validation failed: {}",
+            report.error_summary()
+        ))
+    }
+}
@@ -79,7 +79,8 @@ pub async fn upload_v2(
         let file_content = if let Some(ref content) = item.content {
             content.as_bytes().to_vec()
         } else {
-            std::fs::read(&item.file_path).map_err(|e| format!("Failed to read file: {}", e))?
+            crate::providers::common::read_session_bytes(&item.file_path)
+                .map_err(|e| format!("Failed to read file: {}", e))?
         };
 
         // Compress the file content
@@ -0,0 +1,340 @@
+//! Watcher supervisor
+//!
+//! Checks the heartbeat of every running provider watcher on a fixed tick.
+//! Watchers that exited, panicked, stalled, lost their directory or hit the
+//! OS watch limit are rebuilt from their saved config, with exponential
+//! backoff between attempts. Failures and restarts are kept as a bounded
+//! history for `get_watcher_health`.
+
+use crate::commands::{create_watcher, AppState, Watcher};
+use crate::config::load_provider_config;
+use crate::events::EventBus;
+use crate::logging::{log_info, log_warn};
+use crate::providers::common::health::{
+    HealthEvent, HealthEventKind, HealthHistory, HeartbeatSnapshot, RestartBackoff, WatchFailure,
+    WatcherHeartbeat,
+};
+use crate::providers::common::{HEALTH_HISTORY_LIMIT, RESTART_STABLE_AFTER, SUPERVISOR_TICK};
+use crate::providers::custom_jsonl::{is_custom_provider, load_custom_config};
+use crate::upload_queue::UploadQueue;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Current health of one supervised watcher
+#[derive(Debug, Clone, Serialize)]
+pub struct WatcherHealth {
+    pub provider_id: String,
+    pub healthy: bool,
+    pub problem: Option<HealthEventKind>,
+    pub polling: bool,
+    pub seconds_since_heartbeat: u64,
+    pub last_failure: Option<WatchFailure>,
+    /// Restart attempts since the watcher was last stable
+    pub restart_attempts: u32,
+    pub restarts: u32,
+    pub next_restart_in_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WatcherHealthReport {
+    pub watchers: Vec<WatcherHealth>,
+    /// Newest first
+    pub history: Vec<HealthEvent>,
+}
+
+#[derive(Debug)]
+struct ProviderHealth {
+    snapshot: HeartbeatSnapshot,
+    problem: Option<HealthEventKind>,
+    backoff: RestartBackoff,
+    healthy_since: Instant,
+    restarts: u32,
+}
+
+/// Watcher picked for a restart, with the heartbeat that identifies it
+struct Restart {
+    provider_id: String,
+    heartbeat: WatcherHeartbeat,
+    attempt: u32,
+    delay: Duration,
+}
+
+#[derive(Debug)]
+pub struct WatcherSupervisor {
+    providers: Mutex<BTreeMap<String, ProviderHealth>>,
+    history: Mutex<HealthHistory>,
+}
+
+impl Default for WatcherSupervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WatcherSupervisor {
+    pub fn new() -> Self {
+        Self {
+            providers: Mutex::new(BTreeMap::new()),
+            history: Mutex::new(HealthHistory::new(HEALTH_HISTORY_LIMIT)),
+        }
+    }
+
+    /// Spawn the supervision thread for the watchers in `app_state`
+    pub fn start(self: &Arc<Self>, app_state: &AppState) {
+        let supervisor = Arc::clone(self);
+        let watchers = Arc::clone(&app_state.watchers);
+        let upload_queue = Arc::clone(&app_state.upload_queue);
+        let event_bus = app_state.event_bus.clone();
+        let shutdown = app_state.watcher_shutdown.clone();
+
+        thread::spawn(move || loop {
+            thread::sleep(SUPERVISOR_TICK);
+            // Watchers stopping for shutdown must not be restarted
+            if shutdown.is_cancelled() {
+                break;
+            }
+            supervisor.tick(&watchers, &upload_queue, &event_bus, &shutdown);
+        });
+    }
+
+    fn tick(
+        &self,
+        watchers: &Mutex<HashMap<String, Watcher>>,
+        upload_queue: &Arc<UploadQueue>,
+        event_bus: &EventBus,
+        shutdown: &CancellationToken,
+    ) {
+        // Only watchers still registered are supervised; stop commands remove theirs
+        let watched: Vec<(String, WatcherHeartbeat)> = match watchers.lock() {
+            Ok(watchers) => watchers
+                .iter()
+                .map(|(provider_id, watcher)| (provider_id.clone(), watcher.heartbeat().clone()))
+                .collect(),
+            Err(_) => return,
+        };
+
+        // Building a watcher scans directories, so the locks are not held across it
+        for restart in self.check(&watched) {
+            let result = create_watcher(&restart.provider_id, upload_queue, event_bus, shutdown);
+            let outcome = match result {
+                // Shutdown began while the watcher was being built
+                Ok(watcher) if shutdown.is_cancelled() => {
+                    watcher.stop();
+                    continue;
+                }
+                Ok(watcher) => Ok(replace_watcher(watchers, &restart, watcher)),
+                Err(e) => Err(e),
+            };
+            self.record_restart(&restart, outcome);
+        }
+    }
+
+    /// Update health from the heartbeats and pick the watchers due for a restart
+    fn check(&self, watched: &[(String, WatcherHeartbeat)]) -> Vec<Restart> {
+        let (Ok(mut providers), Ok(mut history)) = (self.providers.lock(), self.history.lock())
+        else {
+            return Vec::new();
+        };
+        providers.retain(|provider_id, _| watched.iter().any(|(id, _)| id == provider_id));
+
+        let mut restarts = Vec::new();
+        for (provider_id, heartbeat) in watched {
+            let now = Instant::now();
+            let snapshot = heartbeat.snapshot();
+            let problem = snapshot.problem(provider_id);
+            let health = providers
+                .entry(provider_id.clone())
+                .or_insert_with(|| ProviderHealth {
+                    snapshot: snapshot.clone(),
+                    problem: None,
+                    backoff: RestartBackoff::default(),
+                    healthy_since: now,
+                    restarts: 0,
+                });
+            health.snapshot = snapshot;
+
+            let Some(problem) = problem else {
+                if health.problem.take().is_some() {
+                    history.record(provider_id, HealthEventKind::Recovered, "");
+                }
+                if health.backoff.attempts() > 0
+                    && health.healthy_since.elapsed() >= RESTART_STABLE_AFTER
+                {
+                    health.backoff.reset();
+                }
+                continue;
+            };
+
+            // A provider disabled in config stops its own watcher on purpose
+            if !provider_enabled(provider_id) {
+                continue;
+            }
+
+            if health.problem != Some(problem) {
+                let detail = describe(&health.snapshot, problem);
+                let _ = log_warn(provider_id, &format!("⚠ Watcher unhealthy: {}", detail));
+                history.record(provider_id, problem, detail);
+                if problem == HealthEventKind::WatchLimit {
+                    history.record(
+                        provider_id,
+                        HealthEventKind::PollingFallback,
+                        "restarting with a polling watcher",
+                    );
+                }
+                health.problem = Some(problem);
+            }
+
+            if !health.backoff.ready(now) {
+                continue;
+            }
+            let delay = health.backoff.schedule(now);
+            restarts.push(Restart {
+                provider_id: provider_id.clone(),
+                heartbeat: heartbeat.clone(),
+                attempt: health.backoff.attempts(),
+                delay,
+            });
+        }
+
+        restarts
+    }
+
+    fn record_restart(&self, restart: &Restart, outcome: Result<bool, String>) {
+        let (Ok(mut providers), Ok(mut history)) = (self.providers.lock(), self.history.lock())
+        else {
+            return;
+        };
+        let provider_id = &restart.provider_id;
+
+        match outcome {
+            Ok(true) => {
+                if let Some(health) = providers.get_mut(provider_id) {
+                    health.healthy_since = Instant::now();
+                    health.restarts += 1;
+                }
+                let _ = log_info(
+                    provider_id,
+                    &format!("🔄 Watcher restarted (attempt {})", restart.attempt),
+                );
+                history.record(
+                    provider_id,
+                    HealthEventKind::Restarted,
+                    format!("attempt {}", restart.attempt),
+                );
+            }
+            // Stopped or replaced by the user while the new watcher was built
+            Ok(false) => {
+                providers.remove(provider_id);
+            }
+            Err(e) => {
+                let detail = format!(
+                    "attempt {}: {} (retrying in {}s)",
+                    restart.attempt,
+                    e,
+                    restart.delay.as_secs()
+                );
+                let _ = log_warn(
+                    provider_id,
+                    &format!("⚠ Watcher restart failed, {}", detail),
+                );
+                history.record(provider_id, HealthEventKind::RestartFailed, detail);
+            }
+        }
+    }
+
+    pub fn report(&self, provider_id: Option<&str>) -> WatcherHealthReport {
+        let now = Instant::now();
+        let watchers = self
+            .providers
+            .lock()
+            .map(|providers| {
+                providers
+                    .iter()
+                    .filter(|(id, _)| provider_id.is_none_or(|wanted| wanted == id.as_str()))
+                    .map(|(id, health)| WatcherHealth {
+                        provider_id: id.clone(),
+                        healthy: health.problem.is_none(),
+                        problem: health.problem,
+                        polling: health.snapshot.polling,
+                        seconds_since_heartbeat: health.snapshot.since_beat.as_secs(),
+                        last_failure: health.snapshot.last_failure.clone(),
+                        restart_attempts: health.backoff.attempts(),
+                        restarts: health.restarts,
+                        next_restart_in_secs: health
+                            .backoff
+                            .next_attempt_in(now)
+                            .filter(|_| health.problem.is_some())
+                            .map(|delay| delay.as_secs()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let history = self
+            .history
+            .lock()
+            .map(|history| history.recent(provider_id))
+            .unwrap_or_default();
+
+        WatcherHealthReport { watchers, history }
+    }
+}
+
+/// Swap in `watcher` if the unhealthy one is still registered
+///
+/// Returns false, stopping `watcher`, when the entry was removed or replaced
+/// in the meantime, so a watcher the user stopped is not revived.
+fn replace_watcher(
+    watchers: &Mutex<HashMap<String, Watcher>>,
+    restart: &Restart,
+    watcher: Watcher,
+) -> bool {
+    let Ok(mut watchers) = watchers.lock() else {
+        watcher.stop();
+        return false;
+    };
+    let current = watchers
+        .get(&restart.provider_id)
+        .is_some_and(|current| current.heartbeat().same_watcher(&restart.heartbeat));
+    if !current {
+        watcher.stop();
+        return false;
+    }
+
+    if let Some(previous) = watchers.insert(restart.provider_id.clone(), watcher) {
+        previous.stop();
+    }
+    true
+}
+
+fn provider_enabled(provider_id: &str) -> bool {
+    if is_custom_provider(provider_id) {
+        load_custom_config(provider_id)
+            .map(|(config, _)| config.enabled)
+            .unwrap_or(false)
+    } else {
+        load_provider_config(provider_id)
+            .map(|config| config.enabled)
+            .unwrap_or(false)
+    }
+}
+
+fn describe(snapshot: &HeartbeatSnapshot, problem: HealthEventKind) -> String {
+    match problem {
+        HealthEventKind::Panicked => "watcher thread panicked".to_string(),
+        HealthEventKind::Exited => "watcher thread exited".to_string(),
+        HealthEventKind::Stalled => format!("no heartbeat for {}s", snapshot.since_beat.as_secs()),
+        HealthEventKind::RootMissing => {
+            format!("watched directory {} is gone", snapshot.root.display())
+        }
+        HealthEventKind::WatchLimit => match &snapshot.last_failure {
+            Some(WatchFailure::WatchLimit(message)) => message.clone(),
+            _ => "OS file watch limit reached".to_string(),
+        },
+        _ => String::new(),
+    }
+}
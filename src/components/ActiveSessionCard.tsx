@@ -133,6 +133,7 @@ export function ActiveSessionCard({
       }
 
       const diffs = await invoke<any[]>('get_session_git_diff', {
+        sessionId: session.sessionId,
         cwd: session.cwd,
         firstCommitHash: session.firstCommitHash,
         latestCommitHash: session.latestCommitHash || null,
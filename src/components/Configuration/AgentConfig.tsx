@@ -39,6 +39,7 @@ import {
 import { useRescanProgress } from '../../hooks/useRescanProgress'
 import { useSetupInstructions } from '../../hooks/useSetupInstructions'
 import { useToast } from '../../hooks/useToast'
+import { useWatcherHealth } from '../../hooks/useWatcherHealth'
 import type { CodingAgent, ProviderConfig } from '../../types/providers'
 import ConfirmDialog from '../ConfirmDialog'
 import RescanProgress from '../RescanProgress'
@@ -74,6 +75,7 @@ function AgentConfig({ agent, headerActions }: AgentConfigProps) {
   const [isScanning, setIsScanning] = useState(false)
   const toast = useToast()
   const { progress: rescanProgress } = useRescanProgress(agent.id)
+  const { health: watcherHealth } = useWatcherHealth(agent.id)
 
   // Watcher hooks - conditional based on provider
   const { data: claudeWatcherStatus } = useClaudeWatcherStatus()
@@ -112,6 +114,12 @@ function AgentConfig({ agent, headerActions }: AgentConfigProps) {
             : agent.id === 'cursor'
               ? cursorWatcherStatus
               : undefined
+  const unsupportedFormats =
+    watcherStatus && 'unsupported_formats' in watcherStatus
+      ? (watcherStatus.unsupported_formats ?? [])
+      : []
+  const unhealthyWatcher = watcherHealth?.watchers.find(w => !w.healthy)
+  const lastHealthEvent = watcherHealth?.history[0]
   const startWatcher =
     agent.id === 'claude-code'
       ? startClaudeWatcher
@@ -583,6 +591,32 @@ function AgentConfig({ agent, headerActions }: AgentConfigProps) {
                     )}
                   </div>
 
+                  {/* Sessions refused because of their format version */}
+                  {unsupportedFormats.length > 0 && (
+                    <div className="alert alert-warning py-2 text-xs">
+                      <span>
+                        Skipped {unsupportedFormats.length} session
+                        {unsupportedFormats.length !== 1 ? 's' : ''} with unsupported format version{' '}
+                        {[...new Set(unsupportedFormats.map(f => f.version ?? 'unknown'))].join(', ')}{' '}
+                        (supported: {unsupportedFormats[0].supported}). Update GuideAI to import
+                        them.
+                      </span>
+                    </div>
+                  )}
+
+                  {/* Supervisor is restarting a failed watcher */}
+                  {unhealthyWatcher && (
+                    <div className="alert alert-warning py-2 text-xs">
+                      <span>
+                        File watcher stopped working
+                        {lastHealthEvent?.detail ? `: ${lastHealthEvent.detail}` : ''}.
+                        {unhealthyWatcher.next_restart_in_secs !== null
+                          ? ` Restarting in ${unhealthyWatcher.next_restart_in_secs}s.`
+                          : ' Restarting…'}
+                      </span>
+                    </div>
+                  )}
+
                   {/* Help text */}
                   {!canStartWatcher && !watcherStatus?.is_running && (
                     <div className="text-xs text-base-content/60">
@@ -0,0 +1,43 @@
+import { ExclamationTriangleIcon } from '@heroicons/react/24/outline'
+import type React from 'react'
+import type { ConversionStats } from '../hooks/useConversionStats'
+
+interface ConversionFidelityNoticeProps {
+  stats: ConversionStats | null
+}
+
+function formatCounts(counts: Record<string, number>): string {
+  return Object.entries(counts)
+    .map(([key, count]) => `${key} (${count})`)
+    .join(', ')
+}
+
+/**
+ * Warns when records of a session failed to convert or had unrecognized types,
+ * which usually means the provider changed its format.
+ */
+export function ConversionFidelityNotice({
+  stats,
+}: ConversionFidelityNoticeProps): React.ReactElement | null {
+  const unknownTypes = Object.keys(stats?.unknownEventTypes ?? {}).length
+  if (!stats || (stats.failed === 0 && unknownTypes === 0)) {
+    return null
+  }
+
+  return (
+    <div role="alert" className="alert alert-warning py-2 text-sm">
+      <ExclamationTriangleIcon className="w-5 h-5" />
+      <div>
+        <div className="font-semibold">This transcript may be incomplete</div>
+        {stats.failed > 0 && (
+          <div>
+            {stats.failed} of {stats.recordsSeen} records could not be converted
+          </div>
+        )}
+        {unknownTypes > 0 && (
+          <div>Unrecognized record types: {formatCounts(stats.unknownEventTypes)}</div>
+        )}
+      </div>
+    </div>
+  )
+}
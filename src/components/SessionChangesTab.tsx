@@ -15,7 +15,7 @@ import {
 interface FileDiff {
   oldPath: string
   newPath: string
-  changeType: 'added' | 'deleted' | 'modified' | 'renamed'
+  changeType: 'added' | 'deleted' | 'modified' | 'renamed' | 'copied'
   language: string | null
   hunks: string[]
   stats: {
@@ -23,6 +23,8 @@ interface FileDiff {
     deletions: number
   }
   isBinary: boolean
+  isLfs: boolean
+  collapsed: 'lockfile' | 'generated' | 'too_large' | 'total_limit' | null
   oldContent?: string | null
   newContent?: string | null
 }
@@ -42,6 +44,7 @@ interface SessionChangesTabProps {
 }
 
 async function fetchGitDiff(
+  sessionId: string,
   cwd: string,
   firstCommitHash: string,
   latestCommitHash: string | null,
@@ -62,6 +65,7 @@ async function fetchGitDiff(
 
   // Tauri returns snake_case from Rust, so we need to handle it
   const result = await invoke<any[]>('get_session_git_diff', {
+    sessionId,
     cwd,
     firstCommitHash,
     latestCommitHash,
@@ -89,6 +93,8 @@ async function fetchGitDiff(
       deletions: item.stats?.deletions || 0,
     },
     isBinary: item.is_binary || false,
+    isLfs: item.is_lfs || false,
+    collapsed: item.collapsed || null,
     oldContent: item.old_content || null,
     newContent: item.new_content || null,
   }))
@@ -119,6 +125,7 @@ export function SessionChangesTab({
     queryKey: ['session-git-diff', session.sessionId],
     queryFn: () =>
       fetchGitDiff(
+        session.sessionId,
         session.cwd,
         session.first_commit_hash,
         session.latest_commit_hash,
@@ -330,6 +337,14 @@ function FileDiffCard({ file, expanded, onToggle, viewMode }: FileDiffCardProps)
     deleted: 'badge-error',
     modified: 'badge-info',
     renamed: 'badge-warning',
+    copied: 'badge-warning',
+  }
+
+  const collapsedMessages = {
+    lockfile: 'Lockfile changes are hidden',
+    generated: 'Generated file changes are hidden',
+    too_large: 'File is too large to display',
+    total_limit: 'Diff too large - remaining files are hidden',
   }
 
   // Get current theme from document
@@ -379,6 +394,7 @@ function FileDiffCard({ file, expanded, onToggle, viewMode }: FileDiffCardProps)
               {file.changeType}
             </span>
             {file.isBinary && <span className="badge badge-sm">binary</span>}
+            {file.isLfs && <span className="badge badge-sm">LFS</span>}
           </div>
           <div className="flex items-center gap-2 text-sm">
             <span className="text-success">+{file.stats.additions}</span>
@@ -395,6 +411,11 @@ function FileDiffCard({ file, expanded, onToggle, viewMode }: FileDiffCardProps)
               <DocumentTextIcon className="w-12 h-12 mx-auto mb-2 opacity-50" />
               <p>Binary file changed</p>
             </div>
+          ) : file.collapsed ? (
+            <div className="p-8 text-center text-base-content/60">
+              <DocumentTextIcon className="w-12 h-12 mx-auto mb-2 opacity-50" />
+              <p>{collapsedMessages[file.collapsed]}</p>
+            </div>
           ) : validHunks.length === 0 ? (
             <div className="p-8 text-center text-base-content/60">
               <DocumentTextIcon className="w-12 h-12 mx-auto mb-2 opacity-50" />
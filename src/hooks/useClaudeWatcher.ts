@@ -1,11 +1,14 @@
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
 import { invoke } from '@tauri-apps/api/core'
+import type { UnsupportedFormat } from '../types/providers'
 
 export interface ClaudeWatcherStatus {
   is_running: boolean
   pending_uploads: number
   processing_uploads: number
   failed_uploads: number
+  watched_paths?: string[]
+  unsupported_formats?: UnsupportedFormat[]
 }
 
 export interface LogEntry {
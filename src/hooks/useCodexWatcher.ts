@@ -1,11 +1,13 @@
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
 import { invoke } from '@tauri-apps/api/core'
+import type { UnsupportedFormat } from '../types/providers'
 
 export interface CodexWatcherStatus {
   is_running: boolean
   pending_uploads: number
   processing_uploads: number
   failed_uploads: number
+  unsupported_formats?: UnsupportedFormat[]
 }
 
 export function useCodexWatcherStatus() {
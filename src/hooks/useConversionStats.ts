@@ -0,0 +1,27 @@
+import { useQuery } from '@tanstack/react-query'
+import { invoke } from '@tauri-apps/api/core'
+
+export interface ConversionStats {
+  recordsSeen: number
+  converted: number
+  messagesProduced: number
+  skipped: Record<string, number>
+  failed: number
+  unknownEventTypes: Record<string, number>
+  unknownKeys: Record<string, number>
+}
+
+/**
+ * Hook to fetch how faithfully a session's raw records were converted
+ * Returns null if the session hasn't been converted since stats were added
+ */
+export function useConversionStats(sessionId: string | undefined) {
+  const { data, isLoading } = useQuery({
+    queryKey: ['conversion-stats', sessionId],
+    queryFn: () =>
+      invoke<ConversionStats | null>('get_session_conversion_stats', { sessionId }),
+    enabled: !!sessionId,
+  })
+
+  return { stats: data ?? null, loading: isLoading }
+}
@@ -6,6 +6,7 @@ export interface GeminiWatcherStatus {
   pending_uploads: number
   processing_uploads: number
   failed_uploads: number
+  watched_paths?: string[]
 }
 
 export function useGeminiWatcherStatus() {
@@ -115,6 +115,7 @@ export function useSessionProcessing() {
 
             // Fetch git diff using same command as Session Changes tab
             const fileDiffs = await invoke<any[]>('get_session_git_diff', {
+              sessionId,
               cwd: session.cwd,
               firstCommitHash: session.first_commit_hash,
               latestCommitHash: session.latest_commit_hash,
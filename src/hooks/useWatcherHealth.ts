@@ -0,0 +1,57 @@
+import { useQuery } from '@tanstack/react-query'
+import { invoke } from '@tauri-apps/api/core'
+
+export type HealthEventKind =
+  | 'panicked'
+  | 'exited'
+  | 'stalled'
+  | 'root_missing'
+  | 'watch_limit'
+  | 'polling_fallback'
+  | 'restarted'
+  | 'restart_failed'
+  | 'recovered'
+
+export interface WatchFailure {
+  kind: 'watch_limit' | 'error'
+  message: string
+}
+
+export interface WatcherHealth {
+  provider_id: string
+  healthy: boolean
+  problem: HealthEventKind | null
+  polling: boolean
+  seconds_since_heartbeat: number
+  last_failure: WatchFailure | null
+  restart_attempts: number
+  restarts: number
+  next_restart_in_secs: number | null
+}
+
+export interface HealthEvent {
+  provider_id: string
+  kind: HealthEventKind
+  detail: string
+  at: string
+}
+
+export interface WatcherHealthReport {
+  watchers: WatcherHealth[]
+  history: HealthEvent[]
+}
+
+/**
+ * Hook to fetch supervised watcher health and recent failures/restarts
+ * Pass a provider id to narrow both to one provider
+ */
+export function useWatcherHealth(providerId?: string) {
+  const { data, isLoading } = useQuery({
+    queryKey: ['watcher-health', providerId],
+    queryFn: () =>
+      invoke<WatcherHealthReport>('get_watcher_health', { providerId: providerId ?? null }),
+    refetchInterval: 5000,
+  })
+
+  return { health: data ?? null, loading: isLoading }
+}
@@ -4,17 +4,72 @@ import { useLocalProjects } from '../hooks/useLocalProjects'
 
 const PROJECT_TYPE_LABELS: Record<string, string> = {
   nodejs: 'Node.js',
+  nextjs: 'Next.js',
+  nuxt: 'Nuxt',
+  remix: 'Remix',
+  sveltekit: 'SvelteKit',
+  astro: 'Astro',
+  angular: 'Angular',
+  nestjs: 'NestJS',
+  electron: 'Electron',
+  'react-native': 'React Native',
+  vue: 'Vue',
+  svelte: 'Svelte',
+  react: 'React',
+  express: 'Express',
   rust: 'Rust',
   python: 'Python',
+  django: 'Django',
+  fastapi: 'FastAPI',
+  flask: 'Flask',
   go: 'Go',
+  java: 'Java',
+  kotlin: 'Kotlin',
+  'spring-boot': 'Spring Boot',
+  quarkus: 'Quarkus',
+  android: 'Android',
+  dotnet: '.NET',
+  aspnet: 'ASP.NET',
+  blazor: 'Blazor',
+  ruby: 'Ruby',
+  rails: 'Rails',
+  sinatra: 'Sinatra',
+  hanami: 'Hanami',
+  php: 'PHP',
+  laravel: 'Laravel',
+  symfony: 'Symfony',
+  elixir: 'Elixir',
+  phoenix: 'Phoenix',
+  swift: 'Swift',
+  cpp: 'C/C++',
   generic: 'Generic',
 }
 
 const PROJECT_TYPE_COLORS: Record<string, string> = {
   nodejs: 'badge-success',
+  nextjs: 'badge-success',
+  react: 'badge-success',
+  vue: 'badge-success',
   rust: 'badge-error',
   python: 'badge-info',
+  django: 'badge-info',
+  fastapi: 'badge-info',
+  flask: 'badge-info',
   go: 'badge-primary',
+  java: 'badge-warning',
+  kotlin: 'badge-warning',
+  'spring-boot': 'badge-warning',
+  android: 'badge-warning',
+  dotnet: 'badge-secondary',
+  aspnet: 'badge-secondary',
+  ruby: 'badge-error',
+  rails: 'badge-error',
+  php: 'badge-accent',
+  laravel: 'badge-accent',
+  elixir: 'badge-secondary',
+  phoenix: 'badge-secondary',
+  swift: 'badge-warning',
+  cpp: 'badge-primary',
   generic: 'badge-neutral',
 }
 
@@ -28,6 +28,7 @@ import { invoke } from '@tauri-apps/api/core'
 import { listen } from '@tauri-apps/api/event'
 import { useCallback, useEffect, useMemo, useState } from 'react'
 import { useNavigate, useParams } from 'react-router-dom'
+import { ConversionFidelityNotice } from '../components/ConversionFidelityNotice'
 import { JsonBlock } from '../components/JsonBlock'
 import { SessionChangesTab } from '../components/SessionChangesTab'
 import { SessionContextTab } from '../components/SessionContextTab'
@@ -37,6 +38,7 @@ import { useAiProcessing } from '../hooks/useAiProcessing'
 import { useAiProcessingProgress } from '../hooks/useAiProcessingProgress'
 import { useAuth } from '../hooks/useAuth'
 import { useLocalSessionContent } from '../hooks/useLocalSessionContent'
+import { useConversionStats } from '../hooks/useConversionStats'
 import { useLocalSessionMetrics } from '../hooks/useLocalSessionMetrics'
 import { useQuickRating } from '../hooks/useQuickRating'
 import { useSessionActivity } from '../hooks/useSessionActivity'
@@ -322,6 +324,7 @@ export default function SessionDetailPage() {
         return null
       }
       const diffs = await invoke<any[]>('get_session_git_diff', {
+        sessionId,
         cwd: session.cwd,
         firstCommitHash: session.firstCommitHash,
         latestCommitHash: (session as any).latestCommitHash || null,
@@ -477,6 +480,8 @@ export default function SessionDetailPage() {
     session?.sessionId ?? undefined
   )
 
+  const { stats: conversionStats } = useConversionStats(session?.sessionId ?? undefined)
+
   // Handle AI processing
   const handleProcessWithAi = async () => {
     if (!session || !timeline) return
@@ -783,6 +788,8 @@ export default function SessionDetailPage() {
         />
       )}
 
+      <ConversionFidelityNotice stats={conversionStats} />
+
       {/* Tabs Navigation with Controls */}
       <div className="card bg-base-200 border border-base-300 border-b-2 rounded-lg">
         <div className="flex items-stretch">
@@ -219,6 +219,9 @@ describe('SessionDetailPage integration', () => {
       if (command === 'quick_rate_session') {
         return {}
       }
+      if (command === 'get_session_conversion_stats') {
+        return null
+      }
       if (command === 'execute_sql') {
         return []
       }
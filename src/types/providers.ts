@@ -15,6 +15,8 @@ export interface ProviderConfig {
   selectedProjects: string[]
   lastScanned: string | null
   syncMode: SyncMode
+  idleTimeoutMinutes?: number
+  projectRoots?: string[]
 }
 
 export interface CodingAgent {
@@ -188,3 +190,11 @@ export const CODING_AGENTS: CodingAgent[] = [
     setupInstructionsFile: 'cursor.md',
   },
 ]
+
+/** A session a watcher refused because its provider format version isn't supported */
+export interface UnsupportedFormat {
+  session_id: string
+  version: string | null
+  supported: string
+  detected_at: string
+}